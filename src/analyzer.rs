@@ -0,0 +1,931 @@
+#[cfg(feature = "console")]
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use crate::blame::{blame_violations, BlameReport};
+use crate::cli::{config_defaults, resolve_config, Config, ResolvedConfig};
+use crate::codeowners::{OwnersReport, Ownership};
+#[cfg(feature = "console")]
+use crate::directories::directory_tree;
+use crate::history::{append_record, load_history, HistoryRecord};
+use crate::hotspots::{analyze_hotspots, Hotspots};
+use crate::metrics::duplication::detect_duplication;
+use crate::metrics::scoring::compute_project_score;
+use crate::modules::{ModuleGraph, ModuleResolver, FILE_UNIT_NAME};
+#[cfg(feature = "console")]
+use crate::output::blame_output::render_blame_console;
+#[cfg(feature = "console")]
+use crate::output::directories_output::render_directories_console;
+#[cfg(feature = "console")]
+use crate::output::hotspots_output::render_hotspots_console;
+use crate::output::json_output::{
+    attach_blame, attach_owners, attach_packages, build_json, JsonOutput,
+};
+#[cfg(feature = "console")]
+use crate::output::modules_output::render_modules_console;
+#[cfg(feature = "console")]
+use crate::output::owners_output::render_owners_console;
+#[cfg(feature = "console")]
+use crate::output::packages_output::render_packages_console;
+#[cfg(feature = "console")]
+use crate::output::render_console;
+#[cfg(feature = "sarif")]
+use crate::output::sarif_output::{build_sarif, SarifRunProperties};
+use crate::output::violations::{
+    class_violations, coupling_violations, cycle_violations, function_violations, Severity,
+    Violation,
+};
+use crate::output::{determine_exit_code, ExitCode};
+#[cfg(feature = "html")]
+use crate::output::{render_html, HtmlExtras};
+use crate::pipeline::cache::CacheStats;
+#[cfg(feature = "discovery")]
+use crate::pipeline::discover::build_globset;
+#[cfg(feature = "discovery")]
+use crate::pipeline::discover::{discover_project_files, discover_revision_files};
+#[cfg(feature = "discovery")]
+use crate::pipeline::discover_files;
+use crate::pipeline::{analyze_sources, AnalysisCache, SourceFile};
+use crate::tsconfig::TsConfig;
+#[cfg(feature = "discovery")]
+use crate::types::SizeLimitsOverride;
+use crate::types::{
+    AnalysisConfig, CognitiveConfig, CyclomaticConfig, DuplicationConfig, DuplicationResult,
+    FileAnalysisResult, ScoringThresholds, ScoringWeights, SizeLimits, SkippedItem, SwitchCaseMode,
+    SyntaxErrorPolicy,
+};
+use crate::workspaces::{PackagesReport, Workspace};
+
+/// Builder for one analysis run: configuration plus the files to analyze.
+///
+/// This is the entry point for embedding complexityguard in other Rust tools;
+/// the CLI is a thin layer over it. Starts from the built-in defaults, so an
+/// analyzer with only inputs added behaves like the CLI without a config file.
+///
+/// ```no_run
+/// use complexity_guard::Analyzer;
+///
+/// let report = Analyzer::new()
+///     .source("scratch.ts", "export function f(a: number) { return a ?? 1; }")
+///     .run()?;
+/// println!("{} errors, health {:.1}", report.error_count(), report.health_score());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Analyzer {
+    config: Config,
+    #[cfg(feature = "discovery")]
+    paths: Vec<PathBuf>,
+    #[cfg(feature = "discovery")]
+    rev: Option<String>,
+    owner: Option<String>,
+    package: Option<String>,
+    sources: Vec<SourceFile>,
+}
+
+impl Default for Analyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Analyzer {
+    /// An analyzer with the default configuration and no inputs.
+    pub fn new() -> Self {
+        Self {
+            config: config_defaults(),
+            #[cfg(feature = "discovery")]
+            paths: Vec::new(),
+            #[cfg(feature = "discovery")]
+            rev: None,
+            owner: None,
+            package: None,
+            sources: Vec::new(),
+        }
+    }
+
+    /// Overlay `config` (typically a parsed `.complexityguard.json`) on the
+    /// current configuration. Only the values set in `config` change.
+    pub fn config(mut self, config: Config) -> Self {
+        self.config.overlay(config);
+        self
+    }
+
+    /// Edit the configuration in place, e.g. to apply command-line overrides.
+    pub fn configure(mut self, edit: impl FnOnce(&mut Config)) -> Self {
+        edit(&mut self.config);
+        self
+    }
+
+    /// Add a file or directory to analyze. Directories are walked with the
+    /// configured include and exclude patterns, or limit the files of
+    /// `files.project` when set.
+    #[cfg(feature = "discovery")]
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.paths.push(path.into());
+        self
+    }
+
+    /// Add several files or directories to analyze.
+    #[cfg(feature = "discovery")]
+    pub fn paths<I, P>(mut self, paths: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        self.paths.extend(paths.into_iter().map(Into::into));
+        self
+    }
+
+    /// Read the paths from git commit `rev` (a SHA, tag or branch) of the
+    /// repository in the working directory instead of from disk. The working
+    /// tree is not touched, and `Report::commit` holds the commit's SHA.
+    #[cfg(feature = "discovery")]
+    pub fn rev(mut self, rev: impl Into<String>) -> Self {
+        self.rev = Some(rev.into());
+        self
+    }
+
+    /// Only analyze the inputs `owner` owns according to the CODEOWNERS file of
+    /// the working directory's repository (`owners.file` when set). Owners
+    /// match without regard to case; `(unowned)` selects files without one.
+    pub fn owner(mut self, owner: impl Into<String>) -> Self {
+        self.owner = Some(owner.into());
+        self
+    }
+
+    /// Only analyze the inputs in workspace package `package`, by name or by
+    /// path relative to the workspace root, of the npm, yarn or pnpm workspace
+    /// the working directory belongs to. `(root)` selects files outside every
+    /// package.
+    pub fn package(mut self, package: impl Into<String>) -> Self {
+        self.package = Some(package.into());
+        self
+    }
+
+    /// Add an in-memory file. `path` selects the grammar and applicable size
+    /// limits and is the path reported; it does not need to exist.
+    pub fn source(mut self, path: impl Into<PathBuf>, source: impl Into<Vec<u8>>) -> Self {
+        self.sources.push(SourceFile::Memory {
+            path: path.into(),
+            source: source.into(),
+        });
+        self
+    }
+
+    /// The files under the input paths, from disk or from commit `rev`, and
+    /// the commit's SHA. With `files.project`, the project's files under the
+    /// input paths (all of them without any) instead.
+    #[cfg(feature = "discovery")]
+    fn discover(&self) -> anyhow::Result<(Vec<SourceFile>, Option<String>)> {
+        let files = self.config.files.as_ref();
+        let project = files.and_then(|f| f.project.as_deref());
+        if self.paths.is_empty() && project.is_none() {
+            return Ok((Vec::new(), None));
+        }
+        let include = files.and_then(|f| f.include.clone()).unwrap_or_default();
+        let exclude = files.and_then(|f| f.exclude.clone()).unwrap_or_default();
+        let Some(rev) = &self.rev else {
+            let discovered = match project {
+                Some(project) => {
+                    discover_project_files(Path::new(project), &self.paths, &include, &exclude)
+                        .with_context(|| format!("failed to read the files of project {project}"))?
+                }
+                None => discover_files(&self.paths, &include, &exclude)
+                    .context("failed to discover files")?,
+            };
+            return Ok((discovered.into_iter().map(SourceFile::Disk).collect(), None));
+        };
+        if let Some(project) = project {
+            anyhow::bail!("project {project} cannot be analyzed at a git revision");
+        }
+        let dir = Path::new(".");
+        let commit = crate::git::resolve_commit(dir, rev)?;
+        let discovered = discover_revision_files(dir, &commit, &self.paths, &include, &exclude)
+            .with_context(|| format!("failed to read files of {rev}"))?;
+        let sources = discovered
+            .into_iter()
+            .map(|(path, source)| SourceFile::Memory { path, source })
+            .collect();
+        Ok((sources, Some(commit)))
+    }
+
+    /// Resolve the imports between `files` with the path aliases of
+    /// `files.project`, or of the working directory's tsconfig.json when
+    /// there is one, and record the coupling of each file.
+    fn module_graph(&self, files: &mut [FileAnalysisResult]) -> anyhow::Result<ModuleGraph> {
+        let project = self
+            .config
+            .files
+            .as_ref()
+            .and_then(|f| f.project.as_deref());
+        let tsconfig = match project.map(Path::new) {
+            Some(project) => Some(TsConfig::load(project)?),
+            None if Path::new("tsconfig.json").is_file() => {
+                Some(TsConfig::load(Path::new("tsconfig.json"))?)
+            }
+            None => None,
+        };
+        let resolver = ModuleResolver::new(files, tsconfig.as_ref());
+        let graph = ModuleGraph::build(files, &resolver);
+        for (file, module) in files.iter_mut().zip(&graph.modules) {
+            file.coupling = Some(module.coupling);
+            file.cycles = graph
+                .cycles
+                .iter()
+                .filter(|cycle| cycle.files[0] == module.path)
+                .cloned()
+                .collect();
+        }
+        Ok(graph)
+    }
+
+    /// The configuration a run would use, with every default applied.
+    pub fn resolved_config(&self) -> ResolvedConfig {
+        resolve_config(&self.config)
+    }
+
+    /// The analysis settings a run would use. Fails like `run` on invalid
+    /// override globs or an unknown syntax error policy.
+    pub(crate) fn analysis_config(&self) -> anyhow::Result<AnalysisConfig> {
+        build_analysis_config(&self.config, &resolve_config(&self.config))
+            .context("invalid configuration")
+    }
+
+    /// Discover and analyze every input and collect the results into a `Report`.
+    ///
+    /// Fails only on configuration problems (invalid globs, unknown syntax
+    /// error policy), an unreadable input path, an unknown `rev` or, with
+    /// `modules_enabled`, an unreadable tsconfig.json. Files that fail to parse are
+    /// reported in `Report::skipped` and set `Report::has_parse_errors`.
+    pub fn run(&self) -> anyhow::Result<Report> {
+        let resolved = resolve_config(&self.config);
+        let analysis_config =
+            build_analysis_config(&self.config, &resolved).context("invalid configuration")?;
+
+        #[cfg(feature = "discovery")]
+        let (mut inputs, commit) = self.discover()?;
+        #[cfg(not(feature = "discovery"))]
+        let (mut inputs, commit): (Vec<SourceFile>, _) = (Vec::new(), None);
+        inputs.extend(self.sources.iter().cloned());
+        if let Some(owner) = &self.owner {
+            let ownership = Ownership::load(Path::new("."), resolved.owners_file.as_deref())?;
+            inputs.retain(|input| ownership.is_owned_by(input.path(), owner));
+        }
+        if let Some(selector) = &self.package {
+            let workspace = Workspace::load(Path::new("."))?;
+            let package = workspace.resolve(selector)?;
+            inputs.retain(|input| workspace.package_of(input.path()) == package);
+        }
+
+        let start = std::time::Instant::now();
+        let cache = resolved
+            .cache_enabled
+            .then(|| AnalysisCache::new(&resolved.cache_dir));
+        let (mut files, has_parse_errors, skipped) =
+            analyze_sources(&inputs, &analysis_config, resolved.threads, cache.as_ref());
+        let modules = if resolved.modules_enabled {
+            Some(
+                self.module_graph(&mut files)
+                    .context("failed to build the module graph")?,
+            )
+        } else {
+            None
+        };
+        let elapsed_ms = start.elapsed().as_millis() as u64;
+
+        // Duplication detection runs across all files, gated by config
+        let duplication_enabled =
+            self.config.analysis.as_ref().is_some_and(|a| {
+                a.duplication_enabled == Some(true) && a.no_duplication != Some(true)
+            });
+        let duplication = duplication_enabled.then(|| {
+            let file_tokens: Vec<&[_]> = files.iter().map(|f| f.tokens.as_slice()).collect();
+            detect_duplication(&file_tokens, &analysis_config.duplication)
+        });
+
+        let violations = collect_violations(&files, &resolved);
+
+        Ok(Report {
+            files,
+            skipped,
+            duplication,
+            violations,
+            has_parse_errors,
+            elapsed_ms,
+            cache_stats: cache.map(|c| c.stats()),
+            config: resolved,
+            history: Vec::new(),
+            hotspots: None,
+            blame: None,
+            owners: None,
+            packages: None,
+            modules,
+            commit,
+        })
+    }
+}
+
+/// A threshold violation and the function, module or class it belongs to.
+#[derive(Debug, Clone)]
+pub struct ReportViolation {
+    pub path: PathBuf,
+    /// Qualified function name, `<module>`, class name, or `<file>` for the whole file
+    pub name: String,
+    pub violation: Violation,
+}
+
+/// The typed result of an `Analyzer` run.
+#[derive(Debug, Clone)]
+pub struct Report {
+    /// Analyzed files, sorted by path
+    pub files: Vec<FileAnalysisResult>,
+    /// Files and functions excluded by the size guards, syntax error policy or a failure
+    pub skipped: Vec<SkippedItem>,
+    /// Cross-file duplication, when enabled
+    pub duplication: Option<DuplicationResult>,
+    /// Every threshold violation, in file order
+    pub violations: Vec<ReportViolation>,
+    /// A file failed to read or parse, or had syntax errors under `on_syntax_error: fail`
+    pub has_parse_errors: bool,
+    pub elapsed_ms: u64,
+    /// Cache hits and misses, when the analysis cache is enabled
+    pub cache_stats: Option<CacheStats>,
+    /// The configuration the run used; display settings here affect rendering only
+    pub config: ResolvedConfig,
+    /// Recorded runs, oldest first, shown as a trend chart in the HTML report;
+    /// empty until `record_history` is called
+    pub history: Vec<HistoryRecord>,
+    /// Churn × complexity ranking; `None` until `analyze_hotspots` is called
+    pub hotspots: Option<Hotspots>,
+    /// Who last changed each unit with violations; `None` until
+    /// `blame_violations` is called
+    pub blame: Option<BlameReport>,
+    /// Owners of each file and per-owner totals; `None` until
+    /// `attribute_owners` is called
+    pub owners: Option<OwnersReport>,
+    /// Workspace package of each file and per-package totals; `None` until
+    /// `group_packages` is called
+    pub packages: Option<PackagesReport>,
+    /// Imports between the analyzed files, when `modules_enabled`; the
+    /// coupling of each file is on the file itself
+    pub modules: Option<ModuleGraph>,
+    /// SHA of the commit analyzed with `Analyzer::rev`; `None` for the working tree
+    pub commit: Option<String>,
+}
+
+impl Report {
+    pub fn error_count(&self) -> u32 {
+        self.count(Severity::Error)
+    }
+
+    pub fn warning_count(&self) -> u32 {
+        self.count(Severity::Warning)
+    }
+
+    fn count(&self, severity: Severity) -> u32 {
+        self.violations
+            .iter()
+            .filter(|v| v.violation.severity == severity)
+            .count() as u32
+    }
+
+    /// Number of functions analyzed, excluding `<module>` units.
+    pub fn function_count(&self) -> usize {
+        self.files.iter().map(|f| f.functions.len()).sum()
+    }
+
+    /// Project health score: file scores weighted by function count, as
+    /// `compute_project_score`. 100 when no functions were analyzed.
+    pub fn health_score(&self) -> f64 {
+        let scores: Vec<f64> = self.files.iter().map(|f| f.file_score).collect();
+        let counts: Vec<u32> = self
+            .files
+            .iter()
+            .map(|f| f.functions.len() as u32)
+            .collect();
+        compute_project_score(&scores, &counts)
+    }
+
+    /// Append this run to the configured history file and load the full
+    /// history into `self.history`. The record includes the commit checked
+    /// out in the working directory, or `commit` when set.
+    pub fn record_history(&mut self) -> anyhow::Result<()> {
+        let path = Path::new(&self.config.history_file);
+        let mut record = HistoryRecord::new(self).with_git(Path::new("."));
+        if let Some(commit) = &self.commit {
+            record.commit = Some(commit.clone());
+            record.branch = None;
+        }
+        append_record(path, &record)?;
+        self.history = load_history(path)?;
+        Ok(())
+    }
+
+    /// Rank files and functions by how often they changed in the git history
+    /// of the working directory within `config.hotspots_since`.
+    pub fn analyze_hotspots(&mut self) -> anyhow::Result<()> {
+        self.hotspots = Some(analyze_hotspots(
+            &self.files,
+            Path::new("."),
+            &self.config.hotspots_since,
+        )?);
+        Ok(())
+    }
+
+    /// Run `git blame` over every unit with violations in the working
+    /// directory, limited to commits after `config.blame_since` when set.
+    pub fn blame_violations(&mut self) -> anyhow::Result<()> {
+        self.blame = Some(blame_violations(
+            &self.files,
+            &self.violations,
+            Path::new("."),
+            self.config.blame_since.as_deref(),
+        )?);
+        Ok(())
+    }
+
+    /// Attribute every file to its owners in the CODEOWNERS file of the
+    /// working directory's repository, or `config.owners_file` when set.
+    pub fn attribute_owners(&mut self) -> anyhow::Result<()> {
+        let ownership = Ownership::load(Path::new("."), self.config.owners_file.as_deref())?;
+        self.owners = Some(ownership.attribute(&self.files, &self.violations));
+        Ok(())
+    }
+
+    /// Group every file by the package of the npm, yarn or pnpm workspace the
+    /// working directory belongs to, gating packages by `config.packages_fail_on`.
+    pub fn group_packages(&mut self) -> anyhow::Result<()> {
+        let workspace = Workspace::load(Path::new("."))?;
+        self.packages =
+            Some(workspace.group(&self.files, &self.violations, &self.config.packages_fail_on));
+        Ok(())
+    }
+
+    /// Write the module graph to `path`: Graphviz DOT for a `.dot` or `.gv`
+    /// file, JSON otherwise. Fails when the graph was not built.
+    pub fn write_module_graph(&self, path: &Path) -> anyhow::Result<()> {
+        let graph = self
+            .modules
+            .as_ref()
+            .context("the module graph was not built")?;
+        graph.write(path)
+    }
+
+    /// The CLI exit code for this report under the given `--fail-on` mode.
+    /// With packages grouped, each package is gated on its own, by its
+    /// `packages_fail_on` mode when it has one.
+    pub fn exit_code(&self, fail_on: Option<&str>) -> ExitCode {
+        if let Some(packages) = &self.packages {
+            return packages.exit_code(self.has_parse_errors, fail_on);
+        }
+        determine_exit_code(
+            self.has_parse_errors,
+            self.error_count(),
+            self.warning_count(),
+            fail_on,
+            false, // baseline enforcement deferred
+        )
+    }
+
+    #[cfg(feature = "console")]
+    pub fn render_console(&self, writer: &mut dyn Write) -> anyhow::Result<()> {
+        if let (Some(commit), false) = (&self.commit, self.config.quiet) {
+            writeln!(writer, "Commit {commit}")?;
+        }
+        if let (Some(packages), false) = (&self.packages, self.config.quiet) {
+            render_packages_console(packages, &self.config, writer)?;
+        }
+        render_console(
+            &self.files,
+            self.duplication.as_ref(),
+            &self.config,
+            writer,
+            &self.skipped,
+        )?;
+        if self.config.quiet {
+            return Ok(());
+        }
+        if self.config.by_directory {
+            let tree = directory_tree(&self.files, &self.config);
+            render_directories_console(&tree, &self.config, writer)?;
+        }
+        if let Some(hotspots) = &self.hotspots {
+            render_hotspots_console(hotspots, &self.config, writer)?;
+        }
+        if let Some(blame) = &self.blame {
+            render_blame_console(blame, &self.config, writer)?;
+        }
+        if let Some(owners) = &self.owners {
+            render_owners_console(owners, &self.config, writer)?;
+        }
+        if let Some(modules) = &self.modules {
+            render_modules_console(modules, &self.config, writer)?;
+        }
+        Ok(())
+    }
+
+    /// The typed JSON report, as written by `render_json`.
+    pub fn json_output(&self) -> JsonOutput {
+        let mut output = build_json(
+            &self.files,
+            self.duplication.as_ref(),
+            &self.config,
+            self.elapsed_ms,
+            &self.skipped,
+        );
+        output.metadata.commit = self.commit.clone();
+        output.summary.has_parse_errors = self.has_parse_errors;
+        output.hotspots = self.hotspots.clone();
+        if let Some(blame) = &self.blame {
+            attach_blame(&mut output, blame);
+        }
+        if let Some(owners) = &self.owners {
+            attach_owners(&mut output, owners);
+        }
+        if let Some(packages) = &self.packages {
+            attach_packages(&mut output, packages);
+        }
+        output
+    }
+
+    pub fn render_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(&self.json_output())?)
+    }
+
+    #[cfg(feature = "sarif")]
+    pub fn render_sarif(&self) -> anyhow::Result<String> {
+        let mut log = build_sarif(
+            &self.files,
+            self.duplication.as_ref(),
+            &self.config,
+            &self.skipped,
+        );
+        log.runs[0].properties = self
+            .commit
+            .clone()
+            .map(|commit| SarifRunProperties { commit });
+        Ok(serde_json::to_string_pretty(&log)?)
+    }
+
+    #[cfg(feature = "html")]
+    pub fn render_html(&self) -> anyhow::Result<String> {
+        render_html(
+            &self.files,
+            self.duplication.as_ref(),
+            &self.config,
+            self.elapsed_ms,
+            &self.skipped,
+            &HtmlExtras {
+                history: &self.history,
+                hotspots: self.hotspots.as_ref(),
+                blame: self.blame.as_ref(),
+                owners: self.owners.as_ref(),
+                packages: self.packages.as_ref(),
+                modules: self.modules.as_ref(),
+                commit: self.commit.as_deref(),
+            },
+        )
+    }
+}
+
+pub(crate) fn collect_violations(
+    files: &[FileAnalysisResult],
+    config: &ResolvedConfig,
+) -> Vec<ReportViolation> {
+    let file_unit = FILE_UNIT_NAME.to_string();
+    let mut violations = Vec::new();
+    for file in files {
+        let units = file
+            .functions
+            .iter()
+            .chain(&file.module)
+            .map(|func| (&func.qualified_name, function_violations(func, config)));
+        let classes = file
+            .classes
+            .iter()
+            .map(|class| (&class.name, class_violations(class, config)));
+        let mut file_level = coupling_violations(file, config);
+        file_level.extend(cycle_violations(file, config));
+        let coupling = std::iter::once((&file_unit, file_level));
+        for (name, found) in units.chain(classes).chain(coupling) {
+            violations.extend(found.into_iter().map(|violation| ReportViolation {
+                path: file.path.clone(),
+                name: name.clone(),
+                violation,
+            }));
+        }
+    }
+    violations
+}
+
+/// The syntax error policy `analysis.on_syntax_error` names; fails on an
+/// unknown name.
+pub(crate) fn syntax_error_policy(config: &Config) -> anyhow::Result<SyntaxErrorPolicy> {
+    match config
+        .analysis
+        .as_ref()
+        .and_then(|a| a.on_syntax_error.as_deref())
+    {
+        Some(name) => SyntaxErrorPolicy::from_name(name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "invalid syntax error policy '{}' (expected analyze, skip or fail)",
+                name
+            )
+        }),
+        None => Ok(SyntaxErrorPolicy::default()),
+    }
+}
+
+/// Build an AnalysisConfig from the merged Config and ResolvedConfig.
+///
+/// Maps resolved threshold values to AnalysisConfig fields. Uses defaults
+/// where values are not specified in config. Fails if an override's file
+/// patterns are not valid globs or the syntax error policy is unknown.
+fn build_analysis_config(
+    config: &Config,
+    resolved: &ResolvedConfig,
+) -> anyhow::Result<AnalysisConfig> {
+    let cyclomatic = CyclomaticConfig {
+        count_logical_operators: true,
+        count_nullish_coalescing: true,
+        count_optional_chaining: true,
+        count_ternary: true,
+        count_default_params: true,
+        switch_case_mode: SwitchCaseMode::Classic,
+        warning_threshold: resolved.cyclomatic_warning,
+        error_threshold: resolved.cyclomatic_error,
+    };
+
+    let cognitive = CognitiveConfig {
+        warning_threshold: resolved.cognitive_warning,
+        error_threshold: resolved.cognitive_error,
+    };
+
+    let scoring_weights = if let Some(w) = &config.weights {
+        ScoringWeights {
+            cyclomatic: w.cyclomatic.unwrap_or(0.20),
+            cognitive: w.cognitive.unwrap_or(0.30),
+            halstead: w.halstead.unwrap_or(0.15),
+            structural: w.structural.unwrap_or(0.15),
+            duplication: w.duplication.unwrap_or(0.20),
+        }
+    } else {
+        ScoringWeights::default()
+    };
+
+    let scoring_thresholds = ScoringThresholds {
+        cyclomatic_warning: resolved.cyclomatic_warning as f64,
+        cyclomatic_error: resolved.cyclomatic_error as f64,
+        cognitive_warning: resolved.cognitive_warning as f64,
+        cognitive_error: resolved.cognitive_error as f64,
+        halstead_warning: resolved.halstead_volume_warning,
+        halstead_error: resolved.halstead_volume_error,
+        function_length_warning: resolved.line_count_warning as f64,
+        function_length_error: resolved.line_count_error as f64,
+        params_count_warning: resolved.params_count_warning as f64,
+        params_count_error: resolved.params_count_error as f64,
+        nesting_depth_warning: resolved.nesting_depth_warning as f64,
+        nesting_depth_error: resolved.nesting_depth_error as f64,
+    };
+
+    // Exported token streams need tokenization even when detection is off
+    let duplication = DuplicationConfig {
+        min_tokens: 25,
+        enabled: resolved.export_tokens
+            || config
+                .analysis
+                .as_ref()
+                .and_then(|a| a.duplication_enabled)
+                .unwrap_or(false),
+    };
+
+    let size_limits = match config.analysis.as_ref().and_then(|a| a.limits.as_ref()) {
+        Some(limits) => limits.apply_to(&SizeLimits::default()),
+        None => SizeLimits::default(),
+    };
+
+    #[cfg(feature = "discovery")]
+    let size_limit_overrides = size_limit_overrides(config, &size_limits)?;
+    #[cfg(not(feature = "discovery"))]
+    if config
+        .overrides
+        .iter()
+        .flatten()
+        .any(|o| o.analysis.as_ref().is_some_and(|a| a.limits.is_some()))
+    {
+        anyhow::bail!("per-path size limit overrides require the `discovery` feature");
+    }
+
+    let syntax_error_policy = syntax_error_policy(config)?;
+
+    Ok(AnalysisConfig {
+        cyclomatic,
+        cognitive,
+        scoring_weights,
+        scoring_thresholds,
+        duplication,
+        size_limits,
+        #[cfg(feature = "discovery")]
+        size_limit_overrides,
+        syntax_error_policy,
+    })
+}
+
+/// Resolve the size limits of every config override entry on top of `base`.
+#[cfg(feature = "discovery")]
+fn size_limit_overrides(
+    config: &Config,
+    base: &SizeLimits,
+) -> anyhow::Result<Vec<SizeLimitsOverride>> {
+    let mut overrides = Vec::new();
+    for entry in config.overrides.iter().flatten() {
+        if let Some(limits) = entry.analysis.as_ref().and_then(|a| a.limits.as_ref()) {
+            overrides.push(SizeLimitsOverride {
+                files: build_globset(&entry.files)?,
+                limits: limits.apply_to(base),
+            });
+        }
+    }
+    Ok(overrides)
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NESTED: &str = "export function deep(a: number, b: number, c: number, d: number) {
+  if (a) { if (b) { if (c) { if (d) { return 1; } } } }
+  return 0;
+}
+";
+
+    #[test]
+    fn analyzes_in_memory_sources_without_touching_disk() {
+        let report = Analyzer::new()
+            .source("virtual/deep.ts", NESTED)
+            .run()
+            .unwrap();
+
+        assert_eq!(report.files.len(), 1);
+        assert_eq!(report.files[0].path, PathBuf::from("virtual/deep.ts"));
+        assert_eq!(report.files[0].functions[0].name, "deep");
+        assert!(!report.has_parse_errors);
+        assert!(report.skipped.is_empty());
+    }
+
+    #[test]
+    fn collects_violations_and_exit_code() {
+        let report = Analyzer::new().source("deep.ts", NESTED).run().unwrap();
+
+        // 4 params and nesting 4 are both warnings under the defaults
+        assert_eq!(report.warning_count(), 2);
+        assert_eq!(report.error_count(), 0);
+        assert!(report.violations.iter().all(|v| v.name == "deep"));
+        assert_eq!(report.exit_code(None), ExitCode::Success);
+        assert_eq!(report.exit_code(Some("warning")), ExitCode::WarningsFound);
+    }
+
+    #[test]
+    fn config_overlay_changes_thresholds() {
+        let strict: Config = serde_json::from_str(
+            r#"{"analysis": {"thresholds": {"params_count": {"warning": 2, "error": 3}}}}"#,
+        )
+        .unwrap();
+        let report = Analyzer::new()
+            .config(strict)
+            .source("deep.ts", NESTED)
+            .run()
+            .unwrap();
+
+        assert_eq!(report.error_count(), 1);
+        assert_eq!(report.exit_code(None), ExitCode::ErrorsFound);
+    }
+
+    #[cfg(feature = "discovery")]
+    #[test]
+    fn mixes_disk_paths_and_sources() {
+        let report = Analyzer::new()
+            .path(
+                PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                    .join("tests/fixtures/typescript/simple_function.ts"),
+            )
+            .source("b.ts", "function other() { return 1; }")
+            .configure(|c| c.analysis.get_or_insert_with(Default::default).threads = Some(1))
+            .run()
+            .unwrap();
+
+        assert_eq!(report.files.len(), 2);
+        assert_eq!(report.function_count(), 2);
+        assert!(report.health_score() > 0.0 && report.health_score() <= 100.0);
+        assert!(report.cache_stats.is_none());
+        assert!(report.duplication.is_none());
+    }
+
+    #[test]
+    fn duplication_is_gated_by_config() {
+        let report = Analyzer::new()
+            .configure(|c| {
+                c.analysis
+                    .get_or_insert_with(Default::default)
+                    .duplication_enabled = Some(true)
+            })
+            .source("a.ts", NESTED)
+            .source("b.ts", NESTED)
+            .run()
+            .unwrap();
+
+        let duplication = report.duplication.as_ref().unwrap();
+        assert!(!duplication.clone_groups.is_empty());
+    }
+
+    #[test]
+    fn module_graph_is_gated_by_config() {
+        let sources = [
+            ("src/app.ts", "import { util } from \"./util\";\nutil();\n"),
+            ("src/util.ts", "export function util() {}\n"),
+        ];
+        let without = sources
+            .iter()
+            .fold(Analyzer::new(), |a, (path, src)| a.source(*path, *src))
+            .run()
+            .unwrap();
+        assert!(without.modules.is_none());
+        assert!(without.files.iter().all(|f| f.coupling.is_none()));
+
+        let report = sources
+            .iter()
+            .fold(Analyzer::new(), |a, (path, src)| a.source(*path, *src))
+            .configure(|c| c.modules.get_or_insert_with(Default::default).enabled = Some(true))
+            .run()
+            .unwrap();
+        let graph = report.modules.as_ref().unwrap();
+        assert_eq!(graph.edges.len(), 1);
+        let util = report
+            .files
+            .iter()
+            .find(|f| f.path.ends_with("util.ts"))
+            .unwrap();
+        assert_eq!(util.coupling.unwrap().fan_in, 1);
+    }
+
+    #[test]
+    fn import_cycles_are_violations_of_their_first_file() {
+        let report = Analyzer::new()
+            .source(
+                "src/b.ts",
+                "import { a } from \"./a\";\nexport const b = () => a;\n",
+            )
+            .source(
+                "src/a.ts",
+                "\nimport { b } from \"./b\";\nexport const a = () => b;\n",
+            )
+            .configure(|c| c.modules.get_or_insert_with(Default::default).enabled = Some(true))
+            .run()
+            .unwrap();
+        assert_eq!(report.files[0].cycles.len(), 1);
+        assert!(report.files[1].cycles.is_empty());
+
+        let cycles: Vec<_> = report
+            .violations
+            .iter()
+            .filter(|v| v.violation.rule_id == "complexity-guard/import-cycle")
+            .collect();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].path, PathBuf::from("src/a.ts"));
+        assert_eq!(cycles[0].name, FILE_UNIT_NAME);
+        assert_eq!(
+            cycles[0].violation.message,
+            "Import cycle src/a.ts:2 -> src/b.ts:1 -> src/a.ts"
+        );
+        assert_eq!(report.exit_code(None), ExitCode::ErrorsFound);
+    }
+
+    #[test]
+    fn invalid_config_is_an_error() {
+        let result = Analyzer::new()
+            .configure(|c| {
+                c.analysis
+                    .get_or_insert_with(Default::default)
+                    .on_syntax_error = Some("explode".to_string())
+            })
+            .run();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn empty_analyzer_reports_nothing() {
+        let report = Analyzer::new().run().unwrap();
+        assert!(report.files.is_empty());
+        assert_eq!(report.health_score(), 100.0);
+        assert_eq!(report.exit_code(None), ExitCode::Success);
+        assert!(report
+            .render_json()
+            .unwrap()
+            .contains("\"files_analyzed\": 0"));
+    }
+}
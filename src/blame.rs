@@ -0,0 +1,368 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::analyzer::ReportViolation;
+use crate::git::{self, BlameLines};
+use crate::metrics::MODULE_UNIT_NAME;
+use crate::output::violations::Severity;
+use crate::types::FileAnalysisResult;
+
+/// Who last changed a violating function, module or class.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Blame {
+    /// Author of the most lines of the unit
+    pub author: String,
+    /// Lines of the unit last changed by `author`
+    pub author_lines: u32,
+    /// Lines of the unit that were blamed: changed within the `since` window
+    /// and committed
+    pub lines: u32,
+    /// Most recent commit that changed the unit
+    pub commit: String,
+    /// Author date of `commit` as a Unix timestamp
+    pub date: u64,
+}
+
+/// Blame of one unit with violations.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct UnitBlame {
+    pub path: String,
+    /// Qualified function name, `<module>`, or class name
+    pub name: String,
+    pub start_line: usize,
+    pub errors: u32,
+    pub warnings: u32,
+    pub blame: Blame,
+}
+
+/// Violations grouped by the dominant author of their unit.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AuthorSummary {
+    pub author: String,
+    /// Units with violations where this author wrote the most lines
+    pub units: u32,
+    pub errors: u32,
+    pub warnings: u32,
+}
+
+/// Blame for every unit with violations, plus the per-author totals.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BlameReport {
+    /// Only commits after this were blamed, when set
+    pub since: Option<String>,
+    /// Units in report order; units without blamed lines are left out
+    pub units: Vec<UnitBlame>,
+    /// Most errors first, then most warnings
+    pub authors: Vec<AuthorSummary>,
+}
+
+impl BlameReport {
+    /// The blame of the unit `name` starting at `start_line` in the file at
+    /// `path`, if it has one.
+    pub fn find(&self, path: &str, name: &str, start_line: usize) -> Option<&Blame> {
+        self.units
+            .iter()
+            .find(|u| u.path == path && u.name == name && u.start_line == start_line)
+            .map(|u| &u.blame)
+    }
+}
+
+/// A unit with violations and its line range.
+struct Unit {
+    name: String,
+    start_line: usize,
+    end_line: usize,
+    errors: u32,
+    warnings: u32,
+}
+
+/// Run `git blame` over the line range of every unit with violations.
+///
+/// Each file is blamed once, for all of its violating units. Lines changed
+/// before `since` or not committed yet are not attributed; a unit made only
+/// of such lines gets no blame. Files that git does not track are skipped.
+pub fn blame_violations(
+    files: &[FileAnalysisResult],
+    violations: &[ReportViolation],
+    dir: &Path,
+    since: Option<&str>,
+) -> anyhow::Result<BlameReport> {
+    let root = git::repo_root(dir)?;
+
+    // Violating units per file, in report order
+    let mut by_file: Vec<(&FileAnalysisResult, Vec<Unit>)> = Vec::new();
+    for v in violations {
+        let Some(file) = files.iter().find(|f| f.path == v.path) else {
+            continue;
+        };
+        if by_file.last().map_or(true, |(f, _)| f.path != v.path) {
+            by_file.push((file, Vec::new()));
+        }
+        // Repeated names are told apart by where the unit starts
+        let Some((start_line, end_line)) = unit_range(file, &v.name, v.violation.line) else {
+            continue;
+        };
+        let units = &mut by_file.last_mut().expect("just pushed").1;
+        let unit = match units
+            .iter_mut()
+            .position(|u| u.name == v.name && u.start_line == start_line)
+        {
+            Some(i) => &mut units[i],
+            None => {
+                units.push(Unit {
+                    name: v.name.clone(),
+                    start_line,
+                    end_line,
+                    errors: 0,
+                    warnings: 0,
+                });
+                units.last_mut().expect("just pushed")
+            }
+        };
+        match v.violation.severity {
+            Severity::Error => unit.errors += 1,
+            Severity::Warning => unit.warnings += 1,
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    let per_file = by_file.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let per_file = by_file.iter();
+    let blamed: Vec<Vec<UnitBlame>> = per_file
+        .map(|(file, units)| {
+            let Some(path) = git::repo_path(&root, &file.path) else {
+                return Vec::new();
+            };
+            let ranges: Vec<_> = units.iter().map(|u| (u.start_line, u.end_line)).collect();
+            let Ok(lines) = git::blame(&root, &path, &ranges, since) else {
+                return Vec::new();
+            };
+            let display = file.path.to_string_lossy().to_string();
+            units
+                .iter()
+                .filter_map(|unit| {
+                    Some(UnitBlame {
+                        path: display.clone(),
+                        name: unit.name.clone(),
+                        start_line: unit.start_line,
+                        errors: unit.errors,
+                        warnings: unit.warnings,
+                        blame: attribute(&lines, unit.start_line, unit.end_line)?,
+                    })
+                })
+                .collect()
+        })
+        .collect();
+    let units: Vec<UnitBlame> = blamed.into_iter().flatten().collect();
+
+    let mut authors: Vec<AuthorSummary> = Vec::new();
+    for unit in &units {
+        let summary = match authors
+            .iter_mut()
+            .position(|a| a.author == unit.blame.author)
+        {
+            Some(i) => &mut authors[i],
+            None => {
+                authors.push(AuthorSummary {
+                    author: unit.blame.author.clone(),
+                    units: 0,
+                    errors: 0,
+                    warnings: 0,
+                });
+                authors.last_mut().expect("just pushed")
+            }
+        };
+        summary.units += 1;
+        summary.errors += unit.errors;
+        summary.warnings += unit.warnings;
+    }
+    authors.sort_by(|a, b| {
+        b.errors
+            .cmp(&a.errors)
+            .then(b.warnings.cmp(&a.warnings))
+            .then(a.author.cmp(&b.author))
+    });
+
+    Ok(BlameReport {
+        since: since.map(str::to_string),
+        units,
+        authors,
+    })
+}
+
+/// Line range of the function or class called `name` that starts at
+/// `start_line` in `file`, or of the module.
+fn unit_range(file: &FileAnalysisResult, name: &str, start_line: usize) -> Option<(usize, usize)> {
+    if name == MODULE_UNIT_NAME {
+        return file.module.as_ref().map(|m| (m.start_line, m.end_line));
+    }
+    file.functions
+        .iter()
+        .find(|f| f.qualified_name == name && f.start_line == start_line)
+        .map(|f| (f.start_line, f.end_line))
+        .or_else(|| {
+            file.classes
+                .iter()
+                .find(|c| c.name == name && c.start_line == start_line)
+                .map(|c| (c.start_line, c.end_line))
+        })
+}
+
+/// The dominant author and most recent commit of the blamed lines `start..=end`.
+/// Ties between authors go to the one with the more recent change.
+fn attribute(lines: &BlameLines, start: usize, end: usize) -> Option<Blame> {
+    let mut counts: HashMap<&str, (u32, u64)> = HashMap::new();
+    let mut latest: Option<&git::BlameCommit> = None;
+    let mut total = 0;
+    for commit in (start..=end).filter_map(|line| lines.commit_at(line)) {
+        if commit.boundary || commit.is_uncommitted() {
+            continue;
+        }
+        total += 1;
+        let entry = counts.entry(&commit.author).or_default();
+        entry.0 += 1;
+        entry.1 = entry.1.max(commit.timestamp);
+        if latest.map_or(true, |l| commit.timestamp > l.timestamp) {
+            latest = Some(commit);
+        }
+    }
+    let (author, (author_lines, _)) = counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)))?;
+    let latest = latest?;
+    Some(Blame {
+        author: author.to_string(),
+        author_lines,
+        lines: total,
+        commit: latest.hash.clone(),
+        date: latest.timestamp,
+    })
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_repo::git_as;
+    use crate::Analyzer;
+
+    fn commit(dir: &Path, author: &str, date: &str, source: &str) {
+        std::fs::write(dir.join("calc.ts"), source).unwrap();
+        git_as(dir, author, date, &["add", "."]);
+        git_as(dir, author, date, &["commit", "-q", "-m", "change"]);
+    }
+
+    const NESTED: &str = "function nested(a: number, b: number, c: number, d: number) {
+  if (a) {
+    if (b) {
+      if (c) {
+        if (d) {
+          return 1;
+        }
+      }
+    }
+  }
+  return 0;
+}
+";
+
+    fn analyze(dir: &Path) -> crate::Report {
+        let path = dir.join("calc.ts");
+        let source = std::fs::read_to_string(&path).unwrap();
+        Analyzer::new().source(path, source).run().unwrap()
+    }
+
+    #[test]
+    fn attributes_violations_to_dominant_author() {
+        let dir = tempfile::tempdir().unwrap();
+        git_as(
+            dir.path(),
+            "Ada",
+            "2020-01-01T00:00:00+00:00",
+            &["init", "-q"],
+        );
+        commit(dir.path(), "Ada", "2020-01-01T00:00:00+00:00", NESTED);
+        commit(
+            dir.path(),
+            "Grace",
+            "2026-10-01T00:00:00+00:00",
+            &NESTED.replace("return 1;", "return 2;"),
+        );
+
+        let report = analyze(dir.path());
+        assert!(!report.violations.is_empty());
+        let blame = blame_violations(&report.files, &report.violations, dir.path(), None).unwrap();
+        let unit = &blame.units[0];
+        assert_eq!(unit.name, "nested");
+        assert_eq!(unit.blame.author, "Ada");
+        assert_eq!(unit.blame.lines, 12);
+        assert_eq!(unit.blame.author_lines, 11);
+        // The last change is Grace's
+        assert_eq!(unit.blame.date, 1_790_812_800);
+        assert_eq!(blame.authors[0].author, "Ada");
+        assert_eq!(blame.authors[0].units, 1);
+        assert!(blame.find(&unit.path, "nested", 1).is_some());
+
+        // Within the window only Grace's line is blamed
+        let blame = blame_violations(
+            &report.files,
+            &report.violations,
+            dir.path(),
+            Some("2026-01-01"),
+        )
+        .unwrap();
+        let unit = &blame.units[0];
+        assert_eq!(unit.blame.author, "Grace");
+        assert_eq!((unit.blame.lines, unit.blame.author_lines), (1, 1));
+        assert_eq!(blame.since.as_deref(), Some("2026-01-01"));
+    }
+
+    #[test]
+    fn tells_apart_units_with_the_same_name() {
+        let dir = tempfile::tempdir().unwrap();
+        git_as(
+            dir.path(),
+            "Ada",
+            "2020-01-01T00:00:00+00:00",
+            &["init", "-q"],
+        );
+        let simple = "function nested() {\n  return 0;\n}\n";
+        commit(dir.path(), "Ada", "2020-01-01T00:00:00+00:00", simple);
+        commit(
+            dir.path(),
+            "Grace",
+            "2026-10-01T00:00:00+00:00",
+            &format!("{simple}{NESTED}"),
+        );
+
+        let report = analyze(dir.path());
+        let blame = blame_violations(&report.files, &report.violations, dir.path(), None).unwrap();
+        assert_eq!(blame.units.len(), 1);
+        let unit = &blame.units[0];
+        assert_eq!((unit.name.as_str(), unit.start_line), ("nested", 4));
+        assert_eq!(unit.blame.author, "Grace");
+        assert_eq!(unit.blame.lines, 12);
+        assert!(blame.find(&unit.path, "nested", 1).is_none());
+    }
+
+    #[test]
+    fn skips_untracked_files() {
+        let dir = tempfile::tempdir().unwrap();
+        git_as(
+            dir.path(),
+            "Ada",
+            "2020-01-01T00:00:00+00:00",
+            &["init", "-q"],
+        );
+        std::fs::write(dir.path().join("calc.ts"), NESTED).unwrap();
+        let report = analyze(dir.path());
+        let blame = blame_violations(&report.files, &report.violations, dir.path(), None).unwrap();
+        assert!(blame.units.is_empty());
+        assert!(blame.authors.is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
 /// CLI arguments for complexityguard.
@@ -9,6 +9,9 @@ use std::path::PathBuf;
 #[command(about = "Analyze code complexity for TypeScript/JavaScript files")]
 #[command(version)]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Paths to analyze (files or directories)
     pub paths: Vec<PathBuf>,
 
@@ -18,31 +21,51 @@ pub struct Args {
     pub init: bool,
 
     // --- Output ---
-    /// Output format [console, json, sarif, html]
-    #[arg(short = 'f', long)]
+    /// Output format [console, json, sarif, html; compare, --staged: console, markdown, json; trend, history: console, json]
+    #[arg(short = 'f', long, global = true)]
     pub format: Option<String>,
 
     /// Write report to file
-    #[arg(short = 'o', long = "output")]
+    #[arg(short = 'o', long = "output", global = true)]
     pub output_file: Option<String>,
 
     /// Force color output
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub color: bool,
 
     /// Disable color output
-    #[arg(long = "no-color")]
+    #[arg(long = "no-color", global = true)]
     pub no_color: bool,
 
     /// Suppress non-error output
-    #[arg(short = 'q', long)]
+    #[arg(short = 'q', long, global = true)]
     pub quiet: bool,
 
     /// Show detailed output
-    #[arg(short = 'v', long)]
+    #[arg(short = 'v', long, global = true)]
     pub verbose: bool,
 
+    /// Include normalized token streams in JSON output (for `merge`)
+    #[arg(long = "export-tokens")]
+    pub export_tokens: bool,
+
+    /// Show health and violation totals per directory in console output
+    #[arg(long = "by-directory")]
+    pub by_directory: bool,
+
+    /// Directory levels below the root to show (default: 2); implies --by-directory
+    #[arg(long = "directory-depth", value_name = "DEPTH")]
+    pub directory_depth: Option<usize>,
+
     // --- Analysis ---
+    /// Analyze the paths as of this git commit, tag or branch instead of the working tree
+    #[arg(long, value_name = "COMMIT")]
+    pub rev: Option<String>,
+
+    /// Check the staged versions of changed files against HEAD, failing only on new or worsened violations
+    #[arg(long, conflicts_with = "rev")]
+    pub staged: bool,
+
     /// Comma-separated metrics to enable
     #[arg(long)]
     pub metrics: Option<String>,
@@ -59,6 +82,94 @@ pub struct Args {
     #[arg(long)]
     pub threads: Option<u32>,
 
+    /// Reuse results for unchanged files from the analysis cache
+    #[arg(long)]
+    pub cache: bool,
+
+    /// Disable the analysis cache
+    #[arg(long = "no-cache")]
+    pub no_cache: bool,
+
+    /// Cache directory (default: .complexityguard/cache); implies --cache
+    #[arg(long = "cache-dir")]
+    pub cache_dir: Option<String>,
+
+    /// Delete the analysis cache and exit
+    #[arg(long = "clear-cache")]
+    pub clear_cache: bool,
+
+    /// Append a summary of this run to the history file
+    #[arg(long)]
+    pub history: bool,
+
+    /// Do not record this run in the history file
+    #[arg(long = "no-history")]
+    pub no_history: bool,
+
+    /// History file (default: .complexityguard/history.jsonl); implies --history
+    #[arg(long = "history-file", global = true)]
+    pub history_file: Option<String>,
+
+    /// Rank files and functions by git change frequency times complexity
+    #[arg(long)]
+    pub hotspots: bool,
+
+    /// Skip the git hotspot ranking
+    #[arg(long = "no-hotspots")]
+    pub no_hotspots: bool,
+
+    /// Git history window for hotspots (default: "1 year ago"); implies --hotspots
+    #[arg(long = "hotspots-since")]
+    pub hotspots_since: Option<String>,
+
+    /// Attribute violations to the author who wrote most of each unit (git blame)
+    #[arg(long)]
+    pub blame: bool,
+
+    /// Skip git blame attribution
+    #[arg(long = "no-blame")]
+    pub no_blame: bool,
+
+    /// Only blame commits after this date; implies --blame
+    #[arg(long = "blame-since")]
+    pub blame_since: Option<String>,
+
+    /// Total functions, violations and health per CODEOWNERS owner
+    #[arg(long)]
+    pub owners: bool,
+
+    /// Skip CODEOWNERS attribution
+    #[arg(long = "no-owners")]
+    pub no_owners: bool,
+
+    /// Only analyze files this CODEOWNERS owner owns, e.g. @org/team; implies --owners
+    #[arg(long, value_name = "OWNER")]
+    pub owner: Option<String>,
+
+    /// Total functions, violations and health per npm, yarn or pnpm workspace package
+    #[arg(long)]
+    pub packages: bool,
+
+    /// Skip grouping by workspace package
+    #[arg(long = "no-packages")]
+    pub no_packages: bool,
+
+    /// Only analyze files of this workspace package, by name or path; implies --packages
+    #[arg(long, value_name = "NAME")]
+    pub package: Option<String>,
+
+    /// Resolve imports between files and check fan-in, fan-out and instability
+    #[arg(long)]
+    pub modules: bool,
+
+    /// Skip the module graph
+    #[arg(long = "no-modules")]
+    pub no_modules: bool,
+
+    /// Write the module graph as Graphviz DOT (.dot, .gv) or JSON; implies --modules
+    #[arg(long = "module-graph", value_name = "FILE")]
+    pub module_graph: Option<String>,
+
     // --- Files ---
     /// Include files matching pattern (repeatable)
     #[arg(long)]
@@ -68,9 +179,30 @@ pub struct Args {
     #[arg(long)]
     pub exclude: Vec<String>,
 
+    /// Analyze the files of this tsconfig.json and the projects it references
+    /// instead of walking directories; paths then limit the files analyzed
+    #[arg(long, value_name = "TSCONFIG", conflicts_with_all = ["rev", "staged"])]
+    pub project: Option<PathBuf>,
+
+    /// Skip files with more than N lines (default: 10000)
+    #[arg(long = "max-file-lines")]
+    pub max_file_lines: Option<usize>,
+
+    /// Skip functions with more than N lines (default: 5000)
+    #[arg(long = "max-function-lines")]
+    pub max_function_lines: Option<u32>,
+
+    /// Analyze files that look generated or minified instead of skipping them
+    #[arg(long = "include-generated")]
+    pub include_generated: bool,
+
+    /// Handling of files with syntax errors: analyze, skip, fail (default: analyze)
+    #[arg(long = "on-syntax-error")]
+    pub on_syntax_error: Option<String>,
+
     // --- Thresholds ---
     /// Exit non-zero on: warning, error, none
-    #[arg(long = "fail-on")]
+    #[arg(long = "fail-on", global = true)]
     pub fail_on: Option<String>,
 
     /// Exit non-zero if health score below N
@@ -79,7 +211,7 @@ pub struct Args {
 
     // --- Config ---
     /// Use specific config file
-    #[arg(short = 'c', long)]
+    #[arg(short = 'c', long, global = true)]
     pub config: Option<String>,
 
     // --- Baseline ---
@@ -88,6 +220,35 @@ pub struct Args {
     pub baseline: Option<String>,
 }
 
+/// Commands that work on saved reports instead of analyzing source files.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Combine JSON reports from sharded runs into one report
+    Merge {
+        /// JSON reports to merge; a file in several reports is taken from the first
+        #[arg(required = true)]
+        reports: Vec<PathBuf>,
+    },
+    /// Show sparklines of health, violations and directory scores over recorded runs
+    Trend {
+        /// Only show the last N runs
+        #[arg(long)]
+        last: Option<usize>,
+    },
+    /// Show how one function's metrics changed across the git history of its file
+    History {
+        /// The file and function, as `<path>::<function>`
+        target: String,
+    },
+    /// Compare two JSON reports and list regressions and improvements
+    Compare {
+        /// Report of the earlier run
+        old: PathBuf,
+        /// Report of the later run
+        new: PathBuf,
+    },
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -192,6 +353,227 @@ mod tests {
         assert_eq!(args.exclude, vec!["**/*.test.ts"]);
     }
 
+    #[test]
+    fn test_parse_size_limits() {
+        let args = Args::try_parse_from([
+            "complexityguard",
+            "--max-file-lines",
+            "2000",
+            "--max-function-lines",
+            "300",
+            "--include-generated",
+        ])
+        .unwrap();
+        assert_eq!(args.max_file_lines, Some(2000));
+        assert_eq!(args.max_function_lines, Some(300));
+        assert!(args.include_generated);
+    }
+
+    #[test]
+    fn test_parse_on_syntax_error() {
+        let args = Args::try_parse_from(["complexityguard", "--on-syntax-error", "skip"]).unwrap();
+        assert_eq!(args.on_syntax_error, Some("skip".to_string()));
+    }
+
+    #[test]
+    fn test_parse_cache_flags() {
+        let args = Args::try_parse_from([
+            "complexityguard",
+            "--cache",
+            "--cache-dir",
+            "/tmp/cg",
+            "--clear-cache",
+        ])
+        .unwrap();
+        assert!(args.cache);
+        assert!(!args.no_cache);
+        assert_eq!(args.cache_dir, Some("/tmp/cg".to_string()));
+        assert!(args.clear_cache);
+    }
+
+    #[test]
+    fn test_parse_history_flags() {
+        let args = Args::try_parse_from(["complexityguard", "--history", "src/"]).unwrap();
+        assert!(args.history);
+        assert!(!args.no_history);
+
+        let args = Args::try_parse_from([
+            "complexityguard",
+            "trend",
+            "--last",
+            "10",
+            "--history-file",
+            "h.jsonl",
+        ])
+        .unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Trend { last: Some(10) })
+        ));
+        assert_eq!(args.history_file, Some("h.jsonl".to_string()));
+    }
+
+    #[test]
+    fn test_parse_hotspots_flags() {
+        let args = Args::try_parse_from([
+            "complexityguard",
+            "--hotspots",
+            "--hotspots-since",
+            "6 months ago",
+        ])
+        .unwrap();
+        assert!(args.hotspots);
+        assert!(!args.no_hotspots);
+        assert_eq!(args.hotspots_since, Some("6 months ago".to_string()));
+    }
+
+    #[test]
+    fn test_parse_blame_flags() {
+        let args =
+            Args::try_parse_from(["complexityguard", "--blame-since", "3 months ago"]).unwrap();
+        assert!(!args.blame);
+        assert_eq!(args.blame_since, Some("3 months ago".to_string()));
+        let args = Args::try_parse_from(["complexityguard", "--no-blame"]).unwrap();
+        assert!(args.no_blame);
+    }
+
+    #[test]
+    fn test_parse_owner_flags() {
+        let args = Args::try_parse_from(["complexityguard", "--owner", "@org/web", "src"]).unwrap();
+        assert!(!args.owners);
+        assert_eq!(args.owner, Some("@org/web".to_string()));
+        let args = Args::try_parse_from(["complexityguard", "--no-owners"]).unwrap();
+        assert!(args.no_owners);
+    }
+
+    #[test]
+    fn test_parse_package_flags() {
+        let args = Args::try_parse_from(["complexityguard", "--package", "@acme/web"]).unwrap();
+        assert!(!args.packages);
+        assert_eq!(args.package, Some("@acme/web".to_string()));
+        let args = Args::try_parse_from(["complexityguard", "--packages"]).unwrap();
+        assert!(args.packages);
+        let args = Args::try_parse_from(["complexityguard", "--no-packages"]).unwrap();
+        assert!(args.no_packages);
+    }
+
+    #[test]
+    fn test_parse_module_flags() {
+        let args = Args::try_parse_from(["complexityguard", "--module-graph", "deps.dot"]).unwrap();
+        assert!(!args.modules);
+        assert_eq!(args.module_graph, Some("deps.dot".to_string()));
+        let args = Args::try_parse_from(["complexityguard", "--modules"]).unwrap();
+        assert!(args.modules);
+        let args = Args::try_parse_from(["complexityguard", "--no-modules"]).unwrap();
+        assert!(args.no_modules);
+    }
+
+    #[test]
+    fn test_parse_project_flag() {
+        let args =
+            Args::try_parse_from(["complexityguard", "--project", "tsconfig.json", "src"]).unwrap();
+        assert_eq!(args.project, Some(PathBuf::from("tsconfig.json")));
+        assert_eq!(args.paths, vec![PathBuf::from("src")]);
+        assert!(Args::try_parse_from([
+            "complexityguard",
+            "--project",
+            "tsconfig.json",
+            "--rev",
+            "HEAD"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_rev_flag() {
+        let args = Args::try_parse_from(["complexityguard", "--rev", "v1.2.0", "src"]).unwrap();
+        assert_eq!(args.rev, Some("v1.2.0".to_string()));
+        assert_eq!(args.paths, vec![PathBuf::from("src")]);
+    }
+
+    #[test]
+    fn test_parse_staged_flag() {
+        let args = Args::try_parse_from(["complexityguard", "--staged", "src"]).unwrap();
+        assert!(args.staged);
+        assert_eq!(args.paths, vec![PathBuf::from("src")]);
+        assert!(Args::try_parse_from(["complexityguard", "--staged", "--rev", "HEAD"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_history_command() {
+        let args = Args::try_parse_from([
+            "complexityguard",
+            "history",
+            "src/orders.ts::OrderService.checkout",
+            "--format",
+            "json",
+        ])
+        .unwrap();
+        match args.command {
+            Some(Command::History { target }) => {
+                assert_eq!(target, "src/orders.ts::OrderService.checkout")
+            }
+            other => panic!("expected history command, got {:?}", other),
+        }
+        assert_eq!(args.format, Some("json".to_string()));
+    }
+
+    #[test]
+    fn test_parse_compare_command() {
+        let args = Args::try_parse_from([
+            "complexityguard",
+            "compare",
+            "old.json",
+            "new.json",
+            "--format",
+            "markdown",
+        ])
+        .unwrap();
+        match args.command {
+            Some(Command::Compare { old, new }) => {
+                assert_eq!(old, PathBuf::from("old.json"));
+                assert_eq!(new, PathBuf::from("new.json"));
+            }
+            other => panic!("expected compare, got {:?}", other),
+        }
+        assert_eq!(args.format, Some("markdown".to_string()));
+        assert!(Args::try_parse_from(["complexityguard", "compare", "old.json"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_merge_command() {
+        let args = Args::try_parse_from([
+            "complexityguard",
+            "merge",
+            "a.json",
+            "b.json",
+            "--format",
+            "html",
+        ])
+        .unwrap();
+        match args.command {
+            Some(Command::Merge { reports }) => assert_eq!(reports.len(), 2),
+            other => panic!("expected merge, got {:?}", other),
+        }
+        assert_eq!(args.format, Some("html".to_string()));
+        assert!(Args::try_parse_from(["complexityguard", "merge"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_export_tokens() {
+        let args = Args::try_parse_from(["complexityguard", "--export-tokens"]).unwrap();
+        assert!(args.export_tokens);
+    }
+
+    #[test]
+    fn test_parse_directory_flags() {
+        let args = Args::try_parse_from(["complexityguard", "--by-directory"]).unwrap();
+        assert!(args.by_directory);
+        assert_eq!(args.directory_depth, None);
+        let args = Args::try_parse_from(["complexityguard", "--directory-depth", "3"]).unwrap();
+        assert_eq!(args.directory_depth, Some(3));
+    }
+
     #[test]
     fn test_parse_positional_paths() {
         let args = Args::try_parse_from(["complexityguard", "src/", "lib/"]).unwrap();
@@ -233,5 +615,6 @@ mod tests {
         assert!(args.fail_on.is_none());
         assert!(args.config.is_none());
         assert!(args.paths.is_empty());
+        assert!(args.command.is_none());
     }
 }
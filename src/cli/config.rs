@@ -1,3 +1,7 @@
+use std::collections::BTreeMap;
+
+use crate::types::SizeLimits;
+
 /// Top-level configuration structure matching the locked schema.
 ///
 /// All fields are optional to support partial configs and defaults.
@@ -11,6 +15,139 @@ pub struct Config {
     pub overrides: Option<Vec<OverrideConfig>>,
     /// Baseline health score for ratchet enforcement.
     pub baseline: Option<f64>,
+    pub history: Option<HistoryConfig>,
+    pub hotspots: Option<HotspotsConfig>,
+    pub blame: Option<BlameConfig>,
+    pub owners: Option<OwnersConfig>,
+    pub packages: Option<PackagesConfig>,
+    pub modules: Option<ModulesConfig>,
+}
+
+impl Config {
+    /// Overlay the values set in `other` (e.g. a loaded config file) on this config.
+    ///
+    /// `output`, `analysis`, `history`, `hotspots`, `blame`, `owners`, `packages` and `modules` are merged field by field; `files`, `weights`,
+    /// `overrides` and `baseline` are replaced as a whole when set in `other`.
+    pub fn overlay(&mut self, other: Config) {
+        if let Some(output) = other.output {
+            let base = self.output.get_or_insert_with(Default::default);
+            if let Some(fmt) = output.format {
+                base.format = Some(fmt);
+            }
+            if let Some(file) = output.file {
+                base.file = Some(file);
+            }
+            if let Some(v) = output.export_tokens {
+                base.export_tokens = Some(v);
+            }
+            if let Some(v) = output.by_directory {
+                base.by_directory = Some(v);
+            }
+            if let Some(depth) = output.directory_depth {
+                base.directory_depth = Some(depth);
+            }
+        }
+        if let Some(analysis) = other.analysis {
+            let base = self.analysis.get_or_insert_with(Default::default);
+            if let Some(metrics) = analysis.metrics {
+                base.metrics = Some(metrics);
+            }
+            if let Some(thresholds) = analysis.thresholds {
+                base.thresholds = Some(thresholds);
+            }
+            if let Some(v) = analysis.no_duplication {
+                base.no_duplication = Some(v);
+            }
+            if let Some(v) = analysis.duplication_enabled {
+                base.duplication_enabled = Some(v);
+            }
+            if let Some(t) = analysis.threads {
+                base.threads = Some(t);
+            }
+            if let Some(limits) = analysis.limits {
+                base.limits = Some(limits);
+            }
+            if let Some(policy) = analysis.on_syntax_error {
+                base.on_syntax_error = Some(policy);
+            }
+            if let Some(cache) = analysis.cache {
+                base.cache = Some(cache);
+            }
+        }
+        if let Some(history) = other.history {
+            let base = self.history.get_or_insert_with(Default::default);
+            if let Some(v) = history.enabled {
+                base.enabled = Some(v);
+            }
+            if let Some(file) = history.file {
+                base.file = Some(file);
+            }
+        }
+        if let Some(hotspots) = other.hotspots {
+            let base = self.hotspots.get_or_insert_with(Default::default);
+            if let Some(v) = hotspots.enabled {
+                base.enabled = Some(v);
+            }
+            if let Some(since) = hotspots.since {
+                base.since = Some(since);
+            }
+        }
+        if let Some(blame) = other.blame {
+            let base = self.blame.get_or_insert_with(Default::default);
+            if let Some(v) = blame.enabled {
+                base.enabled = Some(v);
+            }
+            if let Some(since) = blame.since {
+                base.since = Some(since);
+            }
+        }
+        if let Some(owners) = other.owners {
+            let base = self.owners.get_or_insert_with(Default::default);
+            if let Some(v) = owners.enabled {
+                base.enabled = Some(v);
+            }
+            if let Some(file) = owners.file {
+                base.file = Some(file);
+            }
+        }
+        if let Some(packages) = other.packages {
+            let base = self.packages.get_or_insert_with(Default::default);
+            if let Some(v) = packages.enabled {
+                base.enabled = Some(v);
+            }
+            if let Some(fail_on) = packages.fail_on {
+                base.fail_on
+                    .get_or_insert_with(Default::default)
+                    .extend(fail_on);
+            }
+        }
+        if let Some(modules) = other.modules {
+            let base = self.modules.get_or_insert_with(Default::default);
+            if let Some(v) = modules.enabled {
+                base.enabled = Some(v);
+            }
+            if let Some(graph) = modules.graph {
+                base.graph = Some(graph);
+            }
+            if let Some(allow_cycles) = modules.allow_cycles {
+                base.allow_cycles
+                    .get_or_insert_with(Default::default)
+                    .extend(allow_cycles);
+            }
+        }
+        if let Some(files) = other.files {
+            self.files = Some(files);
+        }
+        if let Some(weights) = other.weights {
+            self.weights = Some(weights);
+        }
+        if let Some(baseline) = other.baseline {
+            self.baseline = Some(baseline);
+        }
+        if let Some(overrides) = other.overrides {
+            self.overrides = Some(overrides);
+        }
+    }
 }
 
 /// Output format and destination configuration.
@@ -20,6 +157,12 @@ pub struct OutputConfig {
     pub format: Option<String>,
     /// Output file path
     pub file: Option<String>,
+    /// Include each file's normalized token stream in JSON output, for merging shards
+    pub export_tokens: Option<bool>,
+    /// Show per-directory totals in console output (default: false)
+    pub by_directory: Option<bool>,
+    /// Directory levels below the root in the console table (default: 2)
+    pub directory_depth: Option<usize>,
 }
 
 /// Analysis behavior configuration.
@@ -31,6 +174,102 @@ pub struct AnalysisConfig {
     pub no_duplication: Option<bool>,
     pub duplication_enabled: Option<bool>,
     pub threads: Option<u32>,
+    pub limits: Option<LimitsConfig>,
+    /// What to do with files that contain syntax errors: "analyze", "skip" or "fail"
+    pub on_syntax_error: Option<String>,
+    pub cache: Option<CacheConfig>,
+}
+
+/// On-disk analysis cache for unchanged files.
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+pub struct CacheConfig {
+    /// Reuse cached results (default: false)
+    pub enabled: Option<bool>,
+    /// Cache directory (default: ".complexityguard/cache")
+    pub dir: Option<String>,
+}
+
+/// Run history for trend reporting.
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+pub struct HistoryConfig {
+    /// Append a summary of each run to the history file (default: false)
+    pub enabled: Option<bool>,
+    /// History file (default: ".complexityguard/history.jsonl")
+    pub file: Option<String>,
+}
+
+/// Churn × complexity ranking from the local git history.
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+pub struct HotspotsConfig {
+    /// Rank files and functions by change frequency (default: false)
+    pub enabled: Option<bool>,
+    /// History window, in any form `git log --since` accepts (default: "1 year ago")
+    pub since: Option<String>,
+}
+
+/// Git blame attribution of violations.
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+pub struct BlameConfig {
+    /// Record who last changed each violating unit (default: false)
+    pub enabled: Option<bool>,
+    /// Only blame commits after this date, in any form `git blame --since` accepts
+    pub since: Option<String>,
+}
+
+/// Per-owner totals from CODEOWNERS.
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+pub struct OwnersConfig {
+    /// Attribute files to their CODEOWNERS owners (default: false)
+    pub enabled: Option<bool>,
+    /// CODEOWNERS file (default: found in .github/, the repository root or docs/)
+    pub file: Option<String>,
+}
+
+/// Per-package totals for npm, yarn and pnpm workspaces.
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+pub struct PackagesConfig {
+    /// Group results by workspace package (default: false)
+    pub enabled: Option<bool>,
+    /// `--fail-on` mode per package name or path, overriding the run's for that package
+    pub fail_on: Option<BTreeMap<String, String>>,
+}
+
+/// Import graph between the analyzed files.
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+pub struct ModulesConfig {
+    /// Resolve imports and check each file's fan-in, fan-out and instability (default: false)
+    pub enabled: Option<bool>,
+    /// Write the graph to this file: Graphviz DOT for `.dot` and `.gv`, JSON otherwise
+    pub graph: Option<String>,
+    /// Accepted import cycles, each as the paths of its files
+    pub allow_cycles: Option<Vec<Vec<String>>>,
+}
+
+/// Size guards and generated/minified file detection.
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+pub struct LimitsConfig {
+    /// Files with more lines are skipped entirely
+    pub max_file_lines: Option<usize>,
+    /// Functions with more lines are skipped
+    pub max_function_lines: Option<u32>,
+    /// Skip files that look generated or minified
+    pub skip_generated: Option<bool>,
+    /// Average line length above which a file counts as minified
+    pub max_average_line_length: Option<usize>,
+}
+
+impl LimitsConfig {
+    /// Applies the values set in this config on top of `base`.
+    pub fn apply_to(&self, base: &SizeLimits) -> SizeLimits {
+        SizeLimits {
+            max_file_lines: self.max_file_lines.unwrap_or(base.max_file_lines),
+            max_function_lines: self.max_function_lines.unwrap_or(base.max_function_lines),
+            skip_generated: self.skip_generated.unwrap_or(base.skip_generated),
+            max_average_line_length: self
+                .max_average_line_length
+                .unwrap_or(base.max_average_line_length),
+        }
+    }
 }
 
 /// Thresholds organized by metric type.
@@ -47,6 +286,14 @@ pub struct ThresholdsConfig {
     pub params_count: Option<ThresholdPair>,
     pub file_length: Option<ThresholdPair>,
     pub export_count: Option<ThresholdPair>,
+    pub method_count: Option<ThresholdPair>,
+    pub wmc: Option<ThresholdPair>,
+    pub field_count: Option<ThresholdPair>,
+    pub lcom: Option<ThresholdPair>,
+    pub fan_in: Option<ThresholdPair>,
+    pub fan_out: Option<ThresholdPair>,
+    /// Fraction (0-1), as reported; instability has no thresholds by default
+    pub instability: Option<RatioThresholdPair>,
     pub duplication: Option<DuplicationThresholds>,
 }
 
@@ -59,6 +306,13 @@ pub struct ThresholdPair {
     pub error: Option<u32>,
 }
 
+/// Warning and error threshold pair for a metric between 0 and 1.
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+pub struct RatioThresholdPair {
+    pub warning: Option<f64>,
+    pub error: Option<f64>,
+}
+
 /// Duplication percentage thresholds (floating-point).
 #[derive(Debug, Default, Clone, serde::Deserialize)]
 pub struct DuplicationThresholds {
@@ -73,6 +327,9 @@ pub struct DuplicationThresholds {
 pub struct FilesConfig {
     pub include: Option<Vec<String>>,
     pub exclude: Option<Vec<String>>,
+    /// tsconfig.json whose files (with `extends` and `references`) are analyzed
+    /// instead of walking the input paths
+    pub project: Option<String>,
 }
 
 /// Weights for composite score calculation.
@@ -104,6 +361,9 @@ pub struct ResolvedConfig {
     pub color: Option<bool>,
     pub quiet: bool,
     pub verbose: bool,
+    pub export_tokens: bool,
+    pub by_directory: bool,
+    pub directory_depth: usize,
     // Analysis
     pub metrics: Vec<String>,
     // Thresholds (warning and error levels per metric)
@@ -125,8 +385,47 @@ pub struct ResolvedConfig {
     pub line_count_error: u32,
     pub params_count_warning: u32,
     pub params_count_error: u32,
+    // Class thresholds
+    pub method_count_warning: u32,
+    pub method_count_error: u32,
+    pub wmc_warning: u32,
+    pub wmc_error: u32,
+    pub field_count_warning: u32,
+    pub field_count_error: u32,
+    pub lcom_warning: u32,
+    pub lcom_error: u32,
+    // Module coupling thresholds
+    pub fan_in_warning: u32,
+    pub fan_in_error: u32,
+    pub fan_out_warning: u32,
+    pub fan_out_error: u32,
+    /// Instability (0-1) of files with dependents; `None` never triggers
+    pub instability_warning: Option<f64>,
+    pub instability_error: Option<f64>,
     // Threads
     pub threads: u32,
+    // Cache
+    pub cache_enabled: bool,
+    pub cache_dir: String,
+    // History
+    pub history_enabled: bool,
+    pub history_file: String,
+    // Hotspots
+    pub hotspots_enabled: bool,
+    pub hotspots_since: String,
+    // Blame
+    pub blame_enabled: bool,
+    pub blame_since: Option<String>,
+    // Owners
+    pub owners_enabled: bool,
+    pub owners_file: Option<String>,
+    // Packages
+    pub packages_enabled: bool,
+    pub packages_fail_on: BTreeMap<String, String>,
+    // Modules
+    pub modules_enabled: bool,
+    pub modules_graph: Option<String>,
+    pub modules_allow_cycles: Vec<Vec<String>>,
 }
 
 impl Default for ResolvedConfig {
@@ -137,6 +436,9 @@ impl Default for ResolvedConfig {
             color: None,
             quiet: false,
             verbose: false,
+            export_tokens: false,
+            by_directory: false,
+            directory_depth: crate::directories::DEFAULT_DEPTH,
             metrics: vec![
                 "cyclomatic".to_string(),
                 "cognitive".to_string(),
@@ -164,7 +466,36 @@ impl Default for ResolvedConfig {
             line_count_error: 50,
             params_count_warning: 3,
             params_count_error: 6,
+            method_count_warning: 20,
+            method_count_error: 40,
+            wmc_warning: 50,
+            wmc_error: 100,
+            field_count_warning: 15,
+            field_count_error: 30,
+            lcom_warning: 3,
+            lcom_error: 5,
+            fan_in_warning: 20,
+            fan_in_error: 40,
+            fan_out_warning: 15,
+            fan_out_error: 30,
+            instability_warning: None,
+            instability_error: None,
             threads: num_cpus(),
+            cache_enabled: false,
+            cache_dir: crate::pipeline::cache::DEFAULT_CACHE_DIR.to_string(),
+            history_enabled: false,
+            history_file: crate::history::DEFAULT_HISTORY_FILE.to_string(),
+            hotspots_enabled: false,
+            hotspots_since: crate::hotspots::DEFAULT_SINCE.to_string(),
+            blame_enabled: false,
+            blame_since: None,
+            owners_enabled: false,
+            owners_file: None,
+            packages_enabled: false,
+            packages_fail_on: BTreeMap::new(),
+            modules_enabled: false,
+            modules_graph: None,
+            modules_allow_cycles: Vec::new(),
         }
     }
 }
@@ -187,6 +518,72 @@ pub fn resolve_config(config: &Config) -> ResolvedConfig {
         if let Some(file) = &output.file {
             resolved.output_file = Some(file.clone());
         }
+        if let Some(v) = output.export_tokens {
+            resolved.export_tokens = v;
+        }
+        if let Some(v) = output.by_directory {
+            resolved.by_directory = v;
+        }
+        if let Some(depth) = output.directory_depth {
+            resolved.directory_depth = depth;
+        }
+    }
+
+    if let Some(history) = &config.history {
+        if let Some(enabled) = history.enabled {
+            resolved.history_enabled = enabled;
+        }
+        if let Some(file) = &history.file {
+            resolved.history_file = file.clone();
+        }
+    }
+
+    if let Some(hotspots) = &config.hotspots {
+        if let Some(enabled) = hotspots.enabled {
+            resolved.hotspots_enabled = enabled;
+        }
+        if let Some(since) = &hotspots.since {
+            resolved.hotspots_since = since.clone();
+        }
+    }
+
+    if let Some(blame) = &config.blame {
+        if let Some(enabled) = blame.enabled {
+            resolved.blame_enabled = enabled;
+        }
+        if let Some(since) = &blame.since {
+            resolved.blame_since = Some(since.clone());
+        }
+    }
+
+    if let Some(owners) = &config.owners {
+        if let Some(enabled) = owners.enabled {
+            resolved.owners_enabled = enabled;
+        }
+        if let Some(file) = &owners.file {
+            resolved.owners_file = Some(file.clone());
+        }
+    }
+
+    if let Some(modules) = &config.modules {
+        if let Some(enabled) = modules.enabled {
+            resolved.modules_enabled = enabled;
+        }
+        if let Some(graph) = &modules.graph {
+            resolved.modules_graph = Some(graph.clone());
+        }
+        if let Some(allow_cycles) = &modules.allow_cycles {
+            resolved.modules_allow_cycles = allow_cycles.clone();
+        }
+    }
+
+    if let Some(packages) = &config.packages {
+        if let Some(enabled) = packages.enabled {
+            resolved.packages_enabled = enabled;
+        }
+        if let Some(fail_on) = &packages.fail_on {
+            resolved.packages_fail_on = fail_on.clone();
+        }
     }
 
     if let Some(analysis) = &config.analysis {
@@ -196,6 +593,14 @@ pub fn resolve_config(config: &Config) -> ResolvedConfig {
         if let Some(threads) = analysis.threads {
             resolved.threads = threads;
         }
+        if let Some(cache) = &analysis.cache {
+            if let Some(enabled) = cache.enabled {
+                resolved.cache_enabled = enabled;
+            }
+            if let Some(dir) = &cache.dir {
+                resolved.cache_dir = dir.clone();
+            }
+        }
         if let Some(thresholds) = &analysis.thresholds {
             if let Some(t) = &thresholds.cyclomatic {
                 if let Some(w) = t.warning {
@@ -269,6 +674,62 @@ pub fn resolve_config(config: &Config) -> ResolvedConfig {
                     resolved.params_count_error = e;
                 }
             }
+            if let Some(t) = &thresholds.method_count {
+                if let Some(w) = t.warning {
+                    resolved.method_count_warning = w;
+                }
+                if let Some(e) = t.error {
+                    resolved.method_count_error = e;
+                }
+            }
+            if let Some(t) = &thresholds.wmc {
+                if let Some(w) = t.warning {
+                    resolved.wmc_warning = w;
+                }
+                if let Some(e) = t.error {
+                    resolved.wmc_error = e;
+                }
+            }
+            if let Some(t) = &thresholds.field_count {
+                if let Some(w) = t.warning {
+                    resolved.field_count_warning = w;
+                }
+                if let Some(e) = t.error {
+                    resolved.field_count_error = e;
+                }
+            }
+            if let Some(t) = &thresholds.lcom {
+                if let Some(w) = t.warning {
+                    resolved.lcom_warning = w;
+                }
+                if let Some(e) = t.error {
+                    resolved.lcom_error = e;
+                }
+            }
+            if let Some(t) = &thresholds.fan_in {
+                if let Some(w) = t.warning {
+                    resolved.fan_in_warning = w;
+                }
+                if let Some(e) = t.error {
+                    resolved.fan_in_error = e;
+                }
+            }
+            if let Some(t) = &thresholds.fan_out {
+                if let Some(w) = t.warning {
+                    resolved.fan_out_warning = w;
+                }
+                if let Some(e) = t.error {
+                    resolved.fan_out_error = e;
+                }
+            }
+            if let Some(t) = &thresholds.instability {
+                if let Some(w) = t.warning {
+                    resolved.instability_warning = Some(w);
+                }
+                if let Some(e) = t.error {
+                    resolved.instability_error = Some(e);
+                }
+            }
         }
     }
 
@@ -283,6 +744,9 @@ pub fn config_defaults() -> Config {
         output: Some(OutputConfig {
             format: Some("console".to_string()),
             file: None,
+            export_tokens: None,
+            by_directory: None,
+            directory_depth: None,
         }),
         analysis: Some(AnalysisConfig {
             metrics: Some(vec![
@@ -297,6 +761,9 @@ pub fn config_defaults() -> Config {
             no_duplication: Some(false),
             duplication_enabled: Some(false),
             threads: None,
+            limits: None,
+            on_syntax_error: None,
+            cache: None,
         }),
         files: None,
         weights: Some(WeightsConfig {
@@ -308,6 +775,12 @@ pub fn config_defaults() -> Config {
         }),
         overrides: None,
         baseline: None,
+        history: None,
+        hotspots: None,
+        blame: None,
+        owners: None,
+        packages: None,
+        modules: None,
     }
 }
 
@@ -410,6 +883,194 @@ mod tests {
         assert_eq!(cyclomatic.error, Some(20));
     }
 
+    #[test]
+    fn test_resolve_class_thresholds() {
+        let json = r#"{
+            "analysis": {
+                "thresholds": {
+                    "wmc": {"warning": 30},
+                    "lcom": {"warning": 2, "error": 4}
+                }
+            }
+        }"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let resolved = resolve_config(&config);
+        assert_eq!(resolved.wmc_warning, 30);
+        assert_eq!(resolved.wmc_error, 100);
+        assert_eq!(resolved.lcom_warning, 2);
+        assert_eq!(resolved.lcom_error, 4);
+        assert_eq!(resolved.method_count_warning, 20);
+        assert_eq!(resolved.field_count_error, 30);
+    }
+
+    #[test]
+    fn test_resolve_cache_config() {
+        let resolved = resolve_config(&config_defaults());
+        assert!(!resolved.cache_enabled);
+        assert_eq!(resolved.cache_dir, ".complexityguard/cache");
+
+        let json = r#"{"analysis": {"cache": {"enabled": true, "dir": "/tmp/cg-cache"}}}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let resolved = resolve_config(&config);
+        assert!(resolved.cache_enabled);
+        assert_eq!(resolved.cache_dir, "/tmp/cg-cache");
+    }
+
+    #[test]
+    fn test_resolve_history_config() {
+        let resolved = resolve_config(&config_defaults());
+        assert!(!resolved.history_enabled);
+        assert_eq!(resolved.history_file, ".complexityguard/history.jsonl");
+
+        let mut config = config_defaults();
+        config.overlay(serde_json::from_str(r#"{"history": {"enabled": true}}"#).unwrap());
+        config.overlay(serde_json::from_str(r#"{"history": {"file": "h.jsonl"}}"#).unwrap());
+        let resolved = resolve_config(&config);
+        assert!(resolved.history_enabled);
+        assert_eq!(resolved.history_file, "h.jsonl");
+    }
+
+    #[test]
+    fn test_resolve_hotspots_config() {
+        let resolved = resolve_config(&config_defaults());
+        assert!(!resolved.hotspots_enabled);
+        assert_eq!(resolved.hotspots_since, "1 year ago");
+
+        let config: Config =
+            serde_json::from_str(r#"{"hotspots": {"enabled": true, "since": "90 days ago"}}"#)
+                .unwrap();
+        let resolved = resolve_config(&config);
+        assert!(resolved.hotspots_enabled);
+        assert_eq!(resolved.hotspots_since, "90 days ago");
+    }
+
+    #[test]
+    fn test_resolve_blame_config() {
+        let resolved = resolve_config(&config_defaults());
+        assert!(!resolved.blame_enabled);
+        assert!(resolved.blame_since.is_none());
+
+        let mut config = config_defaults();
+        config.overlay(serde_json::from_str(r#"{"blame": {"since": "2026-01-01"}}"#).unwrap());
+        config.overlay(serde_json::from_str(r#"{"blame": {"enabled": true}}"#).unwrap());
+        let resolved = resolve_config(&config);
+        assert!(resolved.blame_enabled);
+        assert_eq!(resolved.blame_since.as_deref(), Some("2026-01-01"));
+    }
+
+    #[test]
+    fn test_resolve_owners_config() {
+        let resolved = resolve_config(&config_defaults());
+        assert!(!resolved.owners_enabled);
+        assert!(resolved.owners_file.is_none());
+
+        let mut config = config_defaults();
+        config.overlay(serde_json::from_str(r#"{"owners": {"file": "OWNERS"}}"#).unwrap());
+        config.overlay(serde_json::from_str(r#"{"owners": {"enabled": true}}"#).unwrap());
+        let resolved = resolve_config(&config);
+        assert!(resolved.owners_enabled);
+        assert_eq!(resolved.owners_file.as_deref(), Some("OWNERS"));
+    }
+
+    #[test]
+    fn test_resolve_packages_config() {
+        let resolved = resolve_config(&config_defaults());
+        assert!(!resolved.packages_enabled);
+        assert!(resolved.packages_fail_on.is_empty());
+
+        let mut config = config_defaults();
+        config.overlay(
+            serde_json::from_str(r#"{"packages": {"fail_on": {"@acme/web": "warning"}}}"#).unwrap(),
+        );
+        config.overlay(
+            serde_json::from_str(
+                r#"{"packages": {"enabled": true, "fail_on": {"packages/legacy": "none"}}}"#,
+            )
+            .unwrap(),
+        );
+        let resolved = resolve_config(&config);
+        assert!(resolved.packages_enabled);
+        assert_eq!(resolved.packages_fail_on.len(), 2);
+        assert_eq!(resolved.packages_fail_on["@acme/web"], "warning");
+        assert_eq!(resolved.packages_fail_on["packages/legacy"], "none");
+    }
+
+    #[test]
+    fn test_resolve_modules_config() {
+        let resolved = resolve_config(&config_defaults());
+        assert!(!resolved.modules_enabled);
+        assert!(resolved.modules_graph.is_none());
+        assert_eq!((resolved.fan_in_warning, resolved.fan_in_error), (20, 40));
+        assert_eq!((resolved.fan_out_warning, resolved.fan_out_error), (15, 30));
+        assert!(resolved.instability_warning.is_none());
+
+        let mut config = config_defaults();
+        config.overlay(
+            serde_json::from_str(
+                r#"{
+                    "modules": {"enabled": true, "graph": "modules.dot"},
+                    "analysis": {"thresholds": {
+                        "fan_in": {"warning": 8},
+                        "fan_out": {"error": 12},
+                        "instability": {"warning": 0.8, "error": 0.95}
+                    }}
+                }"#,
+            )
+            .unwrap(),
+        );
+        let resolved = resolve_config(&config);
+        assert!(resolved.modules_enabled);
+        assert_eq!(resolved.modules_graph.as_deref(), Some("modules.dot"));
+        assert_eq!((resolved.fan_in_warning, resolved.fan_in_error), (8, 40));
+        assert_eq!((resolved.fan_out_warning, resolved.fan_out_error), (15, 12));
+        assert_eq!(resolved.instability_warning, Some(0.8));
+        assert_eq!(resolved.instability_error, Some(0.95));
+    }
+
+    #[test]
+    fn test_overlay_merges_allowed_cycles() {
+        let mut config = config_defaults();
+        config.overlay(
+            serde_json::from_str(r#"{"modules": {"allow_cycles": [["src/a.ts", "src/b.ts"]]}}"#)
+                .unwrap(),
+        );
+        config.overlay(
+            serde_json::from_str(
+                r#"{"modules": {"enabled": true, "allow_cycles": [["src/c.ts", "src/d.ts"]]}}"#,
+            )
+            .unwrap(),
+        );
+        let resolved = resolve_config(&config);
+        assert_eq!(
+            resolved.modules_allow_cycles,
+            [["src/a.ts", "src/b.ts"], ["src/c.ts", "src/d.ts"]]
+        );
+    }
+
+    #[test]
+    fn test_resolve_directory_output() {
+        let resolved = resolve_config(&config_defaults());
+        assert!(!resolved.by_directory);
+        assert_eq!(resolved.directory_depth, 2);
+
+        let mut config = config_defaults();
+        config.overlay(
+            serde_json::from_str(r#"{"output": {"by_directory": true, "directory_depth": 4}}"#)
+                .unwrap(),
+        );
+        let resolved = resolve_config(&config);
+        assert!(resolved.by_directory);
+        assert_eq!(resolved.directory_depth, 4);
+    }
+
+    #[test]
+    fn test_resolve_export_tokens() {
+        assert!(!resolve_config(&config_defaults()).export_tokens);
+        let config: Config =
+            serde_json::from_str(r#"{"output": {"export_tokens": true}}"#).unwrap();
+        assert!(resolve_config(&config).export_tokens);
+    }
+
     #[test]
     fn test_serde_deserialize_files_config() {
         let json = r#"{"files": {"include": ["src/**/*.ts"], "exclude": ["**/*.test.ts"]}}"#;
@@ -435,6 +1096,58 @@ mod tests {
         assert_eq!(config.baseline, Some(75.5));
     }
 
+    #[test]
+    fn test_serde_deserialize_limits_and_overrides() {
+        let json = r#"{
+            "analysis": {"limits": {"max_file_lines": 2000, "skip_generated": false}},
+            "overrides": [
+                {"files": ["src/legacy/**"], "analysis": {"limits": {"max_function_lines": 800}}}
+            ]
+        }"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let limits = config.analysis.unwrap().limits.unwrap();
+        assert_eq!(limits.max_file_lines, Some(2000));
+        assert_eq!(limits.skip_generated, Some(false));
+        assert!(limits.max_function_lines.is_none());
+
+        let overrides = config.overrides.unwrap();
+        assert_eq!(overrides[0].files, vec!["src/legacy/**"]);
+        let override_limits = overrides[0].analysis.as_ref().unwrap().limits.as_ref();
+        assert_eq!(override_limits.unwrap().max_function_lines, Some(800));
+    }
+
+    #[test]
+    fn test_limits_config_apply_to_keeps_unset_values() {
+        let limits = LimitsConfig {
+            max_function_lines: Some(300),
+            ..LimitsConfig::default()
+        };
+        let applied = limits.apply_to(&SizeLimits::default());
+        assert_eq!(applied.max_function_lines, 300);
+        assert_eq!(applied.max_file_lines, SizeLimits::default().max_file_lines);
+        assert!(applied.skip_generated);
+    }
+
+    #[test]
+    fn test_overlay_keeps_unset_values() {
+        let mut config = config_defaults();
+        let file_config: Config = serde_json::from_str(
+            r#"{"output": {"format": "json"}, "analysis": {"threads": 2}, "baseline": 80.0}"#,
+        )
+        .unwrap();
+        config.overlay(file_config);
+
+        let output = config.output.as_ref().unwrap();
+        assert_eq!(output.format, Some("json".to_string()));
+        let analysis = config.analysis.as_ref().unwrap();
+        assert_eq!(analysis.threads, Some(2));
+        // Fields absent from the overlay keep their defaults
+        assert_eq!(analysis.metrics.as_ref().unwrap().len(), 6);
+        assert_eq!(analysis.duplication_enabled, Some(false));
+        assert!(config.weights.is_some());
+        assert_eq!(config.baseline, Some(80.0));
+    }
+
     #[test]
     fn test_config_all_none_by_default() {
         let config = Config::default();
@@ -1,5 +1,8 @@
 use super::args::Args;
-use super::config::{AnalysisConfig, Config, FilesConfig, OutputConfig};
+use super::config::{
+    AnalysisConfig, BlameConfig, CacheConfig, Config, FilesConfig, HistoryConfig, HotspotsConfig,
+    LimitsConfig, ModulesConfig, OutputConfig, OwnersConfig, PackagesConfig,
+};
 
 /// Merge CLI arguments into a Config, with CLI args taking precedence.
 ///
@@ -14,6 +17,16 @@ pub fn merge_args_into_config(args: &Args, config: &mut Config) {
     if let Some(file) = &args.output_file {
         output.file = Some(file.clone());
     }
+    if args.export_tokens {
+        output.export_tokens = Some(true);
+    }
+    if args.by_directory {
+        output.by_directory = Some(true);
+    }
+    if let Some(depth) = args.directory_depth {
+        output.directory_depth = Some(depth);
+        output.by_directory = Some(true);
+    }
 
     // Analysis section
     let analysis = config.analysis.get_or_insert_with(AnalysisConfig::default);
@@ -36,6 +49,116 @@ pub fn merge_args_into_config(args: &Args, config: &mut Config) {
             analysis.metrics = Some(parsed);
         }
     }
+    if args.max_file_lines.is_some() || args.max_function_lines.is_some() || args.include_generated
+    {
+        let limits = analysis.limits.get_or_insert_with(LimitsConfig::default);
+        if let Some(n) = args.max_file_lines {
+            limits.max_file_lines = Some(n);
+        }
+        if let Some(n) = args.max_function_lines {
+            limits.max_function_lines = Some(n);
+        }
+        if args.include_generated {
+            limits.skip_generated = Some(false);
+        }
+    }
+    if let Some(ref policy) = args.on_syntax_error {
+        analysis.on_syntax_error = Some(policy.clone());
+    }
+    if args.cache || args.no_cache || args.cache_dir.is_some() {
+        let cache = analysis.cache.get_or_insert_with(CacheConfig::default);
+        if let Some(dir) = &args.cache_dir {
+            cache.dir = Some(dir.clone());
+            cache.enabled = Some(true);
+        }
+        if args.cache {
+            cache.enabled = Some(true);
+        }
+        if args.no_cache {
+            cache.enabled = Some(false);
+        }
+    }
+
+    // History section
+    if args.history || args.no_history || args.history_file.is_some() {
+        let history = config.history.get_or_insert_with(HistoryConfig::default);
+        if let Some(file) = &args.history_file {
+            history.file = Some(file.clone());
+            history.enabled = Some(true);
+        }
+        if args.history {
+            history.enabled = Some(true);
+        }
+        if args.no_history {
+            history.enabled = Some(false);
+        }
+    }
+
+    // Hotspots section
+    if args.hotspots || args.no_hotspots || args.hotspots_since.is_some() {
+        let hotspots = config.hotspots.get_or_insert_with(HotspotsConfig::default);
+        if let Some(since) = &args.hotspots_since {
+            hotspots.since = Some(since.clone());
+            hotspots.enabled = Some(true);
+        }
+        if args.hotspots {
+            hotspots.enabled = Some(true);
+        }
+        if args.no_hotspots {
+            hotspots.enabled = Some(false);
+        }
+    }
+
+    // Blame section
+    if args.blame || args.no_blame || args.blame_since.is_some() {
+        let blame = config.blame.get_or_insert_with(BlameConfig::default);
+        if let Some(since) = &args.blame_since {
+            blame.since = Some(since.clone());
+            blame.enabled = Some(true);
+        }
+        if args.blame {
+            blame.enabled = Some(true);
+        }
+        if args.no_blame {
+            blame.enabled = Some(false);
+        }
+    }
+
+    // Owners section
+    if args.owners || args.no_owners || args.owner.is_some() {
+        let owners = config.owners.get_or_insert_with(OwnersConfig::default);
+        if args.owners || args.owner.is_some() {
+            owners.enabled = Some(true);
+        }
+        if args.no_owners {
+            owners.enabled = Some(false);
+        }
+    }
+
+    // Packages section
+    if args.packages || args.no_packages || args.package.is_some() {
+        let packages = config.packages.get_or_insert_with(PackagesConfig::default);
+        if args.packages || args.package.is_some() {
+            packages.enabled = Some(true);
+        }
+        if args.no_packages {
+            packages.enabled = Some(false);
+        }
+    }
+
+    // Modules section
+    if args.modules || args.no_modules || args.module_graph.is_some() {
+        let modules = config.modules.get_or_insert_with(ModulesConfig::default);
+        if args.modules || args.module_graph.is_some() {
+            modules.enabled = Some(true);
+        }
+        if args.no_modules {
+            modules.enabled = Some(false);
+        }
+        if let Some(graph) = &args.module_graph {
+            modules.graph = Some(graph.clone());
+        }
+    }
 
     // Files section
     let files = config.files.get_or_insert_with(FilesConfig::default);
@@ -45,6 +168,9 @@ pub fn merge_args_into_config(args: &Args, config: &mut Config) {
     if !args.exclude.is_empty() {
         files.exclude = Some(args.exclude.clone());
     }
+    if let Some(project) = &args.project {
+        files.project = Some(project.display().to_string());
+    }
 
     // Fail-on / thresholds (stored in config for later use by exit code logic)
     // fail_on and fail_health_below are read directly from Args at exit code determination time
@@ -177,6 +303,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_merge_project_flag() {
+        let mut config = config_defaults();
+        let args = parse_args(&["complexityguard", "--project", "tsconfig.app.json"]);
+        merge_args_into_config(&args, &mut config);
+
+        assert_eq!(
+            config.files.as_ref().unwrap().project.as_deref(),
+            Some("tsconfig.app.json")
+        );
+    }
+
     #[test]
     fn test_merge_empty_include_does_not_clobber_config() {
         let mut config = config_defaults();
@@ -184,6 +322,7 @@ mod tests {
         config.files = Some(crate::cli::config::FilesConfig {
             include: Some(vec!["lib/**".to_string()]),
             exclude: None,
+            project: None,
         });
 
         let args = parse_args(&["complexityguard"]);
@@ -196,6 +335,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_merge_size_limits() {
+        let mut config = config_defaults();
+        config.analysis.as_mut().unwrap().limits = Some(LimitsConfig {
+            max_file_lines: Some(2000),
+            max_average_line_length: Some(300),
+            ..LimitsConfig::default()
+        });
+
+        let args = parse_args(&[
+            "complexityguard",
+            "--max-function-lines",
+            "400",
+            "--include-generated",
+        ]);
+        merge_args_into_config(&args, &mut config);
+
+        let limits = config.analysis.as_ref().unwrap().limits.as_ref().unwrap();
+        assert_eq!(limits.max_file_lines, Some(2000));
+        assert_eq!(limits.max_function_lines, Some(400));
+        assert_eq!(limits.skip_generated, Some(false));
+        assert_eq!(limits.max_average_line_length, Some(300));
+    }
+
+    #[test]
+    fn test_merge_on_syntax_error() {
+        let mut config = config_defaults();
+        config.analysis.as_mut().unwrap().on_syntax_error = Some("skip".to_string());
+
+        let args = parse_args(&["complexityguard", "--on-syntax-error", "fail"]);
+        merge_args_into_config(&args, &mut config);
+
+        assert_eq!(
+            config.analysis.as_ref().unwrap().on_syntax_error,
+            Some("fail".to_string())
+        );
+    }
+
     #[test]
     fn test_merge_metrics_comma_separated() {
         let mut config = config_defaults();
@@ -221,4 +398,123 @@ mod tests {
             &vec!["cyclomatic".to_string(), "cognitive".to_string()]
         );
     }
+
+    #[test]
+    fn test_merge_cache_flags() {
+        let mut config = config_defaults();
+        let args = parse_args(&["complexityguard", "--cache-dir", "/tmp/cg"]);
+        merge_args_into_config(&args, &mut config);
+        let cache = config.analysis.as_ref().unwrap().cache.as_ref().unwrap();
+        assert_eq!(cache.enabled, Some(true));
+        assert_eq!(cache.dir, Some("/tmp/cg".to_string()));
+
+        // --no-cache wins over a config file that enables the cache
+        let mut config = config_defaults();
+        config.analysis.as_mut().unwrap().cache = Some(CacheConfig {
+            enabled: Some(true),
+            dir: None,
+        });
+        let args = parse_args(&["complexityguard", "--no-cache"]);
+        merge_args_into_config(&args, &mut config);
+        let cache = config.analysis.as_ref().unwrap().cache.as_ref().unwrap();
+        assert_eq!(cache.enabled, Some(false));
+    }
+
+    #[test]
+    fn test_merge_history_flags() {
+        let mut config = config_defaults();
+        let args = parse_args(&["complexityguard", "--history-file", "h.jsonl"]);
+        merge_args_into_config(&args, &mut config);
+        let history = config.history.as_ref().unwrap();
+        assert_eq!(history.enabled, Some(true));
+        assert_eq!(history.file, Some("h.jsonl".to_string()));
+
+        let args = parse_args(&["complexityguard", "--no-history"]);
+        merge_args_into_config(&args, &mut config);
+        assert_eq!(config.history.as_ref().unwrap().enabled, Some(false));
+    }
+
+    #[test]
+    fn test_merge_hotspots_flags() {
+        let mut config = config_defaults();
+        let args = parse_args(&["complexityguard", "--hotspots-since", "3 months ago"]);
+        merge_args_into_config(&args, &mut config);
+        let hotspots = config.hotspots.as_ref().unwrap();
+        assert_eq!(hotspots.enabled, Some(true));
+        assert_eq!(hotspots.since, Some("3 months ago".to_string()));
+
+        let args = parse_args(&["complexityguard", "--no-hotspots"]);
+        merge_args_into_config(&args, &mut config);
+        assert_eq!(config.hotspots.as_ref().unwrap().enabled, Some(false));
+    }
+
+    #[test]
+    fn test_merge_blame_flags() {
+        let mut config = config_defaults();
+        let args = parse_args(&["complexityguard", "--blame-since", "2026-01-01"]);
+        merge_args_into_config(&args, &mut config);
+        let blame = config.blame.as_ref().unwrap();
+        assert_eq!(blame.enabled, Some(true));
+        assert_eq!(blame.since, Some("2026-01-01".to_string()));
+
+        let args = parse_args(&["complexityguard", "--no-blame"]);
+        merge_args_into_config(&args, &mut config);
+        assert_eq!(config.blame.as_ref().unwrap().enabled, Some(false));
+    }
+
+    #[test]
+    fn test_merge_owners_flags() {
+        let mut config = config_defaults();
+        let args = parse_args(&["complexityguard", "--owner", "@org/web"]);
+        merge_args_into_config(&args, &mut config);
+        assert_eq!(config.owners.as_ref().unwrap().enabled, Some(true));
+
+        let args = parse_args(&["complexityguard", "--no-owners"]);
+        merge_args_into_config(&args, &mut config);
+        assert_eq!(config.owners.as_ref().unwrap().enabled, Some(false));
+    }
+
+    #[test]
+    fn test_merge_packages_flags() {
+        let mut config = config_defaults();
+        let args = parse_args(&["complexityguard", "--package", "@acme/web"]);
+        merge_args_into_config(&args, &mut config);
+        assert_eq!(config.packages.as_ref().unwrap().enabled, Some(true));
+
+        let args = parse_args(&["complexityguard", "--no-packages"]);
+        merge_args_into_config(&args, &mut config);
+        assert_eq!(config.packages.as_ref().unwrap().enabled, Some(false));
+    }
+
+    #[test]
+    fn test_merge_module_flags() {
+        let mut config = config_defaults();
+        let args = parse_args(&["complexityguard", "--module-graph", "deps.json"]);
+        merge_args_into_config(&args, &mut config);
+        let modules = config.modules.as_ref().unwrap();
+        assert_eq!(modules.enabled, Some(true));
+        assert_eq!(modules.graph.as_deref(), Some("deps.json"));
+
+        let args = parse_args(&["complexityguard", "--no-modules"]);
+        merge_args_into_config(&args, &mut config);
+        assert_eq!(config.modules.as_ref().unwrap().enabled, Some(false));
+    }
+
+    #[test]
+    fn test_merge_export_tokens() {
+        let mut config = config_defaults();
+        let args = parse_args(&["complexityguard", "--export-tokens"]);
+        merge_args_into_config(&args, &mut config);
+        assert_eq!(config.output.as_ref().unwrap().export_tokens, Some(true));
+    }
+
+    #[test]
+    fn test_merge_directory_flags() {
+        let mut config = config_defaults();
+        let args = parse_args(&["complexityguard", "--directory-depth", "1"]);
+        merge_args_into_config(&args, &mut config);
+        let output = config.output.as_ref().unwrap();
+        assert_eq!(output.by_directory, Some(true));
+        assert_eq!(output.directory_depth, Some(1));
+    }
 }
@@ -1,9 +1,13 @@
+#[cfg(feature = "cli")]
 pub mod args;
 pub mod config;
 pub mod discovery;
+#[cfg(feature = "cli")]
 pub mod merge;
 
-pub use args::Args;
+#[cfg(feature = "cli")]
+pub use args::{Args, Command};
 pub use config::{config_defaults, resolve_config, Config, ResolvedConfig};
 pub use discovery::discover_config;
+#[cfg(feature = "cli")]
 pub use merge::merge_args_into_config;
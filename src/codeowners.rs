@@ -0,0 +1,449 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::Context;
+
+use crate::analyzer::ReportViolation;
+use crate::git;
+use crate::metrics::scoring::compute_project_score;
+use crate::output::violations::Severity;
+use crate::types::FileAnalysisResult;
+
+/// Where a CODEOWNERS file is looked for, relative to the repository root,
+/// in the order GitHub uses.
+pub const LOCATIONS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// Owner name for files no CODEOWNERS rule assigns an owner.
+pub const UNOWNED: &str = "(unowned)";
+
+/// One `pattern owner...` line.
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    /// Pattern segments; unanchored patterns start with `**`
+    segments: Vec<String>,
+    /// A trailing `/`: matches only the contents of a directory
+    dir_only: bool,
+    owners: Vec<String>,
+}
+
+impl Rule {
+    fn parse(pattern: &str, owners: Vec<String>) -> Self {
+        let dir_only = pattern.ends_with('/');
+        let trimmed = pattern.trim_end_matches('/');
+        // Like .gitignore, a slash at the start or in the middle anchors the
+        // pattern to the root; otherwise it matches at any depth
+        let anchored = trimmed.contains('/');
+        let mut segments: Vec<String> = trimmed
+            .trim_start_matches('/')
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+        if !anchored {
+            segments.insert(0, "**".to_string());
+        }
+        Rule {
+            segments,
+            dir_only,
+            owners,
+        }
+    }
+
+    /// Whether the rule covers `path`: the file itself, or a directory it is in.
+    /// A pattern ending in `/*` covers only the files directly in a directory.
+    fn matches(&self, path: &[&str]) -> bool {
+        let direct_only = self.segments.last().is_some_and(|s| s == "*");
+        (1..=path.len()).any(|len| {
+            let is_file = len == path.len();
+            if (is_file && self.dir_only) || (!is_file && direct_only) {
+                return false;
+            }
+            match_segments(&self.segments, &path[..len])
+        })
+    }
+}
+
+/// Match path segments against pattern segments, where `**` spans any number
+/// of segments, including none.
+pub(crate) fn match_segments(pattern: &[String], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((first, rest)) if first == "**" => {
+            (0..=path.len()).any(|skip| match_segments(rest, &path[skip..]))
+        }
+        Some((first, rest)) => {
+            !path.is_empty() && match_glob(first, path[0]) && match_segments(rest, &path[1..])
+        }
+    }
+}
+
+/// Match one segment against a pattern with `*` and `?` wildcards.
+fn match_glob(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text it was matched up to
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p + 1, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((after, matched)) => {
+                    p = after;
+                    t = matched + 1;
+                    star = Some((after, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// A parsed CODEOWNERS file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CodeOwners {
+    rules: Vec<Rule>,
+}
+
+impl CodeOwners {
+    /// Parse CODEOWNERS text. Blank lines and `#` comments are ignored; a
+    /// pattern with no owners makes matching files unowned.
+    pub fn parse(text: &str) -> Self {
+        let rules = text
+            .lines()
+            .filter_map(|line| {
+                let line = match line.find(" #") {
+                    Some(comment) => &line[..comment],
+                    None => line,
+                };
+                let mut fields = line.split_whitespace();
+                let pattern = fields.next().filter(|p| !p.starts_with('#'))?;
+                Some(Rule::parse(pattern, fields.map(str::to_string).collect()))
+            })
+            .collect();
+        CodeOwners { rules }
+    }
+
+    /// Owners of `path`, relative to the repository root with `/` separators.
+    /// The last matching rule wins; empty when none matches.
+    pub fn owners(&self, path: &str) -> &[String] {
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(&segments))
+            .map_or(&[], |rule| &rule.owners)
+    }
+}
+
+/// The CODEOWNERS file of the repository containing a directory, with the
+/// directory's place in the repository to resolve report paths against.
+#[derive(Debug, Clone)]
+pub struct Ownership {
+    /// The CODEOWNERS file used, relative to the repository root
+    pub file: String,
+    pub codeowners: CodeOwners,
+    root: PathBuf,
+    /// The directory relative to the repository root, ending in `/` unless empty
+    prefix: String,
+}
+
+impl Ownership {
+    /// Find and parse the CODEOWNERS file for `dir`. `file` overrides the
+    /// lookup in `LOCATIONS`, relative to `dir`. Outside a git repository,
+    /// `dir` is taken as the root.
+    pub fn load(dir: &Path, file: Option<&str>) -> anyhow::Result<Self> {
+        let (root, prefix) = match git::repo_root(dir) {
+            Ok(root) => {
+                let prefix = git::run(dir, &["rev-parse", "--show-prefix"])?;
+                (root, prefix.trim().to_string())
+            }
+            Err(_) => (dir.to_path_buf(), String::new()),
+        };
+        let path = match file {
+            Some(file) => dir.join(file),
+            None => LOCATIONS
+                .iter()
+                .map(|location| root.join(location))
+                .find(|path| path.is_file())
+                .with_context(|| {
+                    format!(
+                        "no CODEOWNERS file in {} (looked in .github/, the root and docs/)",
+                        root.display()
+                    )
+                })?,
+        };
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let file = git::repo_path(&root, &path).unwrap_or_else(|| path.display().to_string());
+        Ok(Ownership {
+            file,
+            codeowners: CodeOwners::parse(&text),
+            root,
+            prefix,
+        })
+    }
+
+    /// Owners of a file as reported, relative to the directory passed to
+    /// `load`; `[UNOWNED]` when no rule assigns one.
+    pub fn owners_of(&self, path: &Path) -> Vec<String> {
+        let owners = self.codeowners.owners(&self.repo_path(path));
+        if owners.is_empty() {
+            vec![UNOWNED.to_string()]
+        } else {
+            owners.to_vec()
+        }
+    }
+
+    /// Whether `owner` owns `path`, ignoring case as GitHub does.
+    pub fn is_owned_by(&self, path: &Path, owner: &str) -> bool {
+        self.owners_of(path)
+            .iter()
+            .any(|o| o.eq_ignore_ascii_case(owner))
+    }
+
+    /// `path` relative to the repository root. Relative paths need not exist,
+    /// so files read from a revision or memory resolve too.
+    fn repo_path(&self, path: &Path) -> String {
+        if path.is_absolute() {
+            return git::repo_path(&self.root, path)
+                .unwrap_or_else(|| path.to_string_lossy().to_string());
+        }
+        let mut parts: Vec<String> = self
+            .prefix
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+        for component in path.components() {
+            match component {
+                Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+                Component::ParentDir => {
+                    parts.pop();
+                }
+                _ => {}
+            }
+        }
+        parts.join("/")
+    }
+
+    /// Attribute every file to its owners and total each owner's files.
+    pub fn attribute(
+        &self,
+        files: &[FileAnalysisResult],
+        violations: &[ReportViolation],
+    ) -> OwnersReport {
+        // Error and warning counts per file, so each owner just sums its files
+        let mut counts: HashMap<&Path, (u32, u32)> = HashMap::new();
+        for v in violations {
+            let entry = counts.entry(v.path.as_path()).or_default();
+            match v.violation.severity {
+                Severity::Error => entry.0 += 1,
+                Severity::Warning => entry.1 += 1,
+            }
+        }
+
+        let mut file_owners = BTreeMap::new();
+        let mut by_owner: BTreeMap<String, Vec<&FileAnalysisResult>> = BTreeMap::new();
+        for file in files {
+            let owners = self.owners_of(&file.path);
+            for owner in &owners {
+                by_owner.entry(owner.clone()).or_default().push(file);
+            }
+            file_owners.insert(file.path.to_string_lossy().to_string(), owners);
+        }
+
+        let mut owners: Vec<OwnerSummary> = by_owner
+            .into_iter()
+            .map(|(owner, owned)| {
+                let mut summary = OwnerSummary {
+                    owner,
+                    files: owned.len(),
+                    functions: owned.iter().map(|f| f.functions.len()).sum(),
+                    errors: 0,
+                    warnings: 0,
+                    health_score: 0.0,
+                };
+                for file in &owned {
+                    if let Some((errors, warnings)) = counts.get(file.path.as_path()) {
+                        summary.errors += errors;
+                        summary.warnings += warnings;
+                    }
+                }
+                let scores: Vec<f64> = owned.iter().map(|f| f.file_score).collect();
+                let counts: Vec<u32> = owned.iter().map(|f| f.functions.len() as u32).collect();
+                summary.health_score = compute_project_score(&scores, &counts);
+                summary
+            })
+            .collect();
+        // Unowned files last
+        owners.sort_by_key(|o| o.owner == UNOWNED);
+
+        OwnersReport {
+            file: self.file.clone(),
+            files: file_owners,
+            owners,
+        }
+    }
+}
+
+/// Totals for the files one owner owns. A file with several owners counts
+/// for each of them.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct OwnerSummary {
+    /// Team or user as written in CODEOWNERS, or `(unowned)`
+    pub owner: String,
+    pub files: usize,
+    pub functions: usize,
+    pub errors: u32,
+    pub warnings: u32,
+    /// File scores weighted by function count, like the project score
+    pub health_score: f64,
+}
+
+/// The owners of every analyzed file and the per-owner totals.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct OwnersReport {
+    /// The CODEOWNERS file used, relative to the repository root
+    pub file: String,
+    /// Owners of each file, by report path
+    pub files: BTreeMap<String, Vec<String>>,
+    /// By owner name, with `(unowned)` last
+    pub owners: Vec<OwnerSummary>,
+}
+
+impl OwnersReport {
+    /// Owners of a file by its report path.
+    pub fn owners_of(&self, path: &str) -> &[String] {
+        self.files.get(path).map_or(&[], Vec::as_slice)
+    }
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODEOWNERS: &str = "\
+# Default owners
+*       @org/everyone
+
+*.js    @org/js   # scripts
+/docs/  @org/docs
+apps/   @org/apps
+src/api/**/handlers @org/api
+/build/logs/
+lib/*   @org/lib
+";
+
+    fn owners(path: &str) -> Vec<String> {
+        CodeOwners::parse(CODEOWNERS).owners(path).to_vec()
+    }
+
+    #[test]
+    fn last_matching_rule_wins() {
+        assert_eq!(owners("README.md"), ["@org/everyone"]);
+        assert_eq!(owners("src/deep/app.js"), ["@org/js"]);
+        assert_eq!(owners("docs/guide/intro.md"), ["@org/docs"]);
+        // /docs/ is anchored; a nested docs directory keeps the default
+        assert_eq!(owners("src/docs/a.md"), ["@org/everyone"]);
+        // apps/ matches an apps directory at any depth
+        assert_eq!(owners("services/apps/web/index.ts"), ["@org/apps"]);
+        assert_eq!(owners("src/api/v1/handlers/user.ts"), ["@org/api"]);
+        assert_eq!(owners("src/api/handlers/user.ts"), ["@org/api"]);
+        // A pattern without owners unassigns
+        assert!(owners("build/logs/out.log").is_empty());
+        // lib/* covers direct files only
+        assert_eq!(owners("lib/a.ts"), ["@org/lib"]);
+        assert_eq!(owners("lib/nested/a.ts"), ["@org/everyone"]);
+    }
+
+    #[test]
+    fn matches_globs_within_a_segment() {
+        assert!(match_glob("*.ts", "index.ts"));
+        assert!(match_glob("*.test.*", "a.test.tsx"));
+        assert!(match_glob("fil?.ts", "file.ts"));
+        assert!(!match_glob("*.ts", "index.tsx"));
+        assert!(!match_glob("a*b", "acbd"));
+        assert!(match_glob("*", ""));
+    }
+
+    #[test]
+    fn a_directory_pattern_does_not_match_a_file() {
+        let codeowners = CodeOwners::parse("apps/ @org/apps\n");
+        assert!(codeowners.owners("apps").is_empty());
+        assert_eq!(codeowners.owners("apps/main.ts"), ["@org/apps"]);
+    }
+
+    #[test]
+    fn attributes_files_and_totals_owners() {
+        let ownership = Ownership {
+            file: "CODEOWNERS".to_string(),
+            codeowners: CodeOwners::parse("/src/ @org/core\n/src/ui/ @org/ui @org/core\n"),
+            root: PathBuf::from("/repo"),
+            prefix: String::new(),
+        };
+        let nested = "export function nested(a: number) {\n  if (a) { if (a) { if (a) { if (a) { if (a) { if (a) { return 1; } } } } } }\n  return 0;\n}\n";
+        let flat =
+            "export function one() {\n  return 1;\n}\nexport function two() {\n  return 2;\n}\n";
+        let analyzed = crate::Analyzer::new()
+            .source("src/a.ts", flat)
+            .source("src/ui/b.ts", nested)
+            .source("scripts/c.ts", flat)
+            .run()
+            .unwrap();
+        let (files, violations) = (&analyzed.files, &analyzed.violations);
+        let report = ownership.attribute(files, violations);
+        assert_eq!(report.owners_of("src/ui/b.ts"), ["@org/ui", "@org/core"]);
+        assert_eq!(report.owners_of("scripts/c.ts"), [UNOWNED]);
+        let names: Vec<_> = report.owners.iter().map(|o| o.owner.as_str()).collect();
+        assert_eq!(names, ["@org/core", "@org/ui", UNOWNED]);
+        let core = &report.owners[0];
+        let ui = &report.owners[1];
+        assert_eq!((core.files, core.functions), (2, 3));
+        assert_eq!((core.errors, core.warnings), (ui.errors, ui.warnings));
+        assert!(ui.errors > 0);
+        let b = files
+            .iter()
+            .find(|f| f.path == Path::new("src/ui/b.ts"))
+            .unwrap();
+        assert_eq!(ui.health_score, b.file_score);
+        let a = files
+            .iter()
+            .find(|f| f.path == Path::new("src/a.ts"))
+            .unwrap();
+        // Weighted by function count: a.ts has two functions, b.ts one
+        let expected = (2.0 * a.file_score + b.file_score) / 3.0;
+        assert!((core.health_score - expected).abs() < 1e-9);
+        assert!(ownership.is_owned_by(Path::new("./src/ui/b.ts"), "@ORG/UI"));
+        assert!(ownership.is_owned_by(Path::new("scripts/c.ts"), UNOWNED));
+    }
+
+    #[test]
+    fn resolves_report_paths_against_the_prefix() {
+        let ownership = Ownership {
+            file: "CODEOWNERS".to_string(),
+            codeowners: CodeOwners::parse("/packages/web/ @org/web\n"),
+            root: PathBuf::from("/repo"),
+            prefix: "packages/web/".to_string(),
+        };
+        assert_eq!(
+            ownership.repo_path(Path::new("./src/a.ts")),
+            "packages/web/src/a.ts"
+        );
+        assert_eq!(
+            ownership.repo_path(Path::new("../api/b.ts")),
+            "packages/api/b.ts"
+        );
+        assert_eq!(ownership.owners_of(Path::new("src/a.ts")), ["@org/web"]);
+    }
+}
@@ -0,0 +1,767 @@
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::Path;
+
+use crate::analyzer::{Report, ReportViolation};
+use crate::cli::Config;
+use crate::merge::{merge_reports, Shard};
+use crate::output::determine_exit_code;
+use crate::output::violations::Severity;
+use crate::output::ExitCode;
+use crate::types::FunctionAnalysisResult;
+
+/// Minimum fingerprint similarity for matching a moved or renamed function.
+pub const MIN_SIMILARITY: f64 = 0.9;
+
+/// Functions shorter than this are matched by name only; their fingerprints
+/// cover too few tokens to tell unrelated one-liners apart.
+const MIN_SIMILARITY_LINES: u32 = 3;
+
+/// The differences between two reports of the same project.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Comparison {
+    pub summary: ComparisonSummary,
+    /// Violations that are new, more severe than before, or of a matched
+    /// function whose violating metric got worse
+    pub new_violations: Vec<ViolationChange>,
+    /// Violations that are gone, or less severe than before
+    pub resolved_violations: Vec<ViolationChange>,
+    /// Added, removed, moved and modified functions, by path and line
+    pub functions: Vec<FunctionChange>,
+    /// Files whose health score changed, including added and removed files
+    pub files: Vec<FileChange>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ComparisonSummary {
+    pub old_health: f64,
+    pub new_health: f64,
+    pub new_errors: u32,
+    pub new_warnings: u32,
+    pub resolved_errors: u32,
+    pub resolved_warnings: u32,
+    pub functions_added: usize,
+    pub functions_removed: usize,
+    /// Modified and moved functions
+    pub functions_changed: usize,
+}
+
+/// A violation that appeared or went away.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ViolationChange {
+    pub path: String,
+    /// Qualified function name, `<module>`, class name, or `<file>` for the whole file
+    pub name: String,
+    pub rule_id: String,
+    pub severity: Severity,
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FunctionStatus {
+    Added,
+    Removed,
+    /// Matched by name, with different metrics
+    Modified,
+    /// Matched by body similarity after a rename or a move to another file
+    Moved,
+}
+
+/// A function that differs between the two reports.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FunctionChange {
+    pub status: FunctionStatus,
+    /// Path in the new report, or in the old one for removed functions
+    pub path: String,
+    /// Qualified name in the new report, or in the old one for removed functions
+    pub name: String,
+    pub line: usize,
+    /// Path in the old report, for functions moved to another file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
+    /// Qualified name in the old report, for renamed functions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_name: Option<String>,
+    /// Fingerprint similarity (0–1) for moved functions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f64>,
+    /// Metrics that changed; empty for added and removed functions
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub metrics: Vec<MetricDelta>,
+}
+
+/// One metric's old and new value, named as in the JSON report.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct MetricDelta {
+    pub metric: &'static str,
+    pub old: f64,
+    pub new: f64,
+    pub delta: f64,
+}
+
+/// A file's health score in each report; `None` where the file is absent.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct FileChange {
+    pub path: String,
+    pub old_health: Option<f64>,
+    pub new_health: Option<f64>,
+}
+
+impl Comparison {
+    /// Every regression fails: new errors exit 1 and new warnings exit 2.
+    /// Only an explicit `--fail-on error` lets new warnings pass, and
+    /// `--fail-on none` lets everything pass.
+    pub fn exit_code(&self, fail_on: Option<&str>) -> ExitCode {
+        determine_exit_code(
+            false,
+            self.summary.new_errors,
+            self.summary.new_warnings,
+            Some(fail_on.unwrap_or("warning")),
+            false,
+        )
+    }
+}
+
+/// Compare two saved reports, judging both by the thresholds in `config`.
+///
+/// Violations are recomputed from the metrics, as `merge_reports` does, so a
+/// threshold change between the two runs does not show up as a regression.
+/// Duplication is not compared; it would need the token streams of both runs.
+pub fn compare_reports(old: &Shard, new: &Shard, config: &Config) -> anyhow::Result<Comparison> {
+    let mut config = config.clone();
+    config
+        .analysis
+        .get_or_insert_with(Default::default)
+        .no_duplication = Some(true);
+    let old = merge_reports(std::slice::from_ref(old), &config)?;
+    let new = merge_reports(std::slice::from_ref(new), &config)?;
+    Ok(compare(&old, &new))
+}
+
+/// Compare the staged version of every changed source file under `paths` with
+/// its `HEAD` version, in the repository containing `dir`.
+///
+/// Both versions are read from git and analyzed in memory with `config`, so
+/// unstaged edits in the working tree are ignored. Files unchanged in the
+/// index are not analyzed at all. Duplication detection is disabled.
+#[cfg(feature = "discovery")]
+pub fn compare_staged(
+    dir: &Path,
+    paths: &[std::path::PathBuf],
+    config: &Config,
+) -> anyhow::Result<Comparison> {
+    use crate::pipeline::discover::discover_staged_files;
+    use crate::Analyzer;
+
+    let files = config.files.as_ref();
+    let include = files.and_then(|f| f.include.clone()).unwrap_or_default();
+    let exclude = files.and_then(|f| f.exclude.clone()).unwrap_or_default();
+    let staged = discover_staged_files(dir, paths, &include, &exclude)?;
+
+    let analyze = |sources: Vec<(std::path::PathBuf, Vec<u8>)>| {
+        sources
+            .into_iter()
+            .fold(
+                Analyzer::new().config(config.clone()),
+                |analyzer, (path, source)| analyzer.source(path, source),
+            )
+            .configure(|c| {
+                c.analysis
+                    .get_or_insert_with(Default::default)
+                    .no_duplication = Some(true)
+            })
+            .run()
+    };
+    let old = analyze(staged.head)?;
+    let new = analyze(staged.staged)?;
+    Ok(compare(&old, &new))
+}
+
+/// Compare two reports whose violations were computed with the same thresholds.
+///
+/// Functions are matched by path and qualified name first. Those left over are
+/// matched by body fingerprint similarity, best match first, so a function
+/// that was renamed or moved to another file keeps its history.
+pub fn compare(old: &Report, new: &Report) -> Comparison {
+    let old_units = units(old);
+    let new_units = units(new);
+    let pairs = match_units(&old_units, &new_units);
+
+    let mut functions = Vec::new();
+    let mut new_to_old: HashMap<UnitKey, UnitKey> = HashMap::new();
+    for pair in &pairs {
+        match (pair.old, pair.new) {
+            (Some(i), Some(j)) => {
+                let (o, n) = (&old_units[i], &new_units[j]);
+                new_to_old.insert(n.key(), o.key());
+                let metrics = metric_deltas(o.func, n.func);
+                let moved = pair.similarity.is_some();
+                if !moved && metrics.is_empty() {
+                    continue;
+                }
+                functions.push(FunctionChange {
+                    status: if moved {
+                        FunctionStatus::Moved
+                    } else {
+                        FunctionStatus::Modified
+                    },
+                    path: n.path.display().to_string(),
+                    name: n.func.qualified_name.clone(),
+                    line: n.func.start_line,
+                    old_path: (o.path != n.path).then(|| o.path.display().to_string()),
+                    old_name: (o.func.qualified_name != n.func.qualified_name)
+                        .then(|| o.func.qualified_name.clone()),
+                    similarity: pair.similarity,
+                    metrics,
+                });
+            }
+            (Some(i), None) => functions.push(unpaired(&old_units[i], FunctionStatus::Removed)),
+            (None, Some(j)) => functions.push(unpaired(&new_units[j], FunctionStatus::Added)),
+            (None, None) => {}
+        }
+    }
+    functions.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+
+    let old_to_new: HashMap<UnitKey, UnitKey> = new_to_old.iter().map(|(n, o)| (*o, *n)).collect();
+    let mut new_violations = violation_changes(&new.violations, &old.violations, &new_to_old);
+    new_violations.extend(worsened_violations(
+        new,
+        old,
+        &new_units,
+        &old_units,
+        &new_to_old,
+    ));
+    let resolved_violations = violation_changes(&old.violations, &new.violations, &old_to_new);
+
+    let count = |changes: &[ViolationChange], severity: Severity| {
+        changes.iter().filter(|c| c.severity == severity).count() as u32
+    };
+    let count_status =
+        |status: FunctionStatus| functions.iter().filter(|f| f.status == status).count();
+    let summary = ComparisonSummary {
+        old_health: old.health_score(),
+        new_health: new.health_score(),
+        new_errors: count(&new_violations, Severity::Error),
+        new_warnings: count(&new_violations, Severity::Warning),
+        resolved_errors: count(&resolved_violations, Severity::Error),
+        resolved_warnings: count(&resolved_violations, Severity::Warning),
+        functions_added: count_status(FunctionStatus::Added),
+        functions_removed: count_status(FunctionStatus::Removed),
+        functions_changed: count_status(FunctionStatus::Modified)
+            + count_status(FunctionStatus::Moved),
+    };
+
+    Comparison {
+        summary,
+        new_violations,
+        resolved_violations,
+        functions,
+        files: file_changes(old, new),
+    }
+}
+
+/// A function or module unit, identified by its file and qualified name.
+struct Unit<'a> {
+    path: &'a Path,
+    func: &'a FunctionAnalysisResult,
+}
+
+type UnitKey<'a> = (&'a Path, &'a str);
+
+impl<'a> Unit<'a> {
+    fn key(&self) -> UnitKey<'a> {
+        (self.path, &self.func.qualified_name)
+    }
+}
+
+fn units(report: &Report) -> Vec<Unit<'_>> {
+    report
+        .files
+        .iter()
+        .flat_map(|file| {
+            file.functions.iter().chain(&file.module).map(|func| Unit {
+                path: &file.path,
+                func,
+            })
+        })
+        .collect()
+}
+
+/// Indices into the old and new units; `similarity` is set for fingerprint matches.
+struct Pair {
+    old: Option<usize>,
+    new: Option<usize>,
+    similarity: Option<f64>,
+}
+
+fn match_units(old: &[Unit], new: &[Unit]) -> Vec<Pair> {
+    // Same path and name; repeated names pair up in source order
+    let mut by_key: HashMap<UnitKey, VecDeque<usize>> = HashMap::new();
+    for (i, unit) in old.iter().enumerate() {
+        by_key.entry(unit.key()).or_default().push_back(i);
+    }
+    let mut pairs = Vec::new();
+    let mut old_matched = vec![false; old.len()];
+    let mut new_left = Vec::new();
+    for (j, unit) in new.iter().enumerate() {
+        match by_key.get_mut(&unit.key()).and_then(VecDeque::pop_front) {
+            Some(i) => {
+                old_matched[i] = true;
+                pairs.push(Pair {
+                    old: Some(i),
+                    new: Some(j),
+                    similarity: None,
+                });
+            }
+            None => new_left.push(j),
+        }
+    }
+    let old_left: Vec<usize> = (0..old.len()).filter(|&i| !old_matched[i]).collect();
+
+    // Body similarity for the rest: best first, then same file, then nearest line
+    let mut candidates = Vec::new();
+    for &i in &old_left {
+        for &j in &new_left {
+            if let Some(similarity) = similarity(old[i].func, new[j].func) {
+                let same_file = old[i].path == new[j].path;
+                let distance = old[i].func.start_line.abs_diff(new[j].func.start_line);
+                candidates.push((similarity, same_file, distance, i, j));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0).then(b.1.cmp(&a.1)).then(a.2.cmp(&b.2)));
+    let mut old_taken = HashSet::new();
+    let mut new_taken = HashSet::new();
+    for (similarity, _, _, i, j) in candidates {
+        if old_taken.contains(&i) || new_taken.contains(&j) {
+            continue;
+        }
+        old_taken.insert(i);
+        new_taken.insert(j);
+        pairs.push(Pair {
+            old: Some(i),
+            new: Some(j),
+            similarity: Some(similarity),
+        });
+    }
+
+    pairs.extend(
+        old_left
+            .into_iter()
+            .filter(|i| !old_taken.contains(i))
+            .map(|i| Pair {
+                old: Some(i),
+                new: None,
+                similarity: None,
+            }),
+    );
+    pairs.extend(
+        new_left
+            .into_iter()
+            .filter(|j| !new_taken.contains(j))
+            .map(|j| Pair {
+                old: None,
+                new: Some(j),
+                similarity: None,
+            }),
+    );
+    pairs
+}
+
+/// Fingerprint similarity of two function bodies, if both can be matched by it
+/// and they are similar enough.
+fn similarity(a: &FunctionAnalysisResult, b: &FunctionAnalysisResult) -> Option<f64> {
+    let eligible = |f: &FunctionAnalysisResult| {
+        f.fingerprint != 0 && f.function_length >= MIN_SIMILARITY_LINES
+    };
+    if !eligible(a) || !eligible(b) {
+        return None;
+    }
+    let similarity = 1.0 - (a.fingerprint ^ b.fingerprint).count_ones() as f64 / 64.0;
+    (similarity >= MIN_SIMILARITY).then_some(similarity)
+}
+
+fn metric_deltas(old: &FunctionAnalysisResult, new: &FunctionAnalysisResult) -> Vec<MetricDelta> {
+    [
+        ("cyclomatic", old.cyclomatic as f64, new.cyclomatic as f64),
+        ("cognitive", old.cognitive as f64, new.cognitive as f64),
+        ("halstead_volume", old.halstead_volume, new.halstead_volume),
+        (
+            "nesting_depth",
+            old.nesting_depth as f64,
+            new.nesting_depth as f64,
+        ),
+        (
+            "line_count",
+            old.function_length as f64,
+            new.function_length as f64,
+        ),
+        (
+            "params_count",
+            old.params_count as f64,
+            new.params_count as f64,
+        ),
+        ("health_score", old.health_score, new.health_score),
+    ]
+    .into_iter()
+    .filter(|(_, old, new)| (new - old).abs() > f64::EPSILON)
+    .map(|(metric, old, new)| MetricDelta {
+        metric,
+        old,
+        new,
+        delta: new - old,
+    })
+    .collect()
+}
+
+fn unpaired(unit: &Unit, status: FunctionStatus) -> FunctionChange {
+    FunctionChange {
+        status,
+        path: unit.path.display().to_string(),
+        name: unit.func.qualified_name.clone(),
+        line: unit.func.start_line,
+        old_path: None,
+        old_name: None,
+        similarity: None,
+        metrics: Vec::new(),
+    }
+}
+
+/// Violations in `current` that `other` lacks or has at a lower severity.
+///
+/// `counterpart` maps a unit of `current` to the matching unit of `other`;
+/// units without an entry (classes, unmatched functions) keep their own key.
+fn violation_changes(
+    current: &[ReportViolation],
+    other: &[ReportViolation],
+    counterpart: &HashMap<UnitKey, UnitKey>,
+) -> Vec<ViolationChange> {
+    let mut other_severity: HashMap<(&Path, &str, &str), &Severity> = HashMap::new();
+    for v in other {
+        let key = (
+            v.path.as_path(),
+            v.name.as_str(),
+            v.violation.rule_id.as_str(),
+        );
+        let entry = other_severity.entry(key).or_insert(&v.violation.severity);
+        if v.violation.severity > **entry {
+            *entry = &v.violation.severity;
+        }
+    }
+
+    current
+        .iter()
+        .filter(|v| {
+            let unit: UnitKey = (v.path.as_path(), v.name.as_str());
+            let (path, name) = counterpart.get(&unit).copied().unwrap_or(unit);
+            other_severity
+                .get(&(path, name, v.violation.rule_id.as_str()))
+                .map_or(true, |severity| **severity < v.violation.severity)
+        })
+        .map(|v| ViolationChange {
+            path: v.path.display().to_string(),
+            name: v.name.clone(),
+            rule_id: v.violation.rule_id.clone(),
+            severity: v.violation.severity.clone(),
+            line: v.violation.line,
+            message: v.violation.message.clone(),
+        })
+        .collect()
+}
+
+/// Violations of matched functions that the old report already had at the
+/// same or a higher severity, but whose metric grew since.
+///
+/// The message notes the old value, e.g. "Cyclomatic complexity 46 exceeds
+/// error threshold 20 (was 26)".
+fn worsened_violations(
+    new: &Report,
+    old: &Report,
+    new_units: &[Unit],
+    old_units: &[Unit],
+    new_to_old: &HashMap<UnitKey, UnitKey>,
+) -> Vec<ViolationChange> {
+    fn by_key<'a>(units: &[Unit<'a>]) -> HashMap<UnitKey<'a>, &'a FunctionAnalysisResult> {
+        let mut funcs = HashMap::new();
+        for unit in units {
+            funcs.entry(unit.key()).or_insert(unit.func);
+        }
+        funcs
+    }
+    let new_funcs = by_key(new_units);
+    let old_funcs = by_key(old_units);
+    let mut old_severity: HashMap<(&Path, &str, &str), &Severity> = HashMap::new();
+    for v in &old.violations {
+        let key = (
+            v.path.as_path(),
+            v.name.as_str(),
+            v.violation.rule_id.as_str(),
+        );
+        let entry = old_severity.entry(key).or_insert(&v.violation.severity);
+        if v.violation.severity > **entry {
+            *entry = &v.violation.severity;
+        }
+    }
+
+    new.violations
+        .iter()
+        .filter_map(|v| {
+            let unit: UnitKey = (v.path.as_path(), v.name.as_str());
+            let old_unit = *new_to_old.get(&unit)?;
+            let rule = v.violation.rule_id.as_str();
+            let severity = old_severity.get(&(old_unit.0, old_unit.1, rule))?;
+            if **severity < v.violation.severity {
+                // Already reported as more severe
+                return None;
+            }
+            let (old_value, decimals) = violated_metric(old_funcs.get(&old_unit)?, rule)?;
+            let (new_value, _) = violated_metric(new_funcs.get(&unit)?, rule)?;
+            (new_value > old_value).then(|| ViolationChange {
+                path: v.path.display().to_string(),
+                name: v.name.clone(),
+                rule_id: v.violation.rule_id.clone(),
+                severity: v.violation.severity.clone(),
+                line: v.violation.line,
+                message: format!("{} (was {old_value:.decimals$})", v.violation.message),
+            })
+        })
+        .collect()
+}
+
+/// The value of the metric a function rule checks, with the decimals its
+/// violation message shows.
+fn violated_metric(func: &FunctionAnalysisResult, rule_id: &str) -> Option<(f64, usize)> {
+    let metric = match rule_id {
+        "complexity-guard/cyclomatic" => (func.cyclomatic as f64, 0),
+        "complexity-guard/cognitive" => (func.cognitive as f64, 0),
+        "complexity-guard/halstead-volume" => (func.halstead_volume, 1),
+        "complexity-guard/halstead-difficulty" => (func.halstead_difficulty, 1),
+        "complexity-guard/halstead-effort" => (func.halstead_effort, 1),
+        "complexity-guard/halstead-bugs" => (func.halstead_bugs, 3),
+        "complexity-guard/nesting-depth" => (func.nesting_depth as f64, 0),
+        "complexity-guard/line-count" => (func.function_length as f64, 0),
+        "complexity-guard/param-count" => (func.params_count as f64, 0),
+        _ => return None,
+    };
+    Some(metric)
+}
+
+fn file_changes(old: &Report, new: &Report) -> Vec<FileChange> {
+    let mut scores: BTreeMap<String, (Option<f64>, Option<f64>)> = BTreeMap::new();
+    for file in &old.files {
+        scores.entry(file.path.display().to_string()).or_default().0 = Some(file.file_score);
+    }
+    for file in &new.files {
+        scores.entry(file.path.display().to_string()).or_default().1 = Some(file.file_score);
+    }
+    scores
+        .into_iter()
+        .filter(|(_, (old, new))| match (old, new) {
+            (Some(old), Some(new)) => (new - old).abs() > f64::EPSILON,
+            _ => true,
+        })
+        .map(|(path, (old_health, new_health))| FileChange {
+            path,
+            old_health,
+            new_health,
+        })
+        .collect()
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::config_defaults;
+    use crate::output::json_output::parse_json_report;
+    use crate::Analyzer;
+
+    const TOTAL: &str = "export function total(items: number[]) {
+  let sum = 0;
+  for (const item of items) {
+    if (item > 0) { sum += item * 2; } else { sum -= item; }
+  }
+  return sum;
+}
+";
+
+    const NESTED: &str = "export function total(items: number[]) {
+  let sum = 0;
+  for (const item of items) {
+    if (item > 0) {
+      for (const part of item.parts) {
+        if (part && part.ok) { sum += part.value; } else if (part) { sum -= 1; }
+      }
+    } else { sum -= item; }
+  }
+  return sum;
+}
+";
+
+    fn shard(name: &str, sources: &[(&str, &str)]) -> Shard {
+        let mut analyzer = Analyzer::new();
+        for (path, source) in sources {
+            analyzer = analyzer.source(*path, *source);
+        }
+        let json = analyzer.run().unwrap().render_json().unwrap();
+        Shard {
+            name: name.to_string(),
+            report: parse_json_report(&json).unwrap(),
+        }
+    }
+
+    /// Config that turns the cognitive complexity of `NESTED` into an error.
+    fn strict() -> Config {
+        let mut config = config_defaults();
+        config.overlay(
+            serde_json::from_str(
+                r#"{"analysis": {"thresholds": {"cognitive": {"warning": 4, "error": 8}}}}"#,
+            )
+            .unwrap(),
+        );
+        config
+    }
+
+    #[test]
+    fn identical_reports_have_no_changes() {
+        let old = shard("old.json", &[("src/a.ts", TOTAL)]);
+        let new = shard("new.json", &[("src/a.ts", TOTAL)]);
+        let comparison = compare_reports(&old, &new, &config_defaults()).unwrap();
+
+        assert!(comparison.functions.is_empty());
+        assert!(comparison.files.is_empty());
+        assert!(comparison.new_violations.is_empty());
+        assert_eq!(comparison.exit_code(None), ExitCode::Success);
+    }
+
+    #[test]
+    fn reports_new_violations_and_metric_deltas() {
+        let old = shard("old.json", &[("src/a.ts", TOTAL)]);
+        let new = shard("new.json", &[("src/a.ts", NESTED)]);
+        let comparison = compare_reports(&old, &new, &strict()).unwrap();
+
+        assert_eq!(comparison.functions.len(), 1);
+        let change = &comparison.functions[0];
+        assert_eq!(change.status, FunctionStatus::Modified);
+        let cognitive = change
+            .metrics
+            .iter()
+            .find(|m| m.metric == "cognitive")
+            .unwrap();
+        assert!(cognitive.delta > 0.0);
+
+        // The cognitive warning escalated to an error, so the error is new
+        let rules: Vec<_> = comparison
+            .new_violations
+            .iter()
+            .map(|v| (v.name.as_str(), v.rule_id.as_str()))
+            .collect();
+        assert_eq!(
+            rules,
+            vec![
+                ("total", "complexity-guard/cognitive"),
+                ("total", "complexity-guard/nesting-depth")
+            ]
+        );
+        assert_eq!(comparison.summary.new_errors, 2);
+        assert!(comparison.resolved_violations.is_empty());
+        assert!(comparison.summary.new_health < comparison.summary.old_health);
+        assert_eq!(comparison.exit_code(None), ExitCode::ErrorsFound);
+        assert_eq!(comparison.exit_code(Some("none")), ExitCode::Success);
+
+        // The other way round, the errors are resolved. Only the Halstead
+        // difficulty warning both reports have grew.
+        let comparison = compare_reports(&new, &old, &strict()).unwrap();
+        assert_eq!(comparison.summary.resolved_errors, 2);
+        let messages: Vec<_> = comparison
+            .new_violations
+            .iter()
+            .map(|v| v.message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            ["Halstead difficulty 12.0 exceeds warning threshold 10.0 (was 11.7)"]
+        );
+    }
+
+    #[test]
+    fn existing_errors_that_get_worse_are_regressions() {
+        let mut config = config_defaults();
+        config.overlay(
+            serde_json::from_str(
+                r#"{"analysis": {"thresholds": {"cognitive": {"warning": 1, "error": 2}}}}"#,
+            )
+            .unwrap(),
+        );
+        let old = shard("old.json", &[("src/a.ts", TOTAL)]);
+        let new = shard("new.json", &[("src/a.ts", NESTED)]);
+        let comparison = compare_reports(&old, &new, &config).unwrap();
+
+        let cognitive = comparison
+            .new_violations
+            .iter()
+            .find(|v| v.rule_id == "complexity-guard/cognitive")
+            .unwrap();
+        assert_eq!(cognitive.severity, Severity::Error);
+        assert!(
+            cognitive
+                .message
+                .ends_with("exceeds error threshold 2 (was 4)"),
+            "{}",
+            cognitive.message
+        );
+        assert_eq!(comparison.exit_code(None), ExitCode::ErrorsFound);
+
+        // An error that improves but stays an error is not a regression
+        let comparison = compare_reports(&new, &old, &config).unwrap();
+        assert!(comparison
+            .new_violations
+            .iter()
+            .all(|v| v.rule_id != "complexity-guard/cognitive"));
+    }
+
+    #[test]
+    fn matches_moved_and_renamed_functions_by_body() {
+        let old = shard("old.json", &[("src/a.ts", NESTED)]);
+        let moved = NESTED.replace("total", "sumParts");
+        let new = shard("new.json", &[("lib/sum.ts", moved.as_str())]);
+        let comparison = compare_reports(&old, &new, &strict()).unwrap();
+
+        assert_eq!(comparison.functions.len(), 1);
+        let change = &comparison.functions[0];
+        assert_eq!(change.status, FunctionStatus::Moved);
+        assert_eq!(change.name, "sumParts");
+        assert_eq!(change.old_name.as_deref(), Some("total"));
+        assert_eq!(change.old_path.as_deref(), Some("src/a.ts"));
+        assert!(change.similarity.unwrap() >= MIN_SIMILARITY);
+        // The violation moved with the function, so it is neither new nor resolved
+        assert!(comparison.new_violations.is_empty());
+        assert!(comparison.resolved_violations.is_empty());
+
+        let paths: Vec<_> = comparison.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["lib/sum.ts", "src/a.ts"]);
+    }
+
+    #[test]
+    fn unrelated_functions_are_added_and_removed() {
+        let old = shard("old.json", &[("src/a.ts", TOTAL)]);
+        let new = shard(
+            "new.json",
+            &[(
+                "src/a.ts",
+                "export class Cache {\n  get(key: string) {\n    return this.map.has(key) ? this.map.get(key) : null;\n  }\n}\n",
+            )],
+        );
+        let comparison = compare_reports(&old, &new, &config_defaults()).unwrap();
+
+        let statuses: Vec<_> = comparison.functions.iter().map(|f| f.status).collect();
+        assert_eq!(
+            statuses,
+            vec![FunctionStatus::Removed, FunctionStatus::Added]
+        );
+        assert_eq!(comparison.summary.functions_added, 1);
+        assert_eq!(comparison.summary.functions_removed, 1);
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path};
+
+use crate::analyzer::collect_violations;
+use crate::cli::ResolvedConfig;
+use crate::metrics::scoring::compute_project_score;
+use crate::output::violations::Severity;
+use crate::types::FileAnalysisResult;
+
+/// Levels below the root shown by `--by-directory` unless set.
+pub const DEFAULT_DEPTH: usize = 2;
+
+/// Totals for a directory and every file below it, with its subdirectories.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DirectoryNode {
+    /// Path as reported, `.` for the root
+    pub path: String,
+    /// Last path segment, `.` for the root
+    pub name: String,
+    pub files: usize,
+    pub functions: usize,
+    pub errors: u32,
+    pub warnings: u32,
+    /// File scores weighted by function count, like the project score
+    pub health_score: f64,
+    /// By name
+    pub children: Vec<DirectoryNode>,
+}
+
+/// Directory segments of a report path; `..` is kept so files outside the
+/// working directory group under it, and the root of an absolute path is a
+/// `/` segment of its own.
+fn directory_segments(path: &Path) -> Vec<String> {
+    let parent = path.parent().unwrap_or(Path::new(""));
+    parent
+        .components()
+        .filter_map(|c| match c {
+            Component::Prefix(prefix) => Some(prefix.as_os_str().to_string_lossy().to_string()),
+            Component::RootDir => Some("/".to_string()),
+            Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+            Component::ParentDir => Some("..".to_string()),
+            Component::CurDir => None,
+        })
+        .collect()
+}
+
+/// Path of the directory `name` inside the directory at `parent`.
+fn child_path(parent: &str, name: &str) -> String {
+    match (parent, name) {
+        (".", _) => name.to_string(),
+        (_, "/") => format!("{parent}/"),
+        _ if parent.ends_with('/') => format!("{parent}{name}"),
+        _ => format!("{parent}/{name}"),
+    }
+}
+
+/// A directory's totals while files are being added to the tree.
+#[derive(Default)]
+struct Totals<'a> {
+    errors: u32,
+    warnings: u32,
+    files: Vec<&'a FileAnalysisResult>,
+    children: BTreeMap<String, Totals<'a>>,
+}
+
+/// Roll file results up into a tree of directories. Each node covers every
+/// file below it; violations are counted against `config`'s thresholds.
+pub fn directory_tree(files: &[FileAnalysisResult], config: &ResolvedConfig) -> DirectoryNode {
+    let index: HashMap<&Path, usize> = files
+        .iter()
+        .enumerate()
+        .map(|(i, f)| (f.path.as_path(), i))
+        .collect();
+    let mut counts = vec![(0u32, 0u32); files.len()];
+    for v in &collect_violations(files, config) {
+        let Some(&i) = index.get(v.path.as_path()) else {
+            continue;
+        };
+        match v.violation.severity {
+            Severity::Error => counts[i].0 += 1,
+            Severity::Warning => counts[i].1 += 1,
+        }
+    }
+
+    // One pass: each file is added to every directory above it
+    let mut root = Totals::default();
+    for (file, &(errors, warnings)) in files.iter().zip(&counts) {
+        let mut node = &mut root;
+        for segment in
+            std::iter::once(None).chain(directory_segments(&file.path).into_iter().map(Some))
+        {
+            if let Some(segment) = segment {
+                node = node.children.entry(segment).or_default();
+            }
+            node.errors += errors;
+            node.warnings += warnings;
+            node.files.push(file);
+        }
+    }
+    build_node(".", ".", root)
+}
+
+fn build_node(path: &str, name: &str, totals: Totals) -> DirectoryNode {
+    let scores: Vec<f64> = totals.files.iter().map(|f| f.file_score).collect();
+    let functions: Vec<u32> = totals
+        .files
+        .iter()
+        .map(|f| f.functions.len() as u32)
+        .collect();
+    DirectoryNode {
+        path: path.to_string(),
+        name: name.to_string(),
+        files: totals.files.len(),
+        functions: functions.iter().sum::<u32>() as usize,
+        errors: totals.errors,
+        warnings: totals.warnings,
+        health_score: compute_project_score(&scores, &functions),
+        children: totals
+            .children
+            .into_iter()
+            .map(|(child, totals)| build_node(&child_path(path, &child), &child, totals))
+            .collect(),
+    }
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Analyzer;
+
+    const FLAT: &str =
+        "export function one() {\n  return 1;\n}\nexport function two() {\n  return 2;\n}\n";
+    const NESTED: &str = "export function nested(a: number) {\n  if (a) { if (a) { if (a) { if (a) { if (a) { if (a) { return 1; } } } } } }\n  return 0;\n}\n";
+
+    #[test]
+    fn rolls_files_up_into_directories() {
+        let report = Analyzer::new()
+            .source("./src/api/handler.ts", NESTED)
+            .source("./src/api/routes.ts", FLAT)
+            .source("./src/util.ts", FLAT)
+            .source("./index.ts", FLAT)
+            .source("../shared/lib.ts", FLAT)
+            .run()
+            .unwrap();
+        let tree = directory_tree(&report.files, &report.config);
+
+        assert_eq!(tree.path, ".");
+        assert_eq!(tree.files, 5);
+        assert_eq!(tree.functions, 9);
+        assert_eq!(tree.errors, report.error_count());
+        assert_eq!(tree.warnings, report.warning_count());
+        assert!((tree.health_score - report.health_score()).abs() < 1e-9);
+
+        let names: Vec<_> = tree.children.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(names, ["..", "src"]);
+        let src = &tree.children[1];
+        assert_eq!((src.files, src.functions), (3, 5));
+        let api = &src.children[0];
+        assert_eq!((api.path.as_str(), api.name.as_str()), ("src/api", "api"));
+        assert_eq!(api.files, 2);
+        assert!(api.errors > 0);
+        assert_eq!(api.errors, tree.errors);
+        assert!(api.children.is_empty());
+        let shared = &tree.children[0].children[0];
+        assert_eq!(shared.path, "../shared");
+        assert_eq!((shared.errors, shared.warnings), (0, 0));
+
+        let scores: Vec<f64> = report
+            .files
+            .iter()
+            .filter(|f| f.path.starts_with("./src/api"))
+            .map(|f| f.file_score)
+            .collect();
+        let expected = (scores[0] + 2.0 * scores[1]) / 3.0;
+        assert!((api.health_score - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn keeps_the_root_of_absolute_paths() {
+        let report = Analyzer::new()
+            .source("/home/dev/app/a.ts", FLAT)
+            .source("/home/dev/app/lib/b.ts", FLAT)
+            .run()
+            .unwrap();
+        let tree = directory_tree(&report.files, &report.config);
+
+        let root = &tree.children[0];
+        assert_eq!((root.path.as_str(), root.name.as_str()), ("/", "/"));
+        let home = &root.children[0];
+        assert_eq!((home.path.as_str(), home.name.as_str()), ("/home", "home"));
+        let lib = &home.children[0].children[0].children[0];
+        assert_eq!(lib.path, "/home/dev/app/lib");
+        assert_eq!(lib.files, 1);
+        assert_eq!(home.files, 2);
+    }
+
+    #[test]
+    fn empty_tree_is_healthy() {
+        let tree = directory_tree(&[], &ResolvedConfig::default());
+        assert_eq!((tree.files, tree.functions), (0, 0));
+        assert_eq!(tree.health_score, 100.0);
+        assert!(tree.children.is_empty());
+    }
+}
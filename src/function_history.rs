@@ -0,0 +1,297 @@
+use std::path::Path;
+
+use crate::cli::Config;
+use crate::git::{self, Revision};
+use crate::metrics::analyze_indexed_function;
+use crate::types::{AnalysisConfig, FunctionAnalysisResult};
+use crate::Analyzer;
+
+/// One function's metrics in every commit that changed its file, oldest first.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FunctionHistory {
+    /// Path of the file relative to the repository root
+    pub path: String,
+    /// The function as given: a qualified or short name
+    pub function: String,
+    pub revisions: Vec<FunctionRevision>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FunctionRevision {
+    pub commit: String,
+    /// Author date as a Unix timestamp
+    pub date: u64,
+    pub author: String,
+    pub subject: String,
+    /// Path of the file in this commit; differs from `FunctionHistory::path`
+    /// before a rename
+    pub path: String,
+    /// `None` when the function does not exist in this revision
+    pub metrics: Option<FunctionMetrics>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FunctionMetrics {
+    pub start_line: usize,
+    pub cyclomatic: u32,
+    pub cognitive: u32,
+    pub halstead_volume: f64,
+    pub nesting_depth: u32,
+    pub line_count: u32,
+    pub params_count: u32,
+    pub health_score: f64,
+}
+
+impl From<&FunctionAnalysisResult> for FunctionMetrics {
+    fn from(func: &FunctionAnalysisResult) -> Self {
+        FunctionMetrics {
+            start_line: func.start_line,
+            cyclomatic: func.cyclomatic,
+            cognitive: func.cognitive,
+            halstead_volume: func.halstead_volume,
+            nesting_depth: func.nesting_depth,
+            line_count: func.function_length,
+            params_count: func.params_count,
+            health_score: func.health_score,
+        }
+    }
+}
+
+/// Split a `<path>::<function>` target into its path and function name.
+pub fn parse_target(target: &str) -> Option<(&str, &str)> {
+    let (path, function) = target.split_once("::")?;
+    (!path.is_empty() && !function.is_empty()).then_some((path, function))
+}
+
+/// Analyze every committed revision of the file at `path` and collect the
+/// metrics of `function` in each.
+///
+/// `function` matches a qualified name first, then a short name such as
+/// `OrderService.checkout`, then a bare method name such as `checkout`. Nested
+/// functions are found too, e.g. by `outerFn > innerHelper`, and measured
+/// without the functions nested inside them. Renames are followed as far as `git log --follow` detects them. Each revision is read
+/// with `git show` and analyzed in memory with `config`; the analysis cache is
+/// not used. Fails when `path` is not in a repository, has no commits, or
+/// `function` is in none of them.
+pub fn function_history(
+    dir: &Path,
+    path: &Path,
+    function: &str,
+    config: &Config,
+) -> anyhow::Result<FunctionHistory> {
+    let root = git::repo_root(dir)?;
+    let repo_path = match git::repo_path(&root, &dir.join(path)) {
+        Some(repo_path) => repo_path,
+        // A deleted file: resolve it against the directory's place in the repository
+        None => {
+            let prefix = git::run(dir, &["rev-parse", "--show-prefix"])?;
+            format!(
+                "{}{}",
+                prefix.trim(),
+                path.to_string_lossy().replace('\\', "/")
+            )
+        }
+    };
+
+    let log = git::file_log(&root, &repo_path)?;
+    if log.is_empty() {
+        anyhow::bail!("no commits change {}", repo_path);
+    }
+    let config = Analyzer::new().config(config.clone()).analysis_config()?;
+    let mut revisions = log
+        .iter()
+        .rev()
+        .map(|revision| analyze_revision(&root, revision, function, &config))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    if revisions.iter().all(|r| r.metrics.is_none()) {
+        anyhow::bail!(
+            "function '{}' not found in any of the {} revisions of {}",
+            function,
+            revisions.len(),
+            repo_path
+        );
+    }
+    // Leave out the revisions before the function was added
+    let first = revisions
+        .iter()
+        .position(|r| r.metrics.is_some())
+        .unwrap_or(0);
+    revisions.drain(..first);
+
+    Ok(FunctionHistory {
+        path: repo_path,
+        function: function.to_string(),
+        revisions,
+    })
+}
+
+fn analyze_revision(
+    root: &Path,
+    revision: &Revision,
+    function: &str,
+    config: &AnalysisConfig,
+) -> anyhow::Result<FunctionRevision> {
+    let source = git::show(root, &revision.hash, &revision.path)?;
+    let found = analyze_indexed_function(Path::new(&revision.path), &source, config, |index| {
+        let functions = index.functions();
+        functions
+            .iter()
+            .find(|f| f.qualified_name == function)
+            .or_else(|| functions.iter().find(|f| f.name == function))
+            .or_else(|| {
+                functions
+                    .iter()
+                    .find(|f| f.name.rsplit('.').next() == Some(function))
+            })
+            .map(|f| f.id)
+    })?;
+    Ok(FunctionRevision {
+        commit: revision.hash.clone(),
+        date: revision.timestamp,
+        author: revision.author.clone(),
+        subject: revision.subject.clone(),
+        path: revision.path.clone(),
+        metrics: found.as_ref().map(FunctionMetrics::from),
+    })
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::config_defaults;
+    use crate::git::test_repo::git;
+
+    fn commit(dir: &Path, path: &str, source: &str, message: &str) {
+        std::fs::write(dir.join(path), source).unwrap();
+        git(dir, &["add", "-A"]);
+        git(dir, &["commit", "-q", "-m", message]);
+    }
+
+    const FLAT: &str =
+        "export class OrderService {\n  checkout(a: number) {\n    return a;\n  }\n}\n";
+    const BRANCHY: &str = "export class OrderService {\n  checkout(a: number) {\n    if (a > 0) {\n      if (a > 10) {\n        return 2;\n      }\n      return 1;\n    }\n    return 0;\n  }\n}\n";
+
+    #[test]
+    fn parses_targets() {
+        assert_eq!(
+            parse_target("src/orders.ts::OrderService.checkout"),
+            Some(("src/orders.ts", "OrderService.checkout"))
+        );
+        assert_eq!(parse_target("src/orders.ts"), None);
+        assert_eq!(parse_target("src/orders.ts::"), None);
+    }
+
+    #[test]
+    fn follows_a_function_across_renames() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        commit(dir.path(), "other.ts", "export const x = 1;\n", "Start");
+        commit(
+            dir.path(),
+            "orders.ts",
+            "export const y = 2;\n",
+            "Add orders",
+        );
+        commit(dir.path(), "orders.ts", FLAT, "Add checkout");
+        commit(dir.path(), "orders.ts", BRANCHY, "Handle large orders");
+        git(dir.path(), &["mv", "orders.ts", "checkout.ts"]);
+        git(dir.path(), &["commit", "-q", "-m", "Rename"]);
+
+        let history = function_history(
+            dir.path(),
+            Path::new("checkout.ts"),
+            "OrderService.checkout",
+            &config_defaults(),
+        )
+        .unwrap();
+        assert_eq!(history.path, "checkout.ts");
+        let subjects: Vec<_> = history
+            .revisions
+            .iter()
+            .map(|r| r.subject.as_str())
+            .collect();
+        // The revision before the function existed is left out
+        assert_eq!(subjects, ["Add checkout", "Handle large orders", "Rename"]);
+        assert_eq!(history.revisions[0].path, "orders.ts");
+        assert_eq!(history.revisions[2].path, "checkout.ts");
+        let cyclomatic: Vec<_> = history
+            .revisions
+            .iter()
+            .map(|r| r.metrics.as_ref().unwrap().cyclomatic)
+            .collect();
+        assert_eq!(cyclomatic, [1, 3, 3]);
+
+        // Bare method names match too
+        let history = function_history(
+            dir.path(),
+            Path::new("checkout.ts"),
+            "checkout",
+            &config_defaults(),
+        )
+        .unwrap();
+        assert_eq!(history.revisions.len(), 3);
+
+        let err = function_history(
+            dir.path(),
+            Path::new("checkout.ts"),
+            "missing",
+            &config_defaults(),
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("not found in any of the 4 revisions"));
+    }
+
+    #[test]
+    fn follows_a_nested_function_by_qualified_name() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        commit(
+            dir.path(),
+            "outer.ts",
+            "function outerFn() {\n  function innerHelper(a) {\n    return a;\n  }\n  return innerHelper(1);\n}\n",
+            "Add helper",
+        );
+        commit(
+            dir.path(),
+            "outer.ts",
+            "function outerFn() {\n  function innerHelper(a) {\n    if (a > 0) {\n      return a;\n    }\n    return 0;\n  }\n  return innerHelper(1);\n}\n",
+            "Guard helper",
+        );
+
+        let history = function_history(
+            dir.path(),
+            Path::new("outer.ts"),
+            "outerFn > innerHelper",
+            &config_defaults(),
+        )
+        .unwrap();
+        let lines: Vec<_> = history
+            .revisions
+            .iter()
+            .map(|r| {
+                let metrics = r.metrics.as_ref().unwrap();
+                (metrics.start_line, metrics.cyclomatic, metrics.line_count)
+            })
+            .collect();
+        assert_eq!(lines, [(2, 1, 1), (2, 2, 3)]);
+
+        // The enclosing function's metrics leave the helper's body out
+        let history = function_history(
+            dir.path(),
+            Path::new("outer.ts"),
+            "outerFn",
+            &config_defaults(),
+        )
+        .unwrap();
+        let cyclomatic: Vec<_> = history
+            .revisions
+            .iter()
+            .map(|r| r.metrics.as_ref().unwrap().cyclomatic)
+            .collect();
+        assert_eq!(cyclomatic, [1, 1]);
+    }
+}
@@ -0,0 +1,812 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::Context;
+
+/// A changed line range from a zero-context (`-U0`) diff.
+///
+/// `new_start`/`new_lines` are in the file after the change, `old_start`/`old_lines`
+/// before it. A pure deletion has `new_lines == 0` and `new_start` is the line
+/// after which lines were removed; a pure insertion likewise has `old_lines == 0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+}
+
+impl Hunk {
+    /// Whether the hunk changes any of the lines `start..=end` of the new file.
+    /// A deletion counts when it removes lines from between two of them.
+    pub fn touches(&self, start: usize, end: usize) -> bool {
+        if self.new_lines == 0 {
+            self.new_start >= start && self.new_start < end
+        } else {
+            self.new_start <= end && self.new_start + self.new_lines > start
+        }
+    }
+
+    /// First line of the new file after the hunk.
+    fn new_end(&self) -> usize {
+        self.new_start + self.new_lines.max(1)
+    }
+}
+
+/// One file changed by a commit or in the working tree.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileChange {
+    /// Path relative to the repository root; the old path for a deleted file
+    pub path: String,
+    /// Lines added, from `--numstat`; 0 for binary files
+    pub added: u32,
+    /// Lines deleted, from `--numstat`; 0 for binary files
+    pub deleted: u32,
+    /// Changed ranges, in file order
+    pub hunks: Vec<Hunk>,
+}
+
+/// A commit and the files it changed relative to its first parent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    pub hash: String,
+    /// Author date as a Unix timestamp
+    pub timestamp: u64,
+    pub author: String,
+    pub changes: Vec<FileChange>,
+}
+
+/// A commit that changed one file, and the file's path in that commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Revision {
+    pub hash: String,
+    /// Author date as a Unix timestamp
+    pub timestamp: u64,
+    pub author: String,
+    /// First line of the commit message
+    pub subject: String,
+    /// Path relative to the repository root as of this commit
+    pub path: String,
+}
+
+/// A commit that last changed some blamed lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameCommit {
+    pub hash: String,
+    pub author: String,
+    /// Author date as a Unix timestamp
+    pub timestamp: u64,
+    /// The commit lies at the `--since` boundary: the lines are older than the window
+    pub boundary: bool,
+}
+
+impl BlameCommit {
+    /// Lines changed in the working tree but not committed yet.
+    pub fn is_uncommitted(&self) -> bool {
+        self.hash.bytes().all(|b| b == b'0')
+    }
+}
+
+/// Blame of some lines of a file: the commit that last changed each line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlameLines {
+    pub commits: Vec<BlameCommit>,
+    /// Line number to index into `commits`
+    pub lines: BTreeMap<usize, usize>,
+}
+
+impl BlameLines {
+    /// The commit that last changed `line`, if it was blamed.
+    pub fn commit_at(&self, line: usize) -> Option<&BlameCommit> {
+        self.lines.get(&line).map(|&i| &self.commits[i])
+    }
+}
+
+/// Run git in `dir` and return its stdout, or an error carrying git's stderr.
+pub fn run(dir: &Path, args: &[&str]) -> anyhow::Result<String> {
+    String::from_utf8(run_bytes(dir, args)?).context("git printed invalid UTF-8")
+}
+
+/// Like `run`, for output that quotes file contents: bytes that are not valid
+/// UTF-8, such as latin-1 source files, are replaced instead of failing.
+fn run_lossy(dir: &Path, args: &[&str]) -> anyhow::Result<String> {
+    Ok(String::from_utf8_lossy(&run_bytes(dir, args)?).into_owned())
+}
+
+/// Like `run`, for output that need not be UTF-8.
+fn run_bytes(dir: &Path, args: &[&str]) -> anyhow::Result<Vec<u8>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "core.quotePath=false"])
+        .args(args)
+        .output()
+        .context("failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output.stdout)
+}
+
+/// The root of the repository containing `dir`.
+pub fn repo_root(dir: &Path) -> anyhow::Result<PathBuf> {
+    let root = run(dir, &["rev-parse", "--show-toplevel"])?;
+    Ok(PathBuf::from(root.trim()))
+}
+
+/// `path` relative to the repository `root` with `/` separators, or `None`
+/// when it does not exist or lies outside the repository.
+pub fn repo_path(root: &Path, path: &Path) -> Option<String> {
+    let root = std::fs::canonicalize(root).ok()?;
+    let path = std::fs::canonicalize(path).ok()?;
+    let relative = path.strip_prefix(root).ok()?;
+    let parts: Vec<_> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect();
+    Some(parts.join("/"))
+}
+
+/// Commits since `since` (any date `git log --since` accepts), newest first,
+/// with their line changes to files matching `pathspecs`.
+///
+/// History is followed along first parents, and merges are diffed against
+/// their first parent, so every change is seen once against a linear history.
+/// Renames show as a deletion plus an addition.
+pub fn log(root: &Path, since: &str, pathspecs: &[String]) -> anyhow::Result<Vec<Commit>> {
+    let since = format!("--since={since}");
+    let mut args = vec![
+        "log",
+        "-m",
+        "--first-parent",
+        "--no-renames",
+        "--numstat",
+        "-p",
+        "-U0",
+        "--no-color",
+        "--format=%x01%H%x09%at%x09%an",
+        &since,
+        "--",
+    ];
+    args.extend(pathspecs.iter().map(String::as_str));
+    Ok(parse_log(&run_lossy(root, &args)?))
+}
+
+/// Uncommitted changes to files matching `pathspecs`, relative to `HEAD`.
+/// Empty when the repository has no commits yet.
+pub fn uncommitted(root: &Path, pathspecs: &[String]) -> anyhow::Result<Vec<FileChange>> {
+    if run(root, &["rev-parse", "--verify", "--quiet", "HEAD"]).is_err() {
+        return Ok(Vec::new());
+    }
+    let mut args = vec!["diff", "--no-renames", "-U0", "--no-color", "HEAD", "--"];
+    args.extend(pathspecs.iter().map(String::as_str));
+    Ok(parse_patch(&run_lossy(root, &args)?))
+}
+
+/// The full SHA of the commit `rev` names, e.g. a tag or branch.
+pub fn resolve_commit(dir: &Path, rev: &str) -> anyhow::Result<String> {
+    let commit = run(
+        dir,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{rev}^{{commit}}"),
+        ],
+    )
+    .with_context(|| format!("unknown revision '{rev}'"))?;
+    Ok(commit.trim().to_string())
+}
+
+/// A blob in a commit's tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeEntry {
+    /// Path relative to `dir` as given to `ls_tree`
+    pub path: String,
+    pub oid: String,
+}
+
+/// The regular files under `paths` in commit `rev`, relative to `dir`.
+/// Symbolic links and submodules are left out.
+pub fn ls_tree(dir: &Path, rev: &str, paths: &[&str]) -> anyhow::Result<Vec<TreeEntry>> {
+    let mut args = vec!["ls-tree", "-r", "-z", rev, "--"];
+    args.extend(paths);
+    Ok(parse_ls_tree(&run(dir, &args)?))
+}
+
+/// A file whose staged content differs from `HEAD`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StagedChange {
+    /// Path relative to `dir` as given to `staged_changes`
+    pub path: String,
+    /// Blob of the staged content
+    pub oid: String,
+    /// Path and blob in `HEAD`; the old path for a rename, `None` for an added file
+    pub head: Option<(String, String)>,
+}
+
+/// Files under `paths` that are added, copied, modified or renamed in the
+/// index relative to `HEAD`, with paths relative to `dir`. Changes outside
+/// `dir` are left out.
+pub fn staged_changes(dir: &Path, paths: &[&str]) -> anyhow::Result<Vec<StagedChange>> {
+    let mut args = vec![
+        "diff",
+        "--cached",
+        "--relative",
+        "--raw",
+        "-z",
+        "--no-abbrev",
+        "--diff-filter=ACMR",
+        "--",
+    ];
+    args.extend(paths);
+    Ok(parse_diff_raw(&run(dir, &args)?))
+}
+
+/// The contents of the blobs `oids`, in order, read with one `git cat-file --batch`.
+pub fn cat_blobs(dir: &Path, oids: &[String]) -> anyhow::Result<Vec<Vec<u8>>> {
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["cat-file", "--batch"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to run git")?;
+    // Write from another thread so a full stdout pipe cannot block the input
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input: String = oids.iter().map(|oid| format!("{oid}\n")).collect();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child.wait_with_output().context("failed to run git")?;
+    writer
+        .join()
+        .expect("writer thread panicked")
+        .context("failed to write to git cat-file")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git cat-file failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    parse_cat_file_batch(&output.stdout, oids.len())
+}
+
+/// Commits that changed the file at `path`, relative to the repository
+/// `root`, newest first. Renames are followed, so older revisions may have
+/// another `path`; commits that deleted the file are left out.
+pub fn file_log(root: &Path, path: &str) -> anyhow::Result<Vec<Revision>> {
+    let args = [
+        "log",
+        "--follow",
+        "--name-status",
+        "--no-color",
+        "--format=%x01%H%x09%at%x09%an%x09%s",
+        "--",
+        path,
+    ];
+    Ok(parse_file_log(&run(root, &args)?))
+}
+
+/// The contents of the file at `path`, relative to the repository root, in
+/// commit `rev`.
+pub fn show(root: &Path, rev: &str, path: &str) -> anyhow::Result<Vec<u8>> {
+    run_bytes(root, &["show", &format!("{rev}:{path}")])
+}
+
+/// Blame the working-tree lines `ranges` (1-indexed, inclusive) of the file at
+/// `path`, relative to the repository `root`.
+///
+/// With `since`, lines last changed before it are attributed to a boundary
+/// commit. Fails when the file is not tracked.
+pub fn blame(
+    root: &Path,
+    path: &str,
+    ranges: &[(usize, usize)],
+    since: Option<&str>,
+) -> anyhow::Result<BlameLines> {
+    let mut args = vec![
+        "blame".to_string(),
+        "--porcelain".to_string(),
+        "--root".to_string(),
+    ];
+    if let Some(since) = since {
+        args.push(format!("--since={since}"));
+    }
+    for (start, end) in ranges {
+        args.push(format!("-L{start},{end}"));
+    }
+    args.push("--".to_string());
+    args.push(path.to_string());
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    Ok(parse_blame(&run_lossy(root, &args)?))
+}
+
+/// Parse `git blame --porcelain` output. Commit details are only printed the
+/// first time a commit appears.
+fn parse_blame(text: &str) -> BlameLines {
+    let mut blame = BlameLines::default();
+    let mut index: BTreeMap<String, usize> = BTreeMap::new();
+    let mut current = None;
+    for line in text.lines() {
+        if line.starts_with('\t') {
+            continue;
+        }
+        let mut fields = line.split(' ');
+        let first = fields.next().unwrap_or_default();
+        if first.len() == 40 && first.bytes().all(|b| b.is_ascii_hexdigit()) {
+            let final_line: Option<usize> = fields.nth(1).and_then(|n| n.parse().ok());
+            let i = *index.entry(first.to_string()).or_insert_with(|| {
+                blame.commits.push(BlameCommit {
+                    hash: first.to_string(),
+                    author: String::new(),
+                    timestamp: 0,
+                    boundary: false,
+                });
+                blame.commits.len() - 1
+            });
+            if let Some(final_line) = final_line {
+                blame.lines.insert(final_line, i);
+            }
+            current = Some(i);
+            continue;
+        }
+        let Some(commit) = current.map(|i| &mut blame.commits[i]) else {
+            continue;
+        };
+        match line.split_once(' ') {
+            Some(("author", author)) => commit.author = author.to_string(),
+            Some(("author-time", time)) => commit.timestamp = time.parse().unwrap_or(0),
+            _ if line == "boundary" => commit.boundary = true,
+            _ => {}
+        }
+    }
+    blame
+}
+
+/// Parse `git diff --raw -z` output: `:<modes> <old oid> <new oid> <status>`,
+/// then the path, or the old and new path for renames and copies.
+fn parse_diff_raw(text: &str) -> Vec<StagedChange> {
+    let mut changes = Vec::new();
+    let mut fields = text.split('\0');
+    while let Some(info) = fields.next() {
+        let parts: Vec<&str> = info.trim_start_matches(':').split(' ').collect();
+        let [_, new_mode, old_oid, new_oid, status] = parts[..] else {
+            continue;
+        };
+        let two_paths = status.starts_with('R') || status.starts_with('C');
+        let Some(first) = fields.next() else {
+            break;
+        };
+        let (old_path, path) = if two_paths {
+            let Some(second) = fields.next() else {
+                break;
+            };
+            (first, second)
+        } else {
+            (first, first)
+        };
+        // Symbolic links and submodules have no source to analyze
+        if new_mode == "120000" || new_mode == "160000" {
+            continue;
+        }
+        let added = old_oid.bytes().all(|b| b == b'0');
+        changes.push(StagedChange {
+            path: path.to_string(),
+            oid: new_oid.to_string(),
+            head: (!added).then(|| (old_path.to_string(), old_oid.to_string())),
+        });
+    }
+    changes
+}
+
+/// Parse `git ls-tree -r -z` output, keeping regular files.
+fn parse_ls_tree(text: &str) -> Vec<TreeEntry> {
+    text.split('\0')
+        .filter_map(|entry| {
+            let (info, path) = entry.split_once('\t')?;
+            let mut fields = info.split(' ');
+            let (mode, kind, oid) = (fields.next()?, fields.next()?, fields.next()?);
+            (kind == "blob" && mode != "120000").then(|| TreeEntry {
+                path: path.to_string(),
+                oid: oid.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Parse `count` objects from `git cat-file --batch` output: a
+/// `<oid> <type> <size>` line, the contents, and a newline for each.
+fn parse_cat_file_batch(mut bytes: &[u8], count: usize) -> anyhow::Result<Vec<Vec<u8>>> {
+    let mut blobs = Vec::with_capacity(count);
+    for _ in 0..count {
+        let end = bytes
+            .iter()
+            .position(|&b| b == b'\n')
+            .context("truncated git cat-file output")?;
+        let header = String::from_utf8_lossy(&bytes[..end]);
+        let size: usize = match header.split(' ').nth(2).and_then(|s| s.parse().ok()) {
+            Some(size) => size,
+            None => anyhow::bail!("git cat-file could not read {}", header),
+        };
+        let start = end + 1;
+        let contents = bytes
+            .get(start..start + size)
+            .context("truncated git cat-file output")?;
+        blobs.push(contents.to_vec());
+        bytes = bytes.get(start + size + 1..).unwrap_or_default();
+    }
+    Ok(blobs)
+}
+
+/// Parse `git log --name-status` output in the format `file_log` requests.
+fn parse_file_log(text: &str) -> Vec<Revision> {
+    text.split('\x01')
+        .filter_map(|entry| {
+            let (header, body) = entry.split_once('\n').unwrap_or((entry, ""));
+            let mut fields = header.splitn(4, '\t');
+            let hash = fields.next()?.to_string();
+            let timestamp = fields.next()?.parse().ok()?;
+            let author = fields.next().unwrap_or_default().to_string();
+            let subject = fields.next().unwrap_or_default().to_string();
+            // "M\tpath", "R096\told\tnew"; merges have no status line
+            let status = body.lines().find(|line| !line.is_empty())?;
+            if status.starts_with('D') {
+                return None;
+            }
+            let path = status.rsplit('\t').next()?.to_string();
+            Some(Revision {
+                hash,
+                timestamp,
+                author,
+                subject,
+                path,
+            })
+        })
+        .collect()
+}
+
+/// Parse `git log` output in the format `log` requests.
+fn parse_log(text: &str) -> Vec<Commit> {
+    text.split('\x01')
+        .filter_map(|entry| {
+            let (header, body) = entry.split_once('\n').unwrap_or((entry, ""));
+            let mut fields = header.splitn(3, '\t');
+            let hash = fields.next()?.to_string();
+            let timestamp = fields.next()?.parse().ok()?;
+            let author = fields.next().unwrap_or_default().to_string();
+
+            // Numstat lines come first, then the patch
+            let (numstat, patch) = match body.find("diff --git ") {
+                Some(at) => body.split_at(at),
+                None => (body, ""),
+            };
+            let mut changes = parse_patch(patch);
+            for line in numstat.lines() {
+                let mut fields = line.splitn(3, '\t');
+                let (Some(added), Some(deleted), Some(path)) =
+                    (fields.next(), fields.next(), fields.next())
+                else {
+                    continue;
+                };
+                let (added, deleted) = (added.parse().unwrap_or(0), deleted.parse().unwrap_or(0));
+                match changes.iter_mut().find(|c| c.path == path) {
+                    Some(change) => {
+                        change.added = added;
+                        change.deleted = deleted;
+                    }
+                    None => changes.push(FileChange {
+                        path: path.to_string(),
+                        added,
+                        deleted,
+                        hunks: Vec::new(),
+                    }),
+                }
+            }
+            Some(Commit {
+                hash,
+                timestamp,
+                author,
+                changes,
+            })
+        })
+        .collect()
+}
+
+/// Parse a zero-context patch into per-file hunks.
+fn parse_patch(text: &str) -> Vec<FileChange> {
+    let mut changes: Vec<FileChange> = Vec::new();
+    let mut old_path = None;
+    // `---`/`+++` are only headers before the first hunk; later they are content
+    let mut in_header = false;
+    for line in text.lines() {
+        if line.starts_with("diff --git ") {
+            old_path = None;
+            in_header = true;
+        } else if !in_header {
+            if let (Some(hunk), Some(change)) = (parse_hunk_header(line), changes.last_mut()) {
+                change.hunks.push(hunk);
+            }
+        } else if let Some(path) = line.strip_prefix("--- a/") {
+            old_path = Some(path.trim_end_matches('\t').to_string());
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            let path = match path.strip_prefix("b/") {
+                Some(path) => path.trim_end_matches('\t').to_string(),
+                None => old_path.take().unwrap_or_default(),
+            };
+            changes.push(FileChange {
+                path,
+                ..FileChange::default()
+            });
+            in_header = false;
+        }
+    }
+    changes
+}
+
+/// Parse `@@ -old_start[,old_lines] +new_start[,new_lines] @@ ...`.
+fn parse_hunk_header(line: &str) -> Option<Hunk> {
+    let ranges = line.strip_prefix("@@ -")?;
+    let (old, rest) = ranges.split_once(" +")?;
+    let (new, _) = rest.split_once(" @@")?;
+    let range = |s: &str| -> Option<(usize, usize)> {
+        match s.split_once(',') {
+            Some((start, lines)) => Some((start.parse().ok()?, lines.parse().ok()?)),
+            None => Some((s.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_lines) = range(old)?;
+    let (new_start, new_lines) = range(new)?;
+    Some(Hunk {
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+    })
+}
+
+/// Map the line range `start..=end` of a file back to the file before
+/// `hunks` were applied, or `None` when every line in it was added by them.
+///
+/// Lines of pure additions are dropped from the range. A line that a hunk
+/// rewrote maps onto the lines that hunk replaced, so a range that was
+/// edited throughout still has a past. Other lines shift by the lines the
+/// earlier hunks added or removed.
+pub fn map_range_back(start: usize, end: usize, hunks: &[Hunk]) -> Option<(usize, usize)> {
+    let changed = |line: usize| {
+        hunks
+            .iter()
+            .find(|h| h.new_lines > 0 && h.new_start <= line && line < h.new_start + h.new_lines)
+    };
+    let added = |line: usize| changed(line).filter(|h| h.old_lines == 0);
+    // With zero context, hunks are never adjacent, so one step leaves the added lines
+    let start = added(start).map_or(start, |h| h.new_start + h.new_lines);
+    let end = added(end).map_or(end, |h| h.new_start - 1);
+    if start > end {
+        return None;
+    }
+    let shifted = |line: usize| {
+        let shift: isize = hunks
+            .iter()
+            .filter(|h| h.new_end() <= line)
+            .map(|h| h.old_lines as isize - h.new_lines as isize)
+            .sum();
+        (line as isize + shift) as usize
+    };
+    let start = changed(start).map_or_else(|| shifted(start), |h| h.old_start);
+    let end = changed(end).map_or_else(|| shifted(end), |h| h.old_start + h.old_lines - 1);
+    Some((start, end))
+}
+
+// TESTS
+
+/// Helpers for tests that need a real repository.
+#[cfg(test)]
+pub(crate) mod test_repo {
+    use std::path::Path;
+    use std::process::Command;
+
+    fn command(dir: &Path, author: &str) -> Command {
+        let mut command = Command::new("git");
+        command
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", &format!("user.name={author}")])
+            .args(["-c", "user.email=test@example.com"]);
+        command
+    }
+
+    /// Run git in `dir` with a fixed identity, panicking on failure.
+    pub fn git(dir: &Path, args: &[&str]) {
+        let status = command(dir, "Test").args(args).status().unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    /// Like `git`, as `author` and with `date` as both author and committer date.
+    pub fn git_as(dir: &Path, author: &str, date: &str, args: &[&str]) {
+        let status = command(dir, author)
+            .args(args)
+            .env("GIT_AUTHOR_DATE", date)
+            .env("GIT_COMMITTER_DATE", date)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hunk(old_start: usize, old_lines: usize, new_start: usize, new_lines: usize) -> Hunk {
+        Hunk {
+            old_start,
+            old_lines,
+            new_start,
+            new_lines,
+        }
+    }
+
+    #[test]
+    fn parses_log_with_numstat_and_patch() {
+        let text = "\x01c98563\t1792358885\tAda Lovelace\n\n2\t1\tsrc/f.ts\n1\t0\tg.ts\n-\t-\tlogo.png\n\n\
+diff --git a/src/f.ts b/src/f.ts\nindex de98044..a7bc997 100644\n--- a/src/f.ts\n+++ b/src/f.ts\n\
+@@ -2 +2 @@ a\n--- a/b\n+B\n@@ -3,0 +4 @@ c\n+d\n\
+diff --git a/g.ts b/g.ts\nnew file mode 100644\nindex 0000000..587be6b\n--- /dev/null\n+++ b/g.ts\n\
+@@ -0,0 +1 @@\n+x\n\
+\x0154c030\t1792300000\tAda Lovelace\n\n3\t0\told.ts\n\n\
+diff --git a/old.ts b/old.ts\ndeleted file mode 100644\n--- a/old.ts\n+++ /dev/null\n@@ -1,3 +0,0 @@\n-a\n-b\n-c\n";
+        let commits = parse_log(text);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].hash, "c98563");
+        assert_eq!(commits[0].timestamp, 1_792_358_885);
+        assert_eq!(commits[0].author, "Ada Lovelace");
+        assert_eq!(
+            commits[0].changes[0],
+            FileChange {
+                path: "src/f.ts".to_string(),
+                added: 2,
+                deleted: 1,
+                hunks: vec![hunk(2, 1, 2, 1), hunk(3, 0, 4, 1)],
+            }
+        );
+        assert_eq!(commits[0].changes[1].hunks, vec![hunk(0, 0, 1, 1)]);
+        assert_eq!(commits[0].changes[2].path, "logo.png");
+        assert_eq!(commits[1].changes[0].path, "old.ts");
+        assert_eq!(commits[1].changes[0].hunks, vec![hunk(1, 3, 0, 0)]);
+    }
+
+    #[test]
+    fn parses_file_log_with_renames() {
+        let text = "\x01c98563\t1792358885\tAda Lovelace\tSplit checkout\n\nR087\tsrc/old.ts\tsrc/new.ts\n\
+\x01a1b2c3\t1792300000\tGrace Hopper\tMerge branch\n\
+\x01d4e5f6\t1792200000\tGrace Hopper\tAdd orders\n\nA\tsrc/old.ts\n";
+        let revisions = parse_file_log(text);
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(revisions[0].path, "src/new.ts");
+        assert_eq!(revisions[0].subject, "Split checkout");
+        assert_eq!(revisions[0].author, "Ada Lovelace");
+        assert_eq!(revisions[1].hash, "d4e5f6");
+        assert_eq!(revisions[1].path, "src/old.ts");
+        assert_eq!(revisions[1].timestamp, 1_792_200_000);
+    }
+
+    #[test]
+    fn parses_ls_tree_blobs() {
+        let text = "100644 blob 1111\tsrc/a.ts\x00120000 blob 2222\tsrc/link.ts\x00\
+160000 commit 3333\tvendor/lib\x00100755 blob 4444\tbin/run tool.js\x00";
+        let entries = parse_ls_tree(text);
+        let paths: Vec<_> = entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, ["src/a.ts", "bin/run tool.js"]);
+        assert_eq!(entries[1].oid, "4444");
+    }
+
+    #[test]
+    fn parses_diff_raw() {
+        let zero = "0".repeat(40);
+        let (a, b, c) = ("a".repeat(40), "b".repeat(40), "c".repeat(40));
+        let text = format!(
+            ":100644 100644 {a} {b} M\0src/a.ts\0\
+:000000 100644 {zero} {c} A\0src/new.ts\0\
+:100644 100644 {b} {c} R087\0src/old.ts\0src/moved.ts\0\
+:120000 120000 {a} {b} M\0src/link.ts\0"
+        );
+        let changes = parse_diff_raw(&text);
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0].path, "src/a.ts");
+        assert_eq!(changes[0].oid, b);
+        assert_eq!(changes[0].head, Some(("src/a.ts".to_string(), a.clone())));
+        assert_eq!(changes[1].head, None);
+        assert_eq!(changes[2].path, "src/moved.ts");
+        assert_eq!(changes[2].head, Some(("src/old.ts".to_string(), b.clone())));
+    }
+
+    #[test]
+    fn parses_cat_file_batch() {
+        let bytes = b"1111 blob 5\nab\ncd\n2222 blob 0\n\n";
+        let blobs = parse_cat_file_batch(bytes, 2).unwrap();
+        assert_eq!(blobs, [b"ab\ncd".to_vec(), Vec::new()]);
+
+        let err = parse_cat_file_batch(b"3333 missing\n", 1).unwrap_err();
+        assert!(err.to_string().contains("could not read 3333 missing"));
+        assert!(parse_cat_file_batch(b"1111 blob 9\nab\n", 1).is_err());
+    }
+
+    #[test]
+    fn parses_blame_porcelain() {
+        let text = "\
+54c030f4055acf8c48837a373ad75578e08e96f8 1 1 1
+author Ada Lovelace
+author-mail <ada@example.com>
+author-time 946684800
+summary one
+boundary
+filename f.ts
+\ta
+c985631dd967ab3c26c0eefaed74df1dcca4b789 2 2 2
+author Grace Hopper
+author-time 1792358885
+summary two
+filename f.ts
+\tB
+c985631dd967ab3c26c0eefaed74df1dcca4b789 3 3
+\tc
+0000000000000000000000000000000000000000 5 5 1
+author Not Committed Yet
+author-time 1792359284
+filename f.ts
+\te
+";
+        let blame = parse_blame(text);
+        assert_eq!(blame.commits.len(), 3);
+        let old = blame.commit_at(1).unwrap();
+        assert_eq!(old.author, "Ada Lovelace");
+        assert_eq!(old.timestamp, 946_684_800);
+        assert!(old.boundary);
+        let two = blame.commit_at(3).unwrap();
+        assert_eq!(two.author, "Grace Hopper");
+        assert!(!two.boundary);
+        assert_eq!(blame.commit_at(2), Some(two));
+        assert!(blame.commit_at(4).is_none());
+        assert!(blame.commit_at(5).unwrap().is_uncommitted());
+        assert!(!two.is_uncommitted());
+    }
+
+    #[test]
+    fn hunks_touch_overlapping_ranges() {
+        assert!(hunk(2, 1, 2, 1).touches(1, 3));
+        assert!(!hunk(2, 1, 5, 2).touches(1, 4));
+        assert!(hunk(2, 1, 5, 2).touches(6, 9));
+        // Deleting lines from between lines 5 and 6
+        assert!(hunk(6, 2, 5, 0).touches(3, 8));
+        assert!(!hunk(6, 2, 8, 0).touches(3, 8));
+    }
+
+    #[test]
+    fn maps_ranges_back_through_hunks() {
+        // Two lines inserted before the range, one removed inside it
+        let hunks = [hunk(2, 0, 3, 2), hunk(10, 1, 11, 0)];
+        assert_eq!(map_range_back(6, 14, &hunks), Some((4, 13)));
+        // A range that starts on added lines begins after them
+        assert_eq!(map_range_back(3, 8, &hunks), Some((3, 6)));
+        // A range made only of added lines did not exist before
+        assert_eq!(map_range_back(3, 4, &hunks), None);
+        assert_eq!(map_range_back(1, 5, &[hunk(0, 0, 1, 5)]), None);
+    }
+
+    #[test]
+    fn maps_rewritten_lines_onto_the_lines_they_replaced() {
+        // A function on lines 4-8 rewritten as lines 4-10
+        let hunks = [hunk(4, 5, 4, 7)];
+        assert_eq!(map_range_back(4, 10, &hunks), Some((4, 8)));
+        // Partly rewritten: the untouched tail shifts
+        let hunks = [hunk(2, 1, 2, 3), hunk(6, 0, 9, 1)];
+        assert_eq!(map_range_back(3, 12, &hunks), Some((2, 9)));
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::Context;
+
+use crate::analyzer::Report;
+use crate::git;
+use crate::metrics::scoring::compute_project_score;
+
+/// Default history location, relative to the working directory.
+pub const DEFAULT_HISTORY_FILE: &str = ".complexityguard/history.jsonl";
+
+/// Summary of one run, stored as one line of the history file.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct HistoryRecord {
+    /// Unix timestamp of the run
+    pub timestamp: u64,
+    /// Commit checked out during the run, when run inside a git repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Branch checked out during the run; absent for a detached HEAD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// ComplexityGuard version that recorded the run
+    pub version: String,
+    pub health_score: f64,
+    pub files: usize,
+    pub functions: usize,
+    pub errors: u32,
+    pub warnings: u32,
+    /// Health score of each directory that directly contains analyzed files
+    #[serde(default)]
+    pub directories: BTreeMap<String, f64>,
+}
+
+impl HistoryRecord {
+    /// Summarize `report`, timestamped now and without git information.
+    pub fn new(report: &Report) -> Self {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        HistoryRecord {
+            timestamp,
+            commit: None,
+            branch: None,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            health_score: report.health_score(),
+            files: report.files.len(),
+            functions: report.function_count(),
+            errors: report.error_count(),
+            warnings: report.warning_count(),
+            directories: directory_scores(report),
+        }
+    }
+
+    /// Fill in the commit and branch checked out in `dir`, if it is a git repository.
+    pub fn with_git(mut self, dir: &Path) -> Self {
+        let output = |args: &[&str]| {
+            git::run(dir, args)
+                .ok()
+                .map(|out| out.trim().to_string())
+                .filter(|out| !out.is_empty())
+        };
+        self.commit = output(&["rev-parse", "HEAD"]);
+        self.branch = output(&["rev-parse", "--abbrev-ref", "HEAD"]).filter(|b| b != "HEAD");
+        self
+    }
+}
+
+/// Health score per directory, weighting file scores by function count like
+/// the project score. Files at the top level are under `.`.
+fn directory_scores(report: &Report) -> BTreeMap<String, f64> {
+    let mut groups: BTreeMap<String, (Vec<f64>, Vec<u32>)> = BTreeMap::new();
+    for file in &report.files {
+        let dir = file
+            .path
+            .parent()
+            .map(|p| p.display().to_string())
+            .filter(|p| !p.is_empty())
+            .unwrap_or_else(|| ".".to_string());
+        let (scores, counts) = groups.entry(dir).or_default();
+        scores.push(file.file_score);
+        counts.push(file.functions.len() as u32);
+    }
+    groups
+        .into_iter()
+        .map(|(dir, (scores, counts))| (dir, compute_project_score(&scores, &counts)))
+        .collect()
+}
+
+/// Append `record` as one JSON line to the history file at `path`,
+/// creating the file and its directory if needed.
+pub fn append_record(path: &Path, record: &HistoryRecord) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Load every record in the history file at `path`, oldest first.
+///
+/// A missing file is an empty history. Blank lines are ignored; any other
+/// line that is not a record is an error naming the line.
+pub fn load_history(path: &Path) -> anyhow::Result<Vec<HistoryRecord>> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+    };
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .with_context(|| format!("invalid history record at {}:{}", path.display(), i + 1))
+        })
+        .collect()
+}
+
+/// Format a Unix timestamp as a UTC `YYYY-MM-DD` date.
+pub fn format_date(timestamp: u64) -> String {
+    // Civil date from days since the epoch (Howard Hinnant's algorithm)
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Analyzer;
+
+    fn report() -> Report {
+        Analyzer::new()
+            .source("index.ts", "export function a(x: number) { return x; }")
+            .source(
+                "src/api/users.ts",
+                "export function list(xs: number[]) { return xs.map((x) => x * 2); }",
+            )
+            .source(
+                "src/api/orders.ts",
+                "export function total(xs: number[]) { let s = 0; for (const x of xs) { if (x) { s += x; } } return s; }",
+            )
+            .run()
+            .unwrap()
+    }
+
+    #[test]
+    fn record_summarizes_report() {
+        let report = report();
+        let record = HistoryRecord::new(&report);
+        assert_eq!(record.files, 3);
+        assert_eq!(record.functions, 3);
+        assert_eq!(record.health_score, report.health_score());
+        let dirs: Vec<_> = record.directories.keys().map(String::as_str).collect();
+        assert_eq!(dirs, vec![".", "src/api"]);
+        assert!(record.commit.is_none());
+    }
+
+    #[test]
+    fn appends_and_loads_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested/history.jsonl");
+        assert!(load_history(&path).unwrap().is_empty());
+
+        let mut first = HistoryRecord::new(&report());
+        first.commit = Some("abc123".to_string());
+        let mut second = first.clone();
+        second.timestamp += 60;
+        append_record(&path, &first).unwrap();
+        append_record(&path, &second).unwrap();
+
+        assert_eq!(load_history(&path).unwrap(), vec![first, second]);
+
+        std::fs::write(&path, "{\"timestamp\": 1}\n").unwrap();
+        let err = load_history(&path).unwrap_err();
+        assert!(format!("{err:#}").contains("history.jsonl:1"));
+    }
+
+    #[test]
+    fn formats_dates() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_792_281_600), "2026-10-18");
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+use crate::git::{self, map_range_back, FileChange};
+use crate::types::FileAnalysisResult;
+
+/// Default history window, in any form `git log --since` accepts.
+pub const DEFAULT_SINCE: &str = "1 year ago";
+
+/// Files and functions ranked by how often they change times how complex they are.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Hotspots {
+    /// The window the history was read over, as given to `git log --since`
+    pub since: String,
+    /// Commits in the window that changed a file of an analyzed type
+    pub commits: usize,
+    /// Changed files with a health score below 100, highest score first
+    pub files: Vec<FileHotspot>,
+    /// Changed functions with non-zero cognitive complexity, highest score first
+    pub functions: Vec<FunctionHotspot>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FileHotspot {
+    pub path: String,
+    /// Commits in the window that changed the file
+    pub commits: u32,
+    pub lines_added: u32,
+    pub lines_deleted: u32,
+    pub health_score: f64,
+    /// `commits × (100 − health_score)`
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FunctionHotspot {
+    pub path: String,
+    /// Qualified function name
+    pub name: String,
+    pub start_line: usize,
+    /// Commits in the window that changed a line of the function
+    pub changes: u32,
+    pub cognitive: u32,
+    pub health_score: f64,
+    /// `changes × cognitive`
+    pub score: f64,
+}
+
+/// A file's change counts and its functions' line ranges, followed back
+/// through history one change at a time.
+struct Tracked<'a> {
+    file: &'a FileAnalysisResult,
+    commits: u32,
+    added: u32,
+    deleted: u32,
+    /// Function ranges as of the change being applied; `None` once the
+    /// function did not exist yet
+    ranges: Vec<Option<(usize, usize)>>,
+    changes: Vec<u32>,
+}
+
+impl Tracked<'_> {
+    /// Step back over `change`, counting it when it is a commit in the window
+    /// rather than an uncommitted edit.
+    fn apply(&mut self, change: &FileChange, count: bool) {
+        if count {
+            self.commits += 1;
+            self.added += change.added;
+            self.deleted += change.deleted;
+        }
+        for (range, changes) in self.ranges.iter_mut().zip(&mut self.changes) {
+            if let Some((start, end)) = *range {
+                if count && change.hunks.iter().any(|h| h.touches(start, end)) {
+                    *changes += 1;
+                }
+                *range = map_range_back(start, end, &change.hunks);
+            }
+        }
+    }
+}
+
+/// Rank `files` by their change frequency since `since` in the git
+/// repository containing `dir`.
+///
+/// Functions are located in each past commit by following their current line
+/// ranges back through the diffs, starting from uncommitted changes, so a
+/// function counts as changed only when one of its own lines was. Files
+/// outside the repository are ignored.
+pub fn analyze_hotspots(
+    files: &[FileAnalysisResult],
+    dir: &Path,
+    since: &str,
+) -> anyhow::Result<Hotspots> {
+    let root = git::repo_root(dir)?;
+    let mut tracked: HashMap<String, Tracked> = files
+        .iter()
+        .filter_map(|file| {
+            let path = git::repo_path(&root, &file.path)?;
+            let tracked = Tracked {
+                file,
+                commits: 0,
+                added: 0,
+                deleted: 0,
+                ranges: file
+                    .functions
+                    .iter()
+                    .map(|f| Some((f.start_line, f.end_line)))
+                    .collect(),
+                changes: vec![0; file.functions.len()],
+            };
+            Some((path, tracked))
+        })
+        .collect();
+
+    // Only read the history of the file types that were analyzed
+    let pathspecs: Vec<String> = files
+        .iter()
+        .filter_map(|f| f.path.extension())
+        .map(|ext| format!("*.{}", ext.to_string_lossy()))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    if pathspecs.is_empty() {
+        return Ok(Hotspots {
+            since: since.to_string(),
+            commits: 0,
+            files: Vec::new(),
+            functions: Vec::new(),
+        });
+    }
+
+    for change in git::uncommitted(&root, &pathspecs)? {
+        if let Some(t) = tracked.get_mut(&change.path) {
+            t.apply(&change, false);
+        }
+    }
+    let commits = git::log(&root, since, &pathspecs)?;
+    for commit in &commits {
+        for change in &commit.changes {
+            if let Some(t) = tracked.get_mut(&change.path) {
+                t.apply(change, true);
+            }
+        }
+    }
+
+    let mut file_hotspots = Vec::new();
+    let mut function_hotspots = Vec::new();
+    for t in tracked.values() {
+        let path = t.file.path.display().to_string();
+        if t.commits > 0 && t.file.file_score < 100.0 {
+            file_hotspots.push(FileHotspot {
+                path: path.clone(),
+                commits: t.commits,
+                lines_added: t.added,
+                lines_deleted: t.deleted,
+                health_score: t.file.file_score,
+                score: t.commits as f64 * (100.0 - t.file.file_score),
+            });
+        }
+        for (func, &changes) in t.file.functions.iter().zip(&t.changes) {
+            if changes > 0 && func.cognitive > 0 {
+                function_hotspots.push(FunctionHotspot {
+                    path: path.clone(),
+                    name: func.qualified_name.clone(),
+                    start_line: func.start_line,
+                    changes,
+                    cognitive: func.cognitive,
+                    health_score: func.health_score,
+                    score: f64::from(changes * func.cognitive),
+                });
+            }
+        }
+    }
+    file_hotspots.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.path.cmp(&b.path)));
+    function_hotspots.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(a.path.cmp(&b.path))
+            .then(a.start_line.cmp(&b.start_line))
+    });
+
+    Ok(Hotspots {
+        since: since.to_string(),
+        commits: commits.len(),
+        files: file_hotspots,
+        functions: function_hotspots,
+    })
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_repo::git;
+    use crate::Analyzer;
+
+    fn analyze(dir: &Path) -> crate::Report {
+        let path = dir.join("calc.ts");
+        let source = std::fs::read_to_string(&path).unwrap();
+        Analyzer::new().source(path, source).run().unwrap()
+    }
+
+    fn commit(dir: &Path, source: &str) {
+        std::fs::write(dir.join("calc.ts"), source).unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "change"]);
+    }
+
+    const HOT: &str =
+        "function hot(a: number) {\n  if (a > 0) {\n    return 1;\n  }\n  return 0;\n}\n";
+    const COLD: &str =
+        "function cold(b: number) {\n  if (b) {\n    return b;\n  }\n  return 2;\n}\n";
+
+    #[test]
+    fn counts_changes_per_function() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        commit(dir.path(), &format!("{HOT}\n{COLD}"));
+        commit(dir.path(), &format!("{}\n{COLD}", HOT.replace("1;", "10;")));
+        let hot = HOT.replace("0;", "20;").replace("1;", "10;");
+        commit(dir.path(), &format!("{hot}\n{COLD}"));
+        // Uncommitted lines above both functions shift them down
+        std::fs::write(
+            dir.path().join("calc.ts"),
+            format!("// header\n// more\n{hot}\n{COLD}"),
+        )
+        .unwrap();
+
+        let report = analyze(dir.path());
+        let hotspots = analyze_hotspots(&report.files, dir.path(), DEFAULT_SINCE).unwrap();
+
+        assert_eq!(hotspots.commits, 3);
+        let names: Vec<_> = hotspots
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.changes))
+            .collect();
+        // hot was added and then changed twice; cold only added
+        assert_eq!(names, vec![("hot", 3), ("cold", 1)]);
+        assert_eq!(
+            hotspots.functions[0].score,
+            3.0 * hotspots.functions[0].cognitive as f64
+        );
+
+        let file = &hotspots.files[0];
+        assert_eq!(file.commits, 3);
+        assert_eq!(file.lines_added, 13 + 2);
+        assert_eq!(file.lines_deleted, 2);
+    }
+
+    #[test]
+    fn counts_changes_that_rewrite_a_whole_function() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        commit(dir.path(), COLD);
+        commit(dir.path(), &format!("{HOT}\n{COLD}"));
+        let rewritten = "function hot(x: string) { if (x) { return 3; } return 4; }\n";
+        commit(dir.path(), &format!("{rewritten}\n{COLD}"));
+
+        let report = analyze(dir.path());
+        let hotspots = analyze_hotspots(&report.files, dir.path(), DEFAULT_SINCE).unwrap();
+
+        let names: Vec<_> = hotspots
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.changes))
+            .collect();
+        // hot was added and then rewritten line by line
+        assert_eq!(names, vec![("hot", 2), ("cold", 1)]);
+    }
+
+    #[test]
+    fn reads_history_with_non_utf8_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        // A latin-1 comment, as older files sometimes have
+        let mut latin1 = b"// caf\xe9\n".to_vec();
+        latin1.extend_from_slice(HOT.as_bytes());
+        std::fs::write(dir.path().join("calc.ts"), latin1).unwrap();
+        git(dir.path(), &["add", "."]);
+        git(dir.path(), &["commit", "-q", "-m", "latin-1"]);
+        commit(
+            dir.path(),
+            &format!("// cafe\n{}", HOT.replace("1;", "10;")),
+        );
+
+        let report = analyze(dir.path());
+        let hotspots = analyze_hotspots(&report.files, dir.path(), DEFAULT_SINCE).unwrap();
+
+        assert_eq!(hotspots.commits, 2);
+        assert_eq!(hotspots.functions[0].name, "hot");
+        assert_eq!(hotspots.functions[0].changes, 2);
+    }
+
+    #[test]
+    fn fails_outside_a_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("calc.ts"), HOT).unwrap();
+        let report = analyze(dir.path());
+        assert!(analyze_hotspots(&report.files, dir.path(), DEFAULT_SINCE).is_err());
+    }
+}
@@ -1,6 +1,21 @@
+pub mod analyzer;
+pub mod blame;
 pub mod cli;
+pub mod codeowners;
+pub mod compare;
+pub mod directories;
+pub mod function_history;
+pub mod git;
+pub mod history;
+pub mod hotspots;
+pub mod merge;
 pub mod metrics;
+pub mod modules;
 pub mod output;
 pub mod parser;
 pub mod pipeline;
+pub mod tsconfig;
 pub mod types;
+pub mod workspaces;
+
+pub use analyzer::{Analyzer, Report, ReportViolation};
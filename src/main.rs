@@ -1,16 +1,22 @@
+use std::path::Path;
+
 use clap::Parser;
 use complexity_guard::cli::{
-    config_defaults, discover_config, merge_args_into_config, resolve_config, Args,
+    config_defaults, discover_config, merge_args_into_config, resolve_config, Args, Command,
+    Config, ResolvedConfig,
 };
-use complexity_guard::metrics::duplication::detect_duplication;
-use complexity_guard::output::console::{function_violations, Severity};
+use complexity_guard::compare::{compare_reports, compare_staged, Comparison};
+use complexity_guard::function_history::{function_history, parse_target};
+use complexity_guard::history::load_history;
+use complexity_guard::merge::{merge_reports, Shard};
+use complexity_guard::output::json_output::load_json_report;
 use complexity_guard::output::{
-    determine_exit_code, render_console, render_html, render_json, render_sarif, ExitCode,
-};
-use complexity_guard::types::{
-    AnalysisConfig, CognitiveConfig, CyclomaticConfig, DuplicationConfig, DuplicationResult,
-    ScoringThresholds, ScoringWeights,
+    render_compare_console, render_compare_json, render_compare_markdown,
+    render_function_history_console, render_function_history_json, render_trend_console,
+    render_trend_json, ExitCode,
 };
+use complexity_guard::pipeline::AnalysisCache;
+use complexity_guard::{Analyzer, Report};
 
 fn main() {
     let args = Args::parse();
@@ -21,49 +27,10 @@ fn main() {
         std::process::exit(ExitCode::Success as i32);
     }
 
-    // Discover and load config file
+    // Discover and load config file, overlaid on the built-in defaults
     let mut config = config_defaults();
-
     match discover_config(args.config.as_deref()) {
-        Ok(Some(file_config)) => {
-            // Overlay file config on top of defaults
-            if let Some(output) = file_config.output {
-                let default_output = config.output.get_or_insert_with(Default::default);
-                if let Some(fmt) = output.format {
-                    default_output.format = Some(fmt);
-                }
-                if let Some(file) = output.file {
-                    default_output.file = Some(file);
-                }
-            }
-            if let Some(analysis) = file_config.analysis {
-                let default_analysis = config.analysis.get_or_insert_with(Default::default);
-                if let Some(metrics) = analysis.metrics {
-                    default_analysis.metrics = Some(metrics);
-                }
-                if let Some(thresholds) = analysis.thresholds {
-                    default_analysis.thresholds = Some(thresholds);
-                }
-                if let Some(v) = analysis.no_duplication {
-                    default_analysis.no_duplication = Some(v);
-                }
-                if let Some(v) = analysis.duplication_enabled {
-                    default_analysis.duplication_enabled = Some(v);
-                }
-                if let Some(t) = analysis.threads {
-                    default_analysis.threads = Some(t);
-                }
-            }
-            if let Some(files) = file_config.files {
-                config.files = Some(files);
-            }
-            if let Some(weights) = file_config.weights {
-                config.weights = Some(weights);
-            }
-            if let Some(baseline) = file_config.baseline {
-                config.baseline = Some(baseline);
-            }
-        }
+        Ok(Some(file_config)) => config.overlay(file_config),
         Ok(None) => {
             // No config file found — use defaults only
         }
@@ -76,147 +43,314 @@ fn main() {
     // Apply CLI overrides on top of config file values
     merge_args_into_config(&args, &mut config);
 
-    // Apply color flags to the resolved config
-    let color_override = if args.no_color {
+    if let Some(Command::Compare { old, new }) = &args.command {
+        compare(&load_shard(old), &load_shard(new), &config, &args);
+    }
+
+    if let Some(Command::Trend { last }) = &args.command {
+        trend(*last, &config, &args);
+    }
+
+    if let Some(Command::History { target }) = &args.command {
+        history(target, &config, &args);
+    }
+
+    if let Some(Command::Merge { reports }) = &args.command {
+        let shards: Vec<Shard> = reports.iter().map(|path| load_shard(path)).collect();
+        match merge_reports(&shards, &config) {
+            Ok(report) => finish(report, &args),
+            Err(e) => {
+                eprintln!("Error merging reports: {:#}", e);
+                std::process::exit(ExitCode::ConfigError as i32);
+            }
+        }
+    }
+
+    if args.staged {
+        staged(&config, &args);
+    }
+
+    // A tsconfig project lists its own files; paths only narrow them down
+    let has_project = config.files.as_ref().is_some_and(|f| f.project.is_some());
+    let analyzer = Analyzer::new().config(config);
+
+    // Handle --clear-cache: remove the configured cache directory and exit
+    if args.clear_cache {
+        let resolved = analyzer.resolved_config();
+        let dir = std::path::Path::new(&resolved.cache_dir);
+        match AnalysisCache::clear(dir) {
+            Ok(()) => {
+                if !args.quiet {
+                    println!("Cleared analysis cache at {}", dir.display());
+                }
+                std::process::exit(ExitCode::Success as i32);
+            }
+            Err(e) => {
+                eprintln!("Error clearing cache at {}: {}", dir.display(), e);
+                std::process::exit(ExitCode::ConfigError as i32);
+            }
+        }
+    }
+
+    let analyzer = match &args.rev {
+        Some(rev) => analyzer.rev(rev),
+        None => analyzer,
+    };
+    let analyzer = match &args.owner {
+        Some(owner) => analyzer.owner(owner),
+        None => analyzer,
+    };
+    let analyzer = match &args.package {
+        Some(package) => analyzer.package(package),
+        None => analyzer,
+    };
+
+    // Default to "." when no paths or project provided
+    let analyzer = if args.paths.is_empty() && !has_project {
+        analyzer.path(".")
+    } else {
+        analyzer.paths(&args.paths)
+    };
+
+    match analyzer.run() {
+        Ok(report) => finish(report, &args),
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            std::process::exit(ExitCode::ConfigError as i32);
+        }
+    }
+}
+
+/// Load a saved JSON report, exiting with a config error if it cannot be used.
+fn load_shard(path: &Path) -> Shard {
+    match load_json_report(path) {
+        Ok(report) => Shard {
+            name: path.display().to_string(),
+            report,
+        },
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(ExitCode::ConfigError as i32);
+        }
+    }
+}
+
+/// Compare two reports, render the differences and exit non-zero on regressions.
+fn compare(old: &Shard, new: &Shard, config: &Config, args: &Args) -> ! {
+    let comparison = match compare_reports(old, new, config) {
+        Ok(comparison) => comparison,
+        Err(e) => {
+            eprintln!("Error comparing reports: {:#}", e);
+            std::process::exit(ExitCode::ConfigError as i32);
+        }
+    };
+    render_comparison(&comparison, "compare", config, args);
+}
+
+/// Compare the staged changes with HEAD and exit non-zero when they add or
+/// worsen violations.
+fn staged(config: &Config, args: &Args) -> ! {
+    let paths = if args.paths.is_empty() {
+        vec![Path::new(".").to_path_buf()]
+    } else {
+        args.paths.clone()
+    };
+    match compare_staged(Path::new("."), &paths, config) {
+        Ok(comparison) => render_comparison(&comparison, "--staged", config, args),
+        Err(e) => {
+            eprintln!("Error checking staged changes: {:#}", e);
+            std::process::exit(ExitCode::ConfigError as i32);
+        }
+    }
+}
+
+/// Apply `--color`/`--no-color`, `--quiet` and `--verbose`, which only affect
+/// rendering, to `config`.
+fn apply_display_flags(config: &mut ResolvedConfig, args: &Args) {
+    config.color = if args.no_color {
         Some(false)
     } else if args.color {
         Some(true)
     } else {
         None
     };
+    config.quiet = args.quiet;
+    config.verbose = args.verbose;
+}
 
-    // Resolve the final config (non-optional, defaults applied)
-    let mut resolved = resolve_config(&config);
-    resolved.color = color_override;
-    resolved.quiet = args.quiet;
-    resolved.verbose = args.verbose;
+/// Render a comparison in the configured format and exit with its exit code.
+fn render_comparison(comparison: &Comparison, command: &str, config: &Config, args: &Args) -> ! {
+    let mut resolved = resolve_config(config);
+    apply_display_flags(&mut resolved, args);
 
-    // Default to "." when no paths provided
-    let input_paths: Vec<std::path::PathBuf> = if args.paths.is_empty() {
-        vec![std::path::PathBuf::from(".")]
-    } else {
-        args.paths.clone()
+    let output_result = match resolved.format.as_str() {
+        "json" => render_compare_json(comparison).map(Some),
+        "markdown" => Ok(Some(render_compare_markdown(comparison))),
+        "console" => {
+            render_compare_console(comparison, &resolved, &mut std::io::stdout()).map(|_| None)
+        }
+        format => {
+            eprintln!(
+                "Error: {} supports console, markdown and json output, not '{}'",
+                command, format
+            );
+            std::process::exit(ExitCode::ConfigError as i32);
+        }
     };
+    write_output(output_result, resolved.output_file.as_deref());
+    std::process::exit(comparison.exit_code(args.fail_on.as_deref()) as i32);
+}
+
+/// Render the recorded history, optionally limited to the last `last` runs.
+fn trend(last: Option<usize>, config: &Config, args: &Args) -> ! {
+    let mut resolved = resolve_config(config);
+    apply_display_flags(&mut resolved, args);
 
-    // Extract include/exclude patterns from merged config
-    let include_patterns: Vec<String> = config
-        .files
-        .as_ref()
-        .and_then(|f| f.include.clone())
-        .unwrap_or_default();
-    let exclude_patterns: Vec<String> = config
-        .files
-        .as_ref()
-        .and_then(|f| f.exclude.clone())
-        .unwrap_or_default();
-
-    // Discover files
-    let discovered = match complexity_guard::pipeline::discover_files(
-        &input_paths,
-        &include_patterns,
-        &exclude_patterns,
-    ) {
-        Ok(files) => files,
+    let mut history = match load_history(Path::new(&resolved.history_file)) {
+        Ok(history) => history,
         Err(e) => {
-            eprintln!("Error discovering files: {}", e);
+            eprintln!("Error: {:#}", e);
             std::process::exit(ExitCode::ConfigError as i32);
         }
     };
+    if let Some(last) = last {
+        history.drain(..history.len().saturating_sub(last));
+    }
 
-    // Build AnalysisConfig from resolved config
-    let analysis_config = build_analysis_config(&config, &resolved);
-
-    // Parallel analysis
-    let start = std::time::Instant::now();
-    let (files, has_parse_errors, skipped) = complexity_guard::pipeline::analyze_files_parallel(
-        &discovered,
-        &analysis_config,
-        resolved.threads,
-    );
-    let elapsed_ms = start.elapsed().as_millis() as u64;
-
-    // Duplication detection (post-parallel, gated by flag)
-    let duplication_result: Option<DuplicationResult> = {
-        let dup_enabled = config
-            .analysis
-            .as_ref()
-            .and_then(|a| a.duplication_enabled)
-            .unwrap_or(false);
-        let no_dup = config
-            .analysis
-            .as_ref()
-            .and_then(|a| a.no_duplication)
-            .unwrap_or(false);
-
-        if dup_enabled && !no_dup {
-            let file_tokens: Vec<&[_]> = files.iter().map(|f| f.tokens.as_slice()).collect();
-            Some(detect_duplication(
-                &file_tokens,
-                &analysis_config.duplication,
-            ))
-        } else {
-            None
+    let output_result = match resolved.format.as_str() {
+        "json" => render_trend_json(&history).map(Some),
+        "console" => {
+            render_trend_console(&history, &resolved, &mut std::io::stdout()).map(|_| None)
+        }
+        format => {
+            eprintln!(
+                "Error: trend supports console and json output, not '{}'",
+                format
+            );
+            std::process::exit(ExitCode::ConfigError as i32);
         }
     };
+    write_output(output_result, resolved.output_file.as_deref());
+    std::process::exit(ExitCode::Success as i32);
+}
 
-    // Count violations for exit code
-    let (mut error_count, mut warning_count): (u32, u32) = (0, 0);
-    for file in &files {
-        for func in &file.functions {
-            let violations = function_violations(func, &resolved);
-            for v in &violations {
-                match v.severity {
-                    Severity::Error => error_count += 1,
-                    Severity::Warning => warning_count += 1,
-                }
-            }
+/// Render the metrics of one function in every revision of its file.
+fn history(target: &str, config: &Config, args: &Args) -> ! {
+    let Some((path, function)) = parse_target(target) else {
+        eprintln!(
+            "Error: expected <path>::<function>, e.g. src/orders.ts::OrderService.checkout, not '{}'",
+            target
+        );
+        std::process::exit(ExitCode::ConfigError as i32);
+    };
+    let history = match function_history(Path::new("."), Path::new(path), function, config) {
+        Ok(history) => history,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            std::process::exit(ExitCode::ConfigError as i32);
+        }
+    };
+    let mut resolved = resolve_config(config);
+    apply_display_flags(&mut resolved, args);
+
+    let output_result = match resolved.format.as_str() {
+        "json" => render_function_history_json(&history).map(Some),
+        "console" => render_function_history_console(&history, &resolved, &mut std::io::stdout())
+            .map(|_| None),
+        format => {
+            eprintln!(
+                "Error: history supports console and json output, not '{}'",
+                format
+            );
+            std::process::exit(ExitCode::ConfigError as i32);
+        }
+    };
+    write_output(output_result, resolved.output_file.as_deref());
+    std::process::exit(ExitCode::Success as i32);
+}
+
+/// Render `report` in the requested format and exit with its exit code.
+fn finish(mut report: Report, args: &Args) -> ! {
+    apply_display_flags(&mut report.config, args);
+
+    // Cache stats go to stderr so they never mix with machine-readable output
+    if let (Some(stats), true) = (report.cache_stats, args.verbose) {
+        eprintln!(
+            "Cache: {} hits, {} misses ({})",
+            stats.hits, stats.misses, report.config.cache_dir
+        );
+    }
+
+    // Git-based extras never fail the analysis itself. Hotspots and blame
+    // follow working-tree lines, which a --rev report does not have.
+    if report.config.hotspots_enabled {
+        if report.commit.is_some() {
+            eprintln!("Warning: --hotspots is not supported with --rev, skipping");
+        } else if let Err(e) = report.analyze_hotspots() {
+            eprintln!("Warning: failed to analyze hotspots: {:#}", e);
+        }
+    }
+    if report.config.blame_enabled {
+        if report.commit.is_some() {
+            eprintln!("Warning: --blame is not supported with --rev, skipping");
+        } else if let Err(e) = report.blame_violations() {
+            eprintln!("Warning: failed to blame violations: {:#}", e);
+        }
+    }
+    if report.config.owners_enabled {
+        if let Err(e) = report.attribute_owners() {
+            eprintln!("Warning: failed to attribute owners: {:#}", e);
+        }
+    }
+    if report.config.packages_enabled {
+        if let Err(e) = report.group_packages() {
+            eprintln!("Warning: failed to group packages: {:#}", e);
+        }
+    }
+    if report.config.history_enabled {
+        if let Err(e) = report.record_history() {
+            eprintln!("Warning: failed to record history: {:#}", e);
+        }
+    }
+
+    // The module graph is written next to the report, in its own format
+    if let Some(path) = &report.config.modules_graph {
+        if let Err(e) = report.write_module_graph(Path::new(path)) {
+            eprintln!("Error writing module graph: {:#}", e);
+            std::process::exit(ExitCode::ConfigError as i32);
         }
     }
 
     // Render output in the requested format
-    let output_result: Result<Option<String>, anyhow::Error> = match resolved.format.as_str() {
-        "json" => render_json(
-            &files,
-            duplication_result.as_ref(),
-            &resolved,
-            elapsed_ms,
-            &skipped,
-        )
-        .map(Some),
-        "sarif" => render_sarif(&files, duplication_result.as_ref(), &resolved, &skipped).map(Some),
-        "html" => render_html(
-            &files,
-            duplication_result.as_ref(),
-            &resolved,
-            elapsed_ms,
-            &skipped,
-        )
-        .map(Some),
-        _ => {
+    let output_result: Result<Option<String>, anyhow::Error> = match report.config.format.as_str() {
+        "json" => report.render_json().map(Some),
+        "sarif" => report.render_sarif().map(Some),
+        "html" => report.render_html().map(Some),
+        format => {
             // console format (default) and unknown formats fall through to console
-            if resolved.format != "console" {
-                eprintln!(
-                    "Warning: unknown format '{}', using console",
-                    resolved.format
-                );
-            }
-            match render_console(
-                &files,
-                duplication_result.as_ref(),
-                &resolved,
-                &mut std::io::stdout(),
-                &skipped,
-            ) {
-                Ok(_) => Ok(None), // console writes directly to stdout
-                Err(e) => Err(e),
+            if format != "console" {
+                eprintln!("Warning: unknown format '{}', using console", format);
             }
+            // console writes directly to stdout
+            report.render_console(&mut std::io::stdout()).map(|_| None)
         }
     };
 
+    write_output(output_result, report.config.output_file.as_deref());
+
+    // Determine exit code from actual analysis results
+    let exit_code = report.exit_code(args.fail_on.as_deref());
+    std::process::exit(exit_code as i32);
+}
+
+/// Write rendered output to `output_file`, or stdout when unset.
+///
+/// `None` means the renderer already wrote to stdout (console format).
+fn write_output(output_result: anyhow::Result<Option<String>>, output_file: Option<&str>) {
     match output_result {
         Ok(Some(content)) => {
             // Write to file if --output specified, otherwise stdout
-            if let Some(ref output_path) = resolved.output_file {
+            if let Some(output_path) = output_file {
                 if let Err(e) = std::fs::write(output_path, &content) {
                     eprintln!("Error writing output to {}: {}", output_path, e);
                     std::process::exit(ExitCode::ConfigError as i32);
@@ -233,84 +367,4 @@ fn main() {
             std::process::exit(ExitCode::ConfigError as i32);
         }
     }
-
-    // Determine exit code from actual analysis results
-    let exit_code = determine_exit_code(
-        has_parse_errors,
-        error_count,
-        warning_count,
-        args.fail_on.as_deref(),
-        false, // baseline enforcement deferred
-    );
-
-    std::process::exit(exit_code as i32);
-}
-
-/// Build an AnalysisConfig from the merged Config and ResolvedConfig.
-///
-/// Maps resolved threshold values to AnalysisConfig fields. Uses defaults
-/// where values are not specified in config.
-fn build_analysis_config(
-    config: &complexity_guard::cli::Config,
-    resolved: &complexity_guard::cli::ResolvedConfig,
-) -> AnalysisConfig {
-    let cyclomatic = CyclomaticConfig {
-        count_logical_operators: true,
-        count_nullish_coalescing: true,
-        count_optional_chaining: true,
-        count_ternary: true,
-        count_default_params: true,
-        switch_case_mode: complexity_guard::types::SwitchCaseMode::Classic,
-        warning_threshold: resolved.cyclomatic_warning,
-        error_threshold: resolved.cyclomatic_error,
-    };
-
-    let cognitive = CognitiveConfig {
-        warning_threshold: resolved.cognitive_warning,
-        error_threshold: resolved.cognitive_error,
-    };
-
-    let scoring_weights = if let Some(w) = &config.weights {
-        ScoringWeights {
-            cyclomatic: w.cyclomatic.unwrap_or(0.20),
-            cognitive: w.cognitive.unwrap_or(0.30),
-            halstead: w.halstead.unwrap_or(0.15),
-            structural: w.structural.unwrap_or(0.15),
-            duplication: w.duplication.unwrap_or(0.20),
-        }
-    } else {
-        ScoringWeights::default()
-    };
-
-    let scoring_thresholds = ScoringThresholds {
-        cyclomatic_warning: resolved.cyclomatic_warning as f64,
-        cyclomatic_error: resolved.cyclomatic_error as f64,
-        cognitive_warning: resolved.cognitive_warning as f64,
-        cognitive_error: resolved.cognitive_error as f64,
-        halstead_warning: resolved.halstead_volume_warning,
-        halstead_error: resolved.halstead_volume_error,
-        function_length_warning: resolved.line_count_warning as f64,
-        function_length_error: resolved.line_count_error as f64,
-        params_count_warning: resolved.params_count_warning as f64,
-        params_count_error: resolved.params_count_error as f64,
-        nesting_depth_warning: resolved.nesting_depth_warning as f64,
-        nesting_depth_error: resolved.nesting_depth_error as f64,
-    };
-
-    let duplication = DuplicationConfig {
-        min_tokens: 25,
-        enabled: config
-            .analysis
-            .as_ref()
-            .and_then(|a| a.duplication_enabled)
-            .unwrap_or(false),
-    };
-
-    AnalysisConfig {
-        cyclomatic,
-        cognitive,
-        scoring_weights,
-        scoring_thresholds,
-        duplication,
-    }
 }
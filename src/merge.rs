@@ -0,0 +1,364 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use crate::analyzer::{collect_violations, syntax_error_policy, Report};
+use crate::cli::{resolve_config, Config};
+use crate::metrics::duplication::{detect_duplication, restore_token};
+use crate::metrics::scoring::compute_file_score;
+use crate::output::json_output::{JsonFileOutput, JsonOutput};
+use crate::parser::select_language;
+use crate::types::{
+    DuplicationConfig, FileAnalysisResult, FunctionAnalysisResult, SkipReason, SkippedItem,
+    SyntaxError, SyntaxErrorPolicy, Token,
+};
+
+/// One report to merge, named for error messages (usually the report's path).
+#[derive(Debug, Clone)]
+pub struct Shard {
+    pub name: String,
+    pub report: JsonOutput,
+}
+
+/// Combine JSON reports from sharded runs into a single `Report`.
+///
+/// A file that appears in several shards is taken from the first one, and a
+/// file-level skip is dropped when another shard analyzed that file. Violations
+/// are recomputed against `config`, so every shard is judged by the same
+/// thresholds regardless of the config it ran with.
+///
+/// The merged report has parse errors when a file failed in every shard that
+/// had it, or, under the `fail` syntax error policy of `config`, when a merged
+/// file has syntax errors.
+///
+/// Cross-shard duplication runs when the shards carry token streams
+/// (`--export-tokens`); it then needs them for every file, since a file without
+/// tokens would silently lower the duplication percentage. Asking for
+/// duplication in `config` without any token streams is an error for the same
+/// reason.
+pub fn merge_reports(shards: &[Shard], config: &Config) -> anyhow::Result<Report> {
+    let resolved = resolve_config(config);
+
+    let mut files: Vec<FileAnalysisResult> = Vec::new();
+    let mut missing_tokens: Option<(&str, &str)> = None;
+    let mut seen: HashSet<&str> = HashSet::new();
+    for shard in shards {
+        for file in &shard.report.files {
+            if !seen.insert(&file.path) {
+                continue;
+            }
+            if file.tokens.is_none() {
+                missing_tokens.get_or_insert((&shard.name, &file.path));
+            }
+            files
+                .push(file_result(file).with_context(|| format!("invalid report {}", shard.name))?);
+        }
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut skipped: Vec<SkippedItem> = Vec::new();
+    let mut seen_skips: HashSet<(&str, Option<&str>, usize)> = HashSet::new();
+    for shard in shards {
+        for item in shard.report.skipped.iter().flatten() {
+            let file_level = item.function_name.is_none();
+            if file_level && seen.contains(item.path.as_str()) {
+                continue;
+            }
+            if !seen_skips.insert((&item.path, item.function_name.as_deref(), item.start_line)) {
+                continue;
+            }
+            skipped.push(
+                SkippedItem::try_from(item)
+                    .with_context(|| format!("invalid report {}", shard.name))?,
+            );
+        }
+    }
+    // Judged on the merged files alone: a file one shard failed on but
+    // another analyzed has no parse errors
+    let policy = syntax_error_policy(config).context("invalid configuration")?;
+    let has_parse_errors = skipped
+        .iter()
+        .any(|item| matches!(item.reason, SkipReason::Failed { .. }))
+        || (policy == SyntaxErrorPolicy::Fail && files.iter().any(|f| !f.syntax_errors.is_empty()));
+
+    let any_tokens = files.iter().any(|f| !f.tokens.is_empty());
+    let analysis = config.analysis.as_ref();
+    let requested = analysis.is_some_and(|a| a.duplication_enabled == Some(true));
+    let disabled = analysis.is_some_and(|a| a.no_duplication == Some(true));
+    let duplication = if disabled || !(any_tokens || requested) || files.is_empty() {
+        None
+    } else {
+        if let Some((shard, path)) = missing_tokens {
+            anyhow::bail!(
+                "report {} has no token stream for {}; re-run that shard with --export-tokens \
+                 to include it in cross-shard duplication",
+                shard,
+                path
+            );
+        }
+        let file_tokens: Vec<&[Token]> = files.iter().map(|f| f.tokens.as_slice()).collect();
+        Some(detect_duplication(
+            &file_tokens,
+            &DuplicationConfig {
+                min_tokens: 25,
+                enabled: true,
+            },
+        ))
+    };
+
+    let violations = collect_violations(&files, &resolved);
+    let elapsed_ms = shards
+        .iter()
+        .map(|s| s.report.metadata.elapsed_ms)
+        .max()
+        .unwrap_or(0);
+
+    Ok(Report {
+        files,
+        skipped,
+        duplication,
+        violations,
+        has_parse_errors,
+        elapsed_ms,
+        cache_stats: None,
+        config: resolved,
+        history: Vec::new(),
+        hotspots: None,
+        blame: None,
+        owners: None,
+        packages: None,
+        modules: None,
+        commit: None,
+    })
+}
+
+/// Rebuild a file's analysis result from its JSON form.
+///
+/// The file score is recomputed from the function scores, as `analyze_file`
+/// does, and exported token kinds are interned against the file's grammar.
+fn file_result(file: &JsonFileOutput) -> anyhow::Result<FileAnalysisResult> {
+    let path = PathBuf::from(&file.path);
+    let functions: Vec<FunctionAnalysisResult> = file
+        .functions
+        .iter()
+        .map(FunctionAnalysisResult::from)
+        .collect();
+    let scores: Vec<f64> = functions.iter().map(|f| f.health_score).collect();
+    let tokens = match &file.tokens {
+        Some(kinds) => restore_tokens(&path, kinds)?,
+        None => Vec::new(),
+    };
+
+    Ok(FileAnalysisResult {
+        file_score: compute_file_score(&scores),
+        functions,
+        classes: file.classes.iter().map(Into::into).collect(),
+        module: file.module.as_ref().map(Into::into),
+        tokens,
+        file_length: file.file_length,
+        export_count: file.export_count,
+        syntax_errors: file.syntax_errors.iter().map(SyntaxError::from).collect(),
+        imports: Vec::new(),
+        coupling: file.coupling,
+        cycles: file.cycles.clone(),
+        path,
+    })
+}
+
+fn restore_tokens(path: &Path, kinds: &[String]) -> anyhow::Result<Vec<Token>> {
+    let language = select_language(path)?;
+    kinds
+        .iter()
+        .map(|kind| {
+            restore_token(&language, kind, 0, 0).ok_or_else(|| {
+                anyhow::anyhow!("unknown token kind '{}' in {}", kind, path.display())
+            })
+        })
+        .collect()
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::config_defaults;
+    use crate::output::exit_codes::ExitCode;
+    use crate::output::json_output::{parse_json_report, JsonSkippedItem};
+    use crate::Analyzer;
+
+    const CLONE: &str = "export function total(items: number[]) {
+  let sum = 0;
+  for (const item of items) {
+    if (item > 0) { sum += item * 2; } else { sum -= item; }
+  }
+  return sum;
+}
+";
+
+    /// Analyze `sources` as one shard and load it back from its JSON report.
+    fn shard(name: &str, sources: &[(&str, &str)], export_tokens: bool) -> Shard {
+        let mut analyzer = Analyzer::new().configure(|c| {
+            c.output.get_or_insert_with(Default::default).export_tokens = Some(export_tokens)
+        });
+        for (path, source) in sources {
+            analyzer = analyzer.source(*path, *source);
+        }
+        let json = analyzer.run().unwrap().render_json().unwrap();
+        Shard {
+            name: name.to_string(),
+            report: parse_json_report(&json).unwrap(),
+        }
+    }
+
+    #[test]
+    fn merges_files_and_dedupes_overlap() {
+        let a = shard(
+            "a.json",
+            &[("src/a.ts", CLONE), ("src/shared.ts", CLONE)],
+            false,
+        );
+        let b = shard(
+            "b.json",
+            &[
+                ("src/shared.ts", "function other() { return 1; }"),
+                ("lib/b.ts", CLONE),
+            ],
+            false,
+        );
+        let report = merge_reports(&[a, b], &config_defaults()).unwrap();
+
+        let paths: Vec<_> = report
+            .files
+            .iter()
+            .map(|f| f.path.to_str().unwrap())
+            .collect();
+        assert_eq!(paths, vec!["lib/b.ts", "src/a.ts", "src/shared.ts"]);
+        // The first shard wins for overlapping files
+        let shared = &report.files[2];
+        assert_eq!(shared.functions[0].name, "total");
+        assert!(report.duplication.is_none());
+        assert_eq!(report.function_count(), 3);
+    }
+
+    #[test]
+    fn recomputes_violations_with_merge_thresholds() {
+        let a = shard("a.json", &[("src/a.ts", CLONE)], false);
+        let strict: Config = serde_json::from_str(
+            r#"{"analysis": {"thresholds": {"cognitive": {"warning": 1, "error": 2}}}}"#,
+        )
+        .unwrap();
+        let mut config = config_defaults();
+        config.overlay(strict);
+
+        let report = merge_reports(&[a], &config).unwrap();
+        assert_eq!(report.error_count(), 1);
+        assert_eq!(report.violations[0].name, "total");
+    }
+
+    #[test]
+    fn detects_duplication_across_shards() {
+        let a = shard("a.json", &[("src/a.ts", CLONE)], true);
+        let b = shard("b.json", &[("lib/b.ts", CLONE)], true);
+        let report = merge_reports(&[a, b], &config_defaults()).unwrap();
+
+        let duplication = report.duplication.as_ref().unwrap();
+        assert!(!duplication.clone_groups.is_empty());
+        let files: HashSet<usize> = duplication.clone_groups[0]
+            .instances
+            .iter()
+            .map(|i| i.file_index)
+            .collect();
+        assert_eq!(files.len(), 2, "clone should span both shards");
+    }
+
+    #[test]
+    fn duplication_requires_tokens_from_every_shard() {
+        let a = shard("a.json", &[("src/a.ts", CLONE)], true);
+        let b = shard("b.json", &[("lib/b.ts", CLONE)], false);
+        let err = merge_reports(&[a, b], &config_defaults()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("report b.json has no token stream for lib/b.ts"));
+
+        let a = shard("a.json", &[("src/a.ts", CLONE)], false);
+        let mut config = config_defaults();
+        config.analysis.as_mut().unwrap().duplication_enabled = Some(true);
+        assert!(merge_reports(&[a], &config).is_err());
+    }
+
+    fn failed(path: &str) -> JsonSkippedItem {
+        JsonSkippedItem {
+            path: path.to_string(),
+            function_name: None,
+            start_line: 0,
+            reason: "failed".to_string(),
+            lines: None,
+            max_lines: None,
+            marker: None,
+            average_line_length: None,
+            max_average_line_length: None,
+            error: Some("I/O error".to_string()),
+            syntax_errors: None,
+        }
+    }
+
+    #[test]
+    fn drops_file_skips_analyzed_by_another_shard() {
+        let mut a = shard("a.json", &[("src/a.ts", CLONE)], false);
+        a.report.skipped = Some(vec![failed("src/b.ts"), failed("src/c.ts")]);
+        let mut b = shard("b.json", &[("src/b.ts", CLONE)], false);
+        b.report.skipped = Some(vec![failed("src/c.ts")]);
+        let report = merge_reports(&[a, b], &config_defaults()).unwrap();
+
+        assert_eq!(report.files.len(), 2);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].path, PathBuf::from("src/c.ts"));
+        assert!(report.has_parse_errors);
+
+        // A failure is dropped with its skip once another shard analyzed the file
+        let mut a = shard("a.json", &[("src/a.ts", CLONE)], false);
+        a.report.skipped = Some(vec![failed("src/b.ts")]);
+        a.report.summary.has_parse_errors = true;
+        let b = shard("b.json", &[("src/b.ts", CLONE)], false);
+        let report = merge_reports(&[a, b], &config_defaults()).unwrap();
+        assert!(report.skipped.is_empty());
+        assert!(!report.has_parse_errors);
+    }
+
+    #[test]
+    fn fails_on_syntax_errors_under_the_fail_policy() {
+        let json = Analyzer::new()
+            .configure(|c| {
+                c.analysis
+                    .get_or_insert_with(Default::default)
+                    .on_syntax_error = Some("fail".to_string())
+            })
+            .source("src/broken.ts", "function broken( {\n  return 1;\n}\n")
+            .run()
+            .unwrap()
+            .render_json()
+            .unwrap();
+        let broken = Shard {
+            name: "broken.json".to_string(),
+            report: parse_json_report(&json).unwrap(),
+        };
+        assert!(broken.report.summary.has_parse_errors);
+        let clean = shard("clean.json", &[("src/a.ts", CLONE)], false);
+        let shards = [clean, broken];
+
+        let mut config = config_defaults();
+        config.analysis.as_mut().unwrap().on_syntax_error = Some("fail".to_string());
+        let report = merge_reports(&shards, &config).unwrap();
+        assert!(report.has_parse_errors);
+        assert_eq!(report.exit_code(None), ExitCode::ParseError);
+
+        // The merge's own policy decides, not the shard's
+        let report = merge_reports(&shards, &config_defaults()).unwrap();
+        assert!(!report.has_parse_errors);
+
+        config.analysis.as_mut().unwrap().on_syntax_error = Some("ignore".to_string());
+        assert!(merge_reports(&shards, &config).is_err());
+    }
+}
@@ -0,0 +1,388 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::metrics::cyclomatic;
+use crate::parser::{FunctionIndex, IndexedFunction};
+use crate::types::{ClassAnalysisResult, CyclomaticConfig};
+
+/// Class node types recognized by tree-sitter for TypeScript/JavaScript.
+pub fn is_class_node(kind: &str) -> bool {
+    matches!(
+        kind,
+        "class_declaration" | "abstract_class_declaration" | "class"
+    )
+}
+
+/// A method of a class, as far as cohesion is concerned.
+struct Member<'a, 'tree> {
+    name: String,
+    func: &'a IndexedFunction<'tree>,
+    is_constructor: bool,
+    /// Names accessed as `this.name` in the method body
+    this_members: BTreeSet<String>,
+}
+
+/// Compute size, complexity and cohesion metrics for every class in the file.
+///
+/// Methods are taken from the function index so their cyclomatic complexity
+/// matches the per-function results. Classes nested in functions are included.
+pub fn analyze_classes(
+    index: &FunctionIndex,
+    root: tree_sitter::Node,
+    source: &[u8],
+    config: &CyclomaticConfig,
+) -> Vec<ClassAnalysisResult> {
+    let by_node: HashMap<usize, &IndexedFunction> =
+        index.functions().iter().map(|f| (f.node.id(), f)).collect();
+
+    let mut classes = Vec::new();
+    collect_classes(root, &mut classes);
+    classes
+        .into_iter()
+        .map(|class| analyze_class(class, &by_node, source, config))
+        .collect()
+}
+
+fn collect_classes<'tree>(
+    node: tree_sitter::Node<'tree>,
+    classes: &mut Vec<tree_sitter::Node<'tree>>,
+) {
+    if node.is_named() && is_class_node(node.kind()) {
+        classes.push(node);
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_classes(child, classes);
+    }
+}
+
+fn analyze_class(
+    class: tree_sitter::Node,
+    by_node: &HashMap<usize, &IndexedFunction>,
+    source: &[u8],
+    config: &CyclomaticConfig,
+) -> ClassAnalysisResult {
+    let mut members: Vec<Member> = Vec::new();
+    let mut fields: BTreeSet<String> = BTreeSet::new();
+
+    if let Some(body) = class.child_by_field_name("body") {
+        let mut cursor = body.walk();
+        for item in body.named_children(&mut cursor) {
+            match item.kind() {
+                "method_definition" => {
+                    let Some(name) = member_name(&item, source) else {
+                        continue;
+                    };
+                    let Some(func) = by_node.get(&item.id()) else {
+                        continue;
+                    };
+                    let is_constructor = name == "constructor";
+                    if is_constructor {
+                        fields.extend(parameter_properties(&item, source));
+                    }
+                    members.push(Member {
+                        name,
+                        func,
+                        is_constructor,
+                        this_members: this_members(&item, source),
+                    });
+                }
+                // Class fields: `count = 0` is a field, `onClick = () => {}` a method
+                "public_field_definition" | "field_definition" => {
+                    let Some(name) = member_name(&item, source) else {
+                        continue;
+                    };
+                    match item
+                        .child_by_field_name("value")
+                        .and_then(|value| by_node.get(&value.id()))
+                    {
+                        Some(func) => members.push(Member {
+                            name,
+                            func,
+                            is_constructor: false,
+                            this_members: this_members(&func.node, source),
+                        }),
+                        None => {
+                            fields.insert(name);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Undeclared fields (plain JS) show up only as `this.x` accesses
+    let method_names: BTreeSet<&str> = members.iter().map(|m| m.name.as_str()).collect();
+    for member in &members {
+        for name in &member.this_members {
+            if !method_names.contains(name.as_str()) {
+                fields.insert(name.clone());
+            }
+        }
+    }
+
+    let wmc = members
+        .iter()
+        .map(|m| cyclomatic::analyze_function(m.func, config).complexity)
+        .sum();
+
+    let start = class.start_position();
+    ClassAnalysisResult {
+        name: class_name(&class, source),
+        start_line: start.row + 1,
+        end_line: class.end_position().row + 1,
+        start_col: start.column,
+        method_count: members.len() as u32,
+        wmc,
+        field_count: fields.len() as u32,
+        lcom: lcom4(&members),
+    }
+}
+
+/// LCOM4: the number of connected groups of methods.
+///
+/// Two methods are connected when they access a common field or one calls
+/// (or references) the other through `this`. The constructor is left out, since
+/// it typically initializes every field and would connect everything.
+fn lcom4(members: &[Member]) -> u32 {
+    let methods: Vec<&Member> = members.iter().filter(|m| !m.is_constructor).collect();
+    let mut parent: Vec<usize> = (0..methods.len()).collect();
+
+    fn find(parent: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        parent[i] = root;
+        root
+    }
+
+    for i in 0..methods.len() {
+        for j in (i + 1)..methods.len() {
+            let (a, b) = (methods[i], methods[j]);
+            let connected = a.this_members.contains(&b.name)
+                || b.this_members.contains(&a.name)
+                || a.this_members
+                    .intersection(&b.this_members)
+                    .next()
+                    .is_some();
+            if connected {
+                let (ra, rb) = (find(&mut parent, i), find(&mut parent, j));
+                parent[ra] = rb;
+            }
+        }
+    }
+
+    (0..methods.len())
+        .filter(|&i| find(&mut parent, i) == i)
+        .count() as u32
+}
+
+/// Name of a method or field definition (TS uses "name", JS fields use "property").
+fn member_name(node: &tree_sitter::Node, source: &[u8]) -> Option<String> {
+    let name_node = node
+        .child_by_field_name("name")
+        .or_else(|| node.child_by_field_name("property"))?;
+    name_node.utf8_text(source).ok().map(str::to_string)
+}
+
+/// Class name; class expressions fall back to the variable they are assigned
+/// to, and to `<anonymous>` otherwise.
+pub(crate) fn class_name(class: &tree_sitter::Node, source: &[u8]) -> String {
+    let name_node = class.child_by_field_name("name").or_else(|| {
+        class
+            .parent()
+            .filter(|p| p.kind() == "variable_declarator")
+            .and_then(|p| p.child_by_field_name("name"))
+    });
+    name_node
+        .and_then(|n| n.utf8_text(source).ok())
+        .map(str::to_string)
+        .unwrap_or_else(|| "<anonymous>".to_string())
+}
+
+/// TypeScript parameter properties: `constructor(private readonly db: Db)` declares `db`.
+fn parameter_properties(constructor: &tree_sitter::Node, source: &[u8]) -> Vec<String> {
+    let Some(params) = constructor.child_by_field_name("parameters") else {
+        return Vec::new();
+    };
+    let mut cursor = params.walk();
+    params
+        .named_children(&mut cursor)
+        .filter(|param| {
+            let mut param_cursor = param.walk();
+            let is_property = param
+                .children(&mut param_cursor)
+                .any(|c| c.kind() == "accessibility_modifier" || c.kind() == "readonly");
+            is_property
+        })
+        .filter_map(|param| param.child_by_field_name("pattern"))
+        .filter_map(|pattern| pattern.utf8_text(source).ok().map(str::to_string))
+        .collect()
+}
+
+/// Names accessed as `this.name` inside a method.
+///
+/// Arrow functions share the method's `this` and are searched; other nested
+/// functions and classes bind their own `this` and are not.
+fn this_members(func: &tree_sitter::Node, source: &[u8]) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    let mut cursor = func.walk();
+    for child in func.children(&mut cursor) {
+        collect_this_members(child, source, &mut names);
+    }
+    names
+}
+
+fn collect_this_members(node: tree_sitter::Node, source: &[u8], names: &mut BTreeSet<String>) {
+    let kind = node.kind();
+    if node.is_named()
+        && ((crate::metrics::is_function_node(kind) && kind != "arrow_function")
+            || is_class_node(kind))
+    {
+        return;
+    }
+
+    if kind == "member_expression" {
+        let on_this = node
+            .child_by_field_name("object")
+            .is_some_and(|object| object.kind() == "this");
+        if on_this {
+            if let Some(text) = node
+                .child_by_field_name("property")
+                .and_then(|p| p.utf8_text(source).ok())
+            {
+                names.insert(text.to_string());
+            }
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_this_members(child, source, names);
+    }
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze(source: &str) -> Vec<ClassAnalysisResult> {
+        let language: tree_sitter::Language = tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into();
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&language).unwrap();
+        let tree = parser.parse(source.as_bytes(), None).unwrap();
+        let root = tree.root_node();
+        let index = FunctionIndex::build(root, source.as_bytes());
+        analyze_classes(
+            &index,
+            root,
+            source.as_bytes(),
+            &CyclomaticConfig::default(),
+        )
+    }
+
+    #[test]
+    fn class_with_methods_fixture() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/typescript/class_with_methods.ts");
+        let source = std::fs::read_to_string(path).unwrap();
+        let classes = analyze(&source);
+
+        assert_eq!(classes.len(), 1);
+        let c = &classes[0];
+        assert_eq!(c.name, "UserService");
+        assert_eq!(c.start_line, 5);
+        // constructor, findById, updateEmail, isValidEmail
+        assert_eq!(c.method_count, 4);
+        // 1 + 4 + 3 + 2
+        assert_eq!(c.wmc, 10);
+        // `users` is declared, `db` is a parameter property
+        assert_eq!(c.field_count, 2);
+        // updateEmail calls both other methods, so everything is connected
+        assert_eq!(c.lcom, 1);
+    }
+
+    #[test]
+    fn unrelated_method_groups_raise_lcom() {
+        let classes = analyze(
+            "class Mixed {
+               a = 0;
+               b = 0;
+               incA() { this.a++; }
+               getA() { return this.a; }
+               incB() { this.b++; }
+               log() { console.log('x'); }
+             }",
+        );
+        assert_eq!(classes[0].method_count, 4);
+        assert_eq!(classes[0].field_count, 2);
+        // {incA, getA}, {incB}, {log}
+        assert_eq!(classes[0].lcom, 3);
+    }
+
+    #[test]
+    fn constructor_does_not_connect_methods() {
+        let classes = analyze(
+            "class Pair {
+               constructor() { this.left = 1; this.right = 2; }
+               getLeft() { return this.left; }
+               getRight() { return this.right; }
+             }",
+        );
+        assert_eq!(classes[0].method_count, 3);
+        // Undeclared fields are found through `this.x`
+        assert_eq!(classes[0].field_count, 2);
+        assert_eq!(classes[0].lcom, 2);
+    }
+
+    #[test]
+    fn arrow_fields_are_methods_and_share_this() {
+        let classes = analyze(
+            "class Button {
+               label = 'ok';
+               onClick = () => { this.render(); };
+               render() { return [1].map(() => this.label); }
+             }",
+        );
+        assert_eq!(classes[0].method_count, 2);
+        assert_eq!(classes[0].field_count, 1);
+        assert_eq!(classes[0].lcom, 1);
+    }
+
+    #[test]
+    fn nested_function_this_is_not_the_class() {
+        let classes = analyze(
+            "class Timer {
+               start() { setTimeout(function () { this.tick(); }); }
+               tick() {}
+             }",
+        );
+        assert_eq!(classes[0].field_count, 0);
+        assert_eq!(classes[0].lcom, 2);
+    }
+
+    #[test]
+    fn class_expressions_and_nested_classes() {
+        let classes = analyze(
+            "const Store = class { get() { return 1; } };
+             function factory() { return class Inner { run() { if (x) {} } }; }
+             class Empty {}",
+        );
+        let names: Vec<&str> = classes.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Store", "Inner", "Empty"]);
+        assert_eq!(classes[1].wmc, 2);
+        assert_eq!(classes[2].method_count, 0);
+        assert_eq!(classes[2].lcom, 0);
+    }
+
+    #[test]
+    fn is_class_node_recognizes_all_types() {
+        assert!(is_class_node("class_declaration"));
+        assert!(is_class_node("abstract_class_declaration"));
+        assert!(is_class_node("class"));
+        assert!(!is_class_node("class_body"));
+    }
+}
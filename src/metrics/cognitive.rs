@@ -1,99 +1,41 @@
 use crate::metrics::is_function_node;
+use crate::parser::{FunctionIndex, IndexedFunction};
 use crate::types::CognitiveResult;
 
-/// Analyze all functions in the AST and return cognitive complexity for each.
-pub fn analyze_functions(root: tree_sitter::Node, source: &[u8]) -> Vec<CognitiveResult> {
-    let mut results = Vec::new();
-    walk_and_analyze(root, source, &mut results, None);
-    results
+/// Compute cognitive complexity for every outermost function in the index.
+pub fn analyze_functions(index: &FunctionIndex, source: &[u8]) -> Vec<CognitiveResult> {
+    index
+        .outermost()
+        .map(|func| analyze_function(func, source))
+        .collect()
 }
 
-/// Name context passed from parent nodes to resolve anonymous function names.
-struct NameContext {
-    name: String,
-    class_name: Option<String>,
-}
-
-fn walk_and_analyze(
-    node: tree_sitter::Node,
-    source: &[u8],
-    results: &mut Vec<CognitiveResult>,
-    parent_ctx: Option<&NameContext>,
-) {
-    let kind = node.kind();
-
-    if is_function_node(kind) {
-        let mut name = crate::metrics::extract_function_name(&node, source);
-
-        if let Some(ctx) = parent_ctx {
-            if let Some(ref class_name) = ctx.class_name {
-                if kind == "method_definition" && name != "<anonymous>" {
-                    name = format!("{}.{}", class_name, name);
-                }
-            } else if name == "<anonymous>" && !ctx.name.is_empty() {
-                name = ctx.name.clone();
-            }
-        }
-
-        let complexity = calculate_cognitive_complexity(&node, source, &name);
-        let start = node.start_position();
-        let end = node.end_position();
-
-        results.push(CognitiveResult {
-            name,
-            complexity,
-            start_line: start.row + 1,
-            end_line: end.row + 1,
-            start_col: start.column,
-        });
-
-        // Don't recurse into nested functions
-        return;
-    }
-
-    // Build naming context for children
-    let mut child_ctx: Option<NameContext> = None;
-
-    if kind == "variable_declarator" {
-        if let Some(name_node) = node.child_by_field_name("name") {
-            if let Ok(text) = name_node.utf8_text(source) {
-                child_ctx = Some(NameContext {
-                    name: text.to_string(),
-                    class_name: None,
-                });
-            }
-        }
-    } else if kind == "class_declaration" || kind == "class" {
-        let mut class_name = "class".to_string();
-        for i in 0..node.child_count() as u32 {
-            if let Some(child) = node.child(i) {
-                let ct = child.kind();
-                if ct == "identifier" || ct == "type_identifier" {
-                    if let Ok(text) = child.utf8_text(source) {
-                        class_name = text.to_string();
-                    }
-                    break;
-                }
-            }
-        }
-        child_ctx = Some(NameContext {
-            name: "<anonymous>".to_string(),
-            class_name: Some(class_name),
-        });
-    } else if kind == "class_body" || kind == "arguments" {
-        if let Some(ctx) = parent_ctx {
-            child_ctx = Some(NameContext {
-                name: ctx.name.clone(),
-                class_name: ctx.class_name.clone(),
-            });
-        }
+/// Compute cognitive complexity for a single indexed function.
+///
+/// Recursion is detected by calls to the function's short name.
+pub fn analyze_function(func: &IndexedFunction, source: &[u8]) -> CognitiveResult {
+    CognitiveResult {
+        name: func.name.clone(),
+        complexity: calculate_cognitive_complexity(&func.node, source, &func.short_name),
+        start_line: func.start_line,
+        end_line: func.end_line,
+        start_col: func.start_col,
     }
+}
 
-    for i in 0..node.child_count() as u32 {
-        if let Some(child) = node.child(i) {
-            walk_and_analyze(child, source, results, child_ctx.as_ref().or(parent_ctx));
+/// Compute cognitive complexity of a file's top-level code.
+///
+/// Every function, arrows included, is a boundary: top-level callbacks are
+/// reported as functions of their own. Nesting starts at 0.
+pub fn analyze_module(root: tree_sitter::Node, source: &[u8]) -> u32 {
+    let mut complexity: u32 = 0;
+    let mut nesting: u32 = 0;
+    for i in 0..root.child_count() as u32 {
+        if let Some(stmt) = root.child(i) {
+            visit_node_cognitive(stmt, source, &mut complexity, &mut nesting, "");
         }
     }
+    complexity
 }
 
 /// Calculate cognitive complexity for a function node.
@@ -605,8 +547,8 @@ mod tests {
         let mut parser = tree_sitter::Parser::new();
         parser.set_language(&language).unwrap();
         let tree = parser.parse(source.as_bytes(), None).unwrap();
-        let root = tree.root_node();
-        analyze_functions(root, source.as_bytes())
+        let index = FunctionIndex::build(tree.root_node(), source.as_bytes());
+        analyze_functions(&index, source.as_bytes())
     }
 
     fn find_by_name<'a>(results: &'a [CognitiveResult], name: &str) -> Option<&'a CognitiveResult> {
@@ -1,4 +1,5 @@
 use rustc_hash::FxHashMap;
+use xxhash_rust::xxh3::xxh3_64;
 
 use crate::metrics::halstead::is_type_only_node;
 use crate::types::{CloneGroup, CloneInstance, DuplicationConfig, DuplicationResult, Token};
@@ -22,6 +23,65 @@ pub fn tokenize_tree(root: tree_sitter::Node, source: &[u8]) -> Vec<Token> {
     tokens
 }
 
+/// SimHash of a function body's normalized token trigrams.
+///
+/// Bodies that differ in a few tokens get fingerprints that differ in a few
+/// bits, so the Hamming distance between two fingerprints estimates how
+/// similar the bodies are. Identifiers are normalized, so renaming the function
+/// or its variables leaves the fingerprint unchanged. Returns 0 for an empty body.
+pub fn body_fingerprint(node: tree_sitter::Node, source: &[u8]) -> u64 {
+    let tokens = tokenize_tree(node, source);
+    let width = tokens.len().min(3);
+    if width == 0 {
+        return 0;
+    }
+
+    let mut weights = [0i32; 64];
+    for window in tokens.windows(width) {
+        let bytes: Vec<u8> = window
+            .iter()
+            .flat_map(|t| t.kind_hash.to_le_bytes())
+            .collect();
+        let hash = xxh3_64(&bytes);
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            *weight += if hash >> bit & 1 == 1 { 1 } else { -1 };
+        }
+    }
+    weights
+        .iter()
+        .enumerate()
+        .filter(|(_, &w)| w > 0)
+        .fold(0, |acc, (bit, _)| acc | 1 << bit)
+}
+
+/// Rebuild a token from a stored kind name, as produced by `tokenize_tree`.
+///
+/// The kind is interned against `language` so the token again borrows the
+/// grammar's static string. Returns `None` if the grammar has no such kind.
+pub fn restore_token(
+    language: &tree_sitter::Language,
+    kind: &str,
+    start_byte: usize,
+    end_byte: usize,
+) -> Option<Token> {
+    let kind = if kind == "V" {
+        "V"
+    } else {
+        [true, false].iter().find_map(|&named| {
+            let id = language.id_for_node_kind(kind, named);
+            language
+                .node_kind_for_id(id)
+                .filter(|interned| id != 0 && *interned == kind)
+        })?
+    };
+    Some(Token {
+        kind,
+        kind_hash: token_hash(kind),
+        start_byte,
+        end_byte,
+    })
+}
+
 /// Recursively collect normalized tokens from an AST node.
 #[allow(clippy::only_used_in_recursion)]
 fn tokenize_node(node: tree_sitter::Node, source: &[u8], tokens: &mut Vec<Token>) {
@@ -371,6 +431,33 @@ function greet(name: string): string {
         );
     }
 
+    fn fingerprint(source: &str) -> u64 {
+        let language: tree_sitter::Language = tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into();
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&language).unwrap();
+        let tree = parser.parse(source.as_bytes(), None).unwrap();
+        body_fingerprint(tree.root_node(), source.as_bytes())
+    }
+
+    #[test]
+    fn body_fingerprint_tracks_similarity() {
+        let body = "function total(items) {
+  let sum = 0;
+  for (const item of items) {
+    if (item > 0) { sum += item * 2; } else { sum -= item; }
+  }
+  return sum;
+}";
+        let renamed = body.replace("total", "sumAll").replace("item", "x");
+        let edited = body.replace("return sum;", "console.log(sum);\n  return sum;");
+        let unrelated =
+            "class Cache { get(key) { return this.map.has(key) ? this.map.get(key) : null; } }";
+
+        assert_eq!(fingerprint(body), fingerprint(&renamed));
+        let distance = |a: &str, b: &str| (fingerprint(a) ^ fingerprint(b)).count_ones();
+        assert!(distance(body, &edited) < distance(body, unrelated));
+    }
+
     #[test]
     fn token_hash_deterministic() {
         let h1 = token_hash("function");
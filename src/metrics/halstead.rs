@@ -1,103 +1,56 @@
 use rustc_hash::FxHashMap;
 
 use crate::metrics::is_function_node;
+use crate::parser::{FunctionIndex, IndexedFunction};
 use crate::types::HalsteadResult;
 
-/// Analyze all functions in the AST and return Halstead metrics for each.
-pub fn analyze_functions(root: tree_sitter::Node, source: &[u8]) -> Vec<HalsteadResult> {
-    let mut results = Vec::new();
-    walk_and_analyze(root, source, &mut results, None);
-    results
+/// Compute Halstead metrics for every outermost function in the index.
+pub fn analyze_functions(index: &FunctionIndex, source: &[u8]) -> Vec<HalsteadResult> {
+    index
+        .outermost()
+        .map(|func| analyze_function(func, source))
+        .collect()
 }
 
-/// Name context passed from parent nodes.
-struct NameContext {
-    name: String,
-    class_name: Option<String>,
-}
-
-fn walk_and_analyze(
-    node: tree_sitter::Node,
-    source: &[u8],
-    results: &mut Vec<HalsteadResult>,
-    parent_ctx: Option<&NameContext>,
-) {
-    let kind = node.kind();
-
-    if is_function_node(kind) {
-        let mut name = crate::metrics::extract_function_name(&node, source);
-
-        if let Some(ctx) = parent_ctx {
-            if let Some(ref class_name) = ctx.class_name {
-                if kind == "method_definition" && name != "<anonymous>" {
-                    name = format!("{}.{}", class_name, name);
-                }
-            } else if name == "<anonymous>" && !ctx.name.is_empty() {
-                name = ctx.name.clone();
-            }
-        }
-
-        let (volume, difficulty, effort, time, bugs) = calculate_halstead(&node, source);
-        let start = node.start_position();
-        let end = node.end_position();
-
-        results.push(HalsteadResult {
-            name,
-            volume,
-            difficulty,
-            effort,
-            time,
-            bugs,
-            start_line: start.row + 1,
-            end_line: end.row + 1,
-            start_col: start.column,
-        });
-
-        return;
+/// Compute Halstead metrics for a single indexed function.
+pub fn analyze_function(func: &IndexedFunction, source: &[u8]) -> HalsteadResult {
+    let (volume, difficulty, effort, time, bugs) = calculate_halstead(&func.node, source);
+    HalsteadResult {
+        name: func.name.clone(),
+        volume,
+        difficulty,
+        effort,
+        time,
+        bugs,
+        start_line: func.start_line,
+        end_line: func.end_line,
+        start_col: func.start_col,
     }
+}
 
-    let mut child_ctx: Option<NameContext> = None;
-
-    if kind == "variable_declarator" {
-        if let Some(name_node) = node.child_by_field_name("name") {
-            if let Ok(text) = name_node.utf8_text(source) {
-                child_ctx = Some(NameContext {
-                    name: text.to_string(),
-                    class_name: None,
-                });
-            }
-        }
-    } else if kind == "class_declaration" || kind == "class" {
-        let mut class_name = "class".to_string();
-        for i in 0..node.child_count() as u32 {
-            if let Some(child) = node.child(i) {
-                let ct = child.kind();
-                if ct == "identifier" || ct == "type_identifier" {
-                    if let Ok(text) = child.utf8_text(source) {
-                        class_name = text.to_string();
-                    }
-                    break;
-                }
-            }
-        }
-        child_ctx = Some(NameContext {
-            name: "<anonymous>".to_string(),
-            class_name: Some(class_name),
-        });
-    } else if kind == "class_body" || kind == "arguments" {
-        if let Some(ctx) = parent_ctx {
-            child_ctx = Some(NameContext {
-                name: ctx.name.clone(),
-                class_name: ctx.class_name.clone(),
-            });
-        }
-    }
+/// Compute Halstead metrics of a file's top-level code.
+/// Returns (volume, difficulty, effort, time, bugs).
+pub fn analyze_module<'a>(root: tree_sitter::Node, source: &'a [u8]) -> (f64, f64, f64, f64, f64) {
+    let mut operators: FxHashMap<&'a str, u32> = FxHashMap::default();
+    let mut operands: FxHashMap<&'a str, u32> = FxHashMap::default();
+    let mut n1_total: u32 = 0;
+    let mut n2_total: u32 = 0;
 
-    for i in 0..node.child_count() as u32 {
-        if let Some(child) = node.child(i) {
-            walk_and_analyze(child, source, results, child_ctx.as_ref().or(parent_ctx));
-        }
-    }
+    classify_node(
+        root,
+        source,
+        &mut operators,
+        &mut operands,
+        &mut n1_total,
+        &mut n2_total,
+    );
+
+    compute_halstead_metrics(
+        operators.len() as u32,
+        operands.len() as u32,
+        n1_total,
+        n2_total,
+    )
 }
 
 /// Calculate Halstead metrics for a function node.
@@ -332,8 +285,8 @@ mod tests {
         let mut parser = tree_sitter::Parser::new();
         parser.set_language(&language).unwrap();
         let tree = parser.parse(source.as_bytes(), None).unwrap();
-        let root = tree.root_node();
-        analyze_functions(root, source.as_bytes())
+        let index = FunctionIndex::build(tree.root_node(), source.as_bytes());
+        analyze_functions(&index, source.as_bytes())
     }
 
     fn find_by_name<'a>(results: &'a [HalsteadResult], name: &str) -> Option<&'a HalsteadResult> {
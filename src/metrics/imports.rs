@@ -0,0 +1,144 @@
+use crate::types::{Import, ImportKind};
+
+/// Collect the string-literal module specifiers of every import, re-export,
+/// `require()` call and dynamic `import()` in the file, in source order.
+///
+/// Specifiers built at runtime (template literals with substitutions,
+/// variables) cannot be resolved statically and are left out.
+pub fn collect_imports(root: tree_sitter::Node, source: &[u8]) -> Vec<Import> {
+    let mut imports = Vec::new();
+    let mut cursor = root.walk();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if let Some(import) = import_of(node, source) {
+            imports.push(import);
+        }
+        let children: Vec<_> = node.children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    imports
+}
+
+/// The import `node` makes, if it is one.
+fn import_of(node: tree_sitter::Node, source: &[u8]) -> Option<Import> {
+    let (kind, specifier) = match node.kind() {
+        "import_statement" => {
+            let source_node = node.child_by_field_name("source").or_else(|| {
+                // `import x = require("x")`
+                let mut cursor = node.walk();
+                let clause = node
+                    .children(&mut cursor)
+                    .find(|c| c.kind() == "import_require_clause");
+                clause.and_then(|c| c.child_by_field_name("source"))
+            })?;
+            (ImportKind::Import, source_node)
+        }
+        "export_statement" => (ImportKind::Export, node.child_by_field_name("source")?),
+        "call_expression" => {
+            let function = node.child_by_field_name("function")?;
+            let kind = match function.kind() {
+                "import" => ImportKind::Dynamic,
+                "identifier" if function.utf8_text(source).ok()? == "require" => {
+                    ImportKind::Require
+                }
+                _ => return None,
+            };
+            let arguments = node.child_by_field_name("arguments")?;
+            (kind, arguments.named_child(0)?)
+        }
+        _ => return None,
+    };
+
+    Some(Import {
+        specifier: string_value(specifier, source)?,
+        line: node.start_position().row + 1,
+        kind,
+        type_only: matches!(kind, ImportKind::Import | ImportKind::Export)
+            && has_type_keyword(node),
+    })
+}
+
+/// The contents of a string literal, or of a template literal without
+/// substitutions.
+fn string_value(node: tree_sitter::Node, source: &[u8]) -> Option<String> {
+    let mut cursor = node.walk();
+    let substituted = node
+        .children(&mut cursor)
+        .any(|c| c.kind() == "template_substitution");
+    match node.kind() {
+        "string" => {}
+        "template_string" if !substituted => {}
+        _ => return None,
+    }
+    let text = node.utf8_text(source).ok()?;
+    let inner = text.get(1..text.len().saturating_sub(1))?;
+    Some(inner.to_string())
+}
+
+/// `import type ...` or `export type ... from`.
+fn has_type_keyword(node: tree_sitter::Node) -> bool {
+    let mut cursor = node.walk();
+    let found = node
+        .children(&mut cursor)
+        .take(2)
+        .any(|c| !c.is_named() && c.kind() == "type");
+    found
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn imports(source: &str) -> Vec<Import> {
+        let language: tree_sitter::Language = tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into();
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&language).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        collect_imports(tree.root_node(), source.as_bytes())
+    }
+
+    #[test]
+    fn collects_every_kind_of_import_in_order() {
+        let found = imports(
+            r#"import { a } from "./a";
+import "./side-effect";
+import type { T } from './types';
+import fs = require("fs");
+export { b } from "./b";
+export type { U } from "./types";
+export * from "../c";
+const d = require("./d");
+async function load() {
+  const e = await import(`./e`);
+  const f = await import(name);
+  return require(`./g/${name}`);
+}
+"#,
+        );
+        let summary: Vec<_> = found
+            .iter()
+            .map(|i| (i.specifier.as_str(), i.line, i.kind, i.type_only))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("./a", 1, ImportKind::Import, false),
+                ("./side-effect", 2, ImportKind::Import, false),
+                ("./types", 3, ImportKind::Import, true),
+                ("fs", 4, ImportKind::Import, false),
+                ("./b", 5, ImportKind::Export, false),
+                ("./types", 6, ImportKind::Export, true),
+                ("../c", 7, ImportKind::Export, false),
+                ("./d", 8, ImportKind::Require, false),
+                ("./e", 10, ImportKind::Dynamic, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn local_exports_are_not_imports() {
+        assert!(imports("export const x = 1;\nexport { x as y };\nexport default x;").is_empty());
+    }
+}
@@ -1,15 +1,18 @@
+pub mod classes;
 pub mod cognitive;
 pub mod cyclomatic;
 pub mod duplication;
 pub mod halstead;
+pub mod imports;
 pub mod scoring;
 pub mod structural;
 
 use std::path::Path;
 
+use crate::parser::{FunctionIndex, IndexedFunction};
 use crate::types::{
-    AnalysisConfig, FileAnalysisResult, FunctionAnalysisResult, ParseError, SkipReason,
-    SkippedItem, MAX_FUNCTION_LINES,
+    AnalysisConfig, FileAnalysisResult, FunctionAnalysisResult, FunctionId, NestedFunction,
+    ParseError, SkipReason, SkippedItem, StructuralResult, SyntaxError,
 };
 
 /// Function node types recognized by tree-sitter for TypeScript/JavaScript.
@@ -29,6 +32,9 @@ pub fn is_function_node(kind: &str) -> bool {
     )
 }
 
+/// Name of the synthetic unit holding a file's top-level code.
+pub const MODULE_UNIT_NAME: &str = "<module>";
+
 /// Punctuation tokens excluded when counting parameters.
 pub const PUNCTUATION: &[&str] = &[",", "(", ")", "<", ">", ";"];
 
@@ -66,133 +72,268 @@ pub fn extract_function_name<'a>(node: &tree_sitter::Node<'a>, source: &'a [u8])
 
 /// Analyze a single file and produce a complete FileAnalysisResult.
 ///
-/// Runs all metric analyzers on the same parsed tree in a single pass,
-/// merges per-function results, computes health scores, and embeds
-/// the token sequence for subsequent duplication detection.
+/// Builds the file's `FunctionIndex`, runs every metric analyzer against each
+/// outermost function in it, computes health scores and class metrics, and
+/// embeds the token sequence for subsequent duplication detection.
 ///
-/// Functions exceeding `MAX_FUNCTION_LINES` are excluded from the result and
-/// returned in the `Vec<SkippedItem>` alongside the analysis result.
+/// Functions exceeding the configured maximum function length for `path` are
+/// excluded from the result and returned in the `Vec<SkippedItem>` alongside
+/// the analysis result.
 pub fn analyze_file(
     path: &Path,
     config: &AnalysisConfig,
 ) -> Result<(FileAnalysisResult, Vec<SkippedItem>), ParseError> {
-    let language = crate::parser::select_language(path)?;
+    crate::parser::select_language(path)?;
     let source = std::fs::read(path)?;
+    analyze_source(path, &source, config)
+}
 
-    let mut parser = tree_sitter::Parser::new();
-    parser
-        .set_language(&language)
-        .map_err(|e| ParseError::LanguageError(e.to_string()))?;
-
-    let tree = parser.parse(&source, None).ok_or(ParseError::ParseFailed)?;
+/// Analyze in-memory `source` as if it were the contents of `path`.
+///
+/// `path` only selects the grammar and the size limits that apply; nothing is
+/// read from disk. Otherwise identical to `analyze_file`.
+pub fn analyze_source(
+    path: &Path,
+    source: &[u8],
+    config: &AnalysisConfig,
+) -> Result<(FileAnalysisResult, Vec<SkippedItem>), ParseError> {
+    let tree = parse_source(path, source)?;
     let root = tree.root_node();
-    let has_error = root.has_error();
+    let syntax_errors = crate::parser::collect_syntax_errors(root, source);
 
-    // Run all metric analyzers on the same root node
-    let cyclomatic_results = cyclomatic::analyze_functions(root, &source, &config.cyclomatic);
-    let cognitive_results = cognitive::analyze_functions(root, &source);
-    let halstead_results = halstead::analyze_functions(root, &source);
-    let structural_results = structural::analyze_functions(root, &source);
-    let file_structural = structural::analyze_file(&source, root);
+    // One discovery pass decides the function set; every metric computes against it
+    let index = FunctionIndex::build(root, source);
+    let file_structural = structural::analyze_file(source, root);
 
     // Tokenize BEFORE tree is dropped (avoids re-parse); skip when disabled
     let tokens = if config.duplication.enabled {
-        duplication::tokenize_tree(root, &source)
+        duplication::tokenize_tree(root, source)
     } else {
         Vec::new()
     };
 
-    // All walkers discover functions in the same DFS order
-    let func_count = cyclomatic_results.len();
-    assert_eq!(
-        cognitive_results.len(),
-        func_count,
-        "cognitive and cyclomatic function counts must match"
-    );
-    assert_eq!(
-        halstead_results.len(),
-        func_count,
-        "halstead and cyclomatic function counts must match"
-    );
-    assert_eq!(
-        structural_results.len(),
-        func_count,
-        "structural and cyclomatic function counts must match"
-    );
-
-    // Merge per-function results and compute health scores, skipping oversized functions
-    let mut functions = Vec::with_capacity(func_count);
-    let mut function_scores = Vec::with_capacity(func_count);
+    // Compute per-function metrics and health scores, skipping oversized functions
+    let max_function_lines = config.size_limits_for(path).max_function_lines;
+    let mut functions = Vec::new();
+    let mut function_scores = Vec::new();
     let mut skipped_functions: Vec<SkippedItem> = Vec::new();
 
-    for i in 0..func_count {
-        let cycl = &cyclomatic_results[i];
-        let cogn = &cognitive_results[i];
-        let hal = &halstead_results[i];
-        let struc = &structural_results[i];
+    for func in index.outermost() {
+        let struc = structural::analyze_function(func, source);
 
         // Skip functions that exceed the line count limit
-        if struc.function_length > MAX_FUNCTION_LINES {
+        if struc.function_length > max_function_lines {
             skipped_functions.push(SkippedItem {
                 path: path.to_path_buf(),
-                function_name: Some(cycl.name.clone()),
-                start_line: cycl.start_line,
+                function_name: Some(func.qualified_name.clone()),
+                start_line: func.start_line,
                 reason: SkipReason::FunctionTooLarge {
                     lines: struc.function_length,
-                    max_lines: MAX_FUNCTION_LINES,
+                    max_lines: max_function_lines,
                 },
             });
             continue;
         }
 
-        let health_score = scoring::compute_function_score(
-            cycl.complexity,
-            cogn.complexity,
-            hal.volume,
-            struc.function_length,
-            struc.params_count,
-            struc.nesting_depth,
-            &config.scoring_weights,
-            &config.scoring_thresholds,
-        );
-
-        function_scores.push(health_score);
-
-        functions.push(FunctionAnalysisResult {
-            name: cycl.name.clone(),
-            start_line: cycl.start_line,
-            end_line: cycl.end_line,
-            start_col: cycl.start_col,
-            cyclomatic: cycl.complexity,
-            cognitive: cogn.complexity,
-            halstead_volume: hal.volume,
-            halstead_difficulty: hal.difficulty,
-            halstead_effort: hal.effort,
-            halstead_time: hal.time,
-            halstead_bugs: hal.bugs,
-            function_length: struc.function_length,
-            params_count: struc.params_count,
-            nesting_depth: struc.nesting_depth,
-            health_score,
-        });
+        let result = measure_function(func, struc, &index, source, &syntax_errors, config);
+        function_scores.push(result.health_score);
+        functions.push(result);
     }
 
     let file_score = scoring::compute_file_score(&function_scores);
+    let classes = classes::analyze_classes(&index, root, source, &config.cyclomatic);
+    let module = analyze_module(root, source, &index, &syntax_errors, config);
 
     Ok((
         FileAnalysisResult {
             path: path.to_path_buf(),
             functions,
+            classes,
+            module,
             tokens,
             file_score,
             file_length: file_structural.file_length,
             export_count: file_structural.export_count,
-            error: has_error,
+            syntax_errors,
+            imports: imports::collect_imports(root, source),
+            coupling: None,
+            cycles: Vec::new(),
         },
         skipped_functions,
     ))
 }
 
+/// Parse `source` with the grammar `path` selects.
+fn parse_source(path: &Path, source: &[u8]) -> Result<tree_sitter::Tree, ParseError> {
+    let language = crate::parser::select_language(path)?;
+
+    let mut parser = tree_sitter::Parser::new();
+    parser
+        .set_language(&language)
+        .map_err(|e| ParseError::LanguageError(e.to_string()))?;
+
+    parser.parse(source, None).ok_or(ParseError::ParseFailed)
+}
+
+/// Analyze the one function of in-memory `source` that `select` picks from
+/// the file's `FunctionIndex`, nested functions included.
+///
+/// A nested function is measured like an outermost one: the bodies of the
+/// functions nested inside it are excluded. No size limits apply. Returns
+/// `None` when `select` picks no function.
+pub fn analyze_indexed_function(
+    path: &Path,
+    source: &[u8],
+    config: &AnalysisConfig,
+    select: impl FnOnce(&FunctionIndex) -> Option<FunctionId>,
+) -> Result<Option<FunctionAnalysisResult>, ParseError> {
+    let tree = parse_source(path, source)?;
+    let root = tree.root_node();
+    let syntax_errors = crate::parser::collect_syntax_errors(root, source);
+    let index = FunctionIndex::build(root, source);
+    let Some(func) = select(&index).and_then(|id| index.get(id)) else {
+        return Ok(None);
+    };
+    let struc = structural::analyze_function(func, source);
+    Ok(Some(measure_function(
+        func,
+        struc,
+        &index,
+        source,
+        &syntax_errors,
+        config,
+    )))
+}
+
+/// Run every metric analyzer against `func` and score it.
+fn measure_function(
+    func: &IndexedFunction,
+    struc: StructuralResult,
+    index: &FunctionIndex,
+    source: &[u8],
+    syntax_errors: &[SyntaxError],
+    config: &AnalysisConfig,
+) -> FunctionAnalysisResult {
+    let cycl = cyclomatic::analyze_function(func, &config.cyclomatic);
+    let cogn = cognitive::analyze_function(func, source);
+    let hal = halstead::analyze_function(func, source);
+
+    let health_score = scoring::compute_function_score(
+        cycl.complexity,
+        cogn.complexity,
+        hal.volume,
+        struc.function_length,
+        struc.params_count,
+        struc.nesting_depth,
+        &config.scoring_weights,
+        &config.scoring_thresholds,
+    );
+
+    // Line-based containment: an error inside a nested function also flags its parents
+    let unreliable = syntax_errors
+        .iter()
+        .any(|e| e.line >= func.start_line && e.line <= func.end_line);
+
+    FunctionAnalysisResult {
+        name: func.name.clone(),
+        qualified_name: func.qualified_name.clone(),
+        start_line: func.start_line,
+        end_line: func.end_line,
+        start_col: func.start_col,
+        cyclomatic: cycl.complexity,
+        cognitive: cogn.complexity,
+        halstead_volume: hal.volume,
+        halstead_difficulty: hal.difficulty,
+        halstead_effort: hal.effort,
+        halstead_time: hal.time,
+        halstead_bugs: hal.bugs,
+        function_length: struc.function_length,
+        params_count: struc.params_count,
+        nesting_depth: struc.nesting_depth,
+        health_score,
+        unreliable,
+        fingerprint: duplication::body_fingerprint(func.node, source),
+        nested: index
+            .descendants(func.id)
+            .map(|nested| NestedFunction {
+                name: nested.name.clone(),
+                qualified_name: nested.qualified_name.clone(),
+                start_line: nested.start_line,
+                end_line: nested.end_line,
+            })
+            .collect(),
+    }
+}
+
+/// Measure the statements outside every function as a synthetic `<module>` unit.
+///
+/// The unit has no length or parameters, so only the complexity, Halstead and
+/// nesting thresholds can flag it. Its health score is not part of the file score.
+/// Returns `None` when the top-level code has no Halstead volume.
+fn analyze_module(
+    root: tree_sitter::Node,
+    source: &[u8],
+    index: &FunctionIndex,
+    syntax_errors: &[SyntaxError],
+    config: &AnalysisConfig,
+) -> Option<FunctionAnalysisResult> {
+    let (volume, difficulty, effort, time, bugs) = halstead::analyze_module(root, source);
+    if volume == 0.0 {
+        return None;
+    }
+
+    let cycl = cyclomatic::analyze_module(root, &config.cyclomatic);
+    let cogn = cognitive::analyze_module(root, source);
+    let nesting_depth = structural::module_nesting_depth(root);
+
+    let health_score = scoring::compute_function_score(
+        cycl,
+        cogn,
+        volume,
+        0,
+        0,
+        nesting_depth,
+        &config.scoring_weights,
+        &config.scoring_thresholds,
+    );
+
+    let unreliable = syntax_errors.iter().any(|e| {
+        !index
+            .outermost()
+            .any(|f| e.line >= f.start_line && e.line <= f.end_line)
+    });
+
+    // The program node ends after the trailing newline, on a line of its own
+    let end = root.end_position();
+    let end_line = if end.column == 0 {
+        end.row.max(1)
+    } else {
+        end.row + 1
+    };
+
+    Some(FunctionAnalysisResult {
+        name: MODULE_UNIT_NAME.to_string(),
+        qualified_name: MODULE_UNIT_NAME.to_string(),
+        start_line: 1,
+        end_line,
+        start_col: 0,
+        cyclomatic: cycl,
+        cognitive: cogn,
+        halstead_volume: volume,
+        halstead_difficulty: difficulty,
+        halstead_effort: effort,
+        halstead_time: time,
+        halstead_bugs: bugs,
+        function_length: 0,
+        params_count: 0,
+        nesting_depth,
+        health_score,
+        unreliable,
+        fingerprint: 0,
+        nested: Vec::new(),
+    })
+}
+
 // TESTS
 
 #[cfg(test)]
@@ -231,7 +372,7 @@ mod tests {
         let language: tree_sitter::Language = tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into();
         let mut parser = tree_sitter::Parser::new();
         parser.set_language(&language).unwrap();
-        // A bare arrow function node returns <anonymous>; naming context is resolved by cyclomatic walker
+        // A bare arrow function node returns <anonymous>; naming context is resolved by the function index
         let source = b"const f = () => 1;";
         let tree = parser.parse(source, None).unwrap();
         let root = tree.root_node();
@@ -295,6 +436,16 @@ mod tests {
             "Should find 'default export': {:?}",
             names
         );
+        assert!(
+            names.contains(&"Widget.onClick"),
+            "Should find 'Widget.onClick': {:?}",
+            names
+        );
+        assert!(
+            names.contains(&"compute"),
+            "Should find 'compute': {:?}",
+            names
+        );
     }
 
     #[test]
@@ -358,7 +509,7 @@ mod tests {
         // File-level metrics
         assert_eq!(result.file_length, 2);
         assert_eq!(result.export_count, 1);
-        assert!(!result.error);
+        assert!(result.syntax_errors.is_empty());
 
         // Tokens should be non-empty
         assert!(!result.tokens.is_empty(), "tokens should be non-empty");
@@ -502,4 +653,42 @@ mod tests {
             result.file_score
         );
     }
+
+    #[test]
+    fn analyze_file_module_unit_measures_top_level_code() {
+        let fixture_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/typescript/module_top_level.ts");
+        let config = AnalysisConfig::default();
+        let (result, _skipped) = analyze_file(&fixture_path, &config).unwrap();
+
+        let module = result
+            .module
+            .expect("top-level code should produce a module unit");
+        assert_eq!(module.name, MODULE_UNIT_NAME);
+        assert_eq!((module.start_line, module.end_line), (1, 24));
+        // 1 + `??` + if + for-of + if + `&&`; the callback's ternary is not counted
+        assert_eq!(module.cyclomatic, 6);
+        // ?? (1) + if (1) + for (2) + if (3) + && (1) + else (1)
+        assert_eq!(module.cognitive, 9);
+        assert_eq!(module.nesting_depth, 3);
+        assert_eq!(module.function_length, 0);
+        assert_eq!(module.params_count, 0);
+        assert!(module.halstead_volume > 0.0);
+
+        // The module is reported on its own, not as one of the functions
+        let names: Vec<&str> = result.functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["load", "map callback"]);
+        assert_eq!(result.functions[0].cyclomatic, 2);
+    }
+
+    #[test]
+    fn analyze_file_without_top_level_code_has_no_module() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("only_functions.ts");
+        std::fs::write(&path, "function a() { return 1; }\nfunction b() {}\n").unwrap();
+        let (result, _skipped) = analyze_file(&path, &AnalysisConfig::default()).unwrap();
+
+        assert!(result.module.is_none());
+        assert_eq!(result.functions.len(), 2);
+    }
 }
@@ -1,127 +1,56 @@
 use crate::metrics::{is_function_node, PUNCTUATION};
+use crate::parser::{FunctionIndex, IndexedFunction};
 use crate::types::{FileStructuralResult, StructuralResult};
 
-/// Analyze all functions in the AST and return structural metrics for each.
-pub fn analyze_functions(root: tree_sitter::Node, source: &[u8]) -> Vec<StructuralResult> {
-    let mut results = Vec::new();
-    walk_and_analyze(root, source, &mut results, None);
-    results
+/// Compute structural metrics for every outermost function in the index.
+pub fn analyze_functions(index: &FunctionIndex, source: &[u8]) -> Vec<StructuralResult> {
+    index
+        .outermost()
+        .map(|func| analyze_function(func, source))
+        .collect()
 }
 
-/// Compute file-level structural metrics.
-pub fn analyze_file(source: &[u8], root: tree_sitter::Node) -> FileStructuralResult {
-    let file_length = count_logical_lines(source, 0, source.len());
-    let export_count = count_exports(root);
-    FileStructuralResult {
-        file_length,
-        export_count,
-    }
-}
-
-/// Name context passed from parent nodes to resolve anonymous function names.
-struct NameContext {
-    name: String,
-    class_name: Option<String>,
-}
+/// Compute structural metrics for a single indexed function.
+pub fn analyze_function(func: &IndexedFunction, source: &[u8]) -> StructuralResult {
+    let node = func.node;
 
-fn walk_and_analyze(
-    node: tree_sitter::Node,
-    source: &[u8],
-    results: &mut Vec<StructuralResult>,
-    parent_ctx: Option<&NameContext>,
-) {
-    let kind = node.kind();
-
-    if is_function_node(kind) {
-        let mut name = crate::metrics::extract_function_name(&node, source);
+    // Compute function_length and nesting_depth
+    let mut function_length: u32 = 1; // default for expression-body arrows
+    let mut nesting_depth: u32 = 0;
 
-        // Apply parent context naming
-        if let Some(ctx) = parent_ctx {
-            if let Some(ref class_name) = ctx.class_name {
-                if kind == "method_definition" && name != "<anonymous>" {
-                    name = format!("{}.{}", class_name, name);
-                }
-            } else if name == "<anonymous>" && !ctx.name.is_empty() {
-                name = ctx.name.clone();
-            }
-        }
-
-        let start = node.start_position();
-        let end = node.end_position();
-
-        // Compute function_length and nesting_depth
-        let mut function_length: u32 = 1; // default for expression-body arrows
-        let mut nesting_depth: u32 = 0;
-
-        for i in 0..node.child_count() as u32 {
-            if let Some(child) = node.child(i) {
-                if child.kind() == "statement_block" {
-                    function_length =
-                        count_logical_lines(source, child.start_byte(), child.end_byte());
-                    nesting_depth = max_nesting_depth(child);
-                    break;
-                }
+    for i in 0..node.child_count() as u32 {
+        if let Some(child) = node.child(i) {
+            if child.kind() == "statement_block" {
+                function_length = count_logical_lines(source, child.start_byte(), child.end_byte());
+                nesting_depth = max_nesting_depth(child);
+                break;
             }
         }
-
-        let params_count = count_parameters(node);
-
-        results.push(StructuralResult {
-            name,
-            function_length,
-            params_count,
-            nesting_depth,
-            start_line: start.row + 1,
-            end_line: end.row + 1,
-            start_col: start.column,
-        });
-
-        // Don't recurse into nested functions
-        return;
     }
 
-    // Build naming context for children
-    let mut child_ctx: Option<NameContext> = None;
-
-    if kind == "variable_declarator" {
-        if let Some(name_node) = node.child_by_field_name("name") {
-            if let Ok(text) = name_node.utf8_text(source) {
-                child_ctx = Some(NameContext {
-                    name: text.to_string(),
-                    class_name: None,
-                });
-            }
-        }
-    } else if kind == "class_declaration" || kind == "class" {
-        let mut class_name = "class".to_string();
-        for i in 0..node.child_count() as u32 {
-            if let Some(child) = node.child(i) {
-                let ct = child.kind();
-                if ct == "identifier" || ct == "type_identifier" {
-                    if let Ok(text) = child.utf8_text(source) {
-                        class_name = text.to_string();
-                    }
-                    break;
-                }
-            }
-        }
-        child_ctx = Some(NameContext {
-            name: "<anonymous>".to_string(),
-            class_name: Some(class_name),
-        });
-    } else if kind == "class_body" || kind == "arguments" {
-        if let Some(ctx) = parent_ctx {
-            child_ctx = Some(NameContext {
-                name: ctx.name.clone(),
-                class_name: ctx.class_name.clone(),
-            });
-        }
+    StructuralResult {
+        name: func.name.clone(),
+        function_length,
+        params_count: count_parameters(node),
+        nesting_depth,
+        start_line: func.start_line,
+        end_line: func.end_line,
+        start_col: func.start_col,
     }
+}
 
-    for i in 0..node.child_count() as u32 {
-        if let Some(child) = node.child(i) {
-            walk_and_analyze(child, source, results, child_ctx.as_ref().or(parent_ctx));
-        }
+/// Compute the maximum nesting depth of a file's top-level code.
+pub fn module_nesting_depth(root: tree_sitter::Node) -> u32 {
+    max_nesting_depth(root)
+}
+
+/// Compute file-level structural metrics.
+pub fn analyze_file(source: &[u8], root: tree_sitter::Node) -> FileStructuralResult {
+    let file_length = count_logical_lines(source, 0, source.len());
+    let export_count = count_exports(root);
+    FileStructuralResult {
+        file_length,
+        export_count,
     }
 }
 
@@ -342,8 +271,8 @@ mod tests {
     #[test]
     fn arrow_function_expression_body_length_1() {
         let (tree, bytes) = parse_ts("const double = (x: number) => x * 2;");
-        let root = tree.root_node();
-        let results = analyze_functions(root, &bytes);
+        let index = FunctionIndex::build(tree.root_node(), &bytes);
+        let results = analyze_functions(&index, &bytes);
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].function_length, 1);
         assert_eq!(results[0].name, "double");
@@ -355,8 +284,8 @@ mod tests {
             .join("tests/fixtures/typescript/structural_cases.ts");
         let source = std::fs::read_to_string(&fixture_path).unwrap();
         let (tree, bytes) = parse_ts(&source);
-        let root = tree.root_node();
-        let results = analyze_functions(root, &bytes);
+        let index = FunctionIndex::build(tree.root_node(), &bytes);
+        let results = analyze_functions(&index, &bytes);
 
         // shortFunction: line_count=3, params_count=1, nesting_depth=0
         let r = find_by_name(&results, "shortFunction").unwrap();
@@ -413,7 +342,7 @@ mod tests {
         assert_eq!(r.nesting_depth, 1);
 
         // File-level metrics
-        let file_result = analyze_file(&bytes, root);
+        let file_result = analyze_file(&bytes, tree.root_node());
         assert_eq!(file_result.file_length, 41);
         assert_eq!(file_result.export_count, 4);
     }
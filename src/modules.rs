@@ -0,0 +1,596 @@
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::tsconfig::{normalize, TsConfig};
+use crate::types::{FileAnalysisResult, FileCoupling, ImportCycle, ImportKind, ImportStep};
+
+/// Name violations of a whole file are reported under.
+pub const FILE_UNIT_NAME: &str = "<file>";
+
+/// Extensions tried for a specifier without one, TypeScript first.
+const EXTENSIONS: [&str; 9] = ["ts", "tsx", "mts", "cts", "d.ts", "js", "jsx", "mjs", "cjs"];
+
+/// Resolves import specifiers to analyzed files the way the TypeScript
+/// compiler does: relative paths and `baseUrl`/`paths` aliases, with or
+/// without an extension, to a file or a directory's index file. Packages and
+/// files that were not analyzed do not resolve.
+#[derive(Debug, Clone, Default)]
+pub struct ModuleResolver {
+    /// Index of each analyzed file by its normalized path
+    files: HashMap<PathBuf, usize>,
+    base_url: Option<PathBuf>,
+    paths: Vec<(String, Vec<PathBuf>)>,
+}
+
+impl ModuleResolver {
+    /// A resolver over `files`, with the aliases of `tsconfig` when given.
+    pub fn new(files: &[FileAnalysisResult], tsconfig: Option<&TsConfig>) -> Self {
+        Self {
+            files: files
+                .iter()
+                .enumerate()
+                .map(|(i, f)| (normalize(&f.path), i))
+                .collect(),
+            base_url: tsconfig.and_then(|t| t.base_url.clone()),
+            paths: tsconfig.and_then(|t| t.paths.clone()).unwrap_or_default(),
+        }
+    }
+
+    /// The index of the file `specifier` names when imported from `from`.
+    pub fn resolve(&self, from: &Path, specifier: &str) -> Option<usize> {
+        if specifier.starts_with("./")
+            || specifier.starts_with("../")
+            || specifier == "."
+            || specifier == ".."
+        {
+            let dir = from.parent().unwrap_or(Path::new(""));
+            return self.lookup(&dir.join(specifier));
+        }
+        if let Some((star, targets)) = self.alias(specifier) {
+            return targets
+                .iter()
+                .find_map(|t| self.lookup(Path::new(&t.to_string_lossy().replace('*', star))));
+        }
+        self.base_url
+            .as_ref()
+            .and_then(|base| self.lookup(&base.join(specifier)))
+    }
+
+    /// The `paths` pattern matching `specifier` with the longest prefix
+    /// before its `*`, with the text the `*` matched.
+    fn alias<'a>(&self, specifier: &'a str) -> Option<(&'a str, &[PathBuf])> {
+        let mut best: Option<(usize, &'a str, &[PathBuf])> = None;
+        for (pattern, targets) in &self.paths {
+            let matched = match pattern.split_once('*') {
+                None if pattern == specifier => Some((usize::MAX, "")),
+                None => None,
+                Some((prefix, suffix)) => specifier
+                    .strip_prefix(prefix)
+                    .and_then(|rest| rest.strip_suffix(suffix))
+                    .map(|star| (prefix.len(), star)),
+            };
+            if let Some((len, star)) = matched {
+                if best.map_or(true, |(best_len, ..)| len > best_len) {
+                    best = Some((len, star, targets));
+                }
+            }
+        }
+        best.map(|(_, star, targets)| (star, targets))
+    }
+
+    /// The analyzed file at `path`: as is, with a `.js`-style extension
+    /// swapped for its TypeScript source, with an extension added, or the
+    /// index file of the directory.
+    fn lookup(&self, path: &Path) -> Option<usize> {
+        let path = normalize(path);
+        let text = path.to_string_lossy();
+        let swapped = [("js", "ts"), ("jsx", "tsx"), ("mjs", "mts"), ("cjs", "cts")]
+            .iter()
+            .filter(|(js, _)| path.extension().is_some_and(|e| e == *js))
+            .map(|(_, ts)| path.with_extension(ts));
+        let with_extension = EXTENSIONS
+            .iter()
+            .map(|ext| PathBuf::from(format!("{text}.{ext}")));
+        let index = EXTENSIONS
+            .iter()
+            .map(|ext| path.join(format!("index.{ext}")));
+        std::iter::once(path.clone())
+            .chain(swapped)
+            .chain(with_extension)
+            .chain(index)
+            .find_map(|candidate| self.files.get(&candidate).copied())
+    }
+}
+
+/// How weakly an import ties two files together: static, dynamic, type-only.
+fn import_rank(kind: ImportKind, type_only: bool) -> u8 {
+    match (type_only, kind) {
+        (true, _) => 2,
+        (false, ImportKind::Dynamic) => 1,
+        (false, _) => 0,
+    }
+}
+
+/// The import graph between analyzed files.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ModuleGraph {
+    /// Every analyzed file, in report order
+    pub modules: Vec<ModuleNode>,
+    /// One edge per importing and imported file, by importing file and line
+    pub edges: Vec<ModuleEdge>,
+    /// Every cycle of static imports, by the path of its first file
+    pub cycles: Vec<ImportCycle>,
+}
+
+/// An analyzed file and its coupling.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ModuleNode {
+    pub path: String,
+    #[serde(flatten)]
+    pub coupling: FileCoupling,
+}
+
+/// A file importing another.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ModuleEdge {
+    pub from: String,
+    pub to: String,
+    /// Line of the first import of `to` in `from`, preferring static imports
+    /// over dynamic ones and those over type-only ones
+    pub line: usize,
+    /// Kind of that import
+    pub kind: ImportKind,
+    /// Every import of `to` in `from` is type-only
+    pub type_only: bool,
+}
+
+impl ModuleEdge {
+    /// Whether `from` evaluates `to` while it is itself evaluated, so that
+    /// the edge can be part of an import cycle. Type-only imports are erased
+    /// and dynamic `import()` waits until it is called.
+    pub fn is_static(&self) -> bool {
+        !self.type_only && self.kind != ImportKind::Dynamic
+    }
+}
+
+impl ModuleGraph {
+    /// Resolve the imports of every file and measure each file's coupling.
+    /// A file importing itself is not an edge.
+    pub fn build(files: &[FileAnalysisResult], resolver: &ModuleResolver) -> Self {
+        let mut edges: BTreeMap<(usize, usize), ModuleEdge> = BTreeMap::new();
+        for (from, file) in files.iter().enumerate() {
+            for import in &file.imports {
+                let Some(to) = resolver.resolve(&file.path, &import.specifier) else {
+                    continue;
+                };
+                if to == from {
+                    continue;
+                }
+                edges
+                    .entry((from, to))
+                    .and_modify(|edge| {
+                        if import_rank(import.kind, import.type_only)
+                            < import_rank(edge.kind, edge.type_only)
+                        {
+                            edge.line = import.line;
+                            edge.kind = import.kind;
+                        }
+                        edge.type_only &= import.type_only;
+                    })
+                    .or_insert_with(|| ModuleEdge {
+                        from: file.path.to_string_lossy().to_string(),
+                        to: files[to].path.to_string_lossy().to_string(),
+                        line: import.line,
+                        kind: import.kind,
+                        type_only: import.type_only,
+                    });
+            }
+        }
+
+        let mut fan_in = vec![0u32; files.len()];
+        let mut fan_out = vec![0u32; files.len()];
+        for &(from, to) in edges.keys() {
+            fan_out[from] += 1;
+            fan_in[to] += 1;
+        }
+        let modules = files
+            .iter()
+            .enumerate()
+            .map(|(i, file)| {
+                let total = fan_in[i] + fan_out[i];
+                ModuleNode {
+                    path: file.path.to_string_lossy().to_string(),
+                    coupling: FileCoupling {
+                        fan_in: fan_in[i],
+                        fan_out: fan_out[i],
+                        instability: if total == 0 {
+                            0.0
+                        } else {
+                            fan_out[i] as f64 / total as f64
+                        },
+                    },
+                }
+            })
+            .collect();
+
+        let cycles = import_cycles(files, &edges);
+        let mut edges: Vec<((usize, usize), ModuleEdge)> = edges.into_iter().collect();
+        edges.sort_by_key(|((from, _), edge)| (*from, edge.line));
+        ModuleGraph {
+            modules,
+            edges: edges.into_iter().map(|(_, edge)| edge).collect(),
+            cycles,
+        }
+    }
+
+    /// The graph in Graphviz DOT, with type-only imports dashed and the
+    /// imports of each reported cycle red.
+    pub fn to_dot(&self) -> String {
+        let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+        let mut dot = String::from("digraph modules {\n  rankdir=LR;\n  node [shape=box];\n");
+        for module in &self.modules {
+            let _ = writeln!(dot, "  {};", quote(&module.path));
+        }
+        let cyclic: HashSet<(&str, &str)> = self
+            .cycles
+            .iter()
+            .flat_map(|cycle| {
+                cycle
+                    .chain
+                    .iter()
+                    .zip(cycle.chain.iter().cycle().skip(1))
+                    .map(|(from, to)| (from.path.as_str(), to.path.as_str()))
+            })
+            .collect();
+        for edge in &self.edges {
+            let style = if edge.type_only {
+                " [style=dashed]"
+            } else if cyclic.contains(&(edge.from.as_str(), edge.to.as_str())) {
+                " [color=red]"
+            } else {
+                ""
+            };
+            let _ = writeln!(
+                dot,
+                "  {} -> {}{style};",
+                quote(&edge.from),
+                quote(&edge.to)
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Write the graph to `path`: DOT for a `.dot` or `.gv` file, JSON otherwise.
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        let content = match path.extension().and_then(|e| e.to_str()) {
+            Some("dot" | "gv") => self.to_dot(),
+            _ => serde_json::to_string_pretty(self)?,
+        };
+        std::fs::write(path, content)
+            .map_err(|e| anyhow::anyhow!("failed to write {}: {e}", path.display()))
+    }
+}
+
+/// The import cycles among `files`, given the `edges` between their indices:
+/// each strongly connected component of static edges with more than one
+/// file, with the shortest chain from its first file back to it.
+fn import_cycles(
+    files: &[FileAnalysisResult],
+    edges: &BTreeMap<(usize, usize), ModuleEdge>,
+) -> Vec<ImportCycle> {
+    let mut successors = vec![Vec::new(); files.len()];
+    for (&(from, to), edge) in edges {
+        if edge.is_static() {
+            successors[from].push(to);
+        }
+    }
+
+    let mut components: Vec<Vec<usize>> = strongly_connected(&successors)
+        .into_iter()
+        .filter(|component| component.len() > 1)
+        .collect();
+    for component in &mut components {
+        component.sort_unstable();
+    }
+    components.sort_unstable();
+
+    components
+        .into_iter()
+        .map(|component| {
+            let chain = shortest_cycle(&component, &successors);
+            ImportCycle {
+                files: component
+                    .iter()
+                    .map(|&i| files[i].path.to_string_lossy().to_string())
+                    .collect(),
+                chain: chain
+                    .iter()
+                    .zip(chain.iter().cycle().skip(1))
+                    .map(|(&from, &to)| ImportStep {
+                        path: files[from].path.to_string_lossy().to_string(),
+                        line: edges[&(from, to)].line,
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// Tarjan's strongly connected components, without recursion so deep import
+/// chains cannot overflow the stack.
+fn strongly_connected(successors: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    const UNVISITED: usize = usize::MAX;
+    let count = successors.len();
+    let mut index = vec![UNVISITED; count];
+    let mut low = vec![0; count];
+    let mut on_stack = vec![false; count];
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+    let mut next_index = 0;
+
+    for root in 0..count {
+        if index[root] != UNVISITED {
+            continue;
+        }
+        // Each frame is a node and the position of its next successor
+        let mut frames = vec![(root, 0)];
+        index[root] = next_index;
+        low[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some(frame) = frames.last_mut() {
+            let node = frame.0;
+            if let Some(&successor) = successors[node].get(frame.1) {
+                frame.1 += 1;
+                if index[successor] == UNVISITED {
+                    index[successor] = next_index;
+                    low[successor] = next_index;
+                    next_index += 1;
+                    stack.push(successor);
+                    on_stack[successor] = true;
+                    frames.push((successor, 0));
+                } else if on_stack[successor] {
+                    low[node] = low[node].min(index[successor]);
+                }
+                continue;
+            }
+
+            frames.pop();
+            if let Some(&(parent, _)) = frames.last() {
+                low[parent] = low[parent].min(low[node]);
+            }
+            if low[node] == index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+/// The shortest cycle from the first file of a sorted `component` back to
+/// it, by breadth-first search within the component.
+fn shortest_cycle(component: &[usize], successors: &[Vec<usize>]) -> Vec<usize> {
+    let start = component[0];
+    let mut parent: HashMap<usize, usize> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        for &successor in &successors[node] {
+            if component.binary_search(&successor).is_err() {
+                continue;
+            }
+            if successor == start {
+                let mut chain = vec![node];
+                while let Some(&previous) = parent.get(chain.last().unwrap()) {
+                    chain.push(previous);
+                }
+                chain.reverse();
+                return chain;
+            }
+            if let Entry::Vacant(entry) = parent.entry(successor) {
+                entry.insert(node);
+                queue.push_back(successor);
+            }
+        }
+    }
+    // Unreachable for a strongly connected component
+    vec![start]
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Analyzer;
+
+    fn analyze(sources: &[(&str, &str)]) -> Vec<FileAnalysisResult> {
+        sources
+            .iter()
+            .fold(Analyzer::new(), |analyzer, (path, source)| {
+                analyzer.source(*path, *source)
+            })
+            .run()
+            .unwrap()
+            .files
+    }
+
+    /// The path `specifier` resolves to from `from`.
+    fn resolved(
+        files: &[FileAnalysisResult],
+        resolver: &ModuleResolver,
+        from: &str,
+        specifier: &str,
+    ) -> Option<String> {
+        resolver
+            .resolve(Path::new(from), specifier)
+            .map(|i| files[i].path.to_string_lossy().to_string())
+    }
+
+    #[test]
+    fn resolves_relative_paths_extensions_and_index_files() {
+        let files = analyze(&[
+            ("./src/app.ts", "export const app = 1;"),
+            ("./src/ui/index.tsx", "export const ui = 1;"),
+            ("./src/util.ts", "export const util = 1;"),
+            ("./lib/legacy.js", "module.exports = 1;"),
+        ]);
+        let resolver = ModuleResolver::new(&files, None);
+        let resolve = |spec| resolved(&files, &resolver, "./src/app.ts", spec);
+        assert_eq!(resolve("./util").as_deref(), Some("./src/util.ts"));
+        assert_eq!(resolve("./util.js").as_deref(), Some("./src/util.ts"));
+        assert_eq!(resolve("./util.ts").as_deref(), Some("./src/util.ts"));
+        assert_eq!(resolve("./ui").as_deref(), Some("./src/ui/index.tsx"));
+        assert_eq!(resolve("../lib/legacy").as_deref(), Some("./lib/legacy.js"));
+        assert_eq!(resolve("./missing"), None);
+        assert_eq!(resolve("react"), None);
+    }
+
+    #[test]
+    fn resolves_tsconfig_path_aliases_and_base_url() {
+        let files = analyze(&[
+            ("src/app.ts", ""),
+            ("src/shared/date.ts", ""),
+            ("src/shared/special.ts", ""),
+            ("src/config.ts", ""),
+        ]);
+        let tsconfig = TsConfig {
+            base_url: Some(PathBuf::from("src")),
+            paths: Some(vec![
+                ("@shared/*".to_string(), vec![PathBuf::from("src/shared/*")]),
+                (
+                    "@shared/special/*".to_string(),
+                    vec![PathBuf::from("nowhere/*"), PathBuf::from("src/shared/*")],
+                ),
+                ("config".to_string(), vec![PathBuf::from("src/config")]),
+            ]),
+            ..TsConfig::default()
+        };
+        let resolver = ModuleResolver::new(&files, Some(&tsconfig));
+        let resolve = |spec| resolved(&files, &resolver, "src/app.ts", spec);
+        assert_eq!(
+            resolve("@shared/date").as_deref(),
+            Some("src/shared/date.ts")
+        );
+        assert_eq!(
+            resolve("@shared/special/special").as_deref(),
+            Some("src/shared/special.ts")
+        );
+        assert_eq!(resolve("config").as_deref(), Some("src/config.ts"));
+        assert_eq!(
+            resolve("shared/date").as_deref(),
+            Some("src/shared/date.ts")
+        );
+        assert_eq!(resolve("@shared/none"), None);
+    }
+
+    #[test]
+    fn measures_coupling_and_exports_the_graph() {
+        let files = analyze(&[
+            (
+                "a.ts",
+                "import { b } from './b';\nimport type { C } from './c';\nimport './b';",
+            ),
+            ("b.ts", "export const b = require('./c');"),
+            ("c.ts", "import './c';\nexport type C = number;"),
+        ]);
+        let graph = ModuleGraph::build(&files, &ModuleResolver::new(&files, None));
+
+        let coupling: Vec<_> = graph
+            .modules
+            .iter()
+            .map(|m| (m.path.as_str(), m.coupling.fan_in, m.coupling.fan_out))
+            .collect();
+        assert_eq!(coupling, [("a.ts", 0, 2), ("b.ts", 1, 1), ("c.ts", 2, 0)]);
+        assert_eq!(graph.modules[0].coupling.instability, 1.0);
+        assert_eq!(graph.modules[1].coupling.instability, 0.5);
+        assert_eq!(graph.modules[2].coupling.instability, 0.0);
+
+        let edges: Vec<_> = graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str(), e.line, e.type_only))
+            .collect();
+        assert_eq!(
+            edges,
+            [
+                ("a.ts", "b.ts", 1, false),
+                ("a.ts", "c.ts", 2, true),
+                ("b.ts", "c.ts", 1, false)
+            ]
+        );
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph modules {\n"));
+        assert!(dot.contains("  \"a.ts\" -> \"b.ts\";\n"));
+        assert!(dot.contains("  \"a.ts\" -> \"c.ts\" [style=dashed];\n"));
+        let json = serde_json::to_value(&graph).unwrap();
+        assert_eq!(json["modules"][1]["fan_in"], 1);
+        assert_eq!(json["edges"][2]["kind"], "require");
+    }
+
+    #[test]
+    fn finds_static_import_cycles_with_their_shortest_chain() {
+        let files = analyze(&[
+            ("a.ts", "import { b } from './b';\nimport { d } from './d';"),
+            (
+                "b.ts",
+                "import type { A } from './a';\nexport * from './c';",
+            ),
+            ("c.ts", "\n\nimport './a';\nimport './b';"),
+            ("d.ts", "export const load = () => import('./a');"),
+            (
+                "e.ts",
+                "import type { F } from './f';\nimport { f } from './f';",
+            ),
+            ("f.ts", "const e = require('./e');"),
+            ("g.ts", "import './a';"),
+        ]);
+        let graph = ModuleGraph::build(&files, &ModuleResolver::new(&files, None));
+
+        // The type-only import of a.ts and the dynamic one of d.ts break no
+        // cycle at runtime, so neither joins a.ts, b.ts and c.ts
+        let cycles: Vec<_> = graph
+            .cycles
+            .iter()
+            .map(|cycle| {
+                let chain: Vec<_> = cycle
+                    .chain
+                    .iter()
+                    .map(|s| (s.path.as_str(), s.line))
+                    .collect();
+                (cycle.files.clone(), chain)
+            })
+            .collect();
+        assert_eq!(
+            cycles,
+            [
+                (
+                    vec!["a.ts".to_string(), "b.ts".to_string(), "c.ts".to_string()],
+                    vec![("a.ts", 1), ("b.ts", 2), ("c.ts", 3)]
+                ),
+                (
+                    vec!["e.ts".to_string(), "f.ts".to_string()],
+                    vec![("e.ts", 2), ("f.ts", 1)]
+                ),
+            ]
+        );
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("  \"c.ts\" -> \"a.ts\" [color=red];\n"));
+        assert!(dot.contains("  \"c.ts\" -> \"b.ts\";\n"));
+        assert!(dot.contains("  \"d.ts\" -> \"a.ts\";\n"));
+    }
+}
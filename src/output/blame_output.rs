@@ -0,0 +1,134 @@
+use std::io::Write;
+
+use owo_colors::OwoColorize;
+
+use crate::blame::BlameReport;
+use crate::cli::ResolvedConfig;
+use crate::history::format_date;
+use crate::output::console::should_use_color;
+
+/// Authors and units listed by default; verbose mode lists all.
+const TOP_AUTHORS: usize = 10;
+
+/// Renders the violations-by-author table as a console section. Verbose mode
+/// also lists the blame of every unit.
+pub fn render_blame_console(
+    blame: &BlameReport,
+    config: &ResolvedConfig,
+    writer: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let use_color = should_use_color(config.color);
+    let limit = if config.verbose {
+        usize::MAX
+    } else {
+        TOP_AUTHORS
+    };
+
+    let header = match &blame.since {
+        Some(since) => format!("Violations by author (since {since}):"),
+        None => "Violations by author:".to_string(),
+    };
+    writeln!(writer)?;
+    if use_color {
+        writeln!(writer, "{}", header.bold())?;
+    } else {
+        writeln!(writer, "{header}")?;
+    }
+    if blame.authors.is_empty() {
+        writeln!(writer, "  No violating unit has blamed lines")?;
+        return Ok(());
+    }
+
+    for (idx, author) in blame.authors.iter().take(limit).enumerate() {
+        writeln!(
+            writer,
+            "  {}. {}  {} errors, {} warnings in {} units",
+            idx + 1,
+            author.author,
+            author.errors,
+            author.warnings,
+            author.units
+        )?;
+    }
+    if config.verbose {
+        writeln!(writer, "  Units:")?;
+        for unit in &blame.units {
+            writeln!(
+                writer,
+                "    {} ({}:{})  {} ({}/{} lines), last changed {} in {}",
+                unit.name,
+                unit.path,
+                unit.start_line,
+                unit.blame.author,
+                unit.blame.author_lines,
+                unit.blame.lines,
+                format_date(unit.blame.date),
+                &unit.blame.commit[..unit.blame.commit.len().min(8)]
+            )?;
+        }
+    }
+    Ok(())
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blame::{AuthorSummary, Blame, UnitBlame};
+
+    fn blame() -> BlameReport {
+        BlameReport {
+            since: Some("2026-01-01".to_string()),
+            units: vec![UnitBlame {
+                path: "src/api.ts".to_string(),
+                name: "handler".to_string(),
+                start_line: 12,
+                errors: 2,
+                warnings: 1,
+                blame: Blame {
+                    author: "Ada".to_string(),
+                    author_lines: 30,
+                    lines: 40,
+                    commit: "0123456789abcdef0123456789abcdef01234567".to_string(),
+                    date: 1_790_812_800,
+                },
+            }],
+            authors: (0..12)
+                .map(|i| AuthorSummary {
+                    author: format!("author{i}"),
+                    units: 1,
+                    errors: 12 - i,
+                    warnings: 1,
+                })
+                .collect(),
+        }
+    }
+
+    fn render(config: &ResolvedConfig) -> String {
+        let mut out = Vec::new();
+        render_blame_console(&blame(), config, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn lists_top_authors() {
+        let mut config = ResolvedConfig {
+            color: Some(false),
+            ..ResolvedConfig::default()
+        };
+        let text = render(&config);
+        assert!(text.contains("Violations by author (since 2026-01-01):"));
+        assert!(text.contains("1. author0  12 errors, 1 warnings in 1 units"));
+        assert!(text.contains("10. author9"));
+        assert!(!text.contains("author10"));
+        assert!(!text.contains("Units:"));
+
+        config.verbose = true;
+        let text = render(&config);
+        assert!(text.contains("12. author11"));
+        assert!(text.contains(
+            "handler (src/api.ts:12)  Ada (30/40 lines), last changed 2026-10-01 in 01234567"
+        ));
+    }
+}
@@ -0,0 +1,398 @@
+#[cfg(feature = "console")]
+use std::io::Write;
+
+#[cfg(feature = "console")]
+use owo_colors::OwoColorize;
+
+#[cfg(feature = "console")]
+use crate::cli::ResolvedConfig;
+use crate::compare::{Comparison, FileChange, FunctionChange, FunctionStatus, ViolationChange};
+#[cfg(feature = "console")]
+use crate::output::console::should_use_color;
+use crate::output::violations::Severity;
+
+/// Renders a comparison for the terminal.
+///
+/// Quiet mode shows only the new violations and the verdict.
+#[cfg(feature = "console")]
+pub fn render_compare_console(
+    comparison: &Comparison,
+    config: &ResolvedConfig,
+    writer: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let use_color = should_use_color(config.color);
+    let summary = &comparison.summary;
+
+    if !config.quiet {
+        let delta = summary.new_health - summary.old_health;
+        let health = format!(
+            "Health: {:.1} → {:.1} ({:+.1})",
+            summary.old_health, summary.new_health, delta
+        );
+        if use_color && delta < 0.0 {
+            writeln!(writer, "{}", health.red())?;
+        } else if use_color && delta > 0.0 {
+            writeln!(writer, "{}", health.green())?;
+        } else {
+            writeln!(writer, "{health}")?;
+        }
+    }
+
+    if !comparison.new_violations.is_empty() {
+        writeln!(writer)?;
+        writeln!(writer, "New violations:")?;
+        for change in &comparison.new_violations {
+            let symbol = match change.severity {
+                Severity::Error if use_color => "✗".red().to_string(),
+                Severity::Warning if use_color => "⚠".yellow().to_string(),
+                Severity::Error => "✗".to_string(),
+                Severity::Warning => "⚠".to_string(),
+            };
+            writeln!(writer, "  {symbol} {}", violation_line(change))?;
+        }
+    }
+
+    if !config.quiet {
+        if !comparison.resolved_violations.is_empty() {
+            writeln!(writer)?;
+            writeln!(writer, "Resolved violations:")?;
+            for change in &comparison.resolved_violations {
+                let symbol = if use_color {
+                    "✓".green().to_string()
+                } else {
+                    "✓".to_string()
+                };
+                writeln!(writer, "  {symbol} {}", violation_line(change))?;
+            }
+        }
+
+        if !comparison.functions.is_empty() {
+            writeln!(writer)?;
+            writeln!(writer, "Changed functions:")?;
+            for change in &comparison.functions {
+                let symbol = match change.status {
+                    FunctionStatus::Added => "+",
+                    FunctionStatus::Removed => "-",
+                    FunctionStatus::Modified => "~",
+                    FunctionStatus::Moved => "→",
+                };
+                let detail = function_detail(change);
+                let detail = if detail.is_empty() {
+                    String::new()
+                } else {
+                    format!("  {detail}")
+                };
+                writeln!(
+                    writer,
+                    "  {symbol} {}:{}  {}{detail}",
+                    change.path, change.line, change.name
+                )?;
+            }
+        }
+
+        if !comparison.files.is_empty() {
+            writeln!(writer)?;
+            writeln!(writer, "Files:")?;
+            for change in &comparison.files {
+                writeln!(writer, "  {}  {}", change.path, file_detail(change))?;
+            }
+        }
+        writeln!(writer)?;
+    }
+
+    let resolved = summary.resolved_errors + summary.resolved_warnings;
+    let verdict = verdict(comparison);
+    if !use_color {
+        writeln!(writer, "{verdict}")?;
+    } else if summary.new_errors > 0 {
+        writeln!(writer, "{}", verdict.red())?;
+    } else if summary.new_warnings > 0 {
+        writeln!(writer, "{}", verdict.yellow())?;
+    } else if resolved > 0 {
+        writeln!(writer, "{}", verdict.green())?;
+    } else {
+        writeln!(writer, "{verdict}")?;
+    }
+    Ok(())
+}
+
+/// Renders a comparison as GitHub-flavored markdown, e.g. for a PR comment.
+pub fn render_compare_markdown(comparison: &Comparison) -> String {
+    let summary = &comparison.summary;
+    let mut out = String::from("## Complexity comparison\n\n");
+    out.push_str(&format!(
+        "**Health:** {:.1} → {:.1} ({:+.1})\n\n",
+        summary.old_health,
+        summary.new_health,
+        summary.new_health - summary.old_health
+    ));
+    out.push_str(&format!("{}\n", verdict(comparison)));
+
+    let sections = [
+        ("New violations", &comparison.new_violations),
+        ("Resolved violations", &comparison.resolved_violations),
+    ];
+    for (title, changes) in sections {
+        if changes.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("\n### {title}\n\n"));
+        out.push_str("| Severity | Location | Function | Message |\n");
+        out.push_str("|---|---|---|---|\n");
+        for change in changes {
+            out.push_str(&violation_row(change));
+        }
+    }
+
+    if !comparison.functions.is_empty() {
+        out.push_str("\n### Changed functions\n\n");
+        out.push_str("| Change | Location | Function | Details |\n");
+        out.push_str("|---|---|---|---|\n");
+        for change in &comparison.functions {
+            let status = match change.status {
+                FunctionStatus::Added => "added",
+                FunctionStatus::Removed => "removed",
+                FunctionStatus::Modified => "modified",
+                FunctionStatus::Moved => "moved",
+            };
+            out.push_str(&format!(
+                "| {status} | {} | {} | {} |\n",
+                code(&format!("{}:{}", change.path, change.line)),
+                code(&change.name),
+                escape(&function_detail(change))
+            ));
+        }
+    }
+
+    if !comparison.files.is_empty() {
+        out.push_str("\n### Files\n\n");
+        out.push_str("| File | Health |\n");
+        out.push_str("|---|---|\n");
+        for change in &comparison.files {
+            out.push_str(&format!(
+                "| {} | {} |\n",
+                code(&change.path),
+                file_detail(change)
+            ));
+        }
+    }
+    out
+}
+
+/// Renders a comparison as pretty-printed JSON.
+pub fn render_compare_json(comparison: &Comparison) -> anyhow::Result<String> {
+    #[derive(serde::Serialize)]
+    struct JsonComparison<'a> {
+        version: &'a str,
+        #[serde(flatten)]
+        comparison: &'a Comparison,
+    }
+    Ok(serde_json::to_string_pretty(&JsonComparison {
+        version: env!("CARGO_PKG_VERSION"),
+        comparison,
+    })?)
+}
+
+/// One-line verdict, e.g. `✗ 2 regressions (1 new errors, 1 new warnings), 3 resolved`.
+fn verdict(comparison: &Comparison) -> String {
+    let summary = &comparison.summary;
+    let new = summary.new_errors + summary.new_warnings;
+    let resolved = summary.resolved_errors + summary.resolved_warnings;
+    if new == 0 {
+        return format!("✓ No regressions ({resolved} resolved)");
+    }
+    let symbol = if summary.new_errors > 0 { "✗" } else { "⚠" };
+    format!(
+        "{symbol} {new} regressions ({} new errors, {} new warnings), {resolved} resolved",
+        summary.new_errors, summary.new_warnings
+    )
+}
+
+#[cfg(feature = "console")]
+fn violation_line(change: &ViolationChange) -> String {
+    format!(
+        "{}:{}  {}  {}",
+        change.path, change.line, change.name, change.message
+    )
+}
+
+fn violation_row(change: &ViolationChange) -> String {
+    let severity = match change.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    };
+    format!(
+        "| {severity} | {} | {} | {} |\n",
+        code(&format!("{}:{}", change.path, change.line)),
+        code(&change.name),
+        escape(&change.message)
+    )
+}
+
+/// Metric changes, plus where a moved function came from.
+fn function_detail(change: &FunctionChange) -> String {
+    let mut parts = Vec::new();
+    if change.status == FunctionStatus::Moved {
+        let from = [change.old_path.as_deref(), change.old_name.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let similarity = change.similarity.unwrap_or(1.0) * 100.0;
+        parts.push(format!("from {from} ({similarity:.0}% similar)"));
+    }
+    parts.extend(change.metrics.iter().map(|m| {
+        let precision = match m.metric {
+            "halstead_volume" | "health_score" => 1,
+            _ => 0,
+        };
+        format!(
+            "{} {:.p$} → {:.p$} ({:+.p$})",
+            m.metric,
+            m.old,
+            m.new,
+            m.delta,
+            p = precision
+        )
+    }));
+    parts.join(", ")
+}
+
+fn file_detail(change: &FileChange) -> String {
+    match (change.old_health, change.new_health) {
+        (Some(old), Some(new)) => format!("{old:.1} → {new:.1} ({:+.1})", new - old),
+        (None, Some(new)) => format!("added ({new:.1})"),
+        (Some(old), None) => format!("removed (was {old:.1})"),
+        (None, None) => String::new(),
+    }
+}
+
+/// Escapes table cell separators.
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+/// Formats text as an inline code span inside a table cell.
+fn code(text: &str) -> String {
+    if text.contains('`') {
+        format!("`` {} ``", escape(text))
+    } else {
+        format!("`{}`", escape(text))
+    }
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compare::{ComparisonSummary, MetricDelta};
+
+    fn comparison() -> Comparison {
+        Comparison {
+            summary: ComparisonSummary {
+                old_health: 82.0,
+                new_health: 79.5,
+                new_errors: 1,
+                new_warnings: 0,
+                resolved_errors: 0,
+                resolved_warnings: 1,
+                functions_added: 0,
+                functions_removed: 0,
+                functions_changed: 2,
+            },
+            new_violations: vec![ViolationChange {
+                path: "src/a.ts".to_string(),
+                name: "a || b".to_string(),
+                rule_id: "complexity-guard/cognitive".to_string(),
+                severity: Severity::Error,
+                line: 12,
+                message: "Cognitive complexity 30 exceeds error threshold 25".to_string(),
+            }],
+            resolved_violations: vec![ViolationChange {
+                path: "src/b.ts".to_string(),
+                name: "handler".to_string(),
+                rule_id: "complexity-guard/cyclomatic".to_string(),
+                severity: Severity::Warning,
+                line: 3,
+                message: "Cyclomatic complexity 11 exceeds warning threshold 10".to_string(),
+            }],
+            functions: vec![
+                FunctionChange {
+                    status: FunctionStatus::Modified,
+                    path: "src/a.ts".to_string(),
+                    name: "a || b".to_string(),
+                    line: 12,
+                    old_path: None,
+                    old_name: None,
+                    similarity: None,
+                    metrics: vec![MetricDelta {
+                        metric: "cognitive",
+                        old: 20.0,
+                        new: 30.0,
+                        delta: 10.0,
+                    }],
+                },
+                FunctionChange {
+                    status: FunctionStatus::Moved,
+                    path: "lib/sum.ts".to_string(),
+                    name: "sumParts".to_string(),
+                    line: 1,
+                    old_path: Some("src/a.ts".to_string()),
+                    old_name: Some("total".to_string()),
+                    similarity: Some(0.953125),
+                    metrics: Vec::new(),
+                },
+            ],
+            files: vec![FileChange {
+                path: "src/c.ts".to_string(),
+                old_health: None,
+                new_health: Some(90.0),
+            }],
+        }
+    }
+
+    #[test]
+    fn markdown_lists_changes_in_tables() {
+        let markdown = render_compare_markdown(&comparison());
+        assert!(markdown.contains("**Health:** 82.0 → 79.5 (-2.5)"));
+        assert!(markdown.contains("✗ 1 regressions (1 new errors, 0 new warnings), 1 resolved"));
+        assert!(markdown.contains("| error | `src/a.ts:12` | `a \\|\\| b` |"));
+        assert!(markdown
+            .contains("| modified | `src/a.ts:12` | `a \\|\\| b` | cognitive 20 → 30 (+10) |"));
+        assert!(markdown.contains("from src/a.ts total (95% similar)"));
+        assert!(markdown.contains("| `src/c.ts` | added (90.0) |"));
+    }
+
+    #[test]
+    fn json_includes_version_and_summary() {
+        let json: serde_json::Value =
+            serde_json::from_str(&render_compare_json(&comparison()).unwrap()).unwrap();
+        assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(json["summary"]["new_errors"], 1);
+        assert_eq!(json["new_violations"][0]["severity"], "error");
+        assert_eq!(json["functions"][1]["status"], "moved");
+        assert!(json["functions"][0].get("old_path").is_none());
+    }
+
+    #[cfg(feature = "console")]
+    #[test]
+    fn console_quiet_shows_only_new_violations() {
+        let mut config = crate::cli::resolve_config(&crate::cli::config_defaults());
+        config.color = Some(false);
+        config.quiet = true;
+        let mut out = Vec::new();
+        render_compare_console(&comparison(), &config, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("New violations:"));
+        assert!(!text.contains("Resolved violations:"));
+        assert!(!text.contains("Health:"));
+
+        config.quiet = false;
+        let mut out = Vec::new();
+        render_compare_console(&comparison(), &config, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("  → lib/sum.ts:1  sumParts  from src/a.ts total (95% similar)"));
+        assert!(text.contains("  src/c.ts  added (90.0)"));
+    }
+}
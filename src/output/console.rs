@@ -3,27 +3,16 @@ use std::io::Write;
 use owo_colors::OwoColorize;
 
 use crate::cli::ResolvedConfig;
+use crate::metrics::MODULE_UNIT_NAME;
+use crate::output::violations::{
+    class_violations, coupling_violations, cycle_violations, function_violations,
+    syntax_error_message, worst_severity, Severity, Violation,
+};
 use crate::types::{
-    DuplicationResult, FileAnalysisResult, FunctionAnalysisResult, SkipReason, SkippedItem,
+    ClassAnalysisResult, DuplicationResult, FileAnalysisResult, FileCoupling,
+    FunctionAnalysisResult, SkipReason, SkippedItem, SyntaxError,
 };
 
-/// Severity level for a single threshold violation.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-pub enum Severity {
-    Warning,
-    Error,
-}
-
-/// A single threshold violation for a function metric.
-#[derive(Debug, Clone)]
-pub struct Violation {
-    pub line: usize,
-    pub col: usize,
-    pub severity: Severity,
-    pub message: String,
-    pub rule_id: String,
-}
-
 /// Determines color usage based on flags and environment variables.
 ///
 /// Priority: --no-color > --color > NO_COLOR env > FORCE_COLOR/YES_COLOR env > TTY
@@ -45,270 +34,6 @@ pub fn should_use_color(force_color: Option<bool>) -> bool {
     std::io::stdout().is_terminal()
 }
 
-/// Computes all violations for a function against the resolved thresholds.
-pub fn function_violations(
-    func: &FunctionAnalysisResult,
-    config: &ResolvedConfig,
-) -> Vec<Violation> {
-    let mut violations: Vec<Violation> = Vec::new();
-
-    // Cyclomatic complexity
-    let cyc = func.cyclomatic as f64;
-    if cyc >= config.cyclomatic_error as f64 {
-        violations.push(Violation {
-            line: func.start_line,
-            col: func.start_col,
-            severity: Severity::Error,
-            message: format!(
-                "Cyclomatic complexity {} exceeds error threshold {}",
-                func.cyclomatic, config.cyclomatic_error
-            ),
-            rule_id: "complexity-guard/cyclomatic".to_string(),
-        });
-    } else if cyc >= config.cyclomatic_warning as f64 {
-        violations.push(Violation {
-            line: func.start_line,
-            col: func.start_col,
-            severity: Severity::Warning,
-            message: format!(
-                "Cyclomatic complexity {} exceeds warning threshold {}",
-                func.cyclomatic, config.cyclomatic_warning
-            ),
-            rule_id: "complexity-guard/cyclomatic".to_string(),
-        });
-    }
-
-    // Cognitive complexity
-    let cog = func.cognitive as f64;
-    if cog >= config.cognitive_error as f64 {
-        violations.push(Violation {
-            line: func.start_line,
-            col: func.start_col,
-            severity: Severity::Error,
-            message: format!(
-                "Cognitive complexity {} exceeds error threshold {}",
-                func.cognitive, config.cognitive_error
-            ),
-            rule_id: "complexity-guard/cognitive".to_string(),
-        });
-    } else if cog >= config.cognitive_warning as f64 {
-        violations.push(Violation {
-            line: func.start_line,
-            col: func.start_col,
-            severity: Severity::Warning,
-            message: format!(
-                "Cognitive complexity {} exceeds warning threshold {}",
-                func.cognitive, config.cognitive_warning
-            ),
-            rule_id: "complexity-guard/cognitive".to_string(),
-        });
-    }
-
-    // Halstead volume
-    if func.halstead_volume >= config.halstead_volume_error {
-        violations.push(Violation {
-            line: func.start_line,
-            col: func.start_col,
-            severity: Severity::Error,
-            message: format!(
-                "Halstead volume {:.1} exceeds error threshold {:.1}",
-                func.halstead_volume, config.halstead_volume_error
-            ),
-            rule_id: "complexity-guard/halstead-volume".to_string(),
-        });
-    } else if func.halstead_volume >= config.halstead_volume_warning {
-        violations.push(Violation {
-            line: func.start_line,
-            col: func.start_col,
-            severity: Severity::Warning,
-            message: format!(
-                "Halstead volume {:.1} exceeds warning threshold {:.1}",
-                func.halstead_volume, config.halstead_volume_warning
-            ),
-            rule_id: "complexity-guard/halstead-volume".to_string(),
-        });
-    }
-
-    // Halstead difficulty
-    if func.halstead_difficulty >= config.halstead_difficulty_error {
-        violations.push(Violation {
-            line: func.start_line,
-            col: func.start_col,
-            severity: Severity::Error,
-            message: format!(
-                "Halstead difficulty {:.1} exceeds error threshold {:.1}",
-                func.halstead_difficulty, config.halstead_difficulty_error
-            ),
-            rule_id: "complexity-guard/halstead-difficulty".to_string(),
-        });
-    } else if func.halstead_difficulty >= config.halstead_difficulty_warning {
-        violations.push(Violation {
-            line: func.start_line,
-            col: func.start_col,
-            severity: Severity::Warning,
-            message: format!(
-                "Halstead difficulty {:.1} exceeds warning threshold {:.1}",
-                func.halstead_difficulty, config.halstead_difficulty_warning
-            ),
-            rule_id: "complexity-guard/halstead-difficulty".to_string(),
-        });
-    }
-
-    // Halstead effort
-    if func.halstead_effort >= config.halstead_effort_error {
-        violations.push(Violation {
-            line: func.start_line,
-            col: func.start_col,
-            severity: Severity::Error,
-            message: format!(
-                "Halstead effort {:.1} exceeds error threshold {:.1}",
-                func.halstead_effort, config.halstead_effort_error
-            ),
-            rule_id: "complexity-guard/halstead-effort".to_string(),
-        });
-    } else if func.halstead_effort >= config.halstead_effort_warning {
-        violations.push(Violation {
-            line: func.start_line,
-            col: func.start_col,
-            severity: Severity::Warning,
-            message: format!(
-                "Halstead effort {:.1} exceeds warning threshold {:.1}",
-                func.halstead_effort, config.halstead_effort_warning
-            ),
-            rule_id: "complexity-guard/halstead-effort".to_string(),
-        });
-    }
-
-    // Halstead bugs
-    if func.halstead_bugs >= config.halstead_bugs_error {
-        violations.push(Violation {
-            line: func.start_line,
-            col: func.start_col,
-            severity: Severity::Error,
-            message: format!(
-                "Halstead bugs {:.3} exceeds error threshold {:.3}",
-                func.halstead_bugs, config.halstead_bugs_error
-            ),
-            rule_id: "complexity-guard/halstead-bugs".to_string(),
-        });
-    } else if func.halstead_bugs >= config.halstead_bugs_warning {
-        violations.push(Violation {
-            line: func.start_line,
-            col: func.start_col,
-            severity: Severity::Warning,
-            message: format!(
-                "Halstead bugs {:.3} exceeds warning threshold {:.3}",
-                func.halstead_bugs, config.halstead_bugs_warning
-            ),
-            rule_id: "complexity-guard/halstead-bugs".to_string(),
-        });
-    }
-
-    // Nesting depth
-    let nd = func.nesting_depth as f64;
-    if nd >= config.nesting_depth_error as f64 {
-        violations.push(Violation {
-            line: func.start_line,
-            col: func.start_col,
-            severity: Severity::Error,
-            message: format!(
-                "Nesting depth {} exceeds error threshold {}",
-                func.nesting_depth, config.nesting_depth_error
-            ),
-            rule_id: "complexity-guard/nesting-depth".to_string(),
-        });
-    } else if nd >= config.nesting_depth_warning as f64 {
-        violations.push(Violation {
-            line: func.start_line,
-            col: func.start_col,
-            severity: Severity::Warning,
-            message: format!(
-                "Nesting depth {} exceeds warning threshold {}",
-                func.nesting_depth, config.nesting_depth_warning
-            ),
-            rule_id: "complexity-guard/nesting-depth".to_string(),
-        });
-    }
-
-    // Line count (function_length in FunctionAnalysisResult)
-    let lc = func.function_length as f64;
-    if lc >= config.line_count_error as f64 {
-        violations.push(Violation {
-            line: func.start_line,
-            col: func.start_col,
-            severity: Severity::Error,
-            message: format!(
-                "Line count {} exceeds error threshold {}",
-                func.function_length, config.line_count_error
-            ),
-            rule_id: "complexity-guard/line-count".to_string(),
-        });
-    } else if lc >= config.line_count_warning as f64 {
-        violations.push(Violation {
-            line: func.start_line,
-            col: func.start_col,
-            severity: Severity::Warning,
-            message: format!(
-                "Line count {} exceeds warning threshold {}",
-                func.function_length, config.line_count_warning
-            ),
-            rule_id: "complexity-guard/line-count".to_string(),
-        });
-    }
-
-    // Params count
-    let pc = func.params_count as f64;
-    if pc >= config.params_count_error as f64 {
-        violations.push(Violation {
-            line: func.start_line,
-            col: func.start_col,
-            severity: Severity::Error,
-            message: format!(
-                "Params count {} exceeds error threshold {}",
-                func.params_count, config.params_count_error
-            ),
-            rule_id: "complexity-guard/param-count".to_string(),
-        });
-    } else if pc >= config.params_count_warning as f64 {
-        violations.push(Violation {
-            line: func.start_line,
-            col: func.start_col,
-            severity: Severity::Warning,
-            message: format!(
-                "Params count {} exceeds warning threshold {}",
-                func.params_count, config.params_count_warning
-            ),
-            rule_id: "complexity-guard/param-count".to_string(),
-        });
-    }
-
-    violations
-}
-
-/// Returns the worst severity across all violations for a function.
-pub fn function_status(violations: &[Violation]) -> &'static str {
-    let has_error = violations.iter().any(|v| v.severity == Severity::Error);
-    let has_warning = violations.iter().any(|v| v.severity == Severity::Warning);
-    if has_error {
-        "error"
-    } else if has_warning {
-        "warning"
-    } else {
-        "ok"
-    }
-}
-
-/// Returns the worst severity across all violations for a function as an enum.
-fn worst_severity(violations: &[Violation]) -> Option<Severity> {
-    if violations.iter().any(|v| v.severity == Severity::Error) {
-        Some(Severity::Error)
-    } else if violations.iter().any(|v| v.severity == Severity::Warning) {
-        Some(Severity::Warning)
-    } else {
-        None
-    }
-}
-
 /// Checks whether halstead metrics have any violations.
 fn has_halstead_violation(func: &FunctionAnalysisResult, config: &ResolvedConfig) -> bool {
     func.halstead_volume >= config.halstead_volume_warning
@@ -319,7 +44,8 @@ fn has_halstead_violation(func: &FunctionAnalysisResult, config: &ResolvedConfig
 
 /// Renders the consolidated per-function console line matching Zig format.
 ///
-/// Format: `  {line}:{col}  {symbol}  {severity}  Function '{name}' cyclomatic {N} cognitive {N} [halstead vol {N}] [depth {N}]`
+/// Format: `  {line}:{col}  {symbol}  {severity}  Function '{name}' cyclomatic {N} cognitive {N} [halstead vol {N}] [depth {N}]`;
+/// the module unit is labelled `Module-level code` instead of `Function '<module>'`.
 fn render_function_line(
     func: &FunctionAnalysisResult,
     violations: &[Violation],
@@ -335,6 +61,11 @@ fn render_function_line(
     };
 
     let position = format!("{}:{}", func.start_line, func.start_col);
+    let subject = if func.name == MODULE_UNIT_NAME {
+        "Module-level code".to_string()
+    } else {
+        format!("Function '{}'", func.qualified_name)
+    };
 
     // Build the core line
     let line = if use_color {
@@ -347,14 +78,14 @@ fn render_function_line(
             ),
             Some(Severity::Error) => (symbol.red().to_string(), severity_str.red().to_string()),
         };
-        format!("  {pos_str}  {sym_colored}  {sev_colored}  Function '{name}' cyclomatic {cyc} cognitive {cog}",
-            name = func.name,
+        format!(
+            "  {pos_str}  {sym_colored}  {sev_colored}  {subject} cyclomatic {cyc} cognitive {cog}",
             cyc = func.cyclomatic,
             cog = func.cognitive,
         )
     } else {
-        format!("  {position}  {symbol}  {severity_str}  Function '{name}' cyclomatic {cyc} cognitive {cog}",
-            name = func.name,
+        format!(
+            "  {position}  {symbol}  {severity_str}  {subject} cyclomatic {cyc} cognitive {cog}",
             cyc = func.cyclomatic,
             cog = func.cognitive,
         )
@@ -384,7 +115,119 @@ fn render_function_line(
         structural_parts.push_str(&format!(" [depth {}]", func.nesting_depth));
     }
 
-    format!("{line}{halstead_suffix}{structural_parts}")
+    let unreliable_suffix = if func.unreliable {
+        " [unreliable: syntax error]"
+    } else {
+        ""
+    };
+
+    format!("{line}{halstead_suffix}{structural_parts}{unreliable_suffix}")
+}
+
+/// Renders the per-class console line in the same layout as function lines.
+///
+/// Format: `  {line}:{col}  {symbol}  {severity}  Class '{name}' methods {N} wmc {N} fields {N} lcom {N}`
+fn render_class_line(
+    class: &ClassAnalysisResult,
+    violations: &[Violation],
+    use_color: bool,
+) -> String {
+    let worst = worst_severity(violations);
+    let (symbol, severity_str) = match &worst {
+        None => ("✓", "ok"),
+        Some(Severity::Warning) => ("⚠", "warning"),
+        Some(Severity::Error) => ("✗", "error"),
+    };
+    let position = format!("{}:{}", class.start_line, class.start_col);
+    let metrics = format!(
+        "Class '{}' methods {} wmc {} fields {} lcom {}",
+        class.name, class.method_count, class.wmc, class.field_count, class.lcom
+    );
+
+    if use_color {
+        let (sym_colored, sev_colored) = match &worst {
+            None => (symbol.green().to_string(), severity_str.green().to_string()),
+            Some(Severity::Warning) => (
+                symbol.yellow().to_string(),
+                severity_str.yellow().to_string(),
+            ),
+            Some(Severity::Error) => (symbol.red().to_string(), severity_str.red().to_string()),
+        };
+        format!(
+            "  {}  {sym_colored}  {sev_colored}  {metrics}",
+            position.dimmed()
+        )
+    } else {
+        format!("  {position}  {symbol}  {severity_str}  {metrics}")
+    }
+}
+
+/// Renders the module graph coupling of a file in the same layout as function lines.
+fn render_coupling_line(
+    coupling: &FileCoupling,
+    violations: &[Violation],
+    use_color: bool,
+) -> String {
+    let worst = worst_severity(violations);
+    let (symbol, severity_str) = match &worst {
+        None => ("✓", "ok"),
+        Some(Severity::Warning) => ("⚠", "warning"),
+        Some(Severity::Error) => ("✗", "error"),
+    };
+    let position = "1:0";
+    let metrics = format!(
+        "Module fan-in {} fan-out {} instability {:.2}",
+        coupling.fan_in, coupling.fan_out, coupling.instability
+    );
+
+    if use_color {
+        let (sym_colored, sev_colored) = match &worst {
+            None => (symbol.green().to_string(), severity_str.green().to_string()),
+            Some(Severity::Warning) => (
+                symbol.yellow().to_string(),
+                severity_str.yellow().to_string(),
+            ),
+            Some(Severity::Error) => (symbol.red().to_string(), severity_str.red().to_string()),
+        };
+        format!(
+            "  {}  {sym_colored}  {sev_colored}  {metrics}",
+            position.dimmed()
+        )
+    } else {
+        format!("  {position}  {symbol}  {severity_str}  {metrics}")
+    }
+}
+
+/// Renders an import cycle error in the same layout as function lines.
+fn render_cycle_line(violation: &Violation, use_color: bool) -> String {
+    let position = format!("{}:{}", violation.line, violation.col);
+    let message = &violation.message;
+    if use_color {
+        format!(
+            "  {}  {}  {}  {message}",
+            position.dimmed(),
+            "✗".red(),
+            "error".red()
+        )
+    } else {
+        format!("  {position}  ✗  error  {message}")
+    }
+}
+
+/// Renders a syntax error line in the same layout as function lines.
+fn render_syntax_error_line(error: &SyntaxError, use_color: bool) -> String {
+    let position = format!("{}:{}", error.line, error.column);
+    let message = syntax_error_message(error);
+    if use_color {
+        format!(
+            "  {}  {}  {}  {message}",
+            position.dimmed(),
+            "⚠".yellow(),
+            "syntax".yellow()
+        )
+    } else {
+        format!("  {position}  ⚠  syntax  {message}")
+    }
 }
 
 /// Renders ESLint-style console output for all analysis results using the Zig consolidated format.
@@ -419,10 +262,52 @@ pub fn render_console(
     }
     let mut hotspot_items: Vec<HotspotItem> = Vec::new();
 
+    let mut syntax_error_count: usize = 0;
+    let mut syntax_error_files: usize = 0;
+
     for file in files {
         let mut file_lines: Vec<String> = Vec::new();
         let mut file_has_output = false;
 
+        if !file.syntax_errors.is_empty() {
+            syntax_error_count += file.syntax_errors.len();
+            syntax_error_files += 1;
+            if !config.quiet {
+                for error in &file.syntax_errors {
+                    file_lines.push(render_syntax_error_line(error, use_color));
+                }
+                file_has_output = true;
+            }
+        }
+
+        // Coupling in the module graph, reported on the file's first line
+        if let Some(coupling) = &file.coupling {
+            let violations = coupling_violations(file, config);
+            for v in &violations {
+                match v.severity {
+                    Severity::Error => total_errors += 1,
+                    Severity::Warning => total_warnings += 1,
+                }
+            }
+
+            let show = match worst_severity(&violations) {
+                None => config.verbose,
+                Some(Severity::Warning) => !config.quiet,
+                Some(Severity::Error) => true,
+            };
+            if show {
+                file_lines.push(render_coupling_line(coupling, &violations, use_color));
+                file_has_output = true;
+            }
+        }
+
+        // Import cycles are errors, shown at the import that starts the chain
+        for violation in cycle_violations(file, config) {
+            total_errors += 1;
+            file_lines.push(render_cycle_line(&violation, use_color));
+            file_has_output = true;
+        }
+
         for func in &file.functions {
             total_functions += 1;
             total_health += func.health_score;
@@ -442,7 +327,7 @@ pub fn render_console(
 
             // Track hotspot data
             hotspot_items.push(HotspotItem {
-                name: func.name.clone(),
+                name: func.qualified_name.clone(),
                 path: file.path.display().to_string(),
                 line: func.start_line,
                 cyclomatic: func.cyclomatic,
@@ -466,6 +351,58 @@ pub fn render_console(
             }
         }
 
+        // Top-level code: thresholded like a function, not counted as one
+        if let Some(module) = &file.module {
+            let violations = function_violations(module, config);
+            for v in &violations {
+                match v.severity {
+                    Severity::Error => total_errors += 1,
+                    Severity::Warning => total_warnings += 1,
+                }
+            }
+
+            hotspot_items.push(HotspotItem {
+                name: module.qualified_name.clone(),
+                path: file.path.display().to_string(),
+                line: module.start_line,
+                cyclomatic: module.cyclomatic,
+                cognitive: module.cognitive,
+                halstead_volume: module.halstead_volume,
+            });
+
+            let show = match worst_severity(&violations) {
+                None => config.verbose,
+                Some(Severity::Warning) => !config.quiet,
+                Some(Severity::Error) => true,
+            };
+            if show {
+                let line =
+                    render_function_line(module, &violations, config, use_color, config.verbose);
+                file_lines.push(line);
+                file_has_output = true;
+            }
+        }
+
+        for class in &file.classes {
+            let violations = class_violations(class, config);
+            for v in &violations {
+                match v.severity {
+                    Severity::Error => total_errors += 1,
+                    Severity::Warning => total_warnings += 1,
+                }
+            }
+
+            let show = match worst_severity(&violations) {
+                None => config.verbose,
+                Some(Severity::Warning) => !config.quiet,
+                Some(Severity::Error) => true,
+            };
+            if show {
+                file_lines.push(render_class_line(class, &violations, use_color));
+                file_has_output = true;
+            }
+        }
+
         // Print file section only if it has output
         if file_has_output {
             if use_color {
@@ -528,6 +465,13 @@ pub fn render_console(
         )?;
     }
 
+    if syntax_error_count > 0 {
+        writeln!(
+            writer,
+            "Found {syntax_error_count} syntax errors in {syntax_error_files} files"
+        )?;
+    }
+
     // Duplication section (if present)
     if let Some(dup) = duplication {
         writeln!(writer, "Duplication: {:.1}%", dup.duplication_percentage)?;
@@ -536,7 +480,7 @@ pub fn render_console(
     // Top cyclomatic hotspots (top 5, cyclomatic > 1)
     let mut cycl_hotspots: Vec<_> = hotspot_items.iter().filter(|h| h.cyclomatic > 1).collect();
     if !cycl_hotspots.is_empty() {
-        cycl_hotspots.sort_by(|a, b| b.cyclomatic.cmp(&a.cyclomatic));
+        cycl_hotspots.sort_by_key(|h| std::cmp::Reverse(h.cyclomatic));
         let top_count = cycl_hotspots.len().min(5);
         writeln!(writer)?;
         writeln!(writer, "Top cyclomatic hotspots:")?;
@@ -556,7 +500,7 @@ pub fn render_console(
     // Top cognitive hotspots (top 5, cognitive > 0)
     let mut cog_hotspots: Vec<_> = hotspot_items.iter().filter(|h| h.cognitive > 0).collect();
     if !cog_hotspots.is_empty() {
-        cog_hotspots.sort_by(|a, b| b.cognitive.cmp(&a.cognitive));
+        cog_hotspots.sort_by_key(|h| std::cmp::Reverse(h.cognitive));
         let top_count = cog_hotspots.len().min(5);
         writeln!(writer)?;
         writeln!(writer, "Top cognitive hotspots:")?;
@@ -645,6 +589,25 @@ fn render_skipped_section(
                     item.start_line
                 )
             }
+            SkipReason::GeneratedFile { marker } => {
+                format!("{path_str} — generated file ({marker})")
+            }
+            SkipReason::MinifiedFile {
+                average_line_length,
+                max_average_line_length,
+            } => {
+                format!(
+                    "{path_str} — minified file (average line length {average_line_length}, max {max_average_line_length})"
+                )
+            }
+            SkipReason::Failed { error } => format!("{path_str} — failed: {error}"),
+            SkipReason::SyntaxErrors { errors } => {
+                let first = errors
+                    .first()
+                    .map(|e| format!(", first at {}:{}", e.line, e.column))
+                    .unwrap_or_default();
+                format!("{path_str} — {} syntax errors{first}", errors.len())
+            }
         };
         writeln!(writer, "  {description}")?;
     }
@@ -708,6 +671,7 @@ mod tests {
     ) -> FunctionAnalysisResult {
         FunctionAnalysisResult {
             name: name.to_string(),
+            qualified_name: name.to_string(),
             start_line,
             end_line: start_line + 10,
             start_col: 0,
@@ -722,6 +686,9 @@ mod tests {
             params_count: 1,
             nesting_depth: 1,
             health_score,
+            unreliable: false,
+            fingerprint: 0,
+            nested: Vec::new(),
         }
     }
 
@@ -729,11 +696,16 @@ mod tests {
         FileAnalysisResult {
             path: PathBuf::from(path),
             functions,
+            classes: vec![],
+            module: None,
             tokens: vec![],
             file_score: 90.0,
             file_length: 100,
             export_count: 1,
-            error: false,
+            syntax_errors: vec![],
+            imports: Vec::new(),
+            coupling: None,
+            cycles: Vec::new(),
         }
     }
 
@@ -835,6 +807,69 @@ mod tests {
         assert!(output.contains("error"), "Should show error severity text");
     }
 
+    fn make_class(name: &str, method_count: u32, wmc: u32, lcom: u32) -> ClassAnalysisResult {
+        ClassAnalysisResult {
+            name: name.to_string(),
+            start_line: 3,
+            end_line: 90,
+            start_col: 0,
+            method_count,
+            wmc,
+            field_count: 4,
+            lcom,
+        }
+    }
+
+    #[test]
+    fn test_render_console_class_line_counts_violations() {
+        let mut file = make_file("src/god.ts", vec![]);
+        file.classes = vec![make_class("God", 45, 10, 1), make_class("Tidy", 3, 3, 1)];
+        let config = default_config();
+        let mut buf = Vec::new();
+        render_console(&[file], None, &config, &mut buf, &[]).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+
+        assert!(output.contains("3:0  ✗  error  Class 'God' methods 45 wmc 10 fields 4 lcom 1"));
+        assert!(!output.contains("Tidy"), "ok classes only shown in verbose");
+        assert!(output.contains("Found 0 warnings, 1 errors"));
+    }
+
+    #[test]
+    fn test_render_console_coupling_line_counts_violations() {
+        let mut file = make_file("src/shared.ts", vec![]);
+        file.coupling = Some(FileCoupling {
+            fan_in: 45,
+            fan_out: 2,
+            instability: 2.0 / 47.0,
+        });
+        let config = default_config();
+        let mut buf = Vec::new();
+        render_console(&[file], None, &config, &mut buf, &[]).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+
+        assert!(output.contains("1:0  ✗  error  Module fan-in 45 fan-out 2 instability 0.04"));
+        assert!(output.contains("Found 0 warnings, 1 errors"));
+    }
+
+    #[test]
+    fn test_render_console_module_line_counts_violations() {
+        let mut file = make_file("src/setup.ts", vec![make_func("f", 10, 2, 1, 95.0)]);
+        let mut module = make_func("<module>", 1, 12, 3, 85.0);
+        module.function_length = 0;
+        module.params_count = 0;
+        file.module = Some(module);
+        let config = default_config();
+        let mut buf = Vec::new();
+        render_console(&[file], None, &config, &mut buf, &[]).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+
+        assert!(output.contains("1:0  ⚠  warning  Module-level code cyclomatic 12"));
+        assert!(!output.contains("Function '<module>'"));
+        assert!(output.contains("Found 1 warnings, 0 errors"));
+        // The module is not counted as a function
+        assert!(output.contains("Analyzed 1 files, 1 functions"));
+    }
+
     #[test]
     fn test_render_console_summary_format() {
         let func1 = make_func("f1", 1, 12, 5, 72.0); // warning
@@ -984,69 +1019,6 @@ mod tests {
         assert!(output.contains("problems"), "Should show problems count");
     }
 
-    #[test]
-    fn test_function_violations_cyclomatic_warning() {
-        let func = make_func("f", 1, 12, 0, 80.0);
-        let config = default_config();
-        let violations = function_violations(&func, &config);
-        assert_eq!(violations.len(), 1);
-        assert_eq!(violations[0].severity, Severity::Warning);
-        assert!(violations[0].rule_id.contains("cyclomatic"));
-    }
-
-    #[test]
-    fn test_function_violations_cyclomatic_error() {
-        let func = make_func("f", 1, 25, 0, 40.0);
-        let config = default_config();
-        let violations = function_violations(&func, &config);
-        let cyc_violations: Vec<_> = violations
-            .iter()
-            .filter(|v| v.rule_id.contains("cyclomatic"))
-            .collect();
-        assert_eq!(cyc_violations.len(), 1);
-        assert_eq!(cyc_violations[0].severity, Severity::Error);
-    }
-
-    #[test]
-    fn test_function_violations_no_violations() {
-        let func = make_func("f", 1, 3, 3, 95.0);
-        let config = default_config();
-        let violations = function_violations(&func, &config);
-        assert!(
-            violations.is_empty(),
-            "Should have no violations for low complexity"
-        );
-    }
-
-    #[test]
-    fn test_function_status_ok() {
-        assert_eq!(function_status(&[]), "ok");
-    }
-
-    #[test]
-    fn test_function_status_warning() {
-        let v = Violation {
-            line: 1,
-            col: 0,
-            severity: Severity::Warning,
-            message: "test".to_string(),
-            rule_id: "test".to_string(),
-        };
-        assert_eq!(function_status(&[v]), "warning");
-    }
-
-    #[test]
-    fn test_function_status_error() {
-        let v = Violation {
-            line: 1,
-            col: 0,
-            severity: Severity::Error,
-            message: "test".to_string(),
-            rule_id: "test".to_string(),
-        };
-        assert_eq!(function_status(&[v]), "error");
-    }
-
     #[test]
     fn test_render_console_multiple_files_summary() {
         let func1 = make_func("f", 1, 2, 1, 90.0);
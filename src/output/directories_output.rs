@@ -0,0 +1,126 @@
+use std::io::Write;
+
+use owo_colors::OwoColorize;
+
+use crate::cli::ResolvedConfig;
+use crate::directories::DirectoryNode;
+use crate::output::console::should_use_color;
+
+/// Renders per-directory totals as an indented console table, down to
+/// `config.directory_depth` levels below the root.
+pub fn render_directories_console(
+    root: &DirectoryNode,
+    config: &ResolvedConfig,
+    writer: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let use_color = should_use_color(config.color);
+    let mut rows = Vec::new();
+    collect_rows(root, 0, config.directory_depth, &mut rows);
+    let width = rows
+        .iter()
+        .map(|(depth, node)| depth * 2 + node.path.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("Directory".len());
+
+    writeln!(writer)?;
+    let header = format!(
+        "  {:<width$}  {:>5}  {:>9}  {:>6}  {:>8}  {:>6}",
+        "Directory", "Files", "Functions", "Errors", "Warnings", "Health"
+    );
+    if use_color {
+        writeln!(writer, "{}", "Directories:".bold())?;
+        writeln!(writer, "{}", header.bold())?;
+    } else {
+        writeln!(writer, "Directories:")?;
+        writeln!(writer, "{header}")?;
+    }
+    for (depth, node) in rows {
+        let name = format!("{}{}", "  ".repeat(depth), node.path);
+        let health = format!("{:>6.1}", node.health_score);
+        let health = if !use_color {
+            health
+        } else if node.errors > 0 {
+            health.red().to_string()
+        } else if node.warnings > 0 {
+            health.yellow().to_string()
+        } else {
+            health.green().to_string()
+        };
+        writeln!(
+            writer,
+            "  {:<width$}  {:>5}  {:>9}  {:>6}  {:>8}  {}",
+            name, node.files, node.functions, node.errors, node.warnings, health
+        )?;
+    }
+    Ok(())
+}
+
+/// Nodes in depth-first order with their depth, stopping below `max_depth`.
+fn collect_rows<'a>(
+    node: &'a DirectoryNode,
+    depth: usize,
+    max_depth: usize,
+    rows: &mut Vec<(usize, &'a DirectoryNode)>,
+) {
+    rows.push((depth, node));
+    if depth < max_depth {
+        for child in &node.children {
+            collect_rows(child, depth + 1, max_depth, rows);
+        }
+    }
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(path: &str, errors: u32, children: Vec<DirectoryNode>) -> DirectoryNode {
+        DirectoryNode {
+            path: path.to_string(),
+            name: path.rsplit('/').next().unwrap().to_string(),
+            files: 2,
+            functions: 7,
+            errors,
+            warnings: 1,
+            health_score: 88.25,
+            children,
+        }
+    }
+
+    fn render(depth: usize) -> String {
+        let tree = node(
+            ".",
+            2,
+            vec![node(
+                "src",
+                2,
+                vec![node("src/api", 2, vec![node("src/api/v1", 1, vec![])])],
+            )],
+        );
+        let config = ResolvedConfig {
+            color: Some(false),
+            directory_depth: depth,
+            ..ResolvedConfig::default()
+        };
+        let mut out = Vec::new();
+        render_directories_console(&tree, &config, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn indents_directories_to_the_depth() {
+        let text = render(2);
+        assert!(text.contains(
+            "  Directory    Files  Functions  Errors  Warnings  Health\n  .                2          7       2         1    88.2\n    src            2"
+        ));
+        assert!(text.contains("\n      src/api      2          7       2         1    88.2\n"));
+        assert!(!text.contains("src/api/v1"));
+
+        let text = render(0);
+        assert!(!text.contains("src"));
+        assert!(render(5).contains("        src/api/v1"));
+    }
+}
@@ -0,0 +1,172 @@
+#[cfg(feature = "console")]
+use std::io::Write;
+
+#[cfg(feature = "console")]
+use owo_colors::OwoColorize;
+
+#[cfg(feature = "console")]
+use crate::cli::ResolvedConfig;
+use crate::function_history::FunctionHistory;
+#[cfg(feature = "console")]
+use crate::history::format_date;
+#[cfg(feature = "console")]
+use crate::output::console::should_use_color;
+
+/// Renders a function's metrics per commit as a table, oldest first, followed
+/// by the change between the first and last revision.
+#[cfg(feature = "console")]
+pub fn render_function_history_console(
+    history: &FunctionHistory,
+    config: &ResolvedConfig,
+    writer: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let use_color = should_use_color(config.color);
+    let title = format!(
+        "{} in {} ({} revisions)",
+        history.function,
+        history.path,
+        history.revisions.len()
+    );
+    let header = format!(
+        "{:<8}  {:<10}  {:<16}  {:>10}  {:>9}  {:>7}  {:>5}  {:>6}  {:>6}  Subject",
+        "Commit",
+        "Date",
+        "Author",
+        "Cyclomatic",
+        "Cognitive",
+        "Nesting",
+        "Lines",
+        "Params",
+        "Health"
+    );
+    if use_color {
+        writeln!(writer, "{}", title.bold())?;
+        writeln!(writer)?;
+        writeln!(writer, "{}", header.bold())?;
+    } else {
+        writeln!(writer, "{title}")?;
+        writeln!(writer)?;
+        writeln!(writer, "{header}")?;
+    }
+
+    for revision in &history.revisions {
+        let metrics = match &revision.metrics {
+            Some(m) => format!(
+                "{:>10}  {:>9}  {:>7}  {:>5}  {:>6}  {:>6.1}",
+                m.cyclomatic,
+                m.cognitive,
+                m.nesting_depth,
+                m.line_count,
+                m.params_count,
+                m.health_score
+            ),
+            None => format!(
+                "{:>10}  {:>9}  {:>7}  {:>5}  {:>6}  {:>6}",
+                "—", "—", "—", "—", "—", "—"
+            ),
+        };
+        let mut subject = revision.subject.clone();
+        if revision.path != history.path {
+            subject.push_str(&format!(" (as {})", revision.path));
+        }
+        writeln!(
+            writer,
+            "{:<8}  {:<10}  {:<16.16}  {metrics}  {subject}",
+            &revision.commit[..revision.commit.len().min(8)],
+            format_date(revision.date),
+            revision.author
+        )?;
+    }
+
+    let mut present = history.revisions.iter().filter_map(|r| r.metrics.as_ref());
+    if let (Some(first), Some(last)) = (present.next(), present.next_back()) {
+        writeln!(writer)?;
+        let line = format!(
+            "Cognitive {} → {} ({:+}), health {:.1} → {:.1} ({:+.1})",
+            first.cognitive,
+            last.cognitive,
+            i64::from(last.cognitive) - i64::from(first.cognitive),
+            first.health_score,
+            last.health_score,
+            last.health_score - first.health_score
+        );
+        let delta = last.health_score - first.health_score;
+        if use_color && delta > 0.0 {
+            writeln!(writer, "{}", line.green())?;
+        } else if use_color && delta < 0.0 {
+            writeln!(writer, "{}", line.red())?;
+        } else {
+            writeln!(writer, "{line}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Renders a function's history as pretty-printed JSON.
+pub fn render_function_history_json(history: &FunctionHistory) -> anyhow::Result<String> {
+    Ok(serde_json::to_string_pretty(history)?)
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::function_history::{FunctionMetrics, FunctionRevision};
+
+    fn history() -> FunctionHistory {
+        let revision =
+            |commit: &str, subject: &str, path: &str, cognitive: Option<u32>| FunctionRevision {
+                commit: commit.repeat(40),
+                date: 1_790_812_800,
+                author: "Ada Lovelace".to_string(),
+                subject: subject.to_string(),
+                path: path.to_string(),
+                metrics: cognitive.map(|cognitive| FunctionMetrics {
+                    start_line: 2,
+                    cyclomatic: cognitive + 1,
+                    cognitive,
+                    halstead_volume: 40.0,
+                    nesting_depth: 1,
+                    line_count: 8,
+                    params_count: 1,
+                    health_score: 100.0 - f64::from(cognitive) * 2.0,
+                }),
+            };
+        FunctionHistory {
+            path: "src/checkout.ts".to_string(),
+            function: "OrderService.checkout".to_string(),
+            revisions: vec![
+                revision("a", "Add checkout", "src/orders.ts", Some(1)),
+                revision("b", "Move to helper", "src/checkout.ts", None),
+                revision("c", "Handle large orders", "src/checkout.ts", Some(6)),
+            ],
+        }
+    }
+
+    #[cfg(feature = "console")]
+    #[test]
+    fn console_lists_revisions_and_change() {
+        let config = ResolvedConfig {
+            color: Some(false),
+            ..ResolvedConfig::default()
+        };
+        let mut out = Vec::new();
+        render_function_history_console(&history(), &config, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("OrderService.checkout in src/checkout.ts (3 revisions)"));
+        assert!(text.contains(
+            "aaaaaaaa  2026-10-01  Ada Lovelace               2          1        1      8       1    98.0  Add checkout (as src/orders.ts)"
+        ));
+        assert!(text.contains("bbbbbbbb  2026-10-01  Ada Lovelace               —          —"));
+        assert!(text.contains("Cognitive 1 → 6 (+5), health 98.0 → 88.0 (-10.0)"));
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let json = render_function_history_json(&history()).unwrap();
+        let parsed: FunctionHistory = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, history());
+        assert!(json.contains("\"metrics\": null"));
+    }
+}
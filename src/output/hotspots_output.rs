@@ -0,0 +1,133 @@
+use std::io::Write;
+
+use owo_colors::OwoColorize;
+
+use crate::cli::ResolvedConfig;
+use crate::hotspots::Hotspots;
+use crate::output::console::should_use_color;
+
+/// Files and functions listed by default; verbose mode lists all.
+const TOP_HOTSPOTS: usize = 10;
+
+/// Renders the churn × complexity ranking as a console section.
+pub fn render_hotspots_console(
+    hotspots: &Hotspots,
+    config: &ResolvedConfig,
+    writer: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let use_color = should_use_color(config.color);
+    let limit = if config.verbose {
+        usize::MAX
+    } else {
+        TOP_HOTSPOTS
+    };
+
+    let header = format!(
+        "Change hotspots (since {}, {} commits):",
+        hotspots.since, hotspots.commits
+    );
+    writeln!(writer)?;
+    if use_color {
+        writeln!(writer, "{}", header.bold())?;
+    } else {
+        writeln!(writer, "{header}")?;
+    }
+    if hotspots.files.is_empty() && hotspots.functions.is_empty() {
+        writeln!(
+            writer,
+            "  No analyzed file with findings changed in this window"
+        )?;
+        return Ok(());
+    }
+
+    if !hotspots.files.is_empty() {
+        writeln!(writer, "  Files:")?;
+        for (idx, file) in hotspots.files.iter().take(limit).enumerate() {
+            writeln!(
+                writer,
+                "    {}. {}  score {:.0} ({} commits, +{} -{}, health {:.0})",
+                idx + 1,
+                file.path,
+                file.score,
+                file.commits,
+                file.lines_added,
+                file.lines_deleted,
+                file.health_score
+            )?;
+        }
+    }
+    if !hotspots.functions.is_empty() {
+        writeln!(writer, "  Functions:")?;
+        for (idx, func) in hotspots.functions.iter().take(limit).enumerate() {
+            writeln!(
+                writer,
+                "    {}. {} ({}:{})  score {:.0} ({} changes × cognitive {})",
+                idx + 1,
+                func.name,
+                func.path,
+                func.start_line,
+                func.score,
+                func.changes,
+                func.cognitive
+            )?;
+        }
+    }
+    Ok(())
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hotspots::{FileHotspot, FunctionHotspot};
+
+    fn hotspots() -> Hotspots {
+        Hotspots {
+            since: "1 year ago".to_string(),
+            commits: 42,
+            files: vec![FileHotspot {
+                path: "src/api.ts".to_string(),
+                commits: 12,
+                lines_added: 300,
+                lines_deleted: 120,
+                health_score: 55.0,
+                score: 540.0,
+            }],
+            functions: (0..12)
+                .map(|i| FunctionHotspot {
+                    path: "src/api.ts".to_string(),
+                    name: format!("handler{i}"),
+                    start_line: 10 + i,
+                    changes: 4,
+                    cognitive: 20 - i as u32,
+                    health_score: 40.0,
+                    score: f64::from(4 * (20 - i as u32)),
+                })
+                .collect(),
+        }
+    }
+
+    fn render(config: &ResolvedConfig) -> String {
+        let mut out = Vec::new();
+        render_hotspots_console(&hotspots(), config, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn lists_top_files_and_functions() {
+        let mut config = ResolvedConfig {
+            color: Some(false),
+            ..ResolvedConfig::default()
+        };
+        let text = render(&config);
+        assert!(text.contains("Change hotspots (since 1 year ago, 42 commits):"));
+        assert!(text.contains("1. src/api.ts  score 540 (12 commits, +300 -120, health 55)"));
+        assert!(text.contains("1. handler0 (src/api.ts:10)  score 80 (4 changes × cognitive 20)"));
+        assert!(text.contains("10. handler9"));
+        assert!(!text.contains("handler10"));
+
+        config.verbose = true;
+        assert!(render(&config).contains("12. handler11"));
+    }
+}
@@ -1,10 +1,21 @@
 use minijinja::{context, Environment};
 
+use crate::blame::BlameReport;
 use crate::cli::ResolvedConfig;
-use crate::output::console::function_violations;
+use crate::codeowners::OwnersReport;
+use crate::directories::{directory_tree, DirectoryNode};
+use crate::history::{format_date, HistoryRecord};
+use crate::hotspots::Hotspots;
+use crate::modules::ModuleGraph;
+use crate::output::violations::{
+    class_violations, coupling_violations, cycle_chain, cycle_violations, function_status,
+    function_violations, is_cycle_allowed, syntax_error_message,
+};
 use crate::types::{
-    DuplicationResult, FileAnalysisResult, FunctionAnalysisResult, SkipReason, SkippedItem,
+    ClassAnalysisResult, DuplicationResult, FileAnalysisResult, FunctionAnalysisResult, SkipReason,
+    SkippedItem,
 };
+use crate::workspaces::PackagesReport;
 
 const CSS: &str = include_str!("assets/report.css");
 const JS: &str = include_str!("assets/report.js");
@@ -75,7 +86,8 @@ fn build_function_ctx(func: &FunctionAnalysisResult, config: &ResolvedConfig) ->
     let nesting = func.nesting_depth as f64;
 
     context! {
-        name => func.name.clone(),
+        name => func.qualified_name.clone(),
+        unreliable => func.unreliable,
         health_score_raw => func.health_score,
         health_display => score_display(func.health_score),
         health_class => score_class(func.health_score),
@@ -100,16 +112,59 @@ fn build_function_ctx(func: &FunctionAnalysisResult, config: &ResolvedConfig) ->
     }
 }
 
+/// Build minijinja context for a single class.
+fn build_class_ctx(class: &ClassAnalysisResult, config: &ResolvedConfig) -> minijinja::Value {
+    let methods = class.method_count as f64;
+    let wmc = class.wmc as f64;
+    let fields = class.field_count as f64;
+    let lcom = class.lcom as f64;
+    let mw = config.method_count_warning as f64;
+    let me = config.method_count_error as f64;
+    let ww = config.wmc_warning as f64;
+    let we = config.wmc_error as f64;
+    let fw = config.field_count_warning as f64;
+    let fe = config.field_count_error as f64;
+    let lw = config.lcom_warning as f64;
+    let le = config.lcom_error as f64;
+
+    context! {
+        name => class.name.clone(),
+        start_line => class.start_line,
+        status => function_status(&class_violations(class, config)),
+        method_count => class.method_count,
+        methods_class => metric_class(methods, mw, me),
+        methods_pct => format!("{:.1}", metric_pct(methods, me)),
+        wmc => class.wmc,
+        wmc_class => metric_class(wmc, ww, we),
+        wmc_pct => format!("{:.1}", metric_pct(wmc, we)),
+        field_count => class.field_count,
+        fields_class => metric_class(fields, fw, fe),
+        fields_pct => format!("{:.1}", metric_pct(fields, fe)),
+        lcom => class.lcom,
+        lcom_class => metric_class(lcom, lw, le),
+        lcom_pct => format!("{:.1}", metric_pct(lcom, le)),
+    }
+}
+
 /// Compute the worst violation status string for a file.
 fn worst_status_for_file(file: &FileAnalysisResult, config: &ResolvedConfig) -> &'static str {
     let mut has_warning = false;
-    for func in &file.functions {
-        let violations = function_violations(func, config);
-        for v in &violations {
-            match v.severity {
-                crate::output::console::Severity::Error => return "error",
-                crate::output::console::Severity::Warning => has_warning = true,
-            }
+    let violations = file
+        .functions
+        .iter()
+        .chain(&file.module)
+        .flat_map(|func| function_violations(func, config))
+        .chain(
+            file.classes
+                .iter()
+                .flat_map(|class| class_violations(class, config)),
+        )
+        .chain(coupling_violations(file, config))
+        .chain(cycle_violations(file, config));
+    for v in violations {
+        match v.severity {
+            crate::output::violations::Severity::Error => return "error",
+            crate::output::violations::Severity::Warning => has_warning = true,
         }
     }
     if has_warning {
@@ -119,17 +174,316 @@ fn worst_status_for_file(file: &FileAnalysisResult, config: &ResolvedConfig) ->
     }
 }
 
+/// Build the trend chart context: health and error count per recorded run,
+/// scaled to an SVG viewBox. `None` with fewer than two runs.
+fn build_trend_ctx(history: &[HistoryRecord]) -> Option<minijinja::Value> {
+    const WIDTH: f64 = 600.0;
+    const HEIGHT: f64 = 160.0;
+    const PAD: f64 = 8.0;
+    let (first, last) = match history {
+        [first, .., last] => (first, last),
+        _ => return None,
+    };
+
+    let max_errors = history.iter().map(|r| r.errors).max().unwrap_or(0).max(1);
+    let step = (WIDTH - 2.0 * PAD) / (history.len() - 1) as f64;
+    let y_for = |fraction: f64| PAD + (1.0 - fraction) * (HEIGHT - 2.0 * PAD);
+    let mut health_line = Vec::new();
+    let mut errors_line = Vec::new();
+    let mut points = Vec::new();
+    for (i, record) in history.iter().enumerate() {
+        let x = PAD + i as f64 * step;
+        let y = y_for(record.health_score / 100.0);
+        health_line.push(format!("{x:.1},{y:.1}"));
+        errors_line.push(format!(
+            "{x:.1},{:.1}",
+            y_for(record.errors as f64 / max_errors as f64)
+        ));
+        let commit = record
+            .commit
+            .as_deref()
+            .map(|c| format!(" {}", &c[..c.len().min(8)]))
+            .unwrap_or_default();
+        points.push(context! {
+            x => format!("{x:.1}"),
+            y => format!("{y:.1}"),
+            label => format!(
+                "{}{commit}: health {:.1}, {} errors, {} warnings",
+                format_date(record.timestamp),
+                record.health_score,
+                record.errors,
+                record.warnings
+            ),
+        });
+    }
+
+    Some(context! {
+        width => WIDTH,
+        height => HEIGHT,
+        y_80 => format!("{:.1}", y_for(0.8)),
+        health_line => health_line.join(" "),
+        errors_line => errors_line.join(" "),
+        points => points,
+        count => history.len(),
+        max_errors => max_errors,
+        first_date => format_date(first.timestamp),
+        last_date => format_date(last.timestamp),
+        first_score => format!("{:.1}", first.health_score),
+        last_score => format!("{:.1}", last.health_score),
+        first_errors => first.errors,
+        last_errors => last.errors,
+    })
+}
+
+/// Rows shown per table in the change hotspots section.
+const CHANGE_HOTSPOT_ROWS: usize = 20;
+
+/// Context for the change hotspots section: the top files and functions,
+/// with bars scaled to the highest score in each table.
+fn build_change_hotspots_ctx(hotspots: &Hotspots) -> minijinja::Value {
+    let bar_pct = |score: f64, max: f64| {
+        if max > 0.0 {
+            format!("{:.0}", score / max * 100.0)
+        } else {
+            "0".to_string()
+        }
+    };
+    let max_file = hotspots.files.first().map_or(0.0, |f| f.score);
+    let files: Vec<minijinja::Value> = hotspots
+        .files
+        .iter()
+        .take(CHANGE_HOTSPOT_ROWS)
+        .map(|f| {
+            context! {
+                path => f.path.clone(),
+                score => format!("{:.0}", f.score),
+                bar_pct => bar_pct(f.score, max_file),
+                commits => f.commits,
+                lines_added => f.lines_added,
+                lines_deleted => f.lines_deleted,
+                health_display => score_display(f.health_score),
+                health_class => score_class(f.health_score),
+            }
+        })
+        .collect();
+    let max_function = hotspots.functions.first().map_or(0.0, |f| f.score);
+    let functions: Vec<minijinja::Value> = hotspots
+        .functions
+        .iter()
+        .take(CHANGE_HOTSPOT_ROWS)
+        .map(|f| {
+            context! {
+                name => f.name.clone(),
+                path => f.path.clone(),
+                start_line => f.start_line,
+                score => format!("{:.0}", f.score),
+                bar_pct => bar_pct(f.score, max_function),
+                changes => f.changes,
+                cognitive => f.cognitive,
+                health_display => score_display(f.health_score),
+                health_class => score_class(f.health_score),
+            }
+        })
+        .collect();
+    context! {
+        since => hotspots.since.clone(),
+        commits => hotspots.commits,
+        files => files,
+        functions => functions,
+    }
+}
+
+/// Rows shown per table in the blame section.
+const BLAME_ROWS: usize = 20;
+
+/// Context for the blame section: the by-author table and the blame of each
+/// violating unit, most errors first.
+fn build_blame_ctx(blame: &BlameReport) -> minijinja::Value {
+    let authors: Vec<minijinja::Value> = blame
+        .authors
+        .iter()
+        .take(BLAME_ROWS)
+        .map(|a| {
+            context! {
+                author => a.author.clone(),
+                units => a.units,
+                errors => a.errors,
+                warnings => a.warnings,
+            }
+        })
+        .collect();
+    let mut units: Vec<_> = blame.units.iter().collect();
+    units.sort_by(|a, b| b.errors.cmp(&a.errors).then(b.warnings.cmp(&a.warnings)));
+    let units: Vec<minijinja::Value> = units
+        .into_iter()
+        .take(BLAME_ROWS)
+        .map(|u| {
+            context! {
+                name => u.name.clone(),
+                path => u.path.clone(),
+                start_line => u.start_line,
+                errors => u.errors,
+                warnings => u.warnings,
+                author => u.blame.author.clone(),
+                author_lines => u.blame.author_lines,
+                lines => u.blame.lines,
+                commit => u.blame.commit[..u.blame.commit.len().min(8)].to_string(),
+                date => format_date(u.blame.date),
+            }
+        })
+        .collect();
+    context! {
+        since => blame.since.clone(),
+        authors => authors,
+        units => units,
+    }
+}
+
+/// Directory levels below the root that start expanded in the directory tree.
+const OPEN_DIRECTORY_LEVELS: usize = 1;
+
+/// Context for one node of the directory tree and, recursively, its children.
+fn build_directory_ctx(node: &DirectoryNode) -> minijinja::Value {
+    let children: Vec<minijinja::Value> = node.children.iter().map(build_directory_ctx).collect();
+    context! {
+        name => node.name.clone(),
+        path => node.path.clone(),
+        files => node.files,
+        functions => node.functions,
+        errors => node.errors,
+        warnings => node.warnings,
+        health_class => score_class(node.health_score),
+        health_display => score_display(node.health_score),
+        children => children,
+    }
+}
+
+/// Context for the owners section: every owner's totals, as listed.
+fn build_owners_ctx(owners: &OwnersReport) -> minijinja::Value {
+    let rows: Vec<minijinja::Value> = owners
+        .owners
+        .iter()
+        .map(|o| {
+            context! {
+                owner => o.owner.clone(),
+                files => o.files,
+                functions => o.functions,
+                errors => o.errors,
+                warnings => o.warnings,
+                health_class => score_class(o.health_score),
+                health_display => score_display(o.health_score),
+            }
+        })
+        .collect();
+    context! {
+        file => owners.file.clone(),
+        owners => rows,
+    }
+}
+
+/// Context for the packages section: every package's totals, as listed.
+fn build_packages_ctx(packages: &PackagesReport) -> minijinja::Value {
+    let rows: Vec<minijinja::Value> = packages
+        .packages
+        .iter()
+        .map(|p| {
+            context! {
+                name => p.name.clone(),
+                path => p.path.clone(),
+                files => p.files,
+                functions => p.functions,
+                errors => p.errors,
+                warnings => p.warnings,
+                health_class => score_class(p.health_score),
+                health_display => score_display(p.health_score),
+                fail_on => p.fail_on.clone(),
+            }
+        })
+        .collect();
+    context! {
+        manager => packages.manager.clone(),
+        gated => packages.packages.iter().any(|p| p.fail_on.is_some()),
+        packages => rows,
+    }
+}
+
+/// Context for the modules section: every file with imports between analyzed
+/// files, most depended on first.
+fn build_modules_ctx(graph: &ModuleGraph, config: &ResolvedConfig) -> minijinja::Value {
+    let mut coupled: Vec<_> = graph
+        .modules
+        .iter()
+        .filter(|m| m.coupling.fan_in + m.coupling.fan_out > 0)
+        .collect();
+    coupled.sort_by_key(|m| {
+        (
+            std::cmp::Reverse(m.coupling.fan_in),
+            std::cmp::Reverse(m.coupling.fan_out),
+        )
+    });
+    let rows: Vec<minijinja::Value> = coupled
+        .iter()
+        .map(|m| {
+            context! {
+                path => m.path.clone(),
+                fan_in => m.coupling.fan_in,
+                fan_out => m.coupling.fan_out,
+                instability => format!("{:.2}", m.coupling.instability),
+            }
+        })
+        .collect();
+    let cycles: Vec<minijinja::Value> = graph
+        .cycles
+        .iter()
+        .map(|cycle| {
+            context! {
+                chain => cycle_chain(cycle),
+                files => cycle.files.len(),
+                allowed => is_cycle_allowed(cycle, config),
+            }
+        })
+        .collect();
+    context! {
+        files => graph.modules.len(),
+        imports => graph.edges.len(),
+        modules => rows,
+        cycles => cycles,
+    }
+}
+
+/// Optional report sections built from data beyond the analysis itself.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HtmlExtras<'a> {
+    /// Recorded runs, oldest first; the trend section needs at least two
+    pub history: &'a [HistoryRecord],
+    /// Churn × complexity ranking for the change hotspots section
+    pub hotspots: Option<&'a Hotspots>,
+    /// Blame of violating units for the blame section
+    pub blame: Option<&'a BlameReport>,
+    /// Per-owner totals for the owners section
+    pub owners: Option<&'a OwnersReport>,
+    /// Per-package totals for the packages section
+    pub packages: Option<&'a PackagesReport>,
+    /// Import graph for the modules section
+    pub modules: Option<&'a ModuleGraph>,
+    /// SHA of the analyzed commit, shown in the header
+    pub commit: Option<&'a str>,
+}
+
 /// Render a self-contained HTML report.
 ///
 /// CSS and JS are embedded inline — no external requests are made.
 /// The duplication section is included only when duplication data is present.
 /// The skipped section is included only when skipped items are non-empty.
+/// The packages, trend, change hotspots, blame, owners and modules sections are included
+/// when `extras` holds their data.
 pub fn render_html(
     files: &[FileAnalysisResult],
     duplication: Option<&DuplicationResult>,
     config: &ResolvedConfig,
     elapsed_ms: u64,
     skipped: &[SkippedItem],
+    extras: &HtmlExtras,
 ) -> anyhow::Result<String> {
     let mut env = Environment::new();
     env.add_template("report", TEMPLATE)?;
@@ -176,13 +530,22 @@ pub fn render_html(
     let mut error_count: usize = 0;
     let mut warning_count: usize = 0;
     for file in files {
-        for func in &file.functions {
-            let violations = function_violations(func, config);
-            for v in &violations {
-                match v.severity {
-                    crate::output::console::Severity::Error => error_count += 1,
-                    crate::output::console::Severity::Warning => warning_count += 1,
-                }
+        let violations = file
+            .functions
+            .iter()
+            .chain(&file.module)
+            .flat_map(|func| function_violations(func, config))
+            .chain(
+                file.classes
+                    .iter()
+                    .flat_map(|class| class_violations(class, config)),
+            )
+            .chain(coupling_violations(file, config))
+            .chain(cycle_violations(file, config));
+        for v in violations {
+            match v.severity {
+                crate::output::violations::Severity::Error => error_count += 1,
+                crate::output::violations::Severity::Warning => warning_count += 1,
             }
         }
     }
@@ -190,7 +553,7 @@ pub fn render_html(
     // Build hotspots (up to 5, sorted by health_score ascending)
     let mut all_funcs: Vec<(&FileAnalysisResult, &FunctionAnalysisResult)> = Vec::new();
     for file in files {
-        for func in &file.functions {
+        for func in file.functions.iter().chain(&file.module) {
             all_funcs.push((file, func));
         }
     }
@@ -207,11 +570,12 @@ pub fn render_html(
             let violation_list: Vec<minijinja::Value> = violations
                 .iter()
                 .map(|v| {
-                    let warning_class = if v.severity == crate::output::console::Severity::Warning {
-                        " warning".to_string()
-                    } else {
-                        String::new()
-                    };
+                    let warning_class =
+                        if v.severity == crate::output::violations::Severity::Warning {
+                            " warning".to_string()
+                        } else {
+                            String::new()
+                        };
                     // Short label: extract the metric name from the rule_id
                     let label = match v.rule_id.as_str() {
                         "complexity-guard/cyclomatic" => format!("cyclomatic {}", func.cyclomatic),
@@ -241,7 +605,7 @@ pub fn render_html(
                 .collect();
 
             context! {
-                name => func.name.clone(),
+                name => func.qualified_name.clone(),
                 file_path => file.path.to_string_lossy().to_string(),
                 start_line => func.start_line,
                 cyclomatic => func.cyclomatic,
@@ -257,12 +621,30 @@ pub fn render_html(
     let file_contexts: Vec<minijinja::Value> = files
         .iter()
         .map(|file| {
+            // The top-level `<module>` unit leads the function table
             let fn_contexts: Vec<minijinja::Value> = file
-                .functions
+                .module
                 .iter()
+                .chain(&file.functions)
                 .map(|func| build_function_ctx(func, config))
                 .collect();
+            let class_contexts: Vec<minijinja::Value> = file
+                .classes
+                .iter()
+                .map(|class| build_class_ctx(class, config))
+                .collect();
             let ws = worst_status_for_file(file, config);
+            let syntax_errors: Vec<minijinja::Value> = file
+                .syntax_errors
+                .iter()
+                .map(|e| {
+                    context! {
+                        line => e.line,
+                        column => e.column,
+                        message => syntax_error_message(e),
+                    }
+                })
+                .collect();
             context! {
                 path => file.path.to_string_lossy().to_string(),
                 score_raw => file.file_score,
@@ -271,6 +653,8 @@ pub fn render_html(
                 function_count => file.functions.len(),
                 worst_status => ws,
                 functions => fn_contexts,
+                classes => class_contexts,
+                syntax_errors => syntax_errors,
             }
         })
         .collect();
@@ -283,27 +667,43 @@ pub fn render_html(
             .iter()
             .map(|item| {
                 let path_str = item.path.to_string_lossy().to_string();
-                let (reason_label, lines, max_lines, item_name) = match &item.reason {
+                let (reason_label, detail) = match &item.reason {
                     SkipReason::FileTooLarge { lines, max_lines } => (
-                        "file too large".to_string(),
-                        *lines,
-                        *max_lines,
-                        None::<String>,
+                        "file too large",
+                        format!("{lines} lines (max {max_lines})"),
                     ),
                     SkipReason::FunctionTooLarge { lines, max_lines } => (
-                        "function too large".to_string(),
-                        *lines as usize,
-                        *max_lines as usize,
-                        item.function_name.clone(),
+                        "function too large",
+                        format!("{lines} lines (max {max_lines})"),
+                    ),
+                    SkipReason::GeneratedFile { marker } => {
+                        ("generated file", format!("found {marker}"))
+                    }
+                    SkipReason::MinifiedFile {
+                        average_line_length,
+                        max_average_line_length,
+                    } => (
+                        "minified file",
+                        format!(
+                            "average line length {average_line_length} (max {max_average_line_length})"
+                        ),
+                    ),
+                    SkipReason::Failed { error } => ("failed", error.clone()),
+                    SkipReason::SyntaxErrors { errors } => (
+                        "syntax errors",
+                        errors
+                            .iter()
+                            .map(|e| format!("{}:{} {}", e.line, e.column, syntax_error_message(e)))
+                            .collect::<Vec<_>>()
+                            .join("; "),
                     ),
                 };
                 context! {
                     path => path_str,
-                    item_name => item_name.unwrap_or_default(),
+                    item_name => item.function_name.clone().unwrap_or_default(),
                     start_line => item.start_line,
                     reason => reason_label,
-                    lines => lines,
-                    max_lines => max_lines,
+                    detail => detail,
                 }
             })
             .collect();
@@ -375,6 +775,15 @@ pub fn render_html(
         files => file_contexts,
         duplication => dup_ctx,
         skipped => skipped_ctx,
+        trend => build_trend_ctx(extras.history),
+        change_hotspots => extras.hotspots.map(build_change_hotspots_ctx),
+        blame => extras.blame.map(build_blame_ctx),
+        owners => extras.owners.map(build_owners_ctx),
+        packages => extras.packages.map(build_packages_ctx),
+        modules => extras.modules.map(|graph| build_modules_ctx(graph, config)),
+        directories => (!files.is_empty()).then(|| build_directory_ctx(&directory_tree(files, config))),
+        open_directory_levels => OPEN_DIRECTORY_LEVELS,
+        commit => extras.commit,
         timestamp => timestamp,
     };
 
@@ -396,17 +805,23 @@ mod tests {
         FileAnalysisResult {
             path: PathBuf::from(path),
             functions,
+            classes: vec![],
+            module: None,
             tokens: vec![],
             file_score: 85.0,
             file_length: 50,
             export_count: 1,
-            error: false,
+            syntax_errors: vec![],
+            imports: Vec::new(),
+            coupling: None,
+            cycles: Vec::new(),
         }
     }
 
     fn make_func() -> FunctionAnalysisResult {
         FunctionAnalysisResult {
             name: "myFunction".to_string(),
+            qualified_name: "myFunction".to_string(),
             start_line: 10,
             end_line: 25,
             start_col: 0,
@@ -421,6 +836,9 @@ mod tests {
             params_count: 2,
             nesting_depth: 1,
             health_score: 88.0,
+            unreliable: false,
+            fingerprint: 0,
+            nested: Vec::new(),
         }
     }
 
@@ -455,7 +873,7 @@ mod tests {
     fn html_output_contains_doctype() {
         let files = vec![make_file("src/foo.ts", vec![make_func()])];
         let config = ResolvedConfig::default();
-        let output = render_html(&files, None, &config, 42, &[]).unwrap();
+        let output = render_html(&files, None, &config, 42, &[], &HtmlExtras::default()).unwrap();
         assert!(output.contains("<!DOCTYPE html>"), "expected DOCTYPE html");
     }
 
@@ -463,7 +881,7 @@ mod tests {
     fn html_output_has_embedded_css() {
         let files: Vec<FileAnalysisResult> = vec![];
         let config = ResolvedConfig::default();
-        let output = render_html(&files, None, &config, 10, &[]).unwrap();
+        let output = render_html(&files, None, &config, 10, &[], &HtmlExtras::default()).unwrap();
         assert!(output.contains("<style>"), "expected <style> block");
         assert!(
             output.contains("prefers-color-scheme"),
@@ -475,7 +893,7 @@ mod tests {
     fn html_output_has_embedded_js() {
         let files: Vec<FileAnalysisResult> = vec![];
         let config = ResolvedConfig::default();
-        let output = render_html(&files, None, &config, 10, &[]).unwrap();
+        let output = render_html(&files, None, &config, 10, &[], &HtmlExtras::default()).unwrap();
         assert!(output.contains("<script>"), "expected <script> block");
         assert!(
             output.contains("sortTable"),
@@ -487,7 +905,7 @@ mod tests {
     fn html_output_no_external_url_refs() {
         let files = vec![make_file("src/foo.ts", vec![make_func()])];
         let config = ResolvedConfig::default();
-        let output = render_html(&files, None, &config, 10, &[]).unwrap();
+        let output = render_html(&files, None, &config, 10, &[], &HtmlExtras::default()).unwrap();
         // No external link/script/img tags with http/https src
         assert!(
             !output.contains("<link rel=\"stylesheet\""),
@@ -508,7 +926,8 @@ mod tests {
         let files = vec![make_file("src/foo.ts", vec![make_func()])];
         let config = ResolvedConfig::default();
         let dup = make_dup();
-        let output = render_html(&files, Some(&dup), &config, 10, &[]).unwrap();
+        let output =
+            render_html(&files, Some(&dup), &config, 10, &[], &HtmlExtras::default()).unwrap();
         assert!(
             output.contains("Code Duplication") || output.contains("duplication-section"),
             "expected duplication section when duplication data present"
@@ -523,7 +942,7 @@ mod tests {
     fn html_output_excludes_duplication_section_when_absent() {
         let files = vec![make_file("src/foo.ts", vec![make_func()])];
         let config = ResolvedConfig::default();
-        let output = render_html(&files, None, &config, 10, &[]).unwrap();
+        let output = render_html(&files, None, &config, 10, &[], &HtmlExtras::default()).unwrap();
         // The duplication section heading "Code Duplication" only appears in the HTML section,
         // not in the CSS. When no duplication data, the {% if duplication %} block is not rendered.
         assert!(
@@ -541,7 +960,7 @@ mod tests {
     fn html_output_contains_file_path() {
         let files = vec![make_file("src/mymodule.ts", vec![make_func()])];
         let config = ResolvedConfig::default();
-        let output = render_html(&files, None, &config, 10, &[]).unwrap();
+        let output = render_html(&files, None, &config, 10, &[], &HtmlExtras::default()).unwrap();
         assert!(
             output.contains("src/mymodule.ts"),
             "expected file path in output"
@@ -552,21 +971,348 @@ mod tests {
     fn html_output_contains_function_name() {
         let files = vec![make_file("src/foo.ts", vec![make_func()])];
         let config = ResolvedConfig::default();
-        let output = render_html(&files, None, &config, 10, &[]).unwrap();
+        let output = render_html(&files, None, &config, 10, &[], &HtmlExtras::default()).unwrap();
         assert!(
             output.contains("myFunction"),
             "expected function name in output"
         );
     }
 
+    #[test]
+    fn html_output_contains_class_metrics() {
+        let mut file = make_file("src/foo.ts", vec![make_func()]);
+        file.classes = vec![ClassAnalysisResult {
+            name: "OrderService".to_string(),
+            start_line: 4,
+            end_line: 80,
+            start_col: 0,
+            method_count: 12,
+            wmc: 55,
+            field_count: 3,
+            lcom: 2,
+        }];
+        let config = ResolvedConfig::default();
+        let output = render_html(&[file], None, &config, 10, &[], &HtmlExtras::default()).unwrap();
+        assert!(output.contains("<th>WMC</th>"), "expected class table");
+        assert!(output.contains("OrderService"), "expected class name");
+        assert!(
+            output.contains("<span class=\"score-badge warning\">warning</span>"),
+            "expected class status badge"
+        );
+    }
+
+    #[test]
+    fn html_output_lists_module_unit_in_function_table() {
+        let mut file = make_file("src/setup.ts", vec![make_func()]);
+        let mut module = make_func();
+        module.name = "<module>".to_string();
+        module.qualified_name = "<module>".to_string();
+        file.module = Some(module);
+        let config = ResolvedConfig::default();
+        let output = render_html(&[file], None, &config, 10, &[], &HtmlExtras::default()).unwrap();
+        assert!(output.contains("&lt;module&gt;"), "expected module row");
+    }
+
     #[test]
     fn html_output_contains_complexity_guard_branding() {
         let files: Vec<FileAnalysisResult> = vec![];
         let config = ResolvedConfig::default();
-        let output = render_html(&files, None, &config, 10, &[]).unwrap();
+        let output = render_html(&files, None, &config, 10, &[], &HtmlExtras::default()).unwrap();
         assert!(
             output.contains("ComplexityGuard"),
             "expected ComplexityGuard in output"
         );
     }
+
+    #[test]
+    fn html_output_includes_trend_with_history() {
+        let record = |timestamp: u64, health_score: f64, errors: u32| HistoryRecord {
+            timestamp,
+            commit: Some("0123456789abcdef".to_string()),
+            branch: None,
+            version: TOOL_VERSION.to_string(),
+            health_score,
+            files: 1,
+            functions: 1,
+            errors,
+            warnings: 0,
+            directories: Default::default(),
+        };
+        let config = ResolvedConfig::default();
+        let one = [record(1_790_000_000, 70.0, 4)];
+        let output = render_html(
+            &[],
+            None,
+            &config,
+            10,
+            &[],
+            &HtmlExtras {
+                history: &one,
+                ..HtmlExtras::default()
+            },
+        )
+        .unwrap();
+        assert!(!output.contains("trend-chart\""), "one run has no trend");
+
+        let two = [one[0].clone(), record(1_792_281_600, 85.0, 1)];
+        let output = render_html(
+            &[],
+            None,
+            &config,
+            10,
+            &[],
+            &HtmlExtras {
+                history: &two,
+                ..HtmlExtras::default()
+            },
+        )
+        .unwrap();
+        assert!(output.contains("<svg class=\"trend-chart\""));
+        assert!(output.contains("2 runs from 2026-09-21 to 2026-10-18"));
+        assert!(output.contains("2026-10-18 01234567: health 85.0, 1 errors, 0 warnings"));
+    }
+
+    #[test]
+    fn html_output_includes_change_hotspots() {
+        use crate::hotspots::{FileHotspot, FunctionHotspot};
+
+        let config = ResolvedConfig::default();
+        let output = render_html(&[], None, &config, 10, &[], &HtmlExtras::default()).unwrap();
+        assert!(!output.contains("<h2>Change Hotspots</h2>"));
+
+        let hotspots = Hotspots {
+            since: "6 months ago".to_string(),
+            commits: 17,
+            files: vec![FileHotspot {
+                path: "src/orders.ts".to_string(),
+                commits: 9,
+                lines_added: 240,
+                lines_deleted: 80,
+                health_score: 62.0,
+                score: 342.0,
+            }],
+            functions: vec![FunctionHotspot {
+                path: "src/orders.ts".to_string(),
+                name: "OrderService.total".to_string(),
+                start_line: 12,
+                changes: 6,
+                cognitive: 18,
+                health_score: 48.0,
+                score: 108.0,
+            }],
+        };
+        let output = render_html(
+            &[],
+            None,
+            &config,
+            10,
+            &[],
+            &HtmlExtras {
+                hotspots: Some(&hotspots),
+                ..HtmlExtras::default()
+            },
+        )
+        .unwrap();
+        assert!(output.contains("<h2>Change Hotspots</h2>"));
+        assert!(output.contains("since 6 months ago (17 commits)"));
+        assert!(output.contains("OrderService.total"));
+        assert!(output.contains("src/orders.ts:12"));
+        assert!(output.contains("+240 &minus;80"));
+    }
+
+    #[test]
+    fn html_output_includes_blame() {
+        use crate::blame::{AuthorSummary, Blame, UnitBlame};
+
+        let config = ResolvedConfig::default();
+        let output = render_html(&[], None, &config, 10, &[], &HtmlExtras::default()).unwrap();
+        assert!(!output.contains("<h2>Blame</h2>"));
+
+        let blame = BlameReport {
+            since: Some("2026-01-01".to_string()),
+            units: vec![UnitBlame {
+                path: "src/orders.ts".to_string(),
+                name: "OrderService.total".to_string(),
+                start_line: 12,
+                errors: 2,
+                warnings: 1,
+                blame: Blame {
+                    author: "Ada Lovelace".to_string(),
+                    author_lines: 30,
+                    lines: 40,
+                    commit: "0123456789abcdef0123456789abcdef01234567".to_string(),
+                    date: 1_790_812_800,
+                },
+            }],
+            authors: vec![AuthorSummary {
+                author: "Ada Lovelace".to_string(),
+                units: 1,
+                errors: 2,
+                warnings: 1,
+            }],
+        };
+        let output = render_html(
+            &[],
+            None,
+            &config,
+            10,
+            &[],
+            &HtmlExtras {
+                blame: Some(&blame),
+                ..HtmlExtras::default()
+            },
+        )
+        .unwrap();
+        assert!(output.contains("<h2>Blame</h2>"));
+        assert!(output.contains("changed since 2026-01-01"));
+        assert!(output.contains("Ada Lovelace"));
+        assert!(output.contains("src/orders.ts:12"));
+        assert!(output.contains("01234567"));
+        assert!(output.contains("2026-10-01"));
+    }
+
+    #[test]
+    fn html_output_includes_directory_tree() {
+        let config = ResolvedConfig::default();
+        let output = render_html(&[], None, &config, 10, &[], &HtmlExtras::default()).unwrap();
+        assert!(!output.contains("<h2>Directories</h2>"));
+
+        let files = vec![
+            make_file("src/api/a.ts", vec![make_func()]),
+            make_file("src/b.ts", vec![make_func()]),
+        ];
+        let output = render_html(&files, None, &config, 10, &[], &HtmlExtras::default()).unwrap();
+        assert!(output.contains("<h2>Directories</h2>"));
+        // The root and its children start open; deeper levels start collapsed
+        assert!(output.contains("<details class=\"dir-node\" open><summary><span class=\"dir-name\" title=\".\">.</span>"));
+        assert!(output.contains("<details class=\"dir-node\" open><summary><span class=\"dir-name\" title=\"src\">src</span>"));
+        assert!(output.contains("<div class=\"dir-node dir-leaf\"><span class=\"dir-name\" title=\"src/api\">api</span>"));
+        assert!(output.contains("2 files, 2 functions"));
+    }
+
+    #[test]
+    fn html_output_includes_owners() {
+        use crate::codeowners::OwnerSummary;
+
+        let config = ResolvedConfig::default();
+        let output = render_html(&[], None, &config, 10, &[], &HtmlExtras::default()).unwrap();
+        assert!(!output.contains("<h2>Owners</h2>"));
+
+        let owners = OwnersReport {
+            file: ".github/CODEOWNERS".to_string(),
+            files: Default::default(),
+            owners: vec![OwnerSummary {
+                owner: "@org/payments".to_string(),
+                files: 3,
+                functions: 14,
+                errors: 2,
+                warnings: 5,
+                health_score: 71.4,
+            }],
+        };
+        let output = render_html(
+            &[],
+            None,
+            &config,
+            10,
+            &[],
+            &HtmlExtras {
+                owners: Some(&owners),
+                ..HtmlExtras::default()
+            },
+        )
+        .unwrap();
+        assert!(output.contains("<h2>Owners</h2>"));
+        assert!(output.contains("<code>.github/CODEOWNERS</code>"));
+        assert!(output.contains("<td>@org/payments</td>"));
+        assert!(output.contains("<span class=\"score-badge warning\">71</span>"));
+    }
+
+    #[test]
+    fn html_output_includes_modules_and_cycles() {
+        let report = crate::Analyzer::new()
+            .source("src/a.ts", "import './b';")
+            .source("src/b.ts", "import './a';")
+            .source("src/c.ts", "import './a';")
+            .configure(|c| c.modules.get_or_insert_with(Default::default).enabled = Some(true))
+            .run()
+            .unwrap();
+        let mut config = ResolvedConfig::default();
+        let extras = HtmlExtras {
+            modules: report.modules.as_ref(),
+            ..HtmlExtras::default()
+        };
+        let output = render_html(&report.files, None, &config, 10, &[], &extras).unwrap();
+        assert!(output.contains("<h2>Modules</h2>"));
+        assert!(output.contains("3 imports between 3 analyzed files."));
+        assert!(output.contains("<td>src/a.ts:1 -> src/b.ts:1 -> src/a.ts</td>"));
+        assert!(output.contains("<span class=\"score-badge error\">error</span>"));
+
+        config.modules_allow_cycles = vec![vec!["src/a.ts".to_string(), "src/b.ts".to_string()]];
+        let output = render_html(&report.files, None, &config, 10, &[], &extras).unwrap();
+        assert!(output.contains("<td>allowed</td>"));
+    }
+
+    #[test]
+    fn html_output_puts_packages_first() {
+        use crate::workspaces::PackageSummary;
+
+        let config = ResolvedConfig::default();
+        let output = render_html(&[], None, &config, 10, &[], &HtmlExtras::default()).unwrap();
+        assert!(!output.contains("<h2>Packages</h2>"));
+
+        let summary = |name: &str, path: &str, fail_on: Option<&str>| PackageSummary {
+            name: name.to_string(),
+            path: path.to_string(),
+            files: 3,
+            functions: 14,
+            errors: 0,
+            warnings: 1,
+            health_score: 93.0,
+            fail_on: fail_on.map(str::to_string),
+        };
+        let packages = PackagesReport {
+            manager: "yarn".to_string(),
+            files: Default::default(),
+            packages: vec![
+                summary("@acme/web", "packages/web", Some("warning")),
+                summary("(root)", ".", None),
+            ],
+        };
+        let output = render_html(
+            &[],
+            None,
+            &config,
+            10,
+            &[],
+            &HtmlExtras {
+                packages: Some(&packages),
+                ..HtmlExtras::default()
+            },
+        )
+        .unwrap();
+        let section = output.find("<h2>Packages</h2>").unwrap();
+        assert!(section < output.find("class=\"dashboard\"").unwrap());
+        assert!(output.contains("packages of the yarn workspace"));
+        assert!(
+            output.contains("<td>@acme/web</td>\n            <td><code>packages/web</code></td>")
+        );
+        assert!(output.contains("<th>Fail on</th>"));
+        assert!(output.contains("<td>warning</td>"));
+        assert!(output.contains("<td>-</td>"));
+    }
+
+    #[test]
+    fn html_output_labels_commit() {
+        let config = ResolvedConfig::default();
+        let output = render_html(&[], None, &config, 10, &[], &HtmlExtras::default()).unwrap();
+        assert!(!output.contains("at commit"));
+
+        let extras = HtmlExtras {
+            commit: Some("0123456789abcdef0123456789abcdef01234567"),
+            ..HtmlExtras::default()
+        };
+        let output = render_html(&[], None, &config, 10, &[], &extras).unwrap();
+        assert!(output.contains("at commit <code>0123456789abcdef0123456789abcdef01234567</code>"));
+    }
 }
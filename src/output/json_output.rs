@@ -1,6 +1,20 @@
+use std::path::{Path, PathBuf};
+
+use crate::blame::{AuthorSummary, Blame, BlameReport};
 use crate::cli::ResolvedConfig;
-use crate::output::console::{function_violations, Severity};
-use crate::types::{DuplicationResult, FileAnalysisResult, SkipReason, SkippedItem};
+use crate::codeowners::{OwnerSummary, OwnersReport};
+use crate::directories::{directory_tree, DirectoryNode};
+use crate::hotspots::Hotspots;
+use crate::output::violations::{
+    class_violations, coupling_violations, cycle_violations, function_status, function_violations,
+    Severity,
+};
+use crate::types::{
+    ClassAnalysisResult, DuplicationResult, FileAnalysisResult, FileCoupling,
+    FunctionAnalysisResult, ImportCycle, NestedFunction, SkipReason, SkippedItem, SyntaxError,
+    SyntaxErrorKind,
+};
+use crate::workspaces::{PackageSummary, PackagesReport};
 
 /// Duplication thresholds used for computing per-file and project status.
 /// These match the Zig defaults since ResolvedConfig does not currently carry
@@ -10,29 +24,115 @@ const DUP_FILE_ERROR: f64 = 5.0;
 const DUP_PROJECT_WARNING: f64 = 3.0;
 const DUP_PROJECT_ERROR: f64 = 5.0;
 
+/// Version of the JSON report schema, stored as `schema_version`.
+///
+/// Bumped whenever a field is removed, renamed or changes meaning, so tools
+/// reading saved reports can refuse ones they would misinterpret. Adding an
+/// optional field does not bump it.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Why a saved JSON report could not be loaded.
+#[derive(thiserror::Error, Debug)]
+pub enum ReportLoadError {
+    #[error("failed to read report {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("not a complexityguard JSON report: {0}")]
+    Invalid(#[from] serde_json::Error),
+
+    #[error("report has no schema_version (written by an older complexityguard); regenerate it")]
+    MissingSchemaVersion,
+
+    #[error("report schema version {found} is not supported (expected {expected}); regenerate it with complexityguard {}", env!("CARGO_PKG_VERSION"))]
+    UnsupportedSchemaVersion { found: u64, expected: u32 },
+}
+
 // --- JSON output structs matching Zig schema exactly ---
 
 /// A skipped file or function entry in JSON output.
-#[derive(serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct JsonSkippedItem {
     pub path: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub function_name: Option<String>,
     pub start_line: usize,
-    /// Human-readable: "file_too_large" or "function_too_large"
+    /// "file_too_large", "function_too_large", "generated_file", "minified_file",
+    /// "failed" or "syntax_errors"
     pub reason: String,
-    /// Actual line count of the skipped item
-    pub lines: usize,
-    /// Configured maximum line count threshold
-    pub max_lines: usize,
+    /// Actual line count of the skipped item (size guard skips only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lines: Option<usize>,
+    /// Configured maximum line count threshold (size guard skips only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_lines: Option<usize>,
+    /// Generated-code marker that was found (generated files only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker: Option<String>,
+    /// Average line length of the file (minified files only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub average_line_length: Option<usize>,
+    /// Configured minified-code threshold (minified files only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_average_line_length: Option<usize>,
+    /// Read or parse error message (failed files only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Syntax errors that caused the skip (syntax error policy "skip" only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub syntax_errors: Option<Vec<JsonSyntaxError>>,
+}
+
+/// A syntax error location within a file.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct JsonSyntaxError {
+    pub line: usize,
+    pub column: usize,
+    /// "unexpected" or "missing"
+    pub kind: String,
+    pub snippet: String,
+}
+
+impl From<&JsonSyntaxError> for SyntaxError {
+    fn from(error: &JsonSyntaxError) -> Self {
+        let kind = match error.kind.as_str() {
+            "missing" => SyntaxErrorKind::Missing,
+            _ => SyntaxErrorKind::Unexpected,
+        };
+        SyntaxError {
+            line: error.line,
+            column: error.column,
+            kind,
+            snippet: error.snippet.clone(),
+        }
+    }
+}
+
+impl From<&SyntaxError> for JsonSyntaxError {
+    fn from(error: &SyntaxError) -> Self {
+        let kind = match error.kind {
+            SyntaxErrorKind::Unexpected => "unexpected",
+            SyntaxErrorKind::Missing => "missing",
+        };
+        JsonSyntaxError {
+            line: error.line,
+            column: error.column,
+            kind: kind.to_string(),
+            snippet: error.snippet.clone(),
+        }
+    }
 }
 
 /// Top-level JSON output matching the Zig JsonOutput struct.
 ///
 /// Field names are snake_case matching the Zig JSON schema exactly.
-#[derive(serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct JsonOutput {
     pub version: String,
+    /// Report schema version, see `SCHEMA_VERSION`
+    pub schema_version: u32,
     pub timestamp: u64,
     pub summary: JsonSummary,
     pub files: Vec<JsonFileOutput>,
@@ -40,10 +140,53 @@ pub struct JsonOutput {
     pub duplication: Option<JsonDuplicationOutput>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub skipped: Option<Vec<JsonSkippedItem>>,
+    /// Totals per directory, nested from the root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directories: Option<DirectoryNode>,
+    /// Churn × complexity ranking, when hotspots were analyzed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hotspots: Option<Hotspots>,
+    /// Violations per author, when blame attribution ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blame: Option<JsonBlameOutput>,
+    /// Totals per CODEOWNERS owner, when owners were attributed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owners: Option<JsonOwnersOutput>,
+    /// Totals per workspace package, when files were grouped by package
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub packages: Option<JsonPackagesOutput>,
+}
+
+/// Git blame totals for the run; the blame of each unit is on the unit itself.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct JsonBlameOutput {
+    /// Only commits after this were blamed, when set
+    pub since: Option<String>,
+    /// Most errors first, then most warnings
+    pub authors: Vec<AuthorSummary>,
+}
+
+/// CODEOWNERS totals for the run; the owners of each file are on the file itself.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct JsonOwnersOutput {
+    /// The CODEOWNERS file used, relative to the repository root
+    pub file: String,
+    /// By owner name, with `(unowned)` last
+    pub owners: Vec<OwnerSummary>,
+}
+
+/// Workspace package totals for the run; the package of each file is on the
+/// file itself.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct JsonPackagesOutput {
+    /// `npm`, `yarn` or `pnpm`
+    pub manager: String,
+    /// By package name, with `(root)` last
+    pub packages: Vec<PackageSummary>,
 }
 
 /// Summary statistics for the entire run.
-#[derive(serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct JsonSummary {
     pub files_analyzed: usize,
     pub total_functions: usize,
@@ -53,23 +196,71 @@ pub struct JsonSummary {
     pub status: String,
     pub health_score: f64,
     pub skipped_count: usize,
+    pub syntax_error_count: usize,
+    /// Whether a file failed to read or parse, or had syntax errors under the
+    /// `fail` policy; these runs exit with the parse error code
+    #[serde(default)]
+    pub has_parse_errors: bool,
 }
 
 /// Per-file output matching the Zig JsonFileOutput struct.
-#[derive(serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct JsonFileOutput {
     pub path: String,
     pub functions: Vec<JsonFunctionOutput>,
+    /// Top-level code outside every function, as a `<module>` unit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module: Option<JsonFunctionOutput>,
     pub file_length: u32,
     pub export_count: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub classes: Vec<JsonClassOutput>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub syntax_errors: Vec<JsonSyntaxError>,
+    /// Normalized token kinds for cross-report duplication (`--export-tokens` only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<Vec<String>>,
+    /// CODEOWNERS owners of the file, when owners were attributed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<String>,
+    /// Workspace package of the file, when files were grouped by package
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    /// Fan-in, fan-out and instability, when the module graph was built
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coupling: Option<FileCoupling>,
+    /// Import cycles this file starts, when the module graph was built
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cycles: Vec<ImportCycle>,
+}
+
+/// Per-class size, complexity and cohesion metrics.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct JsonClassOutput {
+    pub name: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub start_col: usize,
+    pub method_count: u32,
+    pub wmc: u32,
+    pub field_count: u32,
+    pub lcom: u32,
+    /// "ok", "warning", or "error"
+    pub status: String,
+    /// Who last changed the class, for classes with violations when blame ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blame: Option<Blame>,
 }
 
 /// Per-function output matching the Zig JsonFunctionOutput struct.
 ///
 /// All field names match Zig exactly (snake_case).
-#[derive(serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct JsonFunctionOutput {
+    /// Short name, e.g. "Foo.bar" or "map callback"
     pub name: String,
+    /// Name qualified by owner, call site and enclosing functions
+    pub qualified_name: String,
     pub start_line: usize,
     pub end_line: usize,
     pub start_col: usize,
@@ -85,17 +276,32 @@ pub struct JsonFunctionOutput {
     pub health_score: f64,
     /// "ok", "warning", or "error"
     pub status: String,
+    /// Present (true) only when a syntax error lies inside the function
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub unreliable: bool,
+    /// Body similarity fingerprint as 16 hex digits; absent for the module unit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    /// Who last changed the function, for functions with violations when blame ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blame: Option<Blame>,
+    /// Functions nested inside this one, by short and qualified name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub nested: Vec<NestedFunction>,
 }
 
 /// Execution metadata.
-#[derive(serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct JsonMetadata {
     pub elapsed_ms: u64,
     pub thread_count: u32,
+    /// SHA of the analyzed commit, when a git revision was analyzed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
 }
 
 /// Duplication detection results matching the Zig JSON schema exactly.
-#[derive(serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct JsonDuplicationOutput {
     pub enabled: bool,
     pub project_duplication_pct: f64,
@@ -106,14 +312,14 @@ pub struct JsonDuplicationOutput {
 }
 
 /// A single clone group entry matching Zig schema: token_count + locations array.
-#[derive(serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct JsonCloneGroup {
     pub token_count: u32,
     pub locations: Vec<JsonCloneLocation>,
 }
 
 /// A single clone location within a clone group.
-#[derive(serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct JsonCloneLocation {
     pub file: String,
     pub start_line: usize,
@@ -121,7 +327,7 @@ pub struct JsonCloneLocation {
 }
 
 /// Per-file duplication stats matching Zig schema.
-#[derive(serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct JsonDuplicationFileInfo {
     pub path: String,
     pub total_tokens: usize,
@@ -142,6 +348,131 @@ fn duplication_status(pct: f64, warning: f64, error: f64) -> String {
     }
 }
 
+impl From<&JsonFunctionOutput> for FunctionAnalysisResult {
+    /// Rebuilds the analysis result; `halstead_time` is derived from the effort.
+    fn from(func: &JsonFunctionOutput) -> Self {
+        FunctionAnalysisResult {
+            name: func.name.clone(),
+            qualified_name: func.qualified_name.clone(),
+            start_line: func.start_line,
+            end_line: func.end_line,
+            start_col: func.start_col,
+            cyclomatic: func.cyclomatic,
+            cognitive: func.cognitive,
+            halstead_volume: func.halstead_volume,
+            halstead_difficulty: func.halstead_difficulty,
+            halstead_effort: func.halstead_effort,
+            halstead_time: func.halstead_effort / 18.0,
+            halstead_bugs: func.halstead_bugs,
+            function_length: func.line_count,
+            params_count: func.params_count,
+            nesting_depth: func.nesting_depth,
+            health_score: func.health_score,
+            unreliable: func.unreliable,
+            fingerprint: func
+                .fingerprint
+                .as_deref()
+                .and_then(|hex| u64::from_str_radix(hex, 16).ok())
+                .unwrap_or(0),
+            nested: func.nested.clone(),
+        }
+    }
+}
+
+impl From<&JsonClassOutput> for ClassAnalysisResult {
+    fn from(class: &JsonClassOutput) -> Self {
+        ClassAnalysisResult {
+            name: class.name.clone(),
+            start_line: class.start_line,
+            end_line: class.end_line,
+            start_col: class.start_col,
+            method_count: class.method_count,
+            wmc: class.wmc,
+            field_count: class.field_count,
+            lcom: class.lcom,
+        }
+    }
+}
+
+impl TryFrom<&JsonSkippedItem> for SkippedItem {
+    type Error = anyhow::Error;
+
+    fn try_from(item: &JsonSkippedItem) -> anyhow::Result<Self> {
+        let missing = |field: &str| {
+            anyhow::anyhow!(
+                "skipped item {} ({}) has no {}",
+                item.path,
+                item.reason,
+                field
+            )
+        };
+        let reason = match item.reason.as_str() {
+            "file_too_large" => SkipReason::FileTooLarge {
+                lines: item.lines.ok_or_else(|| missing("lines"))?,
+                max_lines: item.max_lines.ok_or_else(|| missing("max_lines"))?,
+            },
+            "function_too_large" => SkipReason::FunctionTooLarge {
+                lines: item.lines.ok_or_else(|| missing("lines"))? as u32,
+                max_lines: item.max_lines.ok_or_else(|| missing("max_lines"))? as u32,
+            },
+            "generated_file" => SkipReason::GeneratedFile {
+                marker: item.marker.clone().ok_or_else(|| missing("marker"))?,
+            },
+            "minified_file" => SkipReason::MinifiedFile {
+                average_line_length: item
+                    .average_line_length
+                    .ok_or_else(|| missing("average_line_length"))?,
+                max_average_line_length: item
+                    .max_average_line_length
+                    .ok_or_else(|| missing("max_average_line_length"))?,
+            },
+            "failed" => SkipReason::Failed {
+                error: item.error.clone().unwrap_or_default(),
+            },
+            "syntax_errors" => SkipReason::SyntaxErrors {
+                errors: item
+                    .syntax_errors
+                    .iter()
+                    .flatten()
+                    .map(SyntaxError::from)
+                    .collect(),
+            },
+            other => anyhow::bail!("unknown skip reason '{}' for {}", other, item.path),
+        };
+        Ok(SkippedItem {
+            path: PathBuf::from(&item.path),
+            function_name: item.function_name.clone(),
+            start_line: item.start_line,
+            reason,
+        })
+    }
+}
+
+fn json_function(func: &FunctionAnalysisResult, status: String) -> JsonFunctionOutput {
+    JsonFunctionOutput {
+        name: func.name.clone(),
+        qualified_name: func.qualified_name.clone(),
+        start_line: func.start_line,
+        end_line: func.end_line,
+        start_col: func.start_col,
+        cyclomatic: func.cyclomatic,
+        cognitive: func.cognitive,
+        halstead_volume: func.halstead_volume,
+        halstead_difficulty: func.halstead_difficulty,
+        halstead_effort: func.halstead_effort,
+        halstead_bugs: func.halstead_bugs,
+        nesting_depth: func.nesting_depth,
+        line_count: func.function_length,
+        params_count: func.params_count,
+        health_score: func.health_score,
+        status,
+        unreliable: func.unreliable,
+        fingerprint: (func.fingerprint != 0).then(|| format!("{:016x}", func.fingerprint)),
+        blame: None,
+        nested: func.nested.clone(),
+    }
+}
+
 /// Renders analysis results as a JSON string matching the Zig schema exactly.
 ///
 /// Sets `timestamp` to current Unix epoch seconds and `version` from CARGO_PKG_VERSION.
@@ -154,6 +485,18 @@ pub fn render_json(
     elapsed_ms: u64,
     skipped: &[SkippedItem],
 ) -> anyhow::Result<String> {
+    let output = build_json(files, duplication, config, elapsed_ms, skipped);
+    Ok(serde_json::to_string_pretty(&output)?)
+}
+
+/// Builds the typed JSON report that `render_json` serializes.
+pub fn build_json(
+    files: &[FileAnalysisResult],
+    duplication: Option<&DuplicationResult>,
+    config: &ResolvedConfig,
+    elapsed_ms: u64,
+    skipped: &[SkippedItem],
+) -> JsonOutput {
     let mut total_warnings: u32 = 0;
     let mut total_errors: u32 = 0;
     let mut total_functions: usize = 0;
@@ -189,31 +532,77 @@ pub fn render_json(
                         "ok".to_string()
                     };
 
-                    JsonFunctionOutput {
-                        name: func.name.clone(),
-                        start_line: func.start_line,
-                        end_line: func.end_line,
-                        start_col: func.start_col,
-                        cyclomatic: func.cyclomatic,
-                        cognitive: func.cognitive,
-                        halstead_volume: func.halstead_volume,
-                        halstead_difficulty: func.halstead_difficulty,
-                        halstead_effort: func.halstead_effort,
-                        halstead_bugs: func.halstead_bugs,
-                        nesting_depth: func.nesting_depth,
-                        line_count: func.function_length,
-                        params_count: func.params_count,
-                        health_score: func.health_score,
-                        status,
+                    json_function(func, status)
+                })
+                .collect();
+
+            let json_classes: Vec<JsonClassOutput> = file
+                .classes
+                .iter()
+                .map(|class| {
+                    let violations = class_violations(class, config);
+                    for v in &violations {
+                        match v.severity {
+                            Severity::Error => total_errors += 1,
+                            Severity::Warning => total_warnings += 1,
+                        }
+                    }
+
+                    JsonClassOutput {
+                        name: class.name.clone(),
+                        start_line: class.start_line,
+                        end_line: class.end_line,
+                        start_col: class.start_col,
+                        method_count: class.method_count,
+                        wmc: class.wmc,
+                        field_count: class.field_count,
+                        lcom: class.lcom,
+                        status: function_status(&violations).to_string(),
+                        blame: None,
                     }
                 })
                 .collect();
 
+            let json_module = file.module.as_ref().map(|module| {
+                let violations = function_violations(module, config);
+                for v in &violations {
+                    match v.severity {
+                        Severity::Error => total_errors += 1,
+                        Severity::Warning => total_warnings += 1,
+                    }
+                }
+                json_function(module, function_status(&violations).to_string())
+            });
+
+            for v in coupling_violations(file, config)
+                .into_iter()
+                .chain(cycle_violations(file, config))
+            {
+                match v.severity {
+                    Severity::Error => total_errors += 1,
+                    Severity::Warning => total_warnings += 1,
+                }
+            }
+
             JsonFileOutput {
                 path: file.path.to_string_lossy().to_string(),
                 functions: json_functions,
+                module: json_module,
                 file_length: file.file_length,
                 export_count: file.export_count,
+                classes: json_classes,
+                syntax_errors: file
+                    .syntax_errors
+                    .iter()
+                    .map(JsonSyntaxError::from)
+                    .collect(),
+                tokens: config
+                    .export_tokens
+                    .then(|| file.tokens.iter().map(|t| t.kind.to_string()).collect()),
+                owners: Vec::new(),
+                package: None,
+                coupling: file.coupling,
+                cycles: file.cycles.clone(),
             }
         })
         .collect();
@@ -324,31 +713,61 @@ pub fn render_json(
             skipped
                 .iter()
                 .map(|item| {
-                    let (reason, lines, max_lines) = match &item.reason {
-                        SkipReason::FileTooLarge { lines, max_lines } => {
-                            ("file_too_large".to_string(), *lines, *max_lines)
-                        }
-                        SkipReason::FunctionTooLarge { lines, max_lines } => (
-                            "function_too_large".to_string(),
-                            *lines as usize,
-                            *max_lines as usize,
-                        ),
-                    };
-                    JsonSkippedItem {
+                    let mut json_item = JsonSkippedItem {
                         path: item.path.to_string_lossy().to_string(),
                         function_name: item.function_name.clone(),
                         start_line: item.start_line,
-                        reason,
-                        lines,
-                        max_lines,
+                        reason: String::new(),
+                        lines: None,
+                        max_lines: None,
+                        marker: None,
+                        average_line_length: None,
+                        max_average_line_length: None,
+                        error: None,
+                        syntax_errors: None,
+                    };
+                    match &item.reason {
+                        SkipReason::FileTooLarge { lines, max_lines } => {
+                            json_item.reason = "file_too_large".to_string();
+                            json_item.lines = Some(*lines);
+                            json_item.max_lines = Some(*max_lines);
+                        }
+                        SkipReason::FunctionTooLarge { lines, max_lines } => {
+                            json_item.reason = "function_too_large".to_string();
+                            json_item.lines = Some(*lines as usize);
+                            json_item.max_lines = Some(*max_lines as usize);
+                        }
+                        SkipReason::GeneratedFile { marker } => {
+                            json_item.reason = "generated_file".to_string();
+                            json_item.marker = Some(marker.clone());
+                        }
+                        SkipReason::MinifiedFile {
+                            average_line_length,
+                            max_average_line_length,
+                        } => {
+                            json_item.reason = "minified_file".to_string();
+                            json_item.average_line_length = Some(*average_line_length);
+                            json_item.max_average_line_length = Some(*max_average_line_length);
+                        }
+                        SkipReason::Failed { error } => {
+                            json_item.reason = "failed".to_string();
+                            json_item.error = Some(error.clone());
+                        }
+                        SkipReason::SyntaxErrors { errors } => {
+                            json_item.reason = "syntax_errors".to_string();
+                            json_item.syntax_errors =
+                                Some(errors.iter().map(JsonSyntaxError::from).collect());
+                        }
                     }
+                    json_item
                 })
                 .collect(),
         )
     };
 
-    let output = JsonOutput {
+    JsonOutput {
         version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version: SCHEMA_VERSION,
         timestamp,
         summary: JsonSummary {
             files_analyzed: files.len(),
@@ -358,17 +777,96 @@ pub fn render_json(
             status: summary_status,
             health_score: avg_health,
             skipped_count: skipped.len(),
+            syntax_error_count: files.iter().map(|f| f.syntax_errors.len()).sum(),
+            has_parse_errors: skipped
+                .iter()
+                .any(|item| matches!(item.reason, SkipReason::Failed { .. })),
         },
         files: json_files,
         metadata: JsonMetadata {
             elapsed_ms,
             thread_count: config.threads,
+            commit: None,
         },
         duplication: json_duplication,
         skipped: json_skipped,
-    };
+        directories: Some(directory_tree(files, config)),
+        hotspots: None,
+        blame: None,
+        owners: None,
+        packages: None,
+    }
+}
 
-    Ok(serde_json::to_string_pretty(&output)?)
+/// Attach `blame` to the report: each blamed unit gets its `blame` field and
+/// the per-author totals go in the top-level `blame` object.
+pub fn attach_blame(output: &mut JsonOutput, blame: &BlameReport) {
+    for file in &mut output.files {
+        let path = file.path.as_str();
+        for func in file.functions.iter_mut().chain(&mut file.module) {
+            func.blame = blame
+                .find(path, &func.qualified_name, func.start_line)
+                .cloned();
+        }
+        for class in &mut file.classes {
+            class.blame = blame.find(path, &class.name, class.start_line).cloned();
+        }
+    }
+    output.blame = Some(JsonBlameOutput {
+        since: blame.since.clone(),
+        authors: blame.authors.clone(),
+    });
+}
+
+/// Sets the owners of every file and the per-owner totals from `owners`.
+pub fn attach_owners(output: &mut JsonOutput, owners: &OwnersReport) {
+    for file in &mut output.files {
+        file.owners = owners.owners_of(&file.path).to_vec();
+    }
+    output.owners = Some(JsonOwnersOutput {
+        file: owners.file.clone(),
+        owners: owners.owners.clone(),
+    });
+}
+
+/// Sets the package of every file and the per-package totals from `packages`.
+pub fn attach_packages(output: &mut JsonOutput, packages: &PackagesReport) {
+    for file in &mut output.files {
+        file.package = packages.package_of(&file.path).map(str::to_string);
+    }
+    output.packages = Some(JsonPackagesOutput {
+        manager: packages.manager.clone(),
+        packages: packages.packages.clone(),
+    });
+}
+
+/// Parses a JSON report written by `render_json`.
+///
+/// The schema version is checked before the rest of the report is decoded, so
+/// a report from an incompatible version fails with
+/// `ReportLoadError::UnsupportedSchemaVersion` rather than a field error.
+pub fn parse_json_report(json: &str) -> Result<JsonOutput, ReportLoadError> {
+    let value: serde_json::Value = serde_json::from_str(json)?;
+    match value.get("schema_version").and_then(|v| v.as_u64()) {
+        None => return Err(ReportLoadError::MissingSchemaVersion),
+        Some(found) if found != SCHEMA_VERSION as u64 => {
+            return Err(ReportLoadError::UnsupportedSchemaVersion {
+                found,
+                expected: SCHEMA_VERSION,
+            })
+        }
+        Some(_) => {}
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Reads and parses a JSON report file, see `parse_json_report`.
+pub fn load_json_report(path: &Path) -> Result<JsonOutput, ReportLoadError> {
+    let json = std::fs::read_to_string(path).map_err(|source| ReportLoadError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    parse_json_report(&json)
 }
 
 // TESTS
@@ -388,6 +886,7 @@ mod tests {
     ) -> FunctionAnalysisResult {
         FunctionAnalysisResult {
             name: name.to_string(),
+            qualified_name: name.to_string(),
             start_line,
             end_line: start_line + 10,
             start_col: 0,
@@ -402,6 +901,9 @@ mod tests {
             params_count: 2,
             nesting_depth: 1,
             health_score,
+            unreliable: false,
+            fingerprint: 0,
+            nested: Vec::new(),
         }
     }
 
@@ -409,11 +911,16 @@ mod tests {
         FileAnalysisResult {
             path: PathBuf::from(path),
             functions,
+            classes: vec![],
+            module: None,
             tokens: vec![],
             file_score: 90.0,
             file_length: 100,
             export_count: 3,
-            error: false,
+            syntax_errors: vec![],
+            imports: Vec::new(),
+            coupling: None,
+            cycles: Vec::new(),
         }
     }
 
@@ -555,6 +1062,30 @@ mod tests {
         assert_eq!(parsed["summary"]["total_functions"].as_u64().unwrap(), 2);
     }
 
+    #[test]
+    fn test_render_json_nests_directory_totals() {
+        let func_ok = make_func("ok", 1, 2, 1, 95.0);
+        let func_err = make_func("bad", 20, 25, 5, 40.0);
+        let files = [
+            make_file("src/api/a.ts", vec![func_err]),
+            make_file("src/b.ts", vec![func_ok]),
+        ];
+        let json_str = render_json(&files, None, &default_config(), 10, &[]).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+
+        let root = &parsed["directories"];
+        assert_eq!(root["path"], ".");
+        assert_eq!(root["errors"], parsed["summary"]["errors"]);
+        let src = &root["children"][0];
+        assert_eq!(src["path"], "src");
+        assert_eq!(src["files"], 2);
+        let api = &src["children"][0];
+        assert_eq!(api["path"], "src/api");
+        assert_eq!(api["functions"], 1);
+        assert_eq!(api["errors"], 1);
+        assert_eq!(api["children"], serde_json::json!([]));
+    }
+
     #[test]
     fn test_render_json_duplication_null_when_absent() {
         let func = make_func("f", 1, 2, 1, 90.0);
@@ -626,11 +1157,16 @@ mod tests {
         let file = FileAnalysisResult {
             path: std::path::PathBuf::from("src/a.ts"),
             functions: vec![make_func("f", 1, 2, 1, 90.0)],
+            classes: vec![],
+            module: None,
             tokens,
             file_score: 90.0,
             file_length: 100,
             export_count: 1,
-            error: false,
+            syntax_errors: vec![],
+            imports: Vec::new(),
+            coupling: None,
+            cycles: Vec::new(),
         };
         let config = default_config();
         let clone_instance = CloneInstance {
@@ -782,6 +1318,157 @@ mod tests {
         assert_eq!(func_obj["nesting_depth"].as_u64().unwrap(), 1);
     }
 
+    #[test]
+    fn test_render_json_skipped_reasons() {
+        let skipped = vec![
+            SkippedItem {
+                path: PathBuf::from("src/big.ts"),
+                function_name: None,
+                start_line: 0,
+                reason: SkipReason::FileTooLarge {
+                    lines: 12000,
+                    max_lines: 10000,
+                },
+            },
+            SkippedItem {
+                path: PathBuf::from("src/schema.ts"),
+                function_name: None,
+                start_line: 0,
+                reason: SkipReason::GeneratedFile {
+                    marker: "@generated".to_string(),
+                },
+            },
+            SkippedItem {
+                path: PathBuf::from("src/vendor.min.js"),
+                function_name: None,
+                start_line: 0,
+                reason: SkipReason::MinifiedFile {
+                    average_line_length: 4096,
+                    max_average_line_length: 200,
+                },
+            },
+        ];
+        let config = default_config();
+        let json_str = render_json(&[], None, &config, 10, &skipped).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+
+        let items = parsed["skipped"].as_array().unwrap();
+        assert_eq!(parsed["summary"]["skipped_count"].as_u64().unwrap(), 3);
+        assert_eq!(items[0]["reason"], "file_too_large");
+        assert_eq!(items[0]["lines"].as_u64().unwrap(), 12000);
+        assert_eq!(items[1]["reason"], "generated_file");
+        assert_eq!(items[1]["marker"], "@generated");
+        assert!(items[1].get("lines").is_none());
+        assert_eq!(items[2]["reason"], "minified_file");
+        assert_eq!(items[2]["average_line_length"].as_u64().unwrap(), 4096);
+        assert_eq!(items[2]["max_average_line_length"].as_u64().unwrap(), 200);
+    }
+
+    #[test]
+    fn test_render_json_failed_file() {
+        let skipped = vec![SkippedItem {
+            path: PathBuf::from("src/gone.ts"),
+            function_name: None,
+            start_line: 0,
+            reason: SkipReason::Failed {
+                error: "I/O error: No such file or directory (os error 2)".to_string(),
+            },
+        }];
+        let config = default_config();
+        let json_str = render_json(&[], None, &config, 10, &skipped).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+
+        let item = &parsed["skipped"][0];
+        assert_eq!(item["path"], "src/gone.ts");
+        assert_eq!(item["reason"], "failed");
+        assert_eq!(
+            item["error"],
+            "I/O error: No such file or directory (os error 2)"
+        );
+    }
+
+    #[test]
+    fn test_render_json_short_and_qualified_names() {
+        let mut func = make_func("it(\"adds item\")", 3, 1, 0, 100.0);
+        func.qualified_name = "describe(\"cart\") > it(\"adds item\")".to_string();
+        let file = make_file("src/cart.test.ts", vec![func]);
+        let config = default_config();
+        let json_str = render_json(&[file], None, &config, 10, &[]).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+
+        let func_obj = &parsed["files"][0]["functions"][0];
+        assert_eq!(func_obj["name"], "it(\"adds item\")");
+        assert_eq!(
+            func_obj["qualified_name"],
+            "describe(\"cart\") > it(\"adds item\")"
+        );
+    }
+
+    #[test]
+    fn test_render_json_classes_with_status() {
+        let mut file = make_file("src/god.ts", vec![make_func("f", 1, 1, 0, 100.0)]);
+        file.classes = vec![crate::types::ClassAnalysisResult {
+            name: "God".to_string(),
+            start_line: 5,
+            end_line: 400,
+            start_col: 0,
+            method_count: 25,
+            wmc: 40,
+            field_count: 6,
+            lcom: 1,
+        }];
+        let config = default_config();
+        let json_str = render_json(&[file], None, &config, 10, &[]).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+
+        let class_obj = &parsed["files"][0]["classes"][0];
+        assert_eq!(class_obj["name"], "God");
+        assert_eq!(class_obj["method_count"], 25);
+        assert_eq!(class_obj["wmc"], 40);
+        assert_eq!(class_obj["field_count"], 6);
+        assert_eq!(class_obj["lcom"], 1);
+        assert_eq!(class_obj["status"], "warning");
+        assert_eq!(parsed["summary"]["warnings"], 1);
+        assert_eq!(parsed["summary"]["status"], "warning");
+    }
+
+    #[test]
+    fn test_render_json_omits_empty_classes() {
+        let file = make_file("src/a.ts", vec![make_func("f", 1, 1, 0, 100.0)]);
+        let config = default_config();
+        let json_str = render_json(&[file], None, &config, 10, &[]).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+        assert!(parsed["files"][0].get("classes").is_none());
+    }
+
+    #[test]
+    fn test_render_json_syntax_errors_and_unreliable_functions() {
+        let mut func = make_func("broken", 1, 2, 1, 90.0);
+        func.unreliable = true;
+        let mut file = make_file(
+            "src/broken.ts",
+            vec![func, make_func("ok", 20, 1, 0, 100.0)],
+        );
+        file.syntax_errors = vec![SyntaxError {
+            line: 3,
+            column: 4,
+            kind: SyntaxErrorKind::Missing,
+            snippet: ";".to_string(),
+        }];
+        let config = default_config();
+        let json_str = render_json(&[file], None, &config, 10, &[]).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+
+        assert_eq!(parsed["summary"]["syntax_error_count"], 1);
+        let file_obj = &parsed["files"][0];
+        assert_eq!(file_obj["syntax_errors"][0]["line"], 3);
+        assert_eq!(file_obj["syntax_errors"][0]["column"], 4);
+        assert_eq!(file_obj["syntax_errors"][0]["kind"], "missing");
+        assert_eq!(file_obj["syntax_errors"][0]["snippet"], ";");
+        assert_eq!(file_obj["functions"][0]["unreliable"], true);
+        assert!(file_obj["functions"][1].get("unreliable").is_none());
+    }
+
     #[test]
     fn test_render_json_file_field_values() {
         let func = make_func("f", 1, 2, 1, 90.0);
@@ -795,4 +1482,268 @@ mod tests {
         assert_eq!(file_obj["file_length"].as_u64().unwrap(), 100);
         assert_eq!(file_obj["export_count"].as_u64().unwrap(), 3);
     }
+
+    #[test]
+    fn test_render_json_module_unit_counts_toward_summary() {
+        let mut file = make_file("src/setup.ts", vec![make_func("f", 1, 1, 0, 100.0)]);
+        let mut module = make_func("<module>", 1, 12, 3, 85.0);
+        module.function_length = 0;
+        module.params_count = 0;
+        file.module = Some(module);
+        let config = default_config();
+        let json_str = render_json(&[file], None, &config, 10, &[]).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+
+        let module_obj = &parsed["files"][0]["module"];
+        assert_eq!(module_obj["name"], "<module>");
+        assert_eq!(module_obj["cyclomatic"], 12);
+        assert_eq!(module_obj["status"], "warning");
+        assert_eq!(parsed["summary"]["warnings"], 1);
+        // The module is not a function and does not enter the health average
+        assert_eq!(parsed["summary"]["total_functions"], 1);
+        assert_eq!(parsed["summary"]["health_score"], 100.0);
+    }
+
+    #[test]
+    fn test_render_json_file_coupling() {
+        let mut file = make_file("src/shared.ts", vec![]);
+        file.coupling = Some(FileCoupling {
+            fan_in: 25,
+            fan_out: 1,
+            instability: 1.0 / 26.0,
+        });
+        let other = make_file("src/other.ts", vec![]);
+        let json_str = render_json(&[file, other], None, &default_config(), 10, &[]).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+
+        let coupling = &parsed["files"][0]["coupling"];
+        assert_eq!(coupling["fan_in"], 25);
+        assert_eq!(coupling["fan_out"], 1);
+        assert_eq!(parsed["summary"]["warnings"], 1);
+        assert!(parsed["files"][1].get("coupling").is_none());
+    }
+
+    #[test]
+    fn test_render_json_omits_missing_module() {
+        let file = make_file("src/foo.ts", vec![make_func("f", 1, 1, 0, 100.0)]);
+        let json_str = render_json(&[file], None, &default_config(), 10, &[]).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+        assert!(parsed["files"][0].get("module").is_none());
+    }
+
+    #[test]
+    fn test_json_report_round_trips() {
+        use crate::types::DuplicationResult;
+        let mut file = make_file("src/a.ts", vec![make_func("f", 1, 12, 20, 61.25)]);
+        file.module = Some(make_func("<module>", 1, 2, 1, 95.5));
+        file.functions[0].unreliable = true;
+        let skipped = vec![SkippedItem {
+            path: PathBuf::from("src/big.ts"),
+            function_name: None,
+            start_line: 0,
+            reason: SkipReason::FileTooLarge {
+                lines: 12000,
+                max_lines: 10000,
+            },
+        }];
+        let dup = DuplicationResult {
+            clone_groups: vec![],
+            total_tokens: 1000,
+            cloned_tokens: 150,
+            duplication_percentage: 15.0,
+        };
+        let output = build_json(&[file], Some(&dup), &default_config(), 7, &skipped);
+        let json_str = serde_json::to_string_pretty(&output).unwrap();
+
+        let loaded = parse_json_report(&json_str).unwrap();
+        assert_eq!(loaded, output);
+        assert_eq!(loaded.schema_version, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_parse_json_report_fills_omitted_fields() {
+        let json_str = render_json(
+            &[make_file("src/a.ts", vec![make_func("f", 1, 1, 0, 100.0)])],
+            None,
+            &default_config(),
+            3,
+            &[],
+        )
+        .unwrap();
+        let loaded = parse_json_report(&json_str).unwrap();
+        let file = &loaded.files[0];
+        assert!(file.classes.is_empty());
+        assert!(file.syntax_errors.is_empty());
+        assert!(file.module.is_none());
+        assert!(!file.functions[0].unreliable);
+        assert!(loaded.skipped.is_none());
+        assert!(loaded.duplication.is_none());
+    }
+
+    #[test]
+    fn test_parse_json_report_rejects_other_schema_versions() {
+        let json_str = render_json(&[], None, &default_config(), 1, &[]).unwrap();
+        let mut value: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+
+        value["schema_version"] = serde_json::json!(SCHEMA_VERSION + 1);
+        let err = parse_json_report(&value.to_string()).unwrap_err();
+        assert!(matches!(
+            err,
+            ReportLoadError::UnsupportedSchemaVersion { found, expected }
+                if found == SCHEMA_VERSION as u64 + 1 && expected == SCHEMA_VERSION
+        ));
+        assert!(err
+            .to_string()
+            .contains("schema version 2 is not supported"));
+
+        value.as_object_mut().unwrap().remove("schema_version");
+        let err = parse_json_report(&value.to_string()).unwrap_err();
+        assert!(matches!(err, ReportLoadError::MissingSchemaVersion));
+
+        assert!(matches!(
+            parse_json_report("{not json"),
+            Err(ReportLoadError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_attach_blame_sets_unit_and_author_fields() {
+        let file = make_file(
+            "src/a.ts",
+            vec![
+                make_func("f", 1, 1, 0, 100.0),
+                make_func("g", 20, 1, 0, 100.0),
+                make_func("g", 40, 1, 0, 100.0),
+            ],
+        );
+        let mut output = build_json(&[file], None, &default_config(), 3, &[]);
+        let blame = Blame {
+            author: "Ada".to_string(),
+            author_lines: 8,
+            lines: 10,
+            commit: "a".repeat(40),
+            date: 1_700_000_000,
+        };
+        let report = BlameReport {
+            since: Some("2026-01-01".to_string()),
+            units: vec![crate::blame::UnitBlame {
+                path: "src/a.ts".to_string(),
+                name: "g".to_string(),
+                start_line: 20,
+                errors: 1,
+                warnings: 0,
+                blame: blame.clone(),
+            }],
+            authors: vec![AuthorSummary {
+                author: "Ada".to_string(),
+                units: 1,
+                errors: 1,
+                warnings: 0,
+            }],
+        };
+        attach_blame(&mut output, &report);
+
+        let functions = &output.files[0].functions;
+        assert!(functions[0].blame.is_none());
+        assert_eq!(functions[1].blame.as_ref(), Some(&blame));
+        // Only the `g` that was blamed, not every function with its name
+        assert!(functions[2].blame.is_none());
+        let summary = output.blame.as_ref().unwrap();
+        assert_eq!(summary.since.as_deref(), Some("2026-01-01"));
+        assert_eq!(summary.authors, report.authors);
+
+        let json_str = serde_json::to_string(&output).unwrap();
+        assert_eq!(parse_json_report(&json_str).unwrap(), output);
+    }
+
+    #[test]
+    fn test_attach_owners_sets_file_owners_and_totals() {
+        let files = [
+            make_file("src/a.ts", vec![make_func("f", 1, 1, 0, 100.0)]),
+            make_file("scripts/b.ts", vec![make_func("g", 1, 1, 0, 100.0)]),
+        ];
+        let mut output = build_json(&files, None, &default_config(), 3, &[]);
+        let summary = |owner: &str| OwnerSummary {
+            owner: owner.to_string(),
+            files: 1,
+            functions: 1,
+            errors: 0,
+            warnings: 0,
+            health_score: 100.0,
+        };
+        let report = OwnersReport {
+            file: ".github/CODEOWNERS".to_string(),
+            files: [
+                ("src/a.ts".to_string(), vec!["@org/web".to_string()]),
+                ("scripts/b.ts".to_string(), vec!["(unowned)".to_string()]),
+            ]
+            .into(),
+            owners: vec![summary("@org/web"), summary("(unowned)")],
+        };
+        attach_owners(&mut output, &report);
+
+        let paths: Vec<_> = output.files.iter().map(|f| f.path.as_str()).collect();
+        let a = paths.iter().position(|p| *p == "src/a.ts").unwrap();
+        assert_eq!(output.files[a].owners, ["@org/web"]);
+        let owners = output.owners.as_ref().unwrap();
+        assert_eq!(owners.file, ".github/CODEOWNERS");
+        assert_eq!(owners.owners, report.owners);
+
+        let json_str = serde_json::to_string(&output).unwrap();
+        assert_eq!(parse_json_report(&json_str).unwrap(), output);
+    }
+
+    #[test]
+    fn test_attach_packages_sets_file_packages_and_totals() {
+        let files = [
+            make_file("packages/web/a.ts", vec![make_func("f", 1, 1, 0, 100.0)]),
+            make_file("scripts/b.ts", vec![make_func("g", 1, 1, 0, 100.0)]),
+        ];
+        let mut output = build_json(&files, None, &default_config(), 3, &[]);
+        let summary = |name: &str, path: &str, fail_on: Option<&str>| PackageSummary {
+            name: name.to_string(),
+            path: path.to_string(),
+            files: 1,
+            functions: 1,
+            errors: 0,
+            warnings: 0,
+            health_score: 100.0,
+            fail_on: fail_on.map(str::to_string),
+        };
+        let report = PackagesReport {
+            manager: "pnpm".to_string(),
+            files: [
+                ("packages/web/a.ts".to_string(), "@acme/web".to_string()),
+                ("scripts/b.ts".to_string(), "(root)".to_string()),
+            ]
+            .into(),
+            packages: vec![
+                summary("@acme/web", "packages/web", Some("warning")),
+                summary("(root)", ".", None),
+            ],
+        };
+        attach_packages(&mut output, &report);
+
+        let b = output
+            .files
+            .iter()
+            .find(|f| f.path == "scripts/b.ts")
+            .unwrap();
+        assert_eq!(b.package.as_deref(), Some("(root)"));
+        let packages = output.packages.as_ref().unwrap();
+        assert_eq!(packages.manager, "pnpm");
+        assert_eq!(packages.packages, report.packages);
+
+        let json_str = serde_json::to_string(&output).unwrap();
+        assert!(json_str.contains(r#""fail_on":"warning""#));
+        assert_eq!(json_str.matches("fail_on").count(), 1);
+        assert_eq!(parse_json_report(&json_str).unwrap(), output);
+    }
+
+    #[test]
+    fn test_load_json_report_reports_missing_file() {
+        let err = load_json_report(Path::new("/nonexistent/report.json")).unwrap_err();
+        assert!(matches!(err, ReportLoadError::Io { .. }));
+        assert!(err.to_string().contains("/nonexistent/report.json"));
+    }
 }
@@ -1,11 +1,42 @@
+#[cfg(feature = "console")]
+pub mod blame_output;
+pub mod compare_output;
+#[cfg(feature = "console")]
 pub mod console;
+#[cfg(feature = "console")]
+pub mod directories_output;
 pub mod exit_codes;
+pub mod function_history_output;
+#[cfg(feature = "console")]
+pub mod hotspots_output;
+#[cfg(feature = "html")]
 pub mod html_output;
 pub mod json_output;
+#[cfg(feature = "console")]
+pub mod modules_output;
+#[cfg(feature = "console")]
+pub mod owners_output;
+#[cfg(feature = "console")]
+pub mod packages_output;
+#[cfg(feature = "sarif")]
 pub mod sarif_output;
+pub mod trend_output;
+pub mod violations;
 
+#[cfg(feature = "console")]
+pub use compare_output::render_compare_console;
+pub use compare_output::{render_compare_json, render_compare_markdown};
+#[cfg(feature = "console")]
 pub use console::render_console;
 pub use exit_codes::{determine_exit_code, ExitCode};
-pub use html_output::render_html;
+#[cfg(feature = "console")]
+pub use function_history_output::render_function_history_console;
+pub use function_history_output::render_function_history_json;
+#[cfg(feature = "html")]
+pub use html_output::{render_html, HtmlExtras};
 pub use json_output::render_json;
+#[cfg(feature = "sarif")]
 pub use sarif_output::render_sarif;
+#[cfg(feature = "console")]
+pub use trend_output::render_trend_console;
+pub use trend_output::render_trend_json;
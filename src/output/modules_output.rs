@@ -0,0 +1,176 @@
+use std::io::Write;
+
+use owo_colors::OwoColorize;
+
+use crate::cli::ResolvedConfig;
+use crate::modules::ModuleGraph;
+use crate::output::console::should_use_color;
+use crate::output::violations::is_cycle_allowed;
+
+/// Most depended-on files listed by default; verbose mode lists every file
+/// with imports between analyzed files.
+const TOP_MODULES: usize = 10;
+
+/// Renders the module graph as a console table of the files with the highest
+/// fan-in, then fan-out.
+pub fn render_modules_console(
+    graph: &ModuleGraph,
+    config: &ResolvedConfig,
+    writer: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let use_color = should_use_color(config.color);
+    let title = format!(
+        "Modules ({} files, {} imports):",
+        graph.modules.len(),
+        graph.edges.len()
+    );
+    writeln!(writer)?;
+    if use_color {
+        writeln!(writer, "{}", title.bold())?;
+    } else {
+        writeln!(writer, "{title}")?;
+    }
+
+    let mut coupled: Vec<_> = graph
+        .modules
+        .iter()
+        .filter(|m| m.coupling.fan_in + m.coupling.fan_out > 0)
+        .collect();
+    if coupled.is_empty() {
+        writeln!(writer, "  No imports between analyzed files")?;
+        return Ok(());
+    }
+    coupled.sort_by_key(|m| {
+        (
+            std::cmp::Reverse(m.coupling.fan_in),
+            std::cmp::Reverse(m.coupling.fan_out),
+        )
+    });
+    if !config.verbose {
+        coupled.truncate(TOP_MODULES);
+    }
+
+    let width = coupled
+        .iter()
+        .map(|m| m.path.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("Module".len());
+    let header = format!(
+        "  {:<width$}  {:>6}  {:>7}  {:>11}",
+        "Module", "Fan-in", "Fan-out", "Instability"
+    );
+    if use_color {
+        writeln!(writer, "{}", header.bold())?;
+    } else {
+        writeln!(writer, "{header}")?;
+    }
+    for module in coupled {
+        writeln!(
+            writer,
+            "  {:<width$}  {:>6}  {:>7}  {:>11.2}",
+            module.path,
+            module.coupling.fan_in,
+            module.coupling.fan_out,
+            module.coupling.instability
+        )?;
+    }
+    if !graph.cycles.is_empty() {
+        let allowed = graph
+            .cycles
+            .iter()
+            .filter(|cycle| is_cycle_allowed(cycle, config))
+            .count();
+        writeln!(
+            writer,
+            "  Import cycles: {} ({allowed} allowed)",
+            graph.cycles.len()
+        )?;
+    }
+    Ok(())
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::ModuleNode;
+    use crate::types::{FileCoupling, ImportCycle, ImportStep};
+
+    fn graph() -> ModuleGraph {
+        let node = |path: &str, fan_in: u32, fan_out: u32| ModuleNode {
+            path: path.to_string(),
+            coupling: FileCoupling {
+                fan_in,
+                fan_out,
+                instability: if fan_in + fan_out == 0 {
+                    0.0
+                } else {
+                    fan_out as f64 / (fan_in + fan_out) as f64
+                },
+            },
+        };
+        let mut modules = vec![
+            node("src/app.ts", 0, 3),
+            node("src/util.ts", 2, 0),
+            node("src/api.ts", 1, 1),
+            node("README.ts", 0, 0),
+        ];
+        modules.extend((0..10).map(|i| node(&format!("src/leaf{i}.ts"), 0, 1)));
+        ModuleGraph {
+            modules,
+            edges: Vec::new(),
+            cycles: Vec::new(),
+        }
+    }
+
+    fn render(verbose: bool) -> String {
+        let config = ResolvedConfig {
+            color: Some(false),
+            verbose,
+            ..ResolvedConfig::default()
+        };
+        let mut out = Vec::new();
+        render_modules_console(&graph(), &config, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn lists_the_most_depended_on_files_first() {
+        let text = render(false);
+        assert!(text.starts_with("\nModules (14 files, 0 imports):\n"));
+        assert!(text.contains(
+            "  Module        Fan-in  Fan-out  Instability\n  src/util.ts        2        0         0.00\n  src/api.ts         1        1         0.50\n  src/app.ts         0        3         1.00\n"
+        ));
+        assert!(!text.contains("README.ts"));
+        assert_eq!(text.lines().count(), 13);
+        assert_eq!(render(true).lines().count(), 16);
+    }
+
+    #[test]
+    fn counts_import_cycles_and_the_allowed_ones() {
+        let step = |path: &str| ImportStep {
+            path: path.to_string(),
+            line: 1,
+        };
+        let cycle = |a: &str, b: &str| ImportCycle {
+            files: vec![a.to_string(), b.to_string()],
+            chain: vec![step(a), step(b)],
+        };
+        let mut graph = graph();
+        graph.cycles = vec![
+            cycle("src/api.ts", "src/app.ts"),
+            cycle("src/leaf0.ts", "src/leaf1.ts"),
+        ];
+        let config = ResolvedConfig {
+            color: Some(false),
+            modules_allow_cycles: vec![vec!["src/api.ts".to_string(), "src/app.ts".to_string()]],
+            ..ResolvedConfig::default()
+        };
+        let mut out = Vec::new();
+        render_modules_console(&graph, &config, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.ends_with("  Import cycles: 2 (1 allowed)\n"), "{text}");
+    }
+}
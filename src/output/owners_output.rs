@@ -0,0 +1,117 @@
+use std::io::Write;
+
+use owo_colors::OwoColorize;
+
+use crate::cli::ResolvedConfig;
+use crate::codeowners::OwnersReport;
+use crate::output::console::should_use_color;
+
+/// Renders the per-owner totals as a console section. Verbose mode also lists
+/// each owner's files.
+pub fn render_owners_console(
+    owners: &OwnersReport,
+    config: &ResolvedConfig,
+    writer: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let use_color = should_use_color(config.color);
+    let header = format!("Owners ({}):", owners.file);
+    writeln!(writer)?;
+    if use_color {
+        writeln!(writer, "{}", header.bold())?;
+    } else {
+        writeln!(writer, "{header}")?;
+    }
+    if owners.owners.is_empty() {
+        writeln!(writer, "  No files analyzed")?;
+        return Ok(());
+    }
+
+    let width = owners
+        .owners
+        .iter()
+        .map(|o| o.owner.chars().count())
+        .max()
+        .unwrap_or(0);
+    for owner in &owners.owners {
+        let health = format!("{:>5.1}", owner.health_score);
+        let health = if !use_color {
+            health
+        } else if owner.errors > 0 {
+            health.red().to_string()
+        } else if owner.warnings > 0 {
+            health.yellow().to_string()
+        } else {
+            health.green().to_string()
+        };
+        writeln!(
+            writer,
+            "  {:<width$}  health {}  {} files, {} functions, {} errors, {} warnings",
+            owner.owner, health, owner.files, owner.functions, owner.errors, owner.warnings
+        )?;
+        if config.verbose {
+            for (path, _) in owners
+                .files
+                .iter()
+                .filter(|(_, file_owners)| file_owners.contains(&owner.owner))
+            {
+                writeln!(writer, "    {path}")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codeowners::{OwnerSummary, UNOWNED};
+
+    fn owners() -> OwnersReport {
+        let summary = |owner: &str, errors: u32, health_score: f64| OwnerSummary {
+            owner: owner.to_string(),
+            files: 1,
+            functions: 4,
+            errors,
+            warnings: 2,
+            health_score,
+        };
+        OwnersReport {
+            file: ".github/CODEOWNERS".to_string(),
+            files: [
+                ("src/web/app.ts".to_string(), vec!["@org/web".to_string()]),
+                ("scripts/build.ts".to_string(), vec![UNOWNED.to_string()]),
+            ]
+            .into(),
+            owners: vec![summary("@org/web", 1, 82.25), summary(UNOWNED, 0, 97.0)],
+        }
+    }
+
+    fn render(config: &ResolvedConfig) -> String {
+        let mut out = Vec::new();
+        render_owners_console(&owners(), config, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn lists_owner_totals() {
+        let mut config = ResolvedConfig {
+            color: Some(false),
+            ..ResolvedConfig::default()
+        };
+        let text = render(&config);
+        assert!(text.contains("Owners (.github/CODEOWNERS):"));
+        assert!(
+            text.contains("  @org/web   health  82.2  1 files, 4 functions, 1 errors, 2 warnings")
+        );
+        assert!(text.contains("  (unowned)  health  97.0"));
+        assert!(!text.contains("src/web/app.ts"));
+
+        config.verbose = true;
+        let text = render(&config);
+        assert!(
+            text.contains("82.2  1 files, 4 functions, 1 errors, 2 warnings\n    src/web/app.ts\n")
+        );
+    }
+}
@@ -0,0 +1,140 @@
+use std::io::Write;
+
+use owo_colors::OwoColorize;
+
+use crate::cli::ResolvedConfig;
+use crate::output::console::should_use_color;
+use crate::workspaces::PackagesReport;
+
+/// Renders per-package totals as a console table. A `Fail on` column is added
+/// when any package has its own gate.
+pub fn render_packages_console(
+    packages: &PackagesReport,
+    config: &ResolvedConfig,
+    writer: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let use_color = should_use_color(config.color);
+    let title = format!("Packages ({} workspace):", packages.manager);
+    if use_color {
+        writeln!(writer, "{}", title.bold())?;
+    } else {
+        writeln!(writer, "{title}")?;
+    }
+    if packages.packages.is_empty() {
+        writeln!(writer, "  No files analyzed")?;
+        writeln!(writer)?;
+        return Ok(());
+    }
+
+    let name_width = packages
+        .packages
+        .iter()
+        .map(|p| p.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("Package".len());
+    let path_width = packages
+        .packages
+        .iter()
+        .map(|p| p.path.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("Path".len());
+    let gated = packages.packages.iter().any(|p| p.fail_on.is_some());
+
+    let mut header = format!(
+        "  {:<name_width$}  {:<path_width$}  {:>5}  {:>9}  {:>6}  {:>8}  {:>6}",
+        "Package", "Path", "Files", "Functions", "Errors", "Warnings", "Health"
+    );
+    if gated {
+        header.push_str("  Fail on");
+    }
+    if use_color {
+        writeln!(writer, "{}", header.bold())?;
+    } else {
+        writeln!(writer, "{header}")?;
+    }
+    for package in &packages.packages {
+        let health = format!("{:>6.1}", package.health_score);
+        let health = if !use_color {
+            health
+        } else if package.errors > 0 {
+            health.red().to_string()
+        } else if package.warnings > 0 {
+            health.yellow().to_string()
+        } else {
+            health.green().to_string()
+        };
+        let mut row = format!(
+            "  {:<name_width$}  {:<path_width$}  {:>5}  {:>9}  {:>6}  {:>8}  {}",
+            package.name,
+            package.path,
+            package.files,
+            package.functions,
+            package.errors,
+            package.warnings,
+            health
+        );
+        if gated {
+            row.push_str("  ");
+            row.push_str(package.fail_on.as_deref().unwrap_or("-"));
+        }
+        writeln!(writer, "{row}")?;
+    }
+    writeln!(writer)?;
+    Ok(())
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspaces::{PackageSummary, NO_PACKAGE};
+
+    fn packages(fail_on: Option<&str>) -> PackagesReport {
+        let summary = |name: &str, path: &str, errors: u32, fail_on: Option<&str>| PackageSummary {
+            name: name.to_string(),
+            path: path.to_string(),
+            files: 3,
+            functions: 12,
+            errors,
+            warnings: 1,
+            health_score: 91.25,
+            fail_on: fail_on.map(str::to_string),
+        };
+        PackagesReport {
+            manager: "pnpm".to_string(),
+            files: Default::default(),
+            packages: vec![
+                summary("@acme/web", "packages/web", 2, fail_on),
+                summary(NO_PACKAGE, ".", 0, None),
+            ],
+        }
+    }
+
+    fn render(packages: &PackagesReport) -> String {
+        let config = ResolvedConfig {
+            color: Some(false),
+            ..ResolvedConfig::default()
+        };
+        let mut out = Vec::new();
+        render_packages_console(packages, &config, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn lists_package_totals() {
+        let text = render(&packages(None));
+        assert!(text.starts_with("Packages (pnpm workspace):\n"));
+        assert!(text.contains(
+            "  Package    Path          Files  Functions  Errors  Warnings  Health\n  @acme/web  packages/web      3         12       2         1    91.2\n  (root)     .                 3"
+        ));
+        assert!(!text.contains("Fail on"));
+
+        let text = render(&packages(Some("warning")));
+        assert!(text.contains("Warnings  Health  Fail on\n"));
+        assert!(text.contains("    91.2  warning\n"));
+        assert!(text.contains("    91.2  -\n"));
+    }
+}
@@ -1,6 +1,9 @@
 use crate::cli::ResolvedConfig;
-use crate::output::console::{function_violations, Severity};
-use crate::types::{DuplicationResult, FileAnalysisResult, SkippedItem};
+use crate::output::violations::{
+    class_violations, coupling_violations, cycle_violation, function_violations, reported_cycles,
+    syntax_error_message, Severity,
+};
+use crate::types::{DuplicationResult, FileAnalysisResult, FunctionAnalysisResult, SkippedItem};
 
 const SARIF_SCHEMA: &str =
     "https://raw.githubusercontent.com/oasis-tcs/sarif-spec/master/Schemata/sarif-schema-2.1.0.json";
@@ -22,6 +25,14 @@ const RULE_NESTING_DEPTH: usize = 8;
 const RULE_HEALTH_SCORE: usize = 9;
 const RULE_DUPLICATION: usize = 10;
 const RULE_SKIPPED: usize = 11;
+const RULE_CLASS_METHOD_COUNT: usize = 12;
+const RULE_CLASS_WMC: usize = 13;
+const RULE_CLASS_FIELD_COUNT: usize = 14;
+const RULE_CLASS_LCOM: usize = 15;
+const RULE_FAN_IN: usize = 16;
+const RULE_FAN_OUT: usize = 17;
+const RULE_INSTABILITY: usize = 18;
+const RULE_IMPORT_CYCLE: usize = 19;
 
 // --- SARIF 2.1.0 hand-rolled structs ---
 
@@ -36,7 +47,51 @@ pub struct SarifLog<'a> {
 #[derive(serde::Serialize)]
 pub struct SarifRun {
     pub tool: SarifTool,
+    pub invocations: Vec<SarifInvocation>,
     pub results: Vec<SarifResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub properties: Option<SarifRunProperties>,
+}
+
+/// Property bag of a run.
+#[derive(serde::Serialize)]
+pub struct SarifRunProperties {
+    /// SHA of the analyzed commit
+    pub commit: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct SarifInvocation {
+    #[serde(rename = "executionSuccessful")]
+    pub execution_successful: bool,
+    #[serde(
+        rename = "toolExecutionNotifications",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub tool_execution_notifications: Vec<SarifNotification>,
+}
+
+/// A problem with the tool run itself (e.g. a file that could not be read or
+/// parsed, or a syntax error the parser recovered from).
+#[derive(serde::Serialize)]
+pub struct SarifNotification {
+    pub level: &'static str,
+    pub message: SarifOwnedMessage,
+    pub locations: Vec<SarifNotificationLocation>,
+}
+
+#[derive(serde::Serialize)]
+pub struct SarifNotificationLocation {
+    #[serde(rename = "physicalLocation")]
+    pub physical_location: SarifNotificationPhysicalLocation,
+}
+
+#[derive(serde::Serialize)]
+pub struct SarifNotificationPhysicalLocation {
+    #[serde(rename = "artifactLocation")]
+    pub artifact_location: SarifArtifactLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<SarifRegion>,
 }
 
 #[derive(serde::Serialize)]
@@ -100,6 +155,17 @@ pub struct SarifOwnedMessage {
 pub struct SarifLocation {
     #[serde(rename = "physicalLocation")]
     pub physical_location: SarifPhysicalLocation,
+    #[serde(rename = "logicalLocations", skip_serializing_if = "Vec::is_empty")]
+    pub logical_locations: Vec<SarifLogicalLocation>,
+}
+
+/// A function a result belongs to, by short and fully qualified name.
+#[derive(serde::Serialize)]
+pub struct SarifLogicalLocation {
+    pub name: String,
+    #[serde(rename = "fullyQualifiedName")]
+    pub fully_qualified_name: String,
+    pub kind: &'static str,
 }
 
 #[derive(serde::Serialize)]
@@ -132,7 +198,8 @@ pub struct SarifRelatedLocation {
     pub physical_location: SarifPhysicalLocation,
 }
 
-/// Build all 11 SARIF rule definitions matching the Zig source exactly.
+/// Build all 20 SARIF rule definitions: the 12 from the Zig source, then the
+/// class rules and the module graph rules.
 fn build_rules() -> Vec<SarifRule> {
     vec![
         // RULE 0: Cyclomatic complexity
@@ -303,6 +370,118 @@ fn build_rules() -> Vec<SarifRule> {
                 text: "Consider excluding auto-generated or minified files from analysis using --exclude patterns or the files.exclude config option. If this is a real source file, consider splitting it into smaller modules.",
             },
         },
+        // RULE 12: Class method count
+        SarifRule {
+            id: "complexity-guard/class-method-count",
+            name: "ClassMethodCount",
+            short_description: SarifMessage { text: "Class method count exceeded threshold" },
+            full_description: SarifMessage {
+                text: "Counts the methods of a class, including the constructor, accessors and function-valued fields. Classes with many methods tend to carry many responsibilities and are hard to understand and change as a whole.",
+            },
+            default_configuration: SarifConfiguration { level: "warning" },
+            help_uri: "https://github.com/benvds/complexity-guard/blob/main/docs/class-metrics.md",
+            help: SarifMessage {
+                text: "Split the class along its responsibilities, or move groups of related methods into collaborating classes or plain functions.",
+            },
+        },
+        // RULE 13: Weighted methods per class
+        SarifRule {
+            id: "complexity-guard/class-wmc",
+            name: "WeightedMethodsPerClass",
+            short_description: SarifMessage { text: "Class weighted methods (WMC) exceeded threshold" },
+            full_description: SarifMessage {
+                text: "Weighted Methods per Class (WMC) is the sum of the cyclomatic complexity of a class's methods. It catches classes whose methods are individually simple but together form a large amount of logic.",
+            },
+            default_configuration: SarifConfiguration { level: "warning" },
+            help_uri: "https://github.com/benvds/complexity-guard/blob/main/docs/class-metrics.md",
+            help: SarifMessage {
+                text: "Reduce WMC by extracting cohesive groups of methods into separate classes and simplifying the most complex methods.",
+            },
+        },
+        // RULE 14: Class field count
+        SarifRule {
+            id: "complexity-guard/class-field-count",
+            name: "ClassFieldCount",
+            short_description: SarifMessage { text: "Class field count exceeded threshold" },
+            full_description: SarifMessage {
+                text: "Counts the distinct instance fields of a class: declared fields, TypeScript parameter properties and fields assigned through `this`. A large amount of state makes a class harder to keep consistent.",
+            },
+            default_configuration: SarifConfiguration { level: "warning" },
+            help_uri: "https://github.com/benvds/complexity-guard/blob/main/docs/class-metrics.md",
+            help: SarifMessage {
+                text: "Group related fields into value objects, or move fields together with the methods that use them into a separate class.",
+            },
+        },
+        // RULE 15: Lack of cohesion
+        SarifRule {
+            id: "complexity-guard/class-lcom",
+            name: "LackOfCohesion",
+            short_description: SarifMessage { text: "Class lack of cohesion (LCOM) exceeded threshold" },
+            full_description: SarifMessage {
+                text: "LCOM4 counts the groups of methods that are connected by shared `this` fields or by calling each other, excluding the constructor. A value of 1 means the class is cohesive; higher values mean the class holds several unrelated parts.",
+            },
+            default_configuration: SarifConfiguration { level: "warning" },
+            help_uri: "https://github.com/benvds/complexity-guard/blob/main/docs/class-metrics.md",
+            help: SarifMessage {
+                text: "Each group of connected methods is a candidate for its own class. Split the class along those groups, or turn methods that use no fields into standalone functions.",
+            },
+        },
+        // RULE 16: Fan-in
+        SarifRule {
+            id: "complexity-guard/fan-in",
+            name: "FanIn",
+            short_description: SarifMessage { text: "Module fan-in exceeded threshold" },
+            full_description: SarifMessage {
+                text: "Counts the analyzed files that import this file (afferent coupling). A change to a file many others depend on can break all of them.",
+            },
+            default_configuration: SarifConfiguration { level: "warning" },
+            help_uri: "https://github.com/benvds/complexity-guard/blob/main/docs/cli-reference.md#module-graph",
+            help: SarifMessage {
+                text: "Keep widely imported files small and stable, or split them so each dependent imports only the part it uses.",
+            },
+        },
+        // RULE 17: Fan-out
+        SarifRule {
+            id: "complexity-guard/fan-out",
+            name: "FanOut",
+            short_description: SarifMessage { text: "Module fan-out exceeded threshold" },
+            full_description: SarifMessage {
+                text: "Counts the analyzed files this file imports (efferent coupling). A file that depends on many others changes whenever they do and is hard to test in isolation.",
+            },
+            default_configuration: SarifConfiguration { level: "warning" },
+            help_uri: "https://github.com/benvds/complexity-guard/blob/main/docs/cli-reference.md#module-graph",
+            help: SarifMessage {
+                text: "Split the file by responsibility, or depend on a smaller interface instead of many concrete modules.",
+            },
+        },
+        // RULE 18: Instability
+        SarifRule {
+            id: "complexity-guard/instability",
+            name: "Instability",
+            short_description: SarifMessage { text: "Module instability exceeded threshold" },
+            full_description: SarifMessage {
+                text: "Instability is fan-out / (fan-in + fan-out), from 0 for a file others only depend on to 1 for a file that only depends on others. Files that are imported but mostly depend on others pass their changes on to their dependents.",
+            },
+            default_configuration: SarifConfiguration { level: "warning" },
+            help_uri: "https://github.com/benvds/complexity-guard/blob/main/docs/cli-reference.md#module-graph",
+            help: SarifMessage {
+                text: "Move what dependents need into a file with fewer imports of its own, so the files others rely on change less often.",
+            },
+        },
+        // RULE 19: Import cycle
+        SarifRule {
+            id: "complexity-guard/import-cycle",
+            name: "ImportCycle",
+            short_description: SarifMessage { text: "Files import each other in a cycle" },
+            full_description: SarifMessage {
+                text: "A chain of static imports leads from a file back to itself. Whichever file of the cycle is loaded first runs before the files it imports have finished evaluating, so their exports can be undefined at that point.",
+            },
+            default_configuration: SarifConfiguration { level: "error" },
+            help_uri: "https://github.com/benvds/complexity-guard/blob/main/docs/cli-reference.md#import-cycles",
+            help: SarifMessage {
+                text: "Move what the files share into a module that imports neither, turn imports only needed for types into `import type`, or defer one import with a dynamic `import()`. Accepted cycles can be listed in `modules.allow_cycles`.",
+            },
+        },
     ]
 }
 
@@ -321,6 +500,14 @@ fn rule_id_to_index(rule_id: &str) -> usize {
         "complexity-guard/health-score" => RULE_HEALTH_SCORE,
         "complexity-guard/duplication" => RULE_DUPLICATION,
         "complexity-guard/skipped" => RULE_SKIPPED,
+        "complexity-guard/class-method-count" => RULE_CLASS_METHOD_COUNT,
+        "complexity-guard/class-wmc" => RULE_CLASS_WMC,
+        "complexity-guard/class-field-count" => RULE_CLASS_FIELD_COUNT,
+        "complexity-guard/class-lcom" => RULE_CLASS_LCOM,
+        "complexity-guard/fan-in" => RULE_FAN_IN,
+        "complexity-guard/fan-out" => RULE_FAN_OUT,
+        "complexity-guard/instability" => RULE_INSTABILITY,
+        "complexity-guard/import-cycle" => RULE_IMPORT_CYCLE,
         _ => 0,
     }
 }
@@ -340,6 +527,14 @@ fn rule_id_static(rule_id: &str) -> &'static str {
         "complexity-guard/health-score" => "complexity-guard/health-score",
         "complexity-guard/duplication" => "complexity-guard/duplication",
         "complexity-guard/skipped" => "complexity-guard/skipped",
+        "complexity-guard/class-method-count" => "complexity-guard/class-method-count",
+        "complexity-guard/class-wmc" => "complexity-guard/class-wmc",
+        "complexity-guard/class-field-count" => "complexity-guard/class-field-count",
+        "complexity-guard/class-lcom" => "complexity-guard/class-lcom",
+        "complexity-guard/fan-in" => "complexity-guard/fan-in",
+        "complexity-guard/fan-out" => "complexity-guard/fan-out",
+        "complexity-guard/instability" => "complexity-guard/instability",
+        "complexity-guard/import-cycle" => "complexity-guard/import-cycle",
         _ => "complexity-guard/cyclomatic",
     }
 }
@@ -352,24 +547,62 @@ fn severity_to_level(severity: &Severity) -> &'static str {
     }
 }
 
+/// The unit a result belongs to, followed by the functions nested inside it.
+fn function_logical_locations(
+    func: &FunctionAnalysisResult,
+    kind: &'static str,
+) -> Vec<SarifLogicalLocation> {
+    let unit = SarifLogicalLocation {
+        name: func.name.clone(),
+        fully_qualified_name: func.qualified_name.clone(),
+        kind,
+    };
+    let nested = func.nested.iter().map(|nested| SarifLogicalLocation {
+        name: nested.name.clone(),
+        fully_qualified_name: nested.qualified_name.clone(),
+        kind: "function",
+    });
+    std::iter::once(unit).chain(nested).collect()
+}
+
 /// Render SARIF 2.1.0 output from analysis results.
 ///
-/// Produces a valid SARIF log with all 12 rule definitions and results for
-/// every threshold violation detected across all analyzed files.
-/// Skipped files and functions are reported as "note" level results.
+/// Produces a valid SARIF log with all 20 rule definitions and results for
+/// every function, class and module coupling threshold violation and import
+/// cycle detected across all analyzed files.
+/// Skipped files and functions are reported as "note" level results; files that
+/// failed to read or parse and recovered syntax errors are reported as tool
+/// execution notifications, and a failed file marks the invocation as not
+/// successful.
 pub fn render_sarif(
     files: &[FileAnalysisResult],
     duplication: Option<&DuplicationResult>,
     config: &ResolvedConfig,
     skipped: &[SkippedItem],
 ) -> anyhow::Result<String> {
+    let log = build_sarif(files, duplication, config, skipped);
+    Ok(serde_json::to_string_pretty(&log)?)
+}
+
+/// Builds the SARIF log that `render_sarif` serializes.
+pub fn build_sarif(
+    files: &[FileAnalysisResult],
+    duplication: Option<&DuplicationResult>,
+    config: &ResolvedConfig,
+    skipped: &[SkippedItem],
+) -> SarifLog<'static> {
     let rules = build_rules();
     let mut sarif_results: Vec<SarifResult> = Vec::new();
 
-    // Build results from function threshold violations
+    // Build results from function and top-level module threshold violations
     for file in files {
         let uri = file.path.to_string_lossy().to_string();
-        for func in &file.functions {
+        let units = file
+            .functions
+            .iter()
+            .map(|func| (func, "function"))
+            .chain(file.module.iter().map(|module| (module, "module")));
+        for (func, kind) in units {
             let violations = function_violations(func, config);
             for violation in violations {
                 let rule_id_str = violation.rule_id.as_str();
@@ -378,7 +611,11 @@ pub fn render_sarif(
                     rule_index: rule_id_to_index(rule_id_str),
                     level: severity_to_level(&violation.severity),
                     message: SarifOwnedMessage {
-                        text: violation.message,
+                        text: if func.unreliable {
+                            format!("{} (unreliable: syntax error)", violation.message)
+                        } else {
+                            violation.message
+                        },
                     },
                     locations: vec![SarifLocation {
                         physical_location: SarifPhysicalLocation {
@@ -390,6 +627,7 @@ pub fn render_sarif(
                                 end_line: func.end_line,
                             },
                         },
+                        logical_locations: function_logical_locations(func, kind),
                     }],
                     related_locations: None,
                 });
@@ -397,6 +635,118 @@ pub fn render_sarif(
         }
     }
 
+    // Build results from class threshold violations
+    for file in files {
+        let uri = file.path.to_string_lossy().to_string();
+        for class in &file.classes {
+            for violation in class_violations(class, config) {
+                let rule_id_str = violation.rule_id.as_str();
+                sarif_results.push(SarifResult {
+                    rule_id: rule_id_static(rule_id_str),
+                    rule_index: rule_id_to_index(rule_id_str),
+                    level: severity_to_level(&violation.severity),
+                    message: SarifOwnedMessage {
+                        text: violation.message,
+                    },
+                    locations: vec![SarifLocation {
+                        physical_location: SarifPhysicalLocation {
+                            artifact_location: SarifArtifactLocation { uri: uri.clone() },
+                            region: SarifRegion {
+                                start_line: class.start_line,
+                                start_column: class.start_col + 1,
+                                end_line: class.end_line,
+                            },
+                        },
+                        logical_locations: vec![SarifLogicalLocation {
+                            name: class.name.clone(),
+                            fully_qualified_name: class.name.clone(),
+                            kind: "type",
+                        }],
+                    }],
+                    related_locations: None,
+                });
+            }
+        }
+    }
+
+    // Build results from module coupling violations, on each file's first line
+    for file in files {
+        let uri = file.path.to_string_lossy().to_string();
+        for violation in coupling_violations(file, config) {
+            let rule_id_str = violation.rule_id.as_str();
+            sarif_results.push(SarifResult {
+                rule_id: rule_id_static(rule_id_str),
+                rule_index: rule_id_to_index(rule_id_str),
+                level: severity_to_level(&violation.severity),
+                message: SarifOwnedMessage {
+                    text: violation.message,
+                },
+                locations: vec![SarifLocation {
+                    physical_location: SarifPhysicalLocation {
+                        artifact_location: SarifArtifactLocation { uri: uri.clone() },
+                        region: SarifRegion {
+                            start_line: violation.line,
+                            start_column: violation.col + 1,
+                            end_line: violation.line,
+                        },
+                    },
+                    logical_locations: Vec::new(),
+                }],
+                related_locations: None,
+            });
+        }
+    }
+
+    // Build results from import cycles, with each import of the chain as a related location
+    for file in files {
+        let uri = file.path.to_string_lossy().to_string();
+        for cycle in reported_cycles(file, config) {
+            let violation = cycle_violation(cycle);
+            let related = cycle
+                .chain
+                .iter()
+                .zip(cycle.chain.iter().cycle().skip(1))
+                .enumerate()
+                .map(|(i, (step, next))| SarifRelatedLocation {
+                    id: i,
+                    message: SarifOwnedMessage {
+                        text: format!("Imports {}", next.path),
+                    },
+                    physical_location: SarifPhysicalLocation {
+                        artifact_location: SarifArtifactLocation {
+                            uri: step.path.clone(),
+                        },
+                        region: SarifRegion {
+                            start_line: step.line,
+                            start_column: 1,
+                            end_line: step.line,
+                        },
+                    },
+                })
+                .collect();
+            sarif_results.push(SarifResult {
+                rule_id: "complexity-guard/import-cycle",
+                rule_index: RULE_IMPORT_CYCLE,
+                level: severity_to_level(&violation.severity),
+                message: SarifOwnedMessage {
+                    text: violation.message,
+                },
+                locations: vec![SarifLocation {
+                    physical_location: SarifPhysicalLocation {
+                        artifact_location: SarifArtifactLocation { uri: uri.clone() },
+                        region: SarifRegion {
+                            start_line: violation.line,
+                            start_column: violation.col + 1,
+                            end_line: violation.line,
+                        },
+                    },
+                    logical_locations: Vec::new(),
+                }],
+                related_locations: Some(related),
+            });
+        }
+    }
+
     // Build duplication results if present
     if let Some(dup) = duplication {
         for (group_idx, group) in dup.clone_groups.iter().enumerate() {
@@ -460,6 +810,7 @@ pub fn render_sarif(
                             end_line: primary.end_line,
                         },
                     },
+                    logical_locations: Vec::new(),
                 }],
                 related_locations: if related.is_empty() {
                     None
@@ -470,6 +821,36 @@ pub fn render_sarif(
         }
     }
 
+    // Files that failed to read or parse are tool execution problems, not results
+    let mut notifications: Vec<SarifNotification> = Vec::new();
+
+    // Syntax errors are reported as warnings on the tool run, pinned to their location
+    for file in files {
+        let uri = file.path.to_string_lossy().to_string();
+        for error in &file.syntax_errors {
+            notifications.push(SarifNotification {
+                level: "warning",
+                message: SarifOwnedMessage {
+                    text: format!("Syntax error: {}", syntax_error_message(error)),
+                },
+                locations: vec![SarifNotificationLocation {
+                    physical_location: SarifNotificationPhysicalLocation {
+                        artifact_location: SarifArtifactLocation { uri: uri.clone() },
+                        region: Some(SarifRegion {
+                            start_line: error.line,
+                            start_column: error.column + 1,
+                            end_line: error.line,
+                        }),
+                    },
+                }],
+            });
+        }
+    }
+
+    let execution_successful = !skipped
+        .iter()
+        .any(|item| matches!(item.reason, crate::types::SkipReason::Failed { .. }));
+
     // Build skipped item results as "note" level results
     for item in skipped {
         let uri = item.path.to_string_lossy().to_string();
@@ -483,6 +864,39 @@ pub fn render_sarif(
                     "Function '{fn_name}' skipped: {lines} lines exceeds maximum of {max_lines}"
                 )
             }
+            crate::types::SkipReason::GeneratedFile { marker } => {
+                format!("File skipped: looks generated ({marker})")
+            }
+            crate::types::SkipReason::MinifiedFile {
+                average_line_length,
+                max_average_line_length,
+            } => {
+                format!(
+                    "File skipped: looks minified (average line length {average_line_length} exceeds maximum of {max_average_line_length})"
+                )
+            }
+            crate::types::SkipReason::Failed { error } => {
+                notifications.push(SarifNotification {
+                    level: "error",
+                    message: SarifOwnedMessage {
+                        text: format!("File could not be analyzed: {error}"),
+                    },
+                    locations: vec![SarifNotificationLocation {
+                        physical_location: SarifNotificationPhysicalLocation {
+                            artifact_location: SarifArtifactLocation { uri },
+                            region: None,
+                        },
+                    }],
+                });
+                continue;
+            }
+            crate::types::SkipReason::SyntaxErrors { errors } => {
+                format!(
+                    "File skipped: {} syntax error{}",
+                    errors.len(),
+                    if errors.len() == 1 { "" } else { "s" }
+                )
+            }
         };
         let start_line = if item.start_line == 0 {
             1
@@ -503,12 +917,13 @@ pub fn render_sarif(
                         end_line: start_line,
                     },
                 },
+                logical_locations: Vec::new(),
             }],
             related_locations: None,
         });
     }
 
-    let log = SarifLog {
+    SarifLog {
         schema: SARIF_SCHEMA,
         version: SARIF_VERSION,
         runs: vec![SarifRun {
@@ -520,11 +935,14 @@ pub fn render_sarif(
                     rules,
                 },
             },
+            invocations: vec![SarifInvocation {
+                execution_successful,
+                tool_execution_notifications: notifications,
+            }],
             results: sarif_results,
+            properties: None,
         }],
-    };
-
-    Ok(serde_json::to_string_pretty(&log)?)
+    }
 }
 
 // TESTS
@@ -540,17 +958,23 @@ mod tests {
         FileAnalysisResult {
             path: PathBuf::from(path),
             functions,
+            classes: vec![],
+            module: None,
             tokens: vec![],
             file_score: 100.0,
             file_length: 50,
             export_count: 1,
-            error: false,
+            syntax_errors: vec![],
+            imports: Vec::new(),
+            coupling: None,
+            cycles: Vec::new(),
         }
     }
 
     fn make_func_ok() -> FunctionAnalysisResult {
         FunctionAnalysisResult {
             name: "okFunc".to_string(),
+            qualified_name: "okFunc".to_string(),
             start_line: 1,
             end_line: 10,
             start_col: 0,
@@ -565,12 +989,16 @@ mod tests {
             params_count: 2,
             nesting_depth: 1,
             health_score: 95.0,
+            unreliable: false,
+            fingerprint: 0,
+            nested: Vec::new(),
         }
     }
 
     fn make_func_with_violation() -> FunctionAnalysisResult {
         FunctionAnalysisResult {
             name: "complexFunc".to_string(),
+            qualified_name: "complexFunc".to_string(),
             start_line: 5,
             end_line: 50,
             start_col: 0,
@@ -585,6 +1013,9 @@ mod tests {
             params_count: 2,
             nesting_depth: 1,
             health_score: 40.0,
+            unreliable: false,
+            fingerprint: 0,
+            nested: Vec::new(),
         }
     }
 
@@ -620,13 +1051,13 @@ mod tests {
     }
 
     #[test]
-    fn sarif_output_has_12_rules() {
+    fn sarif_output_has_20_rules() {
         let files: Vec<FileAnalysisResult> = vec![];
         let config = ResolvedConfig::default();
         let output = render_sarif(&files, None, &config, &[]).unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
         let rules = &parsed["runs"][0]["tool"]["driver"]["rules"];
-        assert_eq!(rules.as_array().unwrap().len(), 12);
+        assert_eq!(rules.as_array().unwrap().len(), 20);
     }
 
     #[test]
@@ -651,6 +1082,130 @@ mod tests {
         assert_eq!(rule_ids[9], "complexity-guard/health-score");
         assert_eq!(rule_ids[10], "complexity-guard/duplication");
         assert_eq!(rule_ids[11], "complexity-guard/skipped");
+        assert_eq!(rule_ids[12], "complexity-guard/class-method-count");
+        assert_eq!(rule_ids[13], "complexity-guard/class-wmc");
+        assert_eq!(rule_ids[14], "complexity-guard/class-field-count");
+        assert_eq!(rule_ids[15], "complexity-guard/class-lcom");
+        assert_eq!(rule_ids[16], "complexity-guard/fan-in");
+        assert_eq!(rule_ids[17], "complexity-guard/fan-out");
+        assert_eq!(rule_ids[18], "complexity-guard/instability");
+        assert_eq!(rule_ids[19], "complexity-guard/import-cycle");
+    }
+
+    #[test]
+    fn sarif_coupling_violations_are_on_the_first_line() {
+        let mut file = make_file("src/hub.ts", vec![]);
+        file.coupling = Some(crate::types::FileCoupling {
+            fan_in: 25,
+            fan_out: 2,
+            instability: 0.07,
+        });
+        let config = ResolvedConfig::default();
+        let output = render_sarif(&[file], None, &config, &[]).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let results = parsed["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["ruleId"], "complexity-guard/fan-in");
+        assert_eq!(results[0]["ruleIndex"], 16);
+        assert_eq!(results[0]["level"], "warning");
+        assert_eq!(
+            results[0]["message"]["text"],
+            "Fan-in 25 exceeds warning threshold 20"
+        );
+        let region = &results[0]["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(
+            (region["startLine"].as_u64(), region["startColumn"].as_u64()),
+            (Some(1), Some(1))
+        );
+        assert!(results[0]["locations"][0].get("logicalLocations").is_none());
+    }
+
+    #[test]
+    fn sarif_import_cycles_have_related_locations() {
+        use crate::types::{ImportCycle, ImportStep};
+        let mut file = make_file("src/a.ts", vec![]);
+        let step = |path: &str, line| ImportStep {
+            path: path.to_string(),
+            line,
+        };
+        file.cycles = vec![ImportCycle {
+            files: vec!["src/a.ts".to_string(), "src/b.ts".to_string()],
+            chain: vec![step("src/a.ts", 3), step("src/b.ts", 7)],
+        }];
+        let mut config = ResolvedConfig::default();
+        let output = render_sarif(std::slice::from_ref(&file), None, &config, &[]).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let results = parsed["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["ruleId"], "complexity-guard/import-cycle");
+        assert_eq!(results[0]["ruleIndex"], 19);
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(
+            results[0]["message"]["text"],
+            "Import cycle src/a.ts:3 -> src/b.ts:7 -> src/a.ts"
+        );
+        let region = &results[0]["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(region["startLine"], 3);
+        let related = results[0]["relatedLocations"].as_array().unwrap();
+        assert_eq!(related.len(), 2);
+        assert_eq!(related[1]["id"], 1);
+        assert_eq!(related[1]["message"]["text"], "Imports src/a.ts");
+        let location = &related[1]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/b.ts");
+        assert_eq!(location["region"]["startLine"], 7);
+
+        config.modules_allow_cycles = vec![vec!["src/a.ts".to_string(), "src/b.ts".to_string()]];
+        let output = render_sarif(&[file], None, &config, &[]).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert!(parsed["runs"][0]["results"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn sarif_class_violations_have_type_logical_location() {
+        let mut file = make_file("src/god.ts", vec![]);
+        file.classes = vec![crate::types::ClassAnalysisResult {
+            name: "God".to_string(),
+            start_line: 2,
+            end_line: 300,
+            start_col: 0,
+            method_count: 3,
+            wmc: 120,
+            field_count: 1,
+            lcom: 1,
+        }];
+        let config = ResolvedConfig::default();
+        let output = render_sarif(&[file], None, &config, &[]).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let results = parsed["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["ruleId"], "complexity-guard/class-wmc");
+        assert_eq!(results[0]["ruleIndex"], 13);
+        assert_eq!(results[0]["level"], "error");
+        let region = &results[0]["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(region["startLine"], 2);
+        assert_eq!(region["endLine"], 300);
+        let logical = &results[0]["locations"][0]["logicalLocations"][0];
+        assert_eq!(logical["name"], "God");
+        assert_eq!(logical["kind"], "type");
+    }
+
+    #[test]
+    fn sarif_module_violations_have_module_logical_location() {
+        let mut file = make_file("src/setup.ts", vec![]);
+        let mut module = make_func_ok();
+        module.name = "<module>".to_string();
+        module.qualified_name = "<module>".to_string();
+        module.cyclomatic = 25;
+        file.module = Some(module);
+        let config = ResolvedConfig::default();
+        let output = render_sarif(&[file], None, &config, &[]).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let results = parsed["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["ruleId"], "complexity-guard/cyclomatic");
+        let logical = &results[0]["locations"][0]["logicalLocations"][0];
+        assert_eq!(logical["name"], "<module>");
+        assert_eq!(logical["kind"], "module");
     }
 
     #[test]
@@ -735,6 +1290,25 @@ mod tests {
         assert!(output.contains("\"ruleIndex\""), "expected ruleIndex");
     }
 
+    #[test]
+    fn sarif_results_have_function_logical_location() {
+        let mut func = make_func_with_violation();
+        func.name = "get callback".to_string();
+        func.qualified_name = "routes.get(\"/users\") callback".to_string();
+        let files = vec![make_file("src/routes.ts", vec![func])];
+        let config = ResolvedConfig::default();
+        let output = render_sarif(&files, None, &config, &[]).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        let logical = &parsed["runs"][0]["results"][0]["locations"][0]["logicalLocations"][0];
+        assert_eq!(logical["name"], "get callback");
+        assert_eq!(
+            logical["fullyQualifiedName"],
+            "routes.get(\"/users\") callback"
+        );
+        assert_eq!(logical["kind"], "function");
+    }
+
     #[test]
     fn sarif_no_results_for_ok_functions() {
         let files = vec![make_file("src/ok.ts", vec![make_func_ok()])];
@@ -761,5 +1335,42 @@ mod tests {
             .find(|r| r["ruleId"].as_str().unwrap() == "complexity-guard/cyclomatic")
             .expect("cyclomatic result not found");
         assert_eq!(cyc_result["level"].as_str().unwrap(), "error");
+        // Violations do not make the run itself unsuccessful
+        assert_eq!(
+            parsed["runs"][0]["invocations"][0]["executionSuccessful"],
+            true
+        );
+    }
+
+    #[test]
+    fn sarif_failed_files_are_tool_execution_notifications() {
+        let skipped = vec![SkippedItem {
+            path: std::path::PathBuf::from("src/broken.ts"),
+            function_name: None,
+            start_line: 0,
+            reason: crate::types::SkipReason::Failed {
+                error: "tree-sitter parse returned None".to_string(),
+            },
+        }];
+        let config = ResolvedConfig::default();
+        let output = render_sarif(&[], None, &config, &skipped).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let run = &parsed["runs"][0];
+
+        assert!(run["results"].as_array().unwrap().is_empty());
+        assert_eq!(run["invocations"][0]["executionSuccessful"], false);
+        let notifications = run["invocations"][0]["toolExecutionNotifications"]
+            .as_array()
+            .unwrap();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0]["level"], "error");
+        assert_eq!(
+            notifications[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "src/broken.ts"
+        );
+        assert!(notifications[0]["message"]["text"]
+            .as_str()
+            .unwrap()
+            .contains("tree-sitter parse returned None"));
     }
 }
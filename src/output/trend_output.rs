@@ -0,0 +1,239 @@
+#[cfg(feature = "console")]
+use std::io::Write;
+
+#[cfg(feature = "console")]
+use owo_colors::OwoColorize;
+
+#[cfg(feature = "console")]
+use crate::cli::ResolvedConfig;
+#[cfg(feature = "console")]
+use crate::history::format_date;
+use crate::history::HistoryRecord;
+#[cfg(feature = "console")]
+use crate::output::console::should_use_color;
+
+/// Maximum sparkline length; longer histories are averaged into this many buckets.
+pub const SPARKLINE_WIDTH: usize = 40;
+
+/// Directories shown by default, those whose score changed the most.
+#[cfg(feature = "console")]
+const TOP_DIRECTORIES: usize = 10;
+
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Renders `values` as a sparkline of at most `width` characters, scaled
+/// between their minimum and maximum.
+pub fn sparkline(values: &[f64], width: usize) -> String {
+    let buckets: Vec<f64> = if values.len() <= width {
+        values.to_vec()
+    } else {
+        (0..width)
+            .map(|b| {
+                let bucket = &values[b * values.len() / width..(b + 1) * values.len() / width];
+                bucket.iter().sum::<f64>() / bucket.len() as f64
+            })
+            .collect()
+    };
+    let min = buckets.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = buckets.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    buckets
+        .iter()
+        .map(|v| {
+            if max <= min {
+                BARS[3]
+            } else {
+                let level = ((v - min) / (max - min) * (BARS.len() - 1) as f64).round();
+                BARS[level as usize]
+            }
+        })
+        .collect()
+}
+
+/// Renders recorded runs as sparklines of health, violations, function count
+/// and per-directory health.
+///
+/// Directories are those of the latest run, ordered by how much their score
+/// changed; verbose mode lists all of them instead of the top ten.
+#[cfg(feature = "console")]
+pub fn render_trend_console(
+    history: &[HistoryRecord],
+    config: &ResolvedConfig,
+    writer: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let use_color = should_use_color(config.color);
+    let (first, last) = match history {
+        [] => {
+            writeln!(
+                writer,
+                "No runs recorded in {}. Run an analysis with --history to start recording.",
+                config.history_file
+            )?;
+            return Ok(());
+        }
+        [first, ..] => (first, &history[history.len() - 1]),
+    };
+
+    writeln!(
+        writer,
+        "{} runs from {} to {} ({})",
+        history.len(),
+        format_date(first.timestamp),
+        format_date(last.timestamp),
+        config.history_file
+    )?;
+    writeln!(writer)?;
+
+    // Health rises when things improve; the counts fall
+    let series: [(&str, Vec<f64>, usize, bool); 4] = [
+        (
+            "Health",
+            history.iter().map(|r| r.health_score).collect(),
+            1,
+            true,
+        ),
+        (
+            "Errors",
+            history.iter().map(|r| r.errors as f64).collect(),
+            0,
+            false,
+        ),
+        (
+            "Warnings",
+            history.iter().map(|r| r.warnings as f64).collect(),
+            0,
+            false,
+        ),
+        (
+            "Functions",
+            history.iter().map(|r| r.functions as f64).collect(),
+            0,
+            true,
+        ),
+    ];
+    for (label, values, precision, higher_is_better) in &series {
+        let line = trend_line(label, values, *precision, 10);
+        let delta = values[values.len() - 1] - values[0];
+        let improved = (delta > 0.0) == *higher_is_better;
+        if use_color && delta != 0.0 && *label != "Functions" {
+            if improved {
+                writeln!(writer, "{}", line.green())?;
+            } else {
+                writeln!(writer, "{}", line.red())?;
+            }
+        } else {
+            writeln!(writer, "{line}")?;
+        }
+    }
+
+    let mut directories: Vec<(&str, Vec<f64>)> = last
+        .directories
+        .keys()
+        .map(|dir| {
+            let values = history
+                .iter()
+                .filter_map(|r| r.directories.get(dir).copied())
+                .collect();
+            (dir.as_str(), values)
+        })
+        .collect();
+    if !directories.is_empty() {
+        let change = |values: &[f64]| (values[values.len() - 1] - values[0]).abs();
+        directories.sort_by(|a, b| change(&b.1).total_cmp(&change(&a.1)).then(a.0.cmp(b.0)));
+        if !config.verbose {
+            directories.truncate(TOP_DIRECTORIES);
+        }
+        let label_width = directories.iter().map(|(d, _)| d.len()).max().unwrap_or(0);
+        writeln!(writer)?;
+        writeln!(writer, "Directories:")?;
+        for (dir, values) in &directories {
+            writeln!(writer, "  {}", trend_line(dir, values, 1, label_width))?;
+        }
+    }
+    Ok(())
+}
+
+/// `label  sparkline  first → last (delta)`, with the label padded to `label_width`.
+#[cfg(feature = "console")]
+fn trend_line(label: &str, values: &[f64], precision: usize, label_width: usize) -> String {
+    let first = values[0];
+    let last = values[values.len() - 1];
+    format!(
+        "{label:<label_width$}  {}  {first:.p$} → {last:.p$} ({:+.p$})",
+        sparkline(values, SPARKLINE_WIDTH),
+        last - first,
+        p = precision
+    )
+}
+
+/// Renders recorded runs as a pretty-printed JSON array, oldest first.
+pub fn render_trend_json(history: &[HistoryRecord]) -> anyhow::Result<String> {
+    Ok(serde_json::to_string_pretty(history)?)
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sparkline_scales_between_min_and_max() {
+        assert_eq!(sparkline(&[0.0, 50.0, 100.0], 40), "▁▅█");
+        assert_eq!(sparkline(&[7.0, 7.0], 40), "▄▄");
+        assert_eq!(sparkline(&[], 40), "");
+    }
+
+    #[test]
+    fn sparkline_averages_long_histories() {
+        let values: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        let line = sparkline(&values, 10);
+        assert_eq!(line.chars().count(), 10);
+        assert!(line.starts_with('▁'));
+        assert!(line.ends_with('█'));
+    }
+
+    #[cfg(feature = "console")]
+    #[test]
+    fn console_lists_series_and_directories() {
+        let record = |timestamp: u64, health_score: f64, errors: u32, api: f64| HistoryRecord {
+            timestamp,
+            commit: None,
+            branch: None,
+            version: "0.0.0".to_string(),
+            health_score,
+            files: 2,
+            functions: 10,
+            errors,
+            warnings: 3,
+            directories: [("src/api".to_string(), api), ("src/ui".to_string(), 90.0)]
+                .into_iter()
+                .collect(),
+        };
+        let history = [
+            record(1_790_000_000, 70.0, 5, 60.0),
+            record(1_791_000_000, 75.0, 3, 70.0),
+            record(1_792_281_600, 80.5, 1, 75.0),
+        ];
+        let config = ResolvedConfig {
+            color: Some(false),
+            ..ResolvedConfig::default()
+        };
+        let mut out = Vec::new();
+        render_trend_console(&history, &config, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+
+        assert!(text.starts_with("3 runs from 2026-09-21 to 2026-10-18"));
+        assert!(text.contains("Health      ▁▄█  70.0 → 80.5 (+10.5)"));
+        assert!(text.contains("Errors      █▅▁  5 → 1 (-4)"));
+        // The directory that changed most comes first
+        let api = text.find("src/api").unwrap();
+        let ui = text.find("src/ui").unwrap();
+        assert!(api < ui);
+
+        let mut out = Vec::new();
+        render_trend_console(&[], &config, &mut out).unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .starts_with("No runs recorded"));
+    }
+}
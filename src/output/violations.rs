@@ -0,0 +1,722 @@
+use std::path::Path;
+
+use crate::cli::ResolvedConfig;
+use crate::tsconfig::normalize;
+use crate::types::{
+    ClassAnalysisResult, FileAnalysisResult, FunctionAnalysisResult, ImportCycle, SyntaxError,
+    SyntaxErrorKind,
+};
+
+/// Severity level for a single threshold violation.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A single threshold violation for a function metric.
+#[derive(Debug, Clone)]
+pub struct Violation {
+    pub line: usize,
+    pub col: usize,
+    pub severity: Severity,
+    pub message: String,
+    pub rule_id: String,
+}
+
+/// Computes all violations for a function against the resolved thresholds.
+pub fn function_violations(
+    func: &FunctionAnalysisResult,
+    config: &ResolvedConfig,
+) -> Vec<Violation> {
+    let mut violations: Vec<Violation> = Vec::new();
+
+    // Cyclomatic complexity
+    let cyc = func.cyclomatic as f64;
+    if cyc >= config.cyclomatic_error as f64 {
+        violations.push(Violation {
+            line: func.start_line,
+            col: func.start_col,
+            severity: Severity::Error,
+            message: format!(
+                "Cyclomatic complexity {} exceeds error threshold {}",
+                func.cyclomatic, config.cyclomatic_error
+            ),
+            rule_id: "complexity-guard/cyclomatic".to_string(),
+        });
+    } else if cyc >= config.cyclomatic_warning as f64 {
+        violations.push(Violation {
+            line: func.start_line,
+            col: func.start_col,
+            severity: Severity::Warning,
+            message: format!(
+                "Cyclomatic complexity {} exceeds warning threshold {}",
+                func.cyclomatic, config.cyclomatic_warning
+            ),
+            rule_id: "complexity-guard/cyclomatic".to_string(),
+        });
+    }
+
+    // Cognitive complexity
+    let cog = func.cognitive as f64;
+    if cog >= config.cognitive_error as f64 {
+        violations.push(Violation {
+            line: func.start_line,
+            col: func.start_col,
+            severity: Severity::Error,
+            message: format!(
+                "Cognitive complexity {} exceeds error threshold {}",
+                func.cognitive, config.cognitive_error
+            ),
+            rule_id: "complexity-guard/cognitive".to_string(),
+        });
+    } else if cog >= config.cognitive_warning as f64 {
+        violations.push(Violation {
+            line: func.start_line,
+            col: func.start_col,
+            severity: Severity::Warning,
+            message: format!(
+                "Cognitive complexity {} exceeds warning threshold {}",
+                func.cognitive, config.cognitive_warning
+            ),
+            rule_id: "complexity-guard/cognitive".to_string(),
+        });
+    }
+
+    // Halstead volume
+    if func.halstead_volume >= config.halstead_volume_error {
+        violations.push(Violation {
+            line: func.start_line,
+            col: func.start_col,
+            severity: Severity::Error,
+            message: format!(
+                "Halstead volume {:.1} exceeds error threshold {:.1}",
+                func.halstead_volume, config.halstead_volume_error
+            ),
+            rule_id: "complexity-guard/halstead-volume".to_string(),
+        });
+    } else if func.halstead_volume >= config.halstead_volume_warning {
+        violations.push(Violation {
+            line: func.start_line,
+            col: func.start_col,
+            severity: Severity::Warning,
+            message: format!(
+                "Halstead volume {:.1} exceeds warning threshold {:.1}",
+                func.halstead_volume, config.halstead_volume_warning
+            ),
+            rule_id: "complexity-guard/halstead-volume".to_string(),
+        });
+    }
+
+    // Halstead difficulty
+    if func.halstead_difficulty >= config.halstead_difficulty_error {
+        violations.push(Violation {
+            line: func.start_line,
+            col: func.start_col,
+            severity: Severity::Error,
+            message: format!(
+                "Halstead difficulty {:.1} exceeds error threshold {:.1}",
+                func.halstead_difficulty, config.halstead_difficulty_error
+            ),
+            rule_id: "complexity-guard/halstead-difficulty".to_string(),
+        });
+    } else if func.halstead_difficulty >= config.halstead_difficulty_warning {
+        violations.push(Violation {
+            line: func.start_line,
+            col: func.start_col,
+            severity: Severity::Warning,
+            message: format!(
+                "Halstead difficulty {:.1} exceeds warning threshold {:.1}",
+                func.halstead_difficulty, config.halstead_difficulty_warning
+            ),
+            rule_id: "complexity-guard/halstead-difficulty".to_string(),
+        });
+    }
+
+    // Halstead effort
+    if func.halstead_effort >= config.halstead_effort_error {
+        violations.push(Violation {
+            line: func.start_line,
+            col: func.start_col,
+            severity: Severity::Error,
+            message: format!(
+                "Halstead effort {:.1} exceeds error threshold {:.1}",
+                func.halstead_effort, config.halstead_effort_error
+            ),
+            rule_id: "complexity-guard/halstead-effort".to_string(),
+        });
+    } else if func.halstead_effort >= config.halstead_effort_warning {
+        violations.push(Violation {
+            line: func.start_line,
+            col: func.start_col,
+            severity: Severity::Warning,
+            message: format!(
+                "Halstead effort {:.1} exceeds warning threshold {:.1}",
+                func.halstead_effort, config.halstead_effort_warning
+            ),
+            rule_id: "complexity-guard/halstead-effort".to_string(),
+        });
+    }
+
+    // Halstead bugs
+    if func.halstead_bugs >= config.halstead_bugs_error {
+        violations.push(Violation {
+            line: func.start_line,
+            col: func.start_col,
+            severity: Severity::Error,
+            message: format!(
+                "Halstead bugs {:.3} exceeds error threshold {:.3}",
+                func.halstead_bugs, config.halstead_bugs_error
+            ),
+            rule_id: "complexity-guard/halstead-bugs".to_string(),
+        });
+    } else if func.halstead_bugs >= config.halstead_bugs_warning {
+        violations.push(Violation {
+            line: func.start_line,
+            col: func.start_col,
+            severity: Severity::Warning,
+            message: format!(
+                "Halstead bugs {:.3} exceeds warning threshold {:.3}",
+                func.halstead_bugs, config.halstead_bugs_warning
+            ),
+            rule_id: "complexity-guard/halstead-bugs".to_string(),
+        });
+    }
+
+    // Nesting depth
+    let nd = func.nesting_depth as f64;
+    if nd >= config.nesting_depth_error as f64 {
+        violations.push(Violation {
+            line: func.start_line,
+            col: func.start_col,
+            severity: Severity::Error,
+            message: format!(
+                "Nesting depth {} exceeds error threshold {}",
+                func.nesting_depth, config.nesting_depth_error
+            ),
+            rule_id: "complexity-guard/nesting-depth".to_string(),
+        });
+    } else if nd >= config.nesting_depth_warning as f64 {
+        violations.push(Violation {
+            line: func.start_line,
+            col: func.start_col,
+            severity: Severity::Warning,
+            message: format!(
+                "Nesting depth {} exceeds warning threshold {}",
+                func.nesting_depth, config.nesting_depth_warning
+            ),
+            rule_id: "complexity-guard/nesting-depth".to_string(),
+        });
+    }
+
+    // Line count (function_length in FunctionAnalysisResult)
+    let lc = func.function_length as f64;
+    if lc >= config.line_count_error as f64 {
+        violations.push(Violation {
+            line: func.start_line,
+            col: func.start_col,
+            severity: Severity::Error,
+            message: format!(
+                "Line count {} exceeds error threshold {}",
+                func.function_length, config.line_count_error
+            ),
+            rule_id: "complexity-guard/line-count".to_string(),
+        });
+    } else if lc >= config.line_count_warning as f64 {
+        violations.push(Violation {
+            line: func.start_line,
+            col: func.start_col,
+            severity: Severity::Warning,
+            message: format!(
+                "Line count {} exceeds warning threshold {}",
+                func.function_length, config.line_count_warning
+            ),
+            rule_id: "complexity-guard/line-count".to_string(),
+        });
+    }
+
+    // Params count
+    let pc = func.params_count as f64;
+    if pc >= config.params_count_error as f64 {
+        violations.push(Violation {
+            line: func.start_line,
+            col: func.start_col,
+            severity: Severity::Error,
+            message: format!(
+                "Params count {} exceeds error threshold {}",
+                func.params_count, config.params_count_error
+            ),
+            rule_id: "complexity-guard/param-count".to_string(),
+        });
+    } else if pc >= config.params_count_warning as f64 {
+        violations.push(Violation {
+            line: func.start_line,
+            col: func.start_col,
+            severity: Severity::Warning,
+            message: format!(
+                "Params count {} exceeds warning threshold {}",
+                func.params_count, config.params_count_warning
+            ),
+            rule_id: "complexity-guard/param-count".to_string(),
+        });
+    }
+
+    violations
+}
+
+/// Computes all violations for a class against the resolved class thresholds.
+pub fn class_violations(class: &ClassAnalysisResult, config: &ResolvedConfig) -> Vec<Violation> {
+    let checks = [
+        (
+            "Method count",
+            class.method_count,
+            config.method_count_warning,
+            config.method_count_error,
+            "complexity-guard/class-method-count",
+        ),
+        (
+            "Weighted methods per class",
+            class.wmc,
+            config.wmc_warning,
+            config.wmc_error,
+            "complexity-guard/class-wmc",
+        ),
+        (
+            "Field count",
+            class.field_count,
+            config.field_count_warning,
+            config.field_count_error,
+            "complexity-guard/class-field-count",
+        ),
+        (
+            "Lack of cohesion (LCOM)",
+            class.lcom,
+            config.lcom_warning,
+            config.lcom_error,
+            "complexity-guard/class-lcom",
+        ),
+    ];
+
+    let mut violations: Vec<Violation> = Vec::new();
+    for (label, value, warning, error, rule_id) in checks {
+        let (severity, level, threshold) = if value >= error {
+            (Severity::Error, "error", error)
+        } else if value >= warning {
+            (Severity::Warning, "warning", warning)
+        } else {
+            continue;
+        };
+        violations.push(Violation {
+            line: class.start_line,
+            col: class.start_col,
+            severity,
+            message: format!(
+                "{label} {value} of class '{}' exceeds {level} threshold {threshold}",
+                class.name
+            ),
+            rule_id: rule_id.to_string(),
+        });
+    }
+    violations
+}
+
+/// Computes the violations of a file's fan-in, fan-out and instability in the
+/// module graph, reported on its first line. None when the graph was not built.
+///
+/// Files nothing imports are entry points with an instability of 1 by
+/// definition, so only files with dependents are checked for instability.
+pub fn coupling_violations(file: &FileAnalysisResult, config: &ResolvedConfig) -> Vec<Violation> {
+    let Some(coupling) = file.coupling else {
+        return Vec::new();
+    };
+    let mut checks = vec![
+        (
+            "Fan-in",
+            coupling.fan_in as f64,
+            config.fan_in_warning as f64,
+            config.fan_in_error as f64,
+            "complexity-guard/fan-in",
+        ),
+        (
+            "Fan-out",
+            coupling.fan_out as f64,
+            config.fan_out_warning as f64,
+            config.fan_out_error as f64,
+            "complexity-guard/fan-out",
+        ),
+    ];
+    if coupling.fan_in > 0 {
+        checks.push((
+            "Instability",
+            coupling.instability,
+            config.instability_warning.unwrap_or(f64::INFINITY),
+            config.instability_error.unwrap_or(f64::INFINITY),
+            "complexity-guard/instability",
+        ));
+    }
+
+    let mut violations: Vec<Violation> = Vec::new();
+    for (label, value, warning, error, rule_id) in checks {
+        let (severity, level, threshold) = if value >= error {
+            (Severity::Error, "error", error)
+        } else if value >= warning {
+            (Severity::Warning, "warning", warning)
+        } else {
+            continue;
+        };
+        let message = if label == "Instability" {
+            format!("{label} {value:.2} exceeds {level} threshold {threshold:.2}")
+        } else {
+            format!("{label} {value} exceeds {level} threshold {threshold}")
+        };
+        violations.push(Violation {
+            line: 1,
+            col: 0,
+            severity,
+            message,
+            rule_id: rule_id.to_string(),
+        });
+    }
+    violations
+}
+
+/// The import cycles `file` starts that `modules.allow_cycles` does not accept.
+pub fn reported_cycles<'a>(
+    file: &'a FileAnalysisResult,
+    config: &'a ResolvedConfig,
+) -> impl Iterator<Item = &'a ImportCycle> {
+    file.cycles
+        .iter()
+        .filter(|cycle| !is_cycle_allowed(cycle, config))
+}
+
+/// A cycle is accepted when one allow-list entry names every file in it, so
+/// that a file joining an accepted cycle gets it reported again.
+pub fn is_cycle_allowed(cycle: &ImportCycle, config: &ResolvedConfig) -> bool {
+    config.modules_allow_cycles.iter().any(|allowed| {
+        let allowed: Vec<_> = allowed.iter().map(|p| normalize(Path::new(p))).collect();
+        cycle
+            .files
+            .iter()
+            .all(|file| allowed.contains(&normalize(Path::new(file))))
+    })
+}
+
+/// Computes an error for each import cycle `file` starts, at the import that
+/// starts the chain.
+pub fn cycle_violations(file: &FileAnalysisResult, config: &ResolvedConfig) -> Vec<Violation> {
+    reported_cycles(file, config).map(cycle_violation).collect()
+}
+
+/// The import chain of a cycle as "a.ts:1 -> b.ts:3 -> a.ts".
+pub fn cycle_chain(cycle: &ImportCycle) -> String {
+    let mut chain: Vec<String> = cycle
+        .chain
+        .iter()
+        .map(|step| format!("{}:{}", step.path, step.line))
+        .collect();
+    chain.push(cycle.chain[0].path.clone());
+    chain.join(" -> ")
+}
+
+/// The violation for one import cycle, with its chain.
+pub fn cycle_violation(cycle: &ImportCycle) -> Violation {
+    let start = &cycle.chain[0];
+    let mut message = format!("Import cycle {}", cycle_chain(cycle));
+    if cycle.files.len() > cycle.chain.len() {
+        message.push_str(&format!(
+            " (one of the cycles among {} files)",
+            cycle.files.len()
+        ));
+    }
+    Violation {
+        line: start.line,
+        col: 0,
+        severity: Severity::Error,
+        message,
+        rule_id: "complexity-guard/import-cycle".to_string(),
+    }
+}
+
+/// Describes a syntax error for display, e.g. "Unexpected `{`" or "Missing `;`".
+pub fn syntax_error_message(error: &SyntaxError) -> String {
+    match error.kind {
+        SyntaxErrorKind::Unexpected if error.snippet.is_empty() => "Unexpected input".to_string(),
+        SyntaxErrorKind::Unexpected => format!("Unexpected `{}`", error.snippet),
+        SyntaxErrorKind::Missing => format!("Missing `{}`", error.snippet),
+    }
+}
+
+/// Returns the worst severity across all violations for a function.
+pub fn function_status(violations: &[Violation]) -> &'static str {
+    let has_error = violations.iter().any(|v| v.severity == Severity::Error);
+    let has_warning = violations.iter().any(|v| v.severity == Severity::Warning);
+    if has_error {
+        "error"
+    } else if has_warning {
+        "warning"
+    } else {
+        "ok"
+    }
+}
+
+/// Returns the worst severity across all violations for a function as an enum.
+pub fn worst_severity(violations: &[Violation]) -> Option<Severity> {
+    if violations.iter().any(|v| v.severity == Severity::Error) {
+        Some(Severity::Error)
+    } else if violations.iter().any(|v| v.severity == Severity::Warning) {
+        Some(Severity::Warning)
+    } else {
+        None
+    }
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_func(
+        name: &str,
+        start_line: usize,
+        cyclomatic: u32,
+        cognitive: u32,
+        health_score: f64,
+    ) -> FunctionAnalysisResult {
+        FunctionAnalysisResult {
+            name: name.to_string(),
+            qualified_name: name.to_string(),
+            start_line,
+            end_line: start_line + 10,
+            start_col: 0,
+            cyclomatic,
+            cognitive,
+            halstead_volume: 0.0,
+            halstead_difficulty: 0.0,
+            halstead_effort: 0.0,
+            halstead_time: 0.0,
+            halstead_bugs: 0.0,
+            function_length: 10,
+            params_count: 1,
+            nesting_depth: 1,
+            health_score,
+            unreliable: false,
+            fingerprint: 0,
+            nested: Vec::new(),
+        }
+    }
+
+    fn make_class(name: &str, method_count: u32, wmc: u32, lcom: u32) -> ClassAnalysisResult {
+        ClassAnalysisResult {
+            name: name.to_string(),
+            start_line: 3,
+            end_line: 90,
+            start_col: 0,
+            method_count,
+            wmc,
+            field_count: 4,
+            lcom,
+        }
+    }
+
+    fn default_config() -> ResolvedConfig {
+        ResolvedConfig::default()
+    }
+
+    #[test]
+    fn test_coupling_violations_thresholds() {
+        let mut file = crate::Analyzer::new()
+            .source("hub.ts", "export const x = 1;")
+            .run()
+            .unwrap()
+            .files
+            .remove(0);
+        let mut config = default_config();
+        assert!(coupling_violations(&file, &config).is_empty());
+
+        let coupling = |fan_in, fan_out, instability| crate::types::FileCoupling {
+            fan_in,
+            fan_out,
+            instability,
+        };
+        file.coupling = Some(coupling(45, 16, 0.26));
+        let violations = coupling_violations(&file, &config);
+        let rules: Vec<_> = violations
+            .iter()
+            .map(|v| (v.rule_id.as_str(), &v.severity, v.line))
+            .collect();
+        assert_eq!(
+            rules,
+            vec![
+                ("complexity-guard/fan-in", &Severity::Error, 1),
+                ("complexity-guard/fan-out", &Severity::Warning, 1),
+            ]
+        );
+        assert_eq!(
+            violations[0].message,
+            "Fan-in 45 exceeds error threshold 40"
+        );
+
+        // Entry points are not checked for instability
+        config.instability_warning = Some(0.8);
+        file.coupling = Some(coupling(0, 4, 1.0));
+        assert!(coupling_violations(&file, &config).is_empty());
+        file.coupling = Some(coupling(1, 9, 0.9));
+        let violations = coupling_violations(&file, &config);
+        assert_eq!(
+            violations[0].message,
+            "Instability 0.90 exceeds warning threshold 0.80"
+        );
+    }
+
+    #[test]
+    fn test_cycle_violations_honour_the_allow_list() {
+        use crate::types::ImportStep;
+        let mut file = crate::Analyzer::new()
+            .source("src/a.ts", "export const x = 1;")
+            .run()
+            .unwrap()
+            .files
+            .remove(0);
+        let step = |path: &str, line| ImportStep {
+            path: path.to_string(),
+            line,
+        };
+        file.cycles = vec![
+            ImportCycle {
+                files: vec!["src/a.ts".to_string(), "src/b.ts".to_string()],
+                chain: vec![step("src/a.ts", 2), step("src/b.ts", 5)],
+            },
+            ImportCycle {
+                files: vec![
+                    "src/a.ts".to_string(),
+                    "src/c.ts".to_string(),
+                    "src/d.ts".to_string(),
+                ],
+                chain: vec![step("src/a.ts", 3), step("src/c.ts", 1)],
+            },
+        ];
+        let mut config = default_config();
+        let violations = cycle_violations(&file, &config);
+        let found: Vec<_> = violations
+            .iter()
+            .map(|v| (v.line, v.message.as_str(), &v.severity))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    2,
+                    "Import cycle src/a.ts:2 -> src/b.ts:5 -> src/a.ts",
+                    &Severity::Error
+                ),
+                (
+                    3,
+                    "Import cycle src/a.ts:3 -> src/c.ts:1 -> src/a.ts (one of the cycles among 3 files)",
+                    &Severity::Error
+                ),
+            ]
+        );
+        assert_eq!(violations[0].rule_id, "complexity-guard/import-cycle");
+
+        // An entry must name every file of the cycle
+        config.modules_allow_cycles = vec![
+            vec!["./src/b.ts".to_string(), "src/a.ts".to_string()],
+            vec!["src/a.ts".to_string(), "src/c.ts".to_string()],
+        ];
+        let lines: Vec<_> = cycle_violations(&file, &config)
+            .iter()
+            .map(|v| v.line)
+            .collect();
+        assert_eq!(lines, [3]);
+    }
+
+    #[test]
+    fn test_class_violations_thresholds() {
+        let config = default_config();
+        assert!(class_violations(&make_class("Small", 5, 8, 1), &config).is_empty());
+
+        let violations = class_violations(&make_class("God", 45, 60, 3), &config);
+        let rules: Vec<(&str, &Severity)> = violations
+            .iter()
+            .map(|v| (v.rule_id.as_str(), &v.severity))
+            .collect();
+        assert_eq!(
+            rules,
+            vec![
+                ("complexity-guard/class-method-count", &Severity::Error),
+                ("complexity-guard/class-wmc", &Severity::Warning),
+                ("complexity-guard/class-lcom", &Severity::Warning),
+            ]
+        );
+        assert_eq!(
+            violations[0].message,
+            "Method count 45 of class 'God' exceeds error threshold 40"
+        );
+    }
+
+    #[test]
+    fn test_function_violations_cyclomatic_warning() {
+        let func = make_func("f", 1, 12, 0, 80.0);
+        let config = default_config();
+        let violations = function_violations(&func, &config);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].severity, Severity::Warning);
+        assert!(violations[0].rule_id.contains("cyclomatic"));
+    }
+
+    #[test]
+    fn test_function_violations_cyclomatic_error() {
+        let func = make_func("f", 1, 25, 0, 40.0);
+        let config = default_config();
+        let violations = function_violations(&func, &config);
+        let cyc_violations: Vec<_> = violations
+            .iter()
+            .filter(|v| v.rule_id.contains("cyclomatic"))
+            .collect();
+        assert_eq!(cyc_violations.len(), 1);
+        assert_eq!(cyc_violations[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_function_violations_no_violations() {
+        let func = make_func("f", 1, 3, 3, 95.0);
+        let config = default_config();
+        let violations = function_violations(&func, &config);
+        assert!(
+            violations.is_empty(),
+            "Should have no violations for low complexity"
+        );
+    }
+
+    #[test]
+    fn test_function_status_ok() {
+        assert_eq!(function_status(&[]), "ok");
+    }
+
+    #[test]
+    fn test_function_status_warning() {
+        let v = Violation {
+            line: 1,
+            col: 0,
+            severity: Severity::Warning,
+            message: "test".to_string(),
+            rule_id: "test".to_string(),
+        };
+        assert_eq!(function_status(&[v]), "warning");
+    }
+
+    #[test]
+    fn test_function_status_error() {
+        let v = Violation {
+            line: 1,
+            col: 0,
+            severity: Severity::Error,
+            message: "test".to_string(),
+            rule_id: "test".to_string(),
+        };
+        assert_eq!(function_status(&[v]), "error");
+    }
+}
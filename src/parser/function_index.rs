@@ -0,0 +1,624 @@
+use std::ops::Range;
+
+use crate::metrics::classes::class_name;
+use crate::metrics::{extract_function_name, is_function_node};
+use crate::types::FunctionId;
+
+/// A function found by the index pass.
+///
+/// Holds the tree-sitter node so metrics can compute against it directly;
+/// it therefore borrows from the tree and must not outlive it.
+#[derive(Debug, Clone)]
+pub struct IndexedFunction<'tree> {
+    pub id: FunctionId,
+    /// Innermost enclosing function, if this function is nested in another
+    pub parent: Option<FunctionId>,
+    pub node: tree_sitter::Node<'tree>,
+    /// Reported name, e.g. "Foo.bar", "handler", "map callback"
+    pub name: String,
+    /// Name without the class prefix ("bar" for "Foo.bar")
+    pub short_name: String,
+    /// Name qualified by class, object, call-site and enclosing functions,
+    /// e.g. `routes.get("/users") callback` or `outerFn > innerHelper`
+    pub qualified_name: String,
+    /// Byte range of the function node in the source
+    pub byte_range: Range<usize>,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub start_col: usize,
+}
+
+/// Every function in a file, found by a single depth-first pass.
+///
+/// This is the one place that decides what counts as a function and what it
+/// is called. The parser and all metric analyzers read from it, so they always
+/// agree on the function set and its order. IDs are assigned in depth-first
+/// source order and are stable for a given file content.
+#[derive(Debug, Clone)]
+pub struct FunctionIndex<'tree> {
+    functions: Vec<IndexedFunction<'tree>>,
+}
+
+impl<'tree> FunctionIndex<'tree> {
+    /// Build the index for a parsed tree.
+    pub fn build(root: tree_sitter::Node<'tree>, source: &[u8]) -> Self {
+        let mut functions = Vec::new();
+        walk(root, source, None, None, &mut functions);
+        FunctionIndex { functions }
+    }
+
+    /// All functions, including nested ones, in ID order.
+    pub fn functions(&self) -> &[IndexedFunction<'tree>] {
+        &self.functions
+    }
+
+    pub fn get(&self, id: FunctionId) -> Option<&IndexedFunction<'tree>> {
+        self.functions.get(id.0 as usize)
+    }
+
+    /// Functions that are not nested inside another function.
+    ///
+    /// These are the units metrics are reported for: nested functions are
+    /// accounted for in the metrics of their enclosing function.
+    pub fn outermost(&self) -> impl Iterator<Item = &IndexedFunction<'tree>> {
+        self.functions.iter().filter(|f| f.parent.is_none())
+    }
+
+    /// Functions nested at any depth inside the function `id`, in ID order.
+    pub fn descendants(&self, id: FunctionId) -> impl Iterator<Item = &IndexedFunction<'tree>> {
+        let end = self.get(id).map_or(0, |f| f.byte_range.end);
+        // Depth-first IDs put every descendant right after its ancestor
+        self.functions
+            .iter()
+            .skip(id.0 as usize + 1)
+            .take_while(move |f| f.byte_range.start < end)
+    }
+
+    pub fn len(&self) -> usize {
+        self.functions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+}
+
+/// Name context passed from parent nodes to resolve anonymous function names.
+///
+/// Supports class, class field, object key, callback, and default export
+/// naming patterns.
+#[derive(Clone, Default)]
+struct NameContext {
+    /// Variable or class field name (for `const x = () => {}` patterns)
+    name: String,
+    /// Class name (for `class Foo { bar() {} }` → "Foo.bar")
+    class_name: Option<String>,
+    /// Whether `name` is a class field (for `class Foo { bar = () => {} }` → "Foo.bar")
+    is_class_field: bool,
+    /// Object literal key (for `{ handler: () => {} }` → "handler")
+    object_key: Option<String>,
+    /// Call expression method name (for `arr.map(() => {})` → "map callback")
+    call_name: Option<String>,
+    /// Whether inside a `export default function() {}` → "default export"
+    is_default_export: bool,
+    /// Qualified owner path: variable, class and object keys (`routes.list`)
+    path: Option<String>,
+    /// Qualified call-site label (`routes.get("/users") callback`, `it("adds item")`)
+    call_label: Option<String>,
+}
+
+impl NameContext {
+    fn from_name(name: &str) -> Self {
+        NameContext {
+            name: name.to_string(),
+            path: Some(name.to_string()),
+            ..NameContext::default()
+        }
+    }
+}
+
+/// Callees whose string argument names a test block: `describe("cart")`, `it("adds item")`.
+const TEST_BLOCK_CALLEES: &[&str] = &["describe", "context", "suite", "it", "test"];
+
+/// Separator between an enclosing function and a nested one in qualified names.
+const NESTING_SEPARATOR: &str = " > ";
+
+fn walk<'tree>(
+    node: tree_sitter::Node<'tree>,
+    source: &[u8],
+    parent_ctx: Option<&NameContext>,
+    parent_fn: Option<FunctionId>,
+    functions: &mut Vec<IndexedFunction<'tree>>,
+) {
+    let kind = node.kind();
+
+    // `is_named` rules out the `function` keyword token inside declarations
+    if node.is_named() && is_function_node(kind) {
+        let (short_name, name, local_name) = resolve_name(&node, source, parent_ctx);
+        let qualified_name = match parent_fn {
+            Some(parent) => format!(
+                "{}{}{}",
+                functions[parent.0 as usize].qualified_name, NESTING_SEPARATOR, local_name
+            ),
+            None => local_name,
+        };
+        let id = FunctionId(functions.len() as u32);
+        let start = node.start_position();
+        let end = node.end_position();
+
+        functions.push(IndexedFunction {
+            id,
+            parent: parent_fn,
+            node,
+            name,
+            short_name,
+            qualified_name,
+            byte_range: node.byte_range(),
+            start_line: start.row + 1,
+            end_line: end.row + 1,
+            start_col: start.column,
+        });
+
+        // Naming context does not cross function boundaries
+        for i in 0..node.child_count() as u32 {
+            if let Some(child) = node.child(i) {
+                walk(child, source, None, Some(id), functions);
+            }
+        }
+        return;
+    }
+
+    let child_ctx = child_context(&node, source, parent_ctx);
+
+    for i in 0..node.child_count() as u32 {
+        if let Some(child) = node.child(i) {
+            walk(
+                child,
+                source,
+                child_ctx.as_ref().or(parent_ctx),
+                parent_fn,
+                functions,
+            );
+        }
+    }
+}
+
+/// Resolve a function's name from its node and the surrounding context.
+///
+/// Returns `(short_name, name, local_qualified_name)`: `name` carries the
+/// class prefix for methods and class fields, and the local qualified name
+/// adds the owner path or call site (but not enclosing functions).
+fn resolve_name(
+    node: &tree_sitter::Node,
+    source: &[u8],
+    ctx: Option<&NameContext>,
+) -> (String, String, String) {
+    let kind = node.kind();
+    let mut name = extract_function_name(node, source);
+    let mut class_prefix: Option<&str> = None;
+    let mut qualified: Option<String> = None;
+
+    // Apply parent context naming priorities (matching Zig priority order)
+    if let Some(ctx) = ctx {
+        // Priority 1: class method → "ClassName.methodName"
+        if let Some(ref class_name) = ctx.class_name {
+            if kind == "method_definition" && name != "<anonymous>" {
+                class_prefix = Some(class_name);
+            }
+        }
+        // Methods of classes and of objects assigned to a variable: `routes.list`
+        if let Some(ref path) = ctx.path {
+            if kind == "method_definition" && name != "<anonymous>" {
+                qualified = Some(format!("{}.{}", path, name));
+            }
+        }
+
+        // Priority 2: object key → use key name for anonymous arrow/function
+        if let Some(ref key) = ctx.object_key {
+            if name == "<anonymous>" || kind == "arrow_function" {
+                name = key.clone();
+                qualified = ctx.path.clone();
+            }
+        }
+
+        // Priority 3: callback naming → "callee callback" or "event handler"
+        if let Some(ref call_name) = ctx.call_name {
+            if name == "<anonymous>" || kind == "arrow_function" {
+                if call_name.ends_with(" handler") {
+                    name = call_name.clone();
+                } else {
+                    name = format!("{} callback", call_name);
+                }
+                qualified = ctx.call_label.clone();
+            }
+        }
+
+        // Priority 4: default export → "default export"
+        if ctx.is_default_export && name == "<anonymous>" {
+            name = "default export".to_string();
+        }
+
+        // Priority 5: variable or class field name
+        if name == "<anonymous>" && !ctx.name.is_empty() && ctx.name != "<anonymous>" {
+            name = ctx.name.clone();
+            if ctx.is_class_field {
+                class_prefix = ctx.class_name.as_deref();
+            }
+            qualified = ctx.path.clone();
+        }
+    }
+
+    let name_with_class = match class_prefix {
+        Some(class_name) => format!("{}.{}", class_name, name),
+        None => name.clone(),
+    };
+    let qualified = qualified.unwrap_or_else(|| name_with_class.clone());
+    (name, name_with_class, qualified)
+}
+
+/// Build the naming context a node passes to its children, if it sets one.
+fn child_context(
+    node: &tree_sitter::Node,
+    source: &[u8],
+    parent_ctx: Option<&NameContext>,
+) -> Option<NameContext> {
+    match node.kind() {
+        "variable_declarator" => {
+            let name_node = node.child_by_field_name("name")?;
+            let text = name_node.utf8_text(source).ok()?;
+            Some(NameContext::from_name(text))
+        }
+        "class_declaration" | "class" => {
+            // Same name as the class metrics report
+            let class_name = class_name(node, source);
+            Some(NameContext {
+                name: "<anonymous>".to_string(),
+                path: Some(class_name.clone()),
+                class_name: Some(class_name),
+                ..NameContext::default()
+            })
+        }
+        // Pass through parent context: class context for methods, call context for callbacks
+        "class_body" | "arguments" => parent_ctx.cloned(),
+        // Class field: `class Foo { bar = () => {} }` (TS uses "name", JS uses "property")
+        "public_field_definition" | "field_definition" => {
+            let class_name = parent_ctx.and_then(|ctx| ctx.class_name.clone())?;
+            let field_node = node
+                .child_by_field_name("name")
+                .or_else(|| node.child_by_field_name("property"))?;
+            let field = field_node.utf8_text(source).ok()?;
+            Some(NameContext {
+                name: field.to_string(),
+                path: Some(format!("{}.{}", class_name, field)),
+                class_name: Some(class_name),
+                is_class_field: true,
+                ..NameContext::default()
+            })
+        }
+        "pair" => {
+            // Object literal: `{ handler: () => {} }` — key becomes the function name
+            let key_node = node.child(0)?;
+            let key_kind = key_node.kind();
+            let computed = key_kind == "computed_property_name";
+            if !computed
+                && key_kind != "property_identifier"
+                && key_kind != "string"
+                && key_kind != "identifier"
+            {
+                return None;
+            }
+            let key_text = key_node.utf8_text(source).ok()?;
+            // Strip quotes from string keys; computed keys keep their text: `['computed']`
+            let key = if key_text.starts_with('"') || key_text.starts_with('\'') {
+                &key_text[1..key_text.len() - 1]
+            } else {
+                key_text
+            };
+            // Nested keys extend the owner path: `const routes = { users: { list } }` → "routes.users.list"
+            let path = match parent_ctx.and_then(|ctx| ctx.path.as_ref()) {
+                Some(parent_path) if computed => format!("{}{}", parent_path, key),
+                Some(parent_path) => format!("{}.{}", parent_path, key),
+                None => key.to_string(),
+            };
+            Some(NameContext {
+                name: "<anonymous>".to_string(),
+                object_key: Some(key.to_string()),
+                path: Some(path),
+                ..NameContext::default()
+            })
+        }
+        "call_expression" => {
+            // Track callee for callback naming: `arr.map(() => {})` → "map callback"
+            let callee = node.child(0)?;
+            let method_name = match callee.kind() {
+                // Simple identifier: `map(...)`, `forEach(...)`, `addEventListener(...)`
+                "identifier" => callee.utf8_text(source).ok()?.to_string(),
+                // Member expression: `arr.map`, `obj.forEach`, `document.addEventListener`
+                "member_expression" => get_last_member_segment(&callee, source)?,
+                _ => return None,
+            };
+            let first_string = first_string_argument(node, source);
+            let call_name = if method_name == "addEventListener" {
+                match first_string {
+                    Some(ref ev) => format!("{} handler", ev),
+                    None => "addEventListener handler".to_string(),
+                }
+            } else {
+                method_name
+            };
+            let call_label = call_site_label(&callee, source, first_string.as_deref(), &call_name);
+            Some(NameContext {
+                name: "<anonymous>".to_string(),
+                call_name: Some(call_name),
+                call_label: Some(call_label),
+                ..NameContext::default()
+            })
+        }
+        "export_statement" => {
+            // Check for `export default function() {}` → "default export"
+            let is_default = (0..node.child_count() as u32)
+                .filter_map(|i| node.child(i))
+                .any(|c| c.kind() == "default");
+            is_default.then(|| NameContext {
+                name: "<anonymous>".to_string(),
+                is_default_export: true,
+                ..NameContext::default()
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Qualified label for a callback passed to a call.
+///
+/// Test blocks are named by their title (`describe("cart")`); other callbacks
+/// by the full callee and first string argument (`routes.get("/users") callback`).
+fn call_site_label(
+    callee: &tree_sitter::Node,
+    source: &[u8],
+    first_string: Option<&str>,
+    call_name: &str,
+) -> String {
+    let callee_text = callee
+        .utf8_text(source)
+        .map(|text| text.split_whitespace().collect::<String>())
+        .unwrap_or_default();
+    let suffix = if call_name.ends_with(" handler") {
+        "handler"
+    } else {
+        "callback"
+    };
+    match first_string {
+        Some(arg) => {
+            let base = callee_text.split('.').next().unwrap_or("");
+            if TEST_BLOCK_CALLEES.contains(&base) {
+                format!("{}(\"{}\")", base, arg)
+            } else {
+                format!("{}(\"{}\") {}", callee_text, arg, suffix)
+            }
+        }
+        None => format!("{} {}", callee_text, suffix),
+    }
+}
+
+/// Extract the first string literal argument of a call_expression, without quotes.
+///
+/// For `document.addEventListener("click", ...)` returns `Some("click")`.
+fn first_string_argument(call_node: &tree_sitter::Node, source: &[u8]) -> Option<String> {
+    // Find the `arguments` child
+    for i in 0..call_node.child_count() as u32 {
+        if let Some(args) = call_node.child(i) {
+            if args.kind() == "arguments" {
+                // Look for first string literal in arguments
+                for j in 0..args.child_count() as u32 {
+                    if let Some(arg) = args.child(j) {
+                        if arg.kind() == "string" {
+                            if let Ok(text) = arg.utf8_text(source) {
+                                // Strip surrounding quotes
+                                if text.len() >= 2 {
+                                    return Some(text[1..text.len() - 1].to_string());
+                                }
+                            }
+                        }
+                    }
+                }
+                break;
+            }
+        }
+    }
+    None
+}
+
+/// Extract the last identifier segment from a member_expression node.
+///
+/// For `arr.map` returns `Some("map")`. For `obj.foo.bar` returns `Some("bar")`.
+fn get_last_member_segment(node: &tree_sitter::Node, source: &[u8]) -> Option<String> {
+    let mut last: Option<String> = None;
+    for i in 0..node.child_count() as u32 {
+        if let Some(child) = node.child(i) {
+            let ct = child.kind();
+            if ct == "property_identifier" || ct == "identifier" {
+                if let Ok(text) = child.utf8_text(source) {
+                    last = Some(text.to_string());
+                }
+            }
+        }
+    }
+    last
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_ts(source: &str) -> tree_sitter::Tree {
+        let language: tree_sitter::Language = tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into();
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&language).unwrap();
+        parser.parse(source.as_bytes(), None).unwrap()
+    }
+
+    fn names(source: &str) -> Vec<String> {
+        let tree = parse_ts(source);
+        let index = FunctionIndex::build(tree.root_node(), source.as_bytes());
+        index.functions().iter().map(|f| f.name.clone()).collect()
+    }
+
+    #[test]
+    fn ids_follow_depth_first_source_order() {
+        let source = "function a() { const b = () => 1; }\nfunction c() {}";
+        let tree = parse_ts(source);
+        let index = FunctionIndex::build(tree.root_node(), source.as_bytes());
+
+        let ids: Vec<(u32, &str)> = index
+            .functions()
+            .iter()
+            .map(|f| (f.id.0, f.name.as_str()))
+            .collect();
+        assert_eq!(ids, vec![(0, "a"), (1, "b"), (2, "c")]);
+        assert_eq!(index.get(FunctionId(1)).unwrap().name, "b");
+    }
+
+    #[test]
+    fn nested_functions_record_parent_and_are_not_outermost() {
+        let source = "function outer() { function inner() {} }";
+        let tree = parse_ts(source);
+        let index = FunctionIndex::build(tree.root_node(), source.as_bytes());
+
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.functions()[1].parent, Some(FunctionId(0)));
+        let outermost: Vec<&str> = index.outermost().map(|f| f.name.as_str()).collect();
+        assert_eq!(outermost, vec!["outer"]);
+    }
+
+    #[test]
+    fn byte_range_covers_function_node() {
+        let source = "let x = 1;\nfunction f() { return x; }";
+        let tree = parse_ts(source);
+        let index = FunctionIndex::build(tree.root_node(), source.as_bytes());
+
+        let f = &index.functions()[0];
+        assert_eq!(&source[f.byte_range.clone()], "function f() { return x; }");
+        assert_eq!(f.start_line, 2);
+        assert_eq!(f.end_line, 2);
+        assert_eq!(f.start_col, 0);
+    }
+
+    #[test]
+    fn function_expressions_are_indexed() {
+        assert_eq!(
+            names("const handler = function () { return 1; };"),
+            vec!["handler"]
+        );
+    }
+
+    #[test]
+    fn class_field_arrows_are_named_after_the_field() {
+        let source = "class Foo { bar = () => 1; baz() {} }";
+        let tree = parse_ts(source);
+        let index = FunctionIndex::build(tree.root_node(), source.as_bytes());
+
+        let f = &index.functions()[0];
+        assert_eq!(f.name, "Foo.bar");
+        assert_eq!(f.short_name, "bar");
+        assert_eq!(index.functions()[1].name, "Foo.baz");
+        assert_eq!(index.functions()[1].short_name, "baz");
+    }
+
+    fn qualified_names(source: &str) -> Vec<String> {
+        let tree = parse_ts(source);
+        let index = FunctionIndex::build(tree.root_node(), source.as_bytes());
+        index
+            .functions()
+            .iter()
+            .map(|f| f.qualified_name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn qualified_names_for_classes_and_objects() {
+        assert_eq!(
+            qualified_names(
+                "class UserService { fetchUser() {} }\nconst routes = { users: { list: () => 1 }, show() {} };"
+            ),
+            vec!["UserService.fetchUser", "routes.users.list", "routes.show"]
+        );
+    }
+
+    #[test]
+    fn qualified_names_for_call_sites() {
+        assert_eq!(
+            qualified_names(
+                "routes.get('/users', (req) => req);\nitems.map(x => x);\nel.addEventListener('click', () => {});"
+            ),
+            vec![
+                "routes.get(\"/users\") callback",
+                "items.map callback",
+                "el.addEventListener(\"click\") handler"
+            ]
+        );
+    }
+
+    #[test]
+    fn qualified_names_include_enclosing_functions() {
+        assert_eq!(
+            qualified_names(
+                "describe('cart', () => { it('adds item', () => {}); });\nfunction outerFn() { function innerHelper() {} }"
+            ),
+            vec![
+                "describe(\"cart\")",
+                "describe(\"cart\") > it(\"adds item\")",
+                "outerFn",
+                "outerFn > innerHelper"
+            ]
+        );
+    }
+
+    #[test]
+    fn anonymous_classes_and_computed_keys() {
+        assert_eq!(
+            qualified_names(
+                "export default class { run() {} }\nconst Named = class { stop() {} };\nconst obj = { ['computed']: () => 1 };"
+            ),
+            vec!["<anonymous>.run", "Named.stop", "obj['computed']"]
+        );
+        assert_eq!(
+            names("const obj = { ['computed']: () => 1 };"),
+            vec!["['computed']"]
+        );
+    }
+
+    #[test]
+    fn nested_functions_are_descendants_of_their_ancestors() {
+        let source = "function a() { function b() { const c = () => 1; } }\nfunction d() {}";
+        let tree = parse_ts(source);
+        let index = FunctionIndex::build(tree.root_node(), source.as_bytes());
+
+        let nested: Vec<&str> = index
+            .descendants(FunctionId(0))
+            .map(|f| f.qualified_name.as_str())
+            .collect();
+        assert_eq!(nested, vec!["a > b", "a > b > c"]);
+        assert_eq!(index.descendants(FunctionId(3)).count(), 0);
+    }
+
+    #[test]
+    fn short_names_are_unchanged_by_qualification() {
+        assert_eq!(
+            names("routes.get('/users', (req) => req);\nconst obj = { handler: () => 1 };"),
+            vec!["get callback", "handler"]
+        );
+    }
+
+    #[test]
+    fn callbacks_and_exports_are_named_from_context() {
+        assert_eq!(
+            names(
+                "items.map(x => x);\nel.addEventListener('click', () => {});\nexport default function () {}"
+            ),
+            vec!["map callback", "click handler", "default export"]
+        );
+    }
+}
@@ -1,6 +1,13 @@
+pub mod function_index;
+
 use std::path::Path;
 
-use crate::types::{FunctionInfo, ParseError, ParseResult};
+pub use function_index::{FunctionIndex, IndexedFunction};
+
+use crate::types::{FunctionInfo, ParseError, ParseResult, SyntaxError, SyntaxErrorKind};
+
+/// Maximum number of characters kept in a syntax error snippet.
+const SNIPPET_MAX_CHARS: usize = 40;
 
 /// Select the tree-sitter language grammar based on file extension.
 ///
@@ -16,10 +23,23 @@ pub fn select_language(path: &Path) -> Result<tree_sitter::Language, ParseError>
     }
 }
 
+/// Name of the grammar `select_language` picks for `path`.
+///
+/// Returns `None` for unsupported or missing extensions.
+pub fn grammar_name(path: &Path) -> Option<&'static str> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("ts") => Some("typescript"),
+        Some("tsx") => Some("tsx"),
+        Some("js") | Some("jsx") => Some("javascript"),
+        _ => None,
+    }
+}
+
 /// Parse a source file and extract function information.
 ///
 /// Reads the file, selects the grammar by extension, parses with tree-sitter,
-/// and extracts all function declarations into owned `FunctionInfo` structs.
+/// and copies every function in the file's `FunctionIndex` into owned
+/// `FunctionInfo` structs.
 /// No tree-sitter `Node` or `Tree` references escape this function.
 pub fn parse_file(path: &Path) -> Result<ParseResult, ParseError> {
     // First check extension before attempting I/O
@@ -35,125 +55,73 @@ pub fn parse_file(path: &Path) -> Result<ParseResult, ParseError> {
     let tree = parser.parse(&source, None).ok_or(ParseError::ParseFailed)?;
 
     let root = tree.root_node();
-    let has_error = root.has_error();
-    let functions = extract_functions(root, &source);
+    let syntax_errors = collect_syntax_errors(root, &source);
+    let functions = FunctionIndex::build(root, &source)
+        .functions()
+        .iter()
+        .map(|f| FunctionInfo {
+            id: f.id,
+            parent: f.parent,
+            name: f.short_name.clone(),
+            qualified_name: f.qualified_name.clone(),
+            start_line: f.start_line,
+            start_column: f.start_col,
+            end_line: f.end_line,
+        })
+        .collect();
 
     Ok(ParseResult {
         path: path.to_path_buf(),
         functions,
         source_len: source.len(),
-        error: has_error,
+        syntax_errors,
     })
 }
 
-/// Extract function declarations from the CST using DFS traversal.
+/// Collect every `ERROR` and `MISSING` node in the tree, in source order.
 ///
-/// Matches: `function_declaration`, `method_definition`, and `arrow_function`
-/// when assigned to a variable via `variable_declarator` or `lexical_declaration`.
-fn extract_functions(root: tree_sitter::Node, source: &[u8]) -> Vec<FunctionInfo> {
-    let mut functions = Vec::new();
-    let mut cursor = root.walk();
-
-    traverse(&mut cursor, source, &mut functions);
-    functions
+/// Only subtrees that report `has_error()` are visited. Nodes inside an `ERROR`
+/// node are not reported separately.
+pub fn collect_syntax_errors(root: tree_sitter::Node, source: &[u8]) -> Vec<SyntaxError> {
+    let mut errors = Vec::new();
+    if root.has_error() {
+        visit_syntax_errors(root, source, &mut errors);
+    }
+    errors
 }
 
-fn traverse(
-    cursor: &mut tree_sitter::TreeCursor,
-    source: &[u8],
-    functions: &mut Vec<FunctionInfo>,
-) {
-    loop {
-        let node = cursor.node();
-
-        match node.kind() {
-            "function_declaration" | "method_definition" => {
-                if let Some(name_node) = node.child_by_field_name("name") {
-                    if let Ok(name) = name_node.utf8_text(source) {
-                        functions.push(FunctionInfo {
-                            name: name.to_string(),
-                            start_line: node.start_position().row + 1,
-                            start_column: node.start_position().column,
-                            end_line: node.end_position().row + 1,
-                        });
-                    }
-                }
-            }
-            // Arrow functions assigned to variables: const X = () => ...
-            "variable_declarator" => {
-                if has_arrow_function_value(&node) {
-                    if let Some(name_node) = node.child_by_field_name("name") {
-                        if let Ok(name) = name_node.utf8_text(source) {
-                            // Get the arrow function node for position info
-                            if let Some(value_node) = node.child_by_field_name("value") {
-                                let arrow = if value_node.kind() == "arrow_function" {
-                                    value_node
-                                } else {
-                                    // Could be a type assertion wrapping the arrow
-                                    find_arrow_child(&value_node).unwrap_or(value_node)
-                                };
-                                functions.push(FunctionInfo {
-                                    name: name.to_string(),
-                                    start_line: arrow.start_position().row + 1,
-                                    start_column: arrow.start_position().column,
-                                    end_line: arrow.end_position().row + 1,
-                                });
-                            }
-                        }
-                    }
-                }
-            }
-            _ => {}
-        }
-
-        // DFS: descend, advance sibling, or retreat
-        if cursor.goto_first_child() {
-            continue;
-        }
-        if cursor.goto_next_sibling() {
-            continue;
-        }
-        loop {
-            if !cursor.goto_parent() {
-                return;
-            }
-            if cursor.goto_next_sibling() {
-                break;
-            }
-        }
+fn visit_syntax_errors(node: tree_sitter::Node, source: &[u8], errors: &mut Vec<SyntaxError>) {
+    if node.is_error() || node.is_missing() {
+        let (kind, snippet) = if node.is_missing() {
+            (SyntaxErrorKind::Missing, node.kind().to_string())
+        } else {
+            (SyntaxErrorKind::Unexpected, error_snippet(node, source))
+        };
+        errors.push(SyntaxError {
+            line: node.start_position().row + 1,
+            column: node.start_position().column,
+            kind,
+            snippet,
+        });
+        return;
     }
-}
 
-/// Check if a variable_declarator has an arrow_function as its value
-/// (possibly wrapped in a type assertion like `as React.FC<...>`).
-fn has_arrow_function_value(node: &tree_sitter::Node) -> bool {
-    if let Some(value) = node.child_by_field_name("value") {
-        if value.kind() == "arrow_function" {
-            return true;
-        }
-        // Check for type assertion wrapping: `X: Type = () => ...` or `X = () => ... as Type`
-        if value.kind() == "as_expression"
-            || value.kind() == "satisfies_expression"
-            || value.kind() == "type_assertion"
-        {
-            return find_arrow_child(&value).is_some();
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.has_error() {
+            visit_syntax_errors(child, source, errors);
         }
     }
-    false
 }
 
-/// Find an arrow_function child within a node (for unwrapping type assertions).
-fn find_arrow_child<'a>(node: &'a tree_sitter::Node<'a>) -> Option<tree_sitter::Node<'a>> {
-    let mut cursor = node.walk();
-    if cursor.goto_first_child() {
-        loop {
-            if cursor.node().kind() == "arrow_function" {
-                return Some(cursor.node());
-            }
-            if !cursor.goto_next_sibling() {
-                break;
-            }
-        }
+/// First line of an error node's text, trimmed and shortened for display.
+fn error_snippet(node: tree_sitter::Node, source: &[u8]) -> String {
+    let text = String::from_utf8_lossy(&source[node.start_byte()..node.end_byte()]);
+    let first_line = text.lines().next().unwrap_or("").trim();
+    if first_line.chars().count() > SNIPPET_MAX_CHARS {
+        let truncated: String = first_line.chars().take(SNIPPET_MAX_CHARS).collect();
+        format!("{truncated}…")
+    } else {
+        first_line.to_string()
     }
-    None
 }
@@ -0,0 +1,331 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use xxhash_rust::xxh3::Xxh3;
+
+use crate::metrics::duplication::restore_token;
+use crate::parser::{grammar_name, select_language};
+use crate::types::{AnalysisConfig, FileAnalysisResult, SkippedItem};
+
+/// Default cache location, relative to the working directory.
+pub const DEFAULT_CACHE_DIR: &str = ".complexityguard/cache";
+
+/// Version of the `CacheEntry` layout, part of every key. Bump it whenever
+/// `FileAnalysisResult` or anything stored inside it changes, so entries
+/// written by a build with another layout are misses rather than results
+/// missing the new fields.
+const CACHE_FORMAT_VERSION: u32 = 1;
+
+/// Hit and miss counts for one run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+/// On-disk cache of per-file analysis results.
+///
+/// Entries are keyed by a hash of the file content, the grammar, the tool
+/// version, the entry format version and every `AnalysisConfig` setting that affects a file's result, so
+/// a changed file, upgrade or config edit never returns a stale result. Each
+/// entry is a JSON file named after its key. Unreadable or corrupt entries are
+/// treated as misses and rewritten.
+pub struct AnalysisCache {
+    dir: PathBuf,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+/// A cached `analyze_file` result. The path is not stored: files with the same
+/// content share an entry, so it is filled in from the file being looked up.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CacheEntry {
+    result: FileAnalysisResult,
+    skipped: Vec<SkippedItem>,
+    tokens: Vec<CachedToken>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CachedToken {
+    kind: String,
+    start_byte: usize,
+    end_byte: usize,
+}
+
+/// Distinguishes temp files of concurrent writers within this process.
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+impl AnalysisCache {
+    /// Create a cache rooted at `dir`. The directory is created on first write.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Hits and misses recorded so far.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Delete every entry by removing the cache directory. A missing directory is not an error.
+    pub fn clear(dir: &Path) -> std::io::Result<()> {
+        match std::fs::remove_dir_all(dir) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            other => other,
+        }
+    }
+
+    /// Look up the result for `path` with contents `source`, counting a hit or miss.
+    pub fn load(
+        &self,
+        path: &Path,
+        source: &[u8],
+        config: &AnalysisConfig,
+    ) -> Option<(FileAnalysisResult, Vec<SkippedItem>)> {
+        let found = self.read_entry(path, source, config);
+        let counter = if found.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        found
+    }
+
+    /// Store the result of analyzing `path` with contents `source`.
+    ///
+    /// Write failures are ignored: the cache only ever costs a re-analysis.
+    pub fn store(
+        &self,
+        path: &Path,
+        source: &[u8],
+        config: &AnalysisConfig,
+        result: &FileAnalysisResult,
+        skipped: &[SkippedItem],
+    ) {
+        let Some(key) = cache_key(path, source, config) else {
+            return;
+        };
+        let entry = CacheEntry {
+            result: result.clone(),
+            skipped: skipped.to_vec(),
+            tokens: result
+                .tokens
+                .iter()
+                .map(|t| CachedToken {
+                    kind: t.kind.to_string(),
+                    start_byte: t.start_byte,
+                    end_byte: t.end_byte,
+                })
+                .collect(),
+        };
+        let _ = self.write_entry(&key, &entry);
+    }
+
+    fn read_entry(
+        &self,
+        path: &Path,
+        source: &[u8],
+        config: &AnalysisConfig,
+    ) -> Option<(FileAnalysisResult, Vec<SkippedItem>)> {
+        let key = cache_key(path, source, config)?;
+        let bytes = std::fs::read(self.entry_path(&key)).ok()?;
+        let entry: CacheEntry = serde_json::from_slice(&bytes).ok()?;
+
+        let language = select_language(path).ok()?;
+        let tokens = entry
+            .tokens
+            .iter()
+            .map(|t| restore_token(&language, &t.kind, t.start_byte, t.end_byte))
+            .collect::<Option<Vec<_>>>()?;
+
+        let mut result = entry.result;
+        result.path = path.to_path_buf();
+        result.tokens = tokens;
+        let mut skipped = entry.skipped;
+        for item in &mut skipped {
+            item.path = path.to_path_buf();
+        }
+        Some((result, skipped))
+    }
+
+    /// Write through a temp file and rename, so readers never see a partial entry.
+    fn write_entry(&self, key: &str, entry: &CacheEntry) -> std::io::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let temp = self.dir.join(format!(
+            "{key}.{}-{}.tmp",
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut file = std::fs::File::create(&temp)?;
+        file.write_all(&serde_json::to_vec(entry)?)?;
+        drop(file);
+        std::fs::rename(&temp, self.entry_path(key)).inspect_err(|_| {
+            let _ = std::fs::remove_file(&temp);
+        })
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+}
+
+/// Hex key for a file's cache entry; `None` for files without a supported grammar.
+///
+/// Only the size limits that apply to `path` are hashed, not the whole
+/// override list, so unrelated overrides do not invalidate every entry.
+fn cache_key(path: &Path, source: &[u8], config: &AnalysisConfig) -> Option<String> {
+    versioned_cache_key(CACHE_FORMAT_VERSION, path, source, config)
+}
+
+fn versioned_cache_key(
+    format_version: u32,
+    path: &Path,
+    source: &[u8],
+    config: &AnalysisConfig,
+) -> Option<String> {
+    let grammar = grammar_name(path)?;
+    let settings = format!(
+        "{:?}|{:?}|{:?}|{:?}|{:?}|{:?}",
+        config.cyclomatic,
+        config.cognitive,
+        config.scoring_weights,
+        config.scoring_thresholds,
+        config.duplication,
+        config.size_limits_for(path),
+    );
+
+    let mut hasher = Xxh3::new();
+    for part in [
+        env!("CARGO_PKG_VERSION").as_bytes(),
+        &format_version.to_le_bytes(),
+        grammar.as_bytes(),
+        settings.as_bytes(),
+    ] {
+        hasher.update(part);
+        hasher.update(&[0]);
+    }
+    hasher.update(source);
+    Some(format!("{:032x}", hasher.digest128()))
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::analyze_file;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/typescript")
+            .join(name)
+    }
+
+    #[test]
+    fn round_trips_results_and_tokens() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = AnalysisCache::new(dir.path().join("cache"));
+        let path = fixture("class_with_methods.ts");
+        let source = std::fs::read(&path).unwrap();
+        let config = AnalysisConfig::default();
+
+        assert!(cache.load(&path, &source, &config).is_none());
+        let (result, skipped) = analyze_file(&path, &config).unwrap();
+        cache.store(&path, &source, &config, &result, &skipped);
+
+        let (cached, _) = cache.load(&path, &source, &config).unwrap();
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1 });
+        assert_eq!(cached.path, path);
+        assert_eq!(cached.functions.len(), result.functions.len());
+        assert_eq!(cached.classes.len(), 1);
+        assert_eq!(cached.file_score, result.file_score);
+        assert_eq!(cached.tokens.len(), result.tokens.len());
+        for (a, b) in cached.tokens.iter().zip(&result.tokens) {
+            assert_eq!((a.kind, a.kind_hash), (b.kind, b.kind_hash));
+            assert_eq!((a.start_byte, a.end_byte), (b.start_byte, b.end_byte));
+        }
+    }
+
+    #[test]
+    fn key_changes_with_content_grammar_and_config() {
+        let config = AnalysisConfig::default();
+        let ts = Path::new("a.ts");
+        let key = cache_key(ts, b"let a = 1;", &config).unwrap();
+
+        assert_ne!(key, cache_key(ts, b"let a = 2;", &config).unwrap());
+        assert_ne!(
+            key,
+            cache_key(Path::new("a.js"), b"let a = 1;", &config).unwrap()
+        );
+
+        let mut stricter = AnalysisConfig::default();
+        stricter.cyclomatic.warning_threshold = 5;
+        assert_ne!(key, cache_key(ts, b"let a = 1;", &stricter).unwrap());
+
+        // Same content under another path shares the entry
+        assert_eq!(
+            key,
+            cache_key(Path::new("b/c.ts"), b"let a = 1;", &config).unwrap()
+        );
+        assert!(cache_key(Path::new("README.md"), b"", &config).is_none());
+    }
+
+    #[test]
+    fn entries_of_another_format_version_are_misses() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = AnalysisCache::new(dir.path());
+        let config = AnalysisConfig::default();
+        let path = fixture("class_with_methods.ts");
+        let source = std::fs::read(&path).unwrap();
+        let (result, skipped) = analyze_file(&path, &config).unwrap();
+        cache.store(&path, &source, &config, &result, &skipped);
+
+        // Move the entry to where a build of the previous format would find it
+        let key = cache_key(&path, &source, &config).unwrap();
+        let old_key =
+            versioned_cache_key(CACHE_FORMAT_VERSION - 1, &path, &source, &config).unwrap();
+        assert_ne!(key, old_key);
+        std::fs::rename(cache.entry_path(&key), cache.entry_path(&old_key)).unwrap();
+
+        assert!(cache.load(&path, &source, &config).is_none());
+        assert_eq!(cache.stats().misses, 1);
+    }
+
+    #[test]
+    fn corrupt_entries_are_misses() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = AnalysisCache::new(dir.path());
+        let config = AnalysisConfig::default();
+        let path = Path::new("a.ts");
+        let key = cache_key(path, b"x", &config).unwrap();
+        std::fs::write(cache.entry_path(&key), b"{not json").unwrap();
+
+        assert!(cache.load(path, b"x", &config).is_none());
+        assert_eq!(cache.stats().misses, 1);
+    }
+
+    #[test]
+    fn clear_removes_the_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_dir = dir.path().join("cache");
+        std::fs::create_dir_all(&cache_dir).unwrap();
+        std::fs::write(cache_dir.join("entry.json"), b"{}").unwrap();
+
+        AnalysisCache::clear(&cache_dir).unwrap();
+        assert!(!cache_dir.exists());
+        // Clearing again is fine
+        AnalysisCache::clear(&cache_dir).unwrap();
+    }
+}
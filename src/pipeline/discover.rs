@@ -3,6 +3,8 @@ use std::path::{Path, PathBuf};
 use globset::{GlobSet, GlobSetBuilder};
 use walkdir::WalkDir;
 
+use crate::git;
+
 /// Directory names that are always excluded from file discovery.
 ///
 /// Matches the Zig `EXCLUDED_DIRS` constant in `src/discovery/filter.zig`.
@@ -37,7 +39,7 @@ fn is_declaration_file(path: &Path) -> bool {
 }
 
 /// Constructs a `GlobSet` from a slice of glob pattern strings.
-fn build_globset(patterns: &[String]) -> anyhow::Result<GlobSet> {
+pub fn build_globset(patterns: &[String]) -> anyhow::Result<GlobSet> {
     let mut builder = GlobSetBuilder::new();
     for pat in patterns {
         builder.add(globset::Glob::new(pat)?);
@@ -123,11 +125,152 @@ pub fn discover_files(
     Ok(result)
 }
 
+/// Discover the source files of the TypeScript project `project`, a
+/// tsconfig.json or its directory, and of the projects it references.
+///
+/// The project's `files`, `include` and `exclude` (with `extends` applied)
+/// select the files instead of a directory walk, so `EXCLUDED_DIRS` do not
+/// apply; the include/exclude glob filters and extensions still do. When
+/// `paths` is non-empty, only files under one of them are kept. Sorted.
+pub fn discover_project_files(
+    project: &Path,
+    paths: &[PathBuf],
+    include_patterns: &[String],
+    exclude_patterns: &[String],
+) -> anyhow::Result<Vec<PathBuf>> {
+    let exclude = build_globset(exclude_patterns)?;
+    let include = if include_patterns.is_empty() {
+        None
+    } else {
+        Some(build_globset(include_patterns)?)
+    };
+    let under: Vec<PathBuf> = paths.iter().map(|p| lexical(p)).collect();
+    Ok(crate::tsconfig::project_files(project)?
+        .into_iter()
+        .filter(|file| under.is_empty() || under.iter().any(|p| lexical(file).starts_with(p)))
+        .filter(|file| should_include(file, &exclude, &include))
+        .collect())
+}
+
+/// `path` without `.` segments, so `./src` and `src` compare equal.
+fn lexical(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| !matches!(c, std::path::Component::CurDir))
+        .collect()
+}
+
+/// `should_include` for a path listed by git rather than found by walking
+/// the tree, so `EXCLUDED_DIRS` are checked against its directories.
+fn should_include_listed(path: &Path, exclude: &GlobSet, include: &Option<GlobSet>) -> bool {
+    let excluded_dir = path.parent().is_some_and(|parent| {
+        parent
+            .components()
+            .any(|c| EXCLUDED_DIRS.contains(&c.as_os_str().to_str().unwrap_or_default()))
+    });
+    !excluded_dir && should_include(path, exclude, include)
+}
+
+/// Discover all analysable source files under the given paths in commit `rev`
+/// of the repository containing `dir`, and read their contents.
+///
+/// Files are listed with `git ls-tree` and read with `git cat-file`, so the
+/// working tree is never touched. The same filters as `discover_files` apply,
+/// including `EXCLUDED_DIRS`. Paths are relative to `dir`, in tree order.
+pub fn discover_revision_files(
+    dir: &Path,
+    rev: &str,
+    paths: &[PathBuf],
+    include_patterns: &[String],
+    exclude_patterns: &[String],
+) -> anyhow::Result<Vec<(PathBuf, Vec<u8>)>> {
+    let exclude = build_globset(exclude_patterns)?;
+    let include = if include_patterns.is_empty() {
+        None
+    } else {
+        Some(build_globset(include_patterns)?)
+    };
+
+    let pathspecs: Vec<String> = paths
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    let pathspecs: Vec<&str> = pathspecs.iter().map(String::as_str).collect();
+    let entries: Vec<_> = git::ls_tree(dir, rev, &pathspecs)?
+        .into_iter()
+        .filter(|entry| should_include_listed(Path::new(&entry.path), &exclude, &include))
+        .collect();
+
+    let oids: Vec<String> = entries.iter().map(|e| e.oid.clone()).collect();
+    let contents = git::cat_blobs(dir, &oids)?;
+    Ok(entries
+        .into_iter()
+        .map(|e| PathBuf::from(e.path))
+        .zip(contents)
+        .collect())
+}
+
+/// Staged and `HEAD` contents of the files `discover_staged_files` found.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StagedFiles {
+    /// Content in the index of each changed file
+    pub staged: Vec<(PathBuf, Vec<u8>)>,
+    /// Content in `HEAD` of the same files, under their `HEAD` path; added
+    /// files have none
+    pub head: Vec<(PathBuf, Vec<u8>)>,
+}
+
+/// Discover the analysable source files under the given paths whose staged
+/// content differs from `HEAD`, in the repository containing `dir`, and read
+/// both versions from git.
+///
+/// The same filters as `discover_files` apply to the staged path. Deleted
+/// files are left out. Paths are relative to `dir`.
+pub fn discover_staged_files(
+    dir: &Path,
+    paths: &[PathBuf],
+    include_patterns: &[String],
+    exclude_patterns: &[String],
+) -> anyhow::Result<StagedFiles> {
+    let exclude = build_globset(exclude_patterns)?;
+    let include = if include_patterns.is_empty() {
+        None
+    } else {
+        Some(build_globset(include_patterns)?)
+    };
+
+    let pathspecs: Vec<String> = paths
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    let pathspecs: Vec<&str> = pathspecs.iter().map(String::as_str).collect();
+    let changes: Vec<_> = git::staged_changes(dir, &pathspecs)?
+        .into_iter()
+        .filter(|change| should_include_listed(Path::new(&change.path), &exclude, &include))
+        .collect();
+
+    let staged_oids: Vec<String> = changes.iter().map(|c| c.oid.clone()).collect();
+    let staged = changes
+        .iter()
+        .map(|c| PathBuf::from(&c.path))
+        .zip(git::cat_blobs(dir, &staged_oids)?)
+        .collect();
+    let head_files: Vec<&(String, String)> =
+        changes.iter().filter_map(|c| c.head.as_ref()).collect();
+    let head_oids: Vec<String> = head_files.iter().map(|(_, oid)| oid.clone()).collect();
+    let head = head_files
+        .iter()
+        .map(|(path, _)| PathBuf::from(path))
+        .zip(git::cat_blobs(dir, &head_oids)?)
+        .collect();
+    Ok(StagedFiles { staged, head })
+}
+
 // TESTS
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::git::test_repo::git;
     use std::path::Path;
 
     #[test]
@@ -253,4 +396,142 @@ mod tests {
             "simple_function.ts should still be included"
         );
     }
+
+    #[test]
+    fn test_discover_project_files_limits_to_paths_and_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str| {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "export const a = 1;\n").unwrap();
+        };
+        write("src/api/a.ts");
+        write("src/web/b.ts");
+        write("src/web/b.test.ts");
+        write("src/types.d.ts");
+        write("build/gen.ts");
+        write("scratch.ts");
+        std::fs::write(
+            dir.path().join("tsconfig.json"),
+            r#"{"include": ["src", "build"]}"#,
+        )
+        .unwrap();
+
+        let project = dir.path().join("tsconfig.json");
+        let files =
+            discover_project_files(&project, &[], &[], &["**/*.test.ts".to_string()]).unwrap();
+        let names: Vec<_> = files
+            .iter()
+            .map(|f| f.strip_prefix(dir.path()).unwrap().to_path_buf())
+            .collect();
+        // build/ is listed by the project, so EXCLUDED_DIRS do not apply
+        assert_eq!(
+            names,
+            [
+                PathBuf::from("build/gen.ts"),
+                PathBuf::from("src/api/a.ts"),
+                PathBuf::from("src/web/b.ts")
+            ]
+        );
+
+        let web = dir.path().join("./src/web");
+        let files = discover_project_files(&project, &[web], &[], &[]).unwrap();
+        assert_eq!(files.len(), 2);
+        assert!(files
+            .iter()
+            .all(|f| f.starts_with(dir.path().join("src/web"))));
+    }
+
+    #[test]
+    fn test_discover_revision_files_reads_committed_content() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        for (path, source) in [
+            ("src/a.ts", "export const a = 1;\n"),
+            ("src/a.test.ts", "export const t = 1;\n"),
+            ("src/types.d.ts", "declare const d: number;\n"),
+            ("node_modules/lib/index.js", "module.exports = 1;\n"),
+            ("README.md", "# readme\n"),
+        ] {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, source).unwrap();
+        }
+        git(dir.path(), &["add", "."]);
+        git(dir.path(), &["commit", "-q", "-m", "initial"]);
+        std::fs::write(dir.path().join("src/a.ts"), "export const a = 2;\n").unwrap();
+        std::fs::write(dir.path().join("src/new.ts"), "export const n = 1;\n").unwrap();
+
+        let files = discover_revision_files(
+            dir.path(),
+            "HEAD",
+            &[PathBuf::from(".")],
+            &[],
+            &["**/*.test.ts".to_string()],
+        )
+        .unwrap();
+        assert_eq!(
+            files,
+            vec![(PathBuf::from("src/a.ts"), b"export const a = 1;\n".to_vec())]
+        );
+    }
+
+    #[test]
+    fn test_discover_staged_files_reads_index_and_head() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str, source: &str| {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, source).unwrap();
+        };
+        git(dir.path(), &["init", "-q"]);
+        write("src/a.ts", "export const a = 1;\n");
+        write("src/old.ts", "export const o = 1;\n");
+        write("src/gone.ts", "export const g = 1;\n");
+        git(dir.path(), &["add", "."]);
+        git(dir.path(), &["commit", "-q", "-m", "initial"]);
+
+        write("src/a.ts", "export const a = 2;\n");
+        git(dir.path(), &["add", "src/a.ts"]);
+        // Unstaged edits are not read
+        write("src/a.ts", "export const a = 3;\n");
+        git(dir.path(), &["mv", "src/old.ts", "src/moved.ts"]);
+        git(dir.path(), &["rm", "-q", "src/gone.ts"]);
+        write("src/new.ts", "export const n = 1;\n");
+        write("src/new.test.ts", "export const t = 1;\n");
+        git(dir.path(), &["add", "src/new.ts", "src/new.test.ts"]);
+        write("src/untracked.ts", "export const u = 1;\n");
+
+        let files = discover_staged_files(
+            dir.path(),
+            &[PathBuf::from(".")],
+            &[],
+            &["**/*.test.ts".to_string()],
+        )
+        .unwrap();
+        assert_eq!(
+            files.staged,
+            vec![
+                (PathBuf::from("src/a.ts"), b"export const a = 2;\n".to_vec()),
+                (
+                    PathBuf::from("src/moved.ts"),
+                    b"export const o = 1;\n".to_vec()
+                ),
+                (
+                    PathBuf::from("src/new.ts"),
+                    b"export const n = 1;\n".to_vec()
+                ),
+            ]
+        );
+        assert_eq!(
+            files.head,
+            vec![
+                (PathBuf::from("src/a.ts"), b"export const a = 1;\n".to_vec()),
+                (
+                    PathBuf::from("src/old.ts"),
+                    b"export const o = 1;\n".to_vec()
+                ),
+            ]
+        );
+    }
 }
@@ -0,0 +1,166 @@
+use crate::types::{SizeLimits, SkipReason};
+
+/// Number of leading lines searched for an `@generated` header.
+const HEADER_LINES: usize = 10;
+/// Number of trailing non-empty lines searched for a source map comment.
+const TRAILER_LINES: usize = 3;
+/// Files smaller than this are never treated as minified: a short file with
+/// one long string literal has a high average line length too.
+const MIN_MINIFIED_BYTES: usize = 4 * 1024;
+
+/// Counts lines the same way the file size guard always has: newlines + 1.
+pub fn count_lines(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|&&b| b == b'\n').count() + 1
+}
+
+/// Checks raw file contents against the size guards and generated-file heuristics.
+///
+/// Runs before parsing so tree-sitter is never invoked on skipped files.
+/// Returns the reason to skip the file, or `None` if it should be analyzed.
+pub fn check_file(bytes: &[u8], limits: &SizeLimits) -> Option<SkipReason> {
+    let lines = count_lines(bytes);
+    if lines > limits.max_file_lines {
+        return Some(SkipReason::FileTooLarge {
+            lines,
+            max_lines: limits.max_file_lines,
+        });
+    }
+
+    if !limits.skip_generated {
+        return None;
+    }
+
+    if let Some(marker) = find_generated_marker(bytes) {
+        return Some(SkipReason::GeneratedFile {
+            marker: marker.to_string(),
+        });
+    }
+
+    let average_line_length = bytes.len() / lines;
+    if bytes.len() >= MIN_MINIFIED_BYTES && average_line_length > limits.max_average_line_length {
+        return Some(SkipReason::MinifiedFile {
+            average_line_length,
+            max_average_line_length: limits.max_average_line_length,
+        });
+    }
+
+    None
+}
+
+/// Looks for an `@generated` header or a trailing `sourceMappingURL` comment.
+fn find_generated_marker(bytes: &[u8]) -> Option<&'static str> {
+    let text = String::from_utf8_lossy(bytes);
+
+    if text
+        .lines()
+        .take(HEADER_LINES)
+        .any(|line| line.contains("@generated"))
+    {
+        return Some("@generated");
+    }
+
+    let has_source_map = text
+        .lines()
+        .rev()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .take(TRAILER_LINES)
+        .any(|line| {
+            line.starts_with("//# sourceMappingURL=") || line.starts_with("//@ sourceMappingURL=")
+        });
+    if has_source_map {
+        return Some("sourceMappingURL");
+    }
+
+    None
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MAX_AVERAGE_LINE_LENGTH;
+
+    #[test]
+    fn test_check_file_plain_source_is_analyzed() {
+        let source = b"export function add(a: number, b: number) {\n  return a + b;\n}\n";
+        assert!(check_file(source, &SizeLimits::default()).is_none());
+    }
+
+    #[test]
+    fn test_check_file_too_large() {
+        let source = "const x = 1;\n".repeat(20);
+        let limits = SizeLimits {
+            max_file_lines: 10,
+            ..SizeLimits::default()
+        };
+        match check_file(source.as_bytes(), &limits) {
+            Some(SkipReason::FileTooLarge { lines, max_lines }) => {
+                assert_eq!(lines, 21);
+                assert_eq!(max_lines, 10);
+            }
+            other => panic!("expected FileTooLarge, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_check_file_generated_header() {
+        let source = b"// @generated by protoc-gen-ts\nexport const a = 1;\n";
+        match check_file(source, &SizeLimits::default()) {
+            Some(SkipReason::GeneratedFile { marker }) => assert_eq!(marker, "@generated"),
+            other => panic!("expected GeneratedFile, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_check_file_generated_marker_outside_header_ignored() {
+        let mut source = "const x = 1;\n".repeat(HEADER_LINES);
+        source.push_str("// mentions @generated in a comment\n");
+        assert!(check_file(source.as_bytes(), &SizeLimits::default()).is_none());
+    }
+
+    #[test]
+    fn test_check_file_source_map_comment() {
+        let source = b"var a=1;\n//# sourceMappingURL=app.js.map\n";
+        match check_file(source, &SizeLimits::default()) {
+            Some(SkipReason::GeneratedFile { marker }) => assert_eq!(marker, "sourceMappingURL"),
+            other => panic!("expected GeneratedFile, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_check_file_minified() {
+        let source = format!("{}\n", "a=1;".repeat(MIN_MINIFIED_BYTES / 4));
+        match check_file(source.as_bytes(), &SizeLimits::default()) {
+            Some(SkipReason::MinifiedFile {
+                average_line_length,
+                max_average_line_length,
+            }) => {
+                assert!(average_line_length > max_average_line_length);
+                assert_eq!(max_average_line_length, 200);
+            }
+            other => panic!("expected MinifiedFile, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_check_file_small_file_with_long_line_is_analyzed() {
+        let source = format!(
+            "export const message = \"{}\";\n",
+            "lorem ipsum ".repeat(100)
+        );
+        assert!(source.len() / count_lines(source.as_bytes()) > MAX_AVERAGE_LINE_LENGTH);
+        assert!(check_file(source.as_bytes(), &SizeLimits::default()).is_none());
+    }
+
+    #[test]
+    fn test_check_file_detection_disabled() {
+        let source = b"// @generated\nexport const a = 1;\n";
+        let limits = SizeLimits {
+            skip_generated: false,
+            ..SizeLimits::default()
+        };
+        assert!(check_file(source, &limits).is_none());
+    }
+}
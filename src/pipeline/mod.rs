@@ -1,5 +1,10 @@
+pub mod cache;
+#[cfg(feature = "discovery")]
 pub mod discover;
+pub mod guards;
 pub mod parallel;
 
+pub use cache::AnalysisCache;
+#[cfg(feature = "discovery")]
 pub use discover::discover_files;
-pub use parallel::analyze_files_parallel;
+pub use parallel::{analyze_files_cached, analyze_files_parallel, analyze_sources, SourceFile};
@@ -1,75 +1,104 @@
-use std::path::PathBuf;
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
 
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
-use crate::metrics::analyze_file;
-use crate::types::{AnalysisConfig, FileAnalysisResult, SkipReason, SkippedItem, MAX_FILE_LINES};
+use crate::metrics::{analyze_file, analyze_source};
+use crate::pipeline::cache::AnalysisCache;
+use crate::pipeline::guards;
+use crate::types::{
+    AnalysisConfig, FileAnalysisResult, ParseError, SkipReason, SkippedItem, SyntaxErrorPolicy,
+};
+
+/// A file to analyze: read from disk, or supplied in memory by an embedder.
+///
+/// In-memory sources never touch the filesystem; their path only selects the
+/// grammar and the size limits that apply, and is the path reported.
+#[derive(Debug, Clone)]
+pub enum SourceFile {
+    Disk(PathBuf),
+    Memory { path: PathBuf, source: Vec<u8> },
+}
+
+impl SourceFile {
+    pub fn path(&self) -> &Path {
+        match self {
+            SourceFile::Disk(path) | SourceFile::Memory { path, .. } => path,
+        }
+    }
+}
 
 /// Outcome of processing a single file in the parallel pipeline.
+#[allow(clippy::large_enum_variant)]
 enum FileOutcome {
-    Analyzed(Result<(FileAnalysisResult, Vec<SkippedItem>), crate::types::ParseError>),
+    Analyzed(Result<(FileAnalysisResult, Vec<SkippedItem>), (PathBuf, ParseError)>),
     Skipped(SkippedItem),
 }
 
 /// Analyze a collection of files in parallel using a rayon thread pool.
 ///
 /// Uses a local thread pool (not the global one) to avoid interference between
-/// concurrent test runs. Results are sorted by path for deterministic output.
+/// concurrent test runs. Without the `parallel` feature, files are analyzed one
+/// at a time on a single thread and `threads` is ignored. Results are sorted by
+/// path for deterministic output.
 ///
-/// Files exceeding `MAX_FILE_LINES` lines are skipped entirely (no parsing).
-/// Functions exceeding `MAX_FUNCTION_LINES` lines are excluded from results.
+/// Files exceeding the configured maximum line count, or that look generated
+/// or minified, are skipped entirely (no parsing). Functions exceeding the
+/// configured maximum function length are excluded from results. Files with
+/// syntax errors are handled according to `config.syntax_error_policy`.
 ///
 /// Returns a tuple of `(results, has_parse_errors, skipped)` where:
 /// - `results` is the sorted list of successfully analyzed files
-/// - `has_parse_errors` is `true` if any file failed to parse
-/// - `skipped` is the list of files/functions that were skipped due to size limits
+/// - `has_parse_errors` is `true` if any file failed to read or parse, or contains
+///   syntax errors under `SyntaxErrorPolicy::Fail`
+/// - `skipped` is the list of files/functions that were skipped by the size guards,
+///   plus a `SkipReason::Failed` entry for every file that failed
 pub fn analyze_files_parallel(
     paths: &[PathBuf],
     config: &AnalysisConfig,
     threads: u32,
 ) -> (Vec<FileAnalysisResult>, bool, Vec<SkippedItem>) {
-    // Use a large stack size (64 MiB) to prevent stack overflow when traversing
-    // deeply nested ASTs in large real-world TypeScript files (e.g. the TypeScript
-    // compiler itself). The default rayon stack (~2–8 MiB) is too small for
-    // recursive metric walkers operating on ASTs with nesting depths in the
-    // hundreds or thousands.
-    const STACK_SIZE: usize = 64 * 1024 * 1024; // 64 MiB
+    analyze_files(paths, config, threads, None)
+}
 
-    let pool = rayon::ThreadPoolBuilder::new()
-        .num_threads(threads as usize)
-        .stack_size(STACK_SIZE)
-        .build()
-        .expect("failed to build rayon thread pool");
+/// Like `analyze_files_parallel`, but reuses results from `cache` for files
+/// whose content and relevant settings are unchanged, skipping tree-sitter for
+/// them. Fresh results are written back to the cache.
+///
+/// Size guards still run on every file; hit and miss counts are available from
+/// `cache.stats()` afterwards.
+pub fn analyze_files_cached(
+    paths: &[PathBuf],
+    config: &AnalysisConfig,
+    threads: u32,
+    cache: &AnalysisCache,
+) -> (Vec<FileAnalysisResult>, bool, Vec<SkippedItem>) {
+    analyze_files(paths, config, threads, Some(cache))
+}
 
-    let outcomes: Vec<FileOutcome> = pool.install(|| {
-        paths
-            .par_iter()
-            .map(|p| {
-                // Fast pre-check: read file bytes and count newlines to enforce file size limit.
-                // This avoids invoking tree-sitter on pathologically large files.
-                let bytes = match std::fs::read(p) {
-                    Ok(b) => b,
-                    Err(_) => {
-                        // Let analyze_file handle I/O errors properly
-                        return FileOutcome::Analyzed(analyze_file(p, config));
-                    }
-                };
-                let line_count = bytes.iter().filter(|&&b| b == b'\n').count() + 1;
-                if line_count > MAX_FILE_LINES {
-                    return FileOutcome::Skipped(SkippedItem {
-                        path: p.clone(),
-                        function_name: None,
-                        start_line: 0,
-                        reason: SkipReason::FileTooLarge {
-                            lines: line_count,
-                            max_lines: MAX_FILE_LINES,
-                        },
-                    });
-                }
-                FileOutcome::Analyzed(analyze_file(p, config))
-            })
-            .collect()
-    });
+fn analyze_files(
+    paths: &[PathBuf],
+    config: &AnalysisConfig,
+    threads: u32,
+    cache: Option<&AnalysisCache>,
+) -> (Vec<FileAnalysisResult>, bool, Vec<SkippedItem>) {
+    let inputs: Vec<SourceFile> = paths.iter().cloned().map(SourceFile::Disk).collect();
+    analyze_sources(&inputs, config, threads, cache)
+}
+
+/// Analyze a mix of on-disk and in-memory files in parallel.
+///
+/// Behaves exactly like `analyze_files_parallel` (or `analyze_files_cached`
+/// when `cache` is given) for `SourceFile::Disk` inputs; `SourceFile::Memory`
+/// inputs go through the same guards, cache and syntax error policy.
+pub fn analyze_sources(
+    inputs: &[SourceFile],
+    config: &AnalysisConfig,
+    threads: u32,
+    cache: Option<&AnalysisCache>,
+) -> (Vec<FileAnalysisResult>, bool, Vec<SkippedItem>) {
+    let outcomes = analyze_all(inputs, threads, |input| analyze_one(input, config, cache));
 
     let mut files: Vec<FileAnalysisResult> = Vec::new();
     let mut skipped: Vec<SkippedItem> = Vec::new();
@@ -81,11 +110,36 @@ pub fn analyze_files_parallel(
                 skipped.push(item);
             }
             FileOutcome::Analyzed(Ok((file_result, fn_skipped))) => {
+                if !file_result.syntax_errors.is_empty() {
+                    match config.syntax_error_policy {
+                        SyntaxErrorPolicy::Analyze => {}
+                        SyntaxErrorPolicy::Skip => {
+                            skipped.push(SkippedItem {
+                                path: file_result.path,
+                                function_name: None,
+                                start_line: 0,
+                                reason: SkipReason::SyntaxErrors {
+                                    errors: file_result.syntax_errors,
+                                },
+                            });
+                            continue;
+                        }
+                        SyntaxErrorPolicy::Fail => has_parse_errors = true,
+                    }
+                }
                 skipped.extend(fn_skipped);
                 files.push(file_result);
             }
-            FileOutcome::Analyzed(Err(_)) => {
+            FileOutcome::Analyzed(Err((path, error))) => {
                 has_parse_errors = true;
+                skipped.push(SkippedItem {
+                    path,
+                    function_name: None,
+                    start_line: 0,
+                    reason: SkipReason::Failed {
+                        error: error.to_string(),
+                    },
+                });
             }
         }
     }
@@ -96,6 +150,82 @@ pub fn analyze_files_parallel(
     (files, has_parse_errors, skipped)
 }
 
+// Use a large stack size (64 MiB) to prevent stack overflow when traversing
+// deeply nested ASTs in large real-world TypeScript files (e.g. the TypeScript
+// compiler itself). The default rayon stack (~2–8 MiB) is too small for
+// recursive metric walkers operating on ASTs with nesting depths in the
+// hundreds or thousands.
+const STACK_SIZE: usize = 64 * 1024 * 1024; // 64 MiB
+
+/// Runs `analyze` over every input on a local pool of `threads` threads.
+#[cfg(feature = "parallel")]
+fn analyze_all<F>(inputs: &[SourceFile], threads: u32, analyze: F) -> Vec<FileOutcome>
+where
+    F: Fn(&SourceFile) -> FileOutcome + Sync + Send,
+{
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads as usize)
+        .stack_size(STACK_SIZE)
+        .build()
+        .expect("failed to build rayon thread pool");
+    pool.install(|| inputs.par_iter().map(analyze).collect())
+}
+
+/// Runs `analyze` over every input in order on one thread with a large stack.
+#[cfg(not(feature = "parallel"))]
+fn analyze_all<F>(inputs: &[SourceFile], _threads: u32, analyze: F) -> Vec<FileOutcome>
+where
+    F: Fn(&SourceFile) -> FileOutcome + Sync + Send,
+{
+    std::thread::scope(|scope| {
+        std::thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn_scoped(scope, || inputs.iter().map(analyze).collect())
+            .expect("failed to spawn analysis thread")
+            .join()
+            .expect("analysis thread panicked")
+    })
+}
+
+/// Guards, cache lookup and analysis of one file.
+fn analyze_one(
+    input: &SourceFile,
+    config: &AnalysisConfig,
+    cache: Option<&AnalysisCache>,
+) -> FileOutcome {
+    let p = input.path();
+    // Fast pre-check: read file bytes to enforce size limits and detect generated
+    // code. This avoids invoking tree-sitter on pathologically large files.
+    let bytes: Cow<[u8]> = match input {
+        SourceFile::Disk(_) => match std::fs::read(p) {
+            Ok(b) => Cow::Owned(b),
+            Err(_) => {
+                // Let analyze_file handle I/O errors properly
+                return FileOutcome::Analyzed(
+                    analyze_file(p, config).map_err(|e| (p.to_path_buf(), e)),
+                );
+            }
+        },
+        SourceFile::Memory { source, .. } => Cow::Borrowed(source),
+    };
+    if let Some(reason) = guards::check_file(&bytes, config.size_limits_for(p)) {
+        return FileOutcome::Skipped(SkippedItem {
+            path: p.to_path_buf(),
+            function_name: None,
+            start_line: 0,
+            reason,
+        });
+    }
+    if let Some(hit) = cache.and_then(|c| c.load(p, &bytes, config)) {
+        return FileOutcome::Analyzed(Ok(hit));
+    }
+    let outcome = analyze_source(p, &bytes, config).map_err(|e| (p.to_path_buf(), e));
+    if let (Some(cache), Ok((result, fn_skipped))) = (cache, &outcome) {
+        cache.store(p, &bytes, config, result, fn_skipped);
+    }
+    FileOutcome::Analyzed(outcome)
+}
+
 // TESTS
 
 #[cfg(test)]
@@ -178,6 +308,60 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_analyze_parallel_skips_generated_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let generated = dir.path().join("schema.ts");
+        std::fs::write(&generated, "// @generated\nexport function f() {}\n").unwrap();
+
+        let paths = vec![generated, fixture("simple_function.ts")];
+        let config = AnalysisConfig::default();
+        let (results, has_errors, skipped) = analyze_files_parallel(&paths, &config, 2);
+
+        assert!(!has_errors);
+        assert_eq!(results.len(), 1, "only the hand-written file is analyzed");
+        assert_eq!(skipped.len(), 1);
+        assert!(matches!(
+            skipped[0].reason,
+            SkipReason::GeneratedFile { .. }
+        ));
+    }
+
+    fn write_broken_file(dir: &tempfile::TempDir) -> PathBuf {
+        let path = dir.path().join("broken.ts");
+        std::fs::write(&path, "function ok() { return 1; }\nfunction broken( {\n").unwrap();
+        path
+    }
+
+    #[test]
+    fn test_analyze_parallel_syntax_error_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = vec![write_broken_file(&dir)];
+
+        let config = AnalysisConfig::default();
+        let (results, has_errors, skipped) = analyze_files_parallel(&paths, &config, 1);
+        assert!(!has_errors, "analyze policy should not fail the run");
+        assert!(skipped.is_empty());
+        assert!(!results[0].syntax_errors.is_empty());
+
+        let config = AnalysisConfig {
+            syntax_error_policy: SyntaxErrorPolicy::Skip,
+            ..AnalysisConfig::default()
+        };
+        let (results, has_errors, skipped) = analyze_files_parallel(&paths, &config, 1);
+        assert!(!has_errors);
+        assert!(results.is_empty(), "skip policy should drop the file");
+        assert!(matches!(skipped[0].reason, SkipReason::SyntaxErrors { .. }));
+
+        let config = AnalysisConfig {
+            syntax_error_policy: SyntaxErrorPolicy::Fail,
+            ..AnalysisConfig::default()
+        };
+        let (results, has_errors, _) = analyze_files_parallel(&paths, &config, 1);
+        assert!(has_errors, "fail policy should flag a parse error");
+        assert_eq!(results.len(), 1, "fail policy still reports the file");
+    }
+
     #[test]
     fn test_analyze_parallel_invalid_file_returns_error() {
         // A .rs file is not a supported language; analyze_file returns an error for it.
@@ -186,12 +370,21 @@ mod tests {
 
         let paths = vec![invalid, valid];
         let config = AnalysisConfig::default();
-        let (results, has_errors, _skipped) = analyze_files_parallel(&paths, &config, 2);
+        let (results, has_errors, skipped) = analyze_files_parallel(&paths, &config, 2);
 
         assert!(
             has_errors,
             "unsupported file should trigger parse error flag"
         );
+        // The failure is reported with its path and error message
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].path.ends_with("src/lib.rs"));
+        match &skipped[0].reason {
+            SkipReason::Failed { error } => {
+                assert_eq!(error, "unsupported file extension: rs")
+            }
+            other => panic!("expected Failed, got {:?}", other),
+        }
         // The valid .ts file should still produce a result
         assert_eq!(results.len(), 1, "valid file should still be analyzed");
         let name = results[0]
@@ -201,4 +394,38 @@ mod tests {
             .unwrap_or("");
         assert_eq!(name, "simple_function.ts");
     }
+
+    #[test]
+    fn test_analyze_cached_reuses_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = vec![
+            fixture("simple_function.ts"),
+            fixture("cyclomatic_cases.ts"),
+            fixture("duplication_cases.ts"),
+        ];
+        let config = AnalysisConfig::default();
+        let (fresh, _, _) = analyze_files_parallel(&paths, &config, 2);
+
+        let cache = AnalysisCache::new(dir.path());
+        let (first, _, _) = analyze_files_cached(&paths, &config, 2, &cache);
+        assert_eq!(cache.stats().misses, 3);
+        assert_eq!(cache.stats().hits, 0);
+
+        let cache = AnalysisCache::new(dir.path());
+        let (second, has_errors, skipped) = analyze_files_cached(&paths, &config, 2, &cache);
+        assert_eq!(cache.stats().hits, 3);
+        assert_eq!(cache.stats().misses, 0);
+        assert!(!has_errors);
+        assert!(skipped.is_empty());
+
+        for results in [&first, &second] {
+            assert_eq!(results.len(), fresh.len());
+            for (a, b) in results.iter().zip(&fresh) {
+                assert_eq!(a.path, b.path);
+                assert_eq!(a.functions.len(), b.functions.len());
+                assert_eq!(a.tokens.len(), b.tokens.len());
+                assert_eq!(a.file_score, b.file_score);
+            }
+        }
+    }
 }
@@ -0,0 +1,518 @@
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::Context;
+
+use crate::codeowners::match_segments;
+
+/// Directories the TypeScript compiler never matches with wildcards, and the
+/// default `exclude`.
+const IMPLICIT_EXCLUDES: [&str; 3] = ["node_modules", "bower_components", "jspm_packages"];
+
+/// A tsconfig.json with its `extends` chain applied. Paths are resolved
+/// against the directory of the config that set them, starting from the path
+/// the config was loaded by.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TsConfig {
+    /// The file it was loaded from
+    pub path: PathBuf,
+    pub files: Option<Vec<PathBuf>>,
+    /// `include` globs
+    pub include: Option<Vec<PathBuf>>,
+    /// `exclude` globs
+    pub exclude: Option<Vec<PathBuf>>,
+    /// `compilerOptions.allowJs`
+    pub allow_js: Option<bool>,
+    /// `compilerOptions.outDir`, excluded by default
+    pub out_dir: Option<PathBuf>,
+    /// `compilerOptions.baseUrl`
+    pub base_url: Option<PathBuf>,
+    /// `compilerOptions.paths`: each pattern with the paths it maps to,
+    /// resolved against `baseUrl`, or the config's directory without one
+    pub paths: Option<Vec<(String, Vec<PathBuf>)>>,
+    /// tsconfig files of the referenced projects; not inherited
+    pub references: Vec<PathBuf>,
+}
+
+impl TsConfig {
+    /// Load a tsconfig.json, or the tsconfig.json in a directory, and the
+    /// configs it extends.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let path = if path.is_dir() {
+            path.join("tsconfig.json")
+        } else {
+            path.to_path_buf()
+        };
+        Self::load_extending(&normalize(&path), &mut Vec::new())
+    }
+
+    /// Load `path` on top of the configs it extends. `chain` holds the
+    /// configs extending it, to reject circular `extends`.
+    fn load_extending(path: &Path, chain: &mut Vec<PathBuf>) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let value: serde_json::Value = serde_json::from_str(&strip_jsonc(&text))
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        let key = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        if chain.contains(&key) {
+            anyhow::bail!("{} extends itself", path.display());
+        }
+        chain.push(key);
+
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let mut config = TsConfig::default();
+        let extends: Vec<&str> = match value.get("extends") {
+            Some(serde_json::Value::String(base)) => vec![base.as_str()],
+            Some(serde_json::Value::Array(bases)) => {
+                bases.iter().filter_map(|b| b.as_str()).collect()
+            }
+            _ => Vec::new(),
+        };
+        // Later bases override earlier ones, and the config itself all of them
+        for base in extends {
+            let base_path = resolve_extends(dir, base)
+                .with_context(|| format!("cannot find '{base}', extended by {}", path.display()))?;
+            config.inherit(Self::load_extending(&base_path, chain)?);
+        }
+        chain.pop();
+
+        let paths = |key: &str| {
+            value.get(key).and_then(|v| v.as_array()).map(|list| {
+                list.iter()
+                    .filter_map(|p| p.as_str())
+                    .map(|p| normalize(&dir.join(p)))
+                    .collect::<Vec<_>>()
+            })
+        };
+        config.path = path.to_path_buf();
+        config.files = paths("files").or(config.files);
+        config.include = paths("include").or(config.include);
+        config.exclude = paths("exclude").or(config.exclude);
+        if let Some(options) = value.get("compilerOptions") {
+            if let Some(allow_js) = options.get("allowJs").and_then(|v| v.as_bool()) {
+                config.allow_js = Some(allow_js);
+            }
+            if let Some(out_dir) = options.get("outDir").and_then(|v| v.as_str()) {
+                config.out_dir = Some(normalize(&dir.join(out_dir)));
+            }
+            if let Some(base_url) = options.get("baseUrl").and_then(|v| v.as_str()) {
+                config.base_url = Some(normalize(&dir.join(base_url)));
+            }
+            if let Some(paths) = options.get("paths").and_then(|v| v.as_object()) {
+                let base = config.base_url.clone().unwrap_or_else(|| dir.to_path_buf());
+                config.paths = Some(
+                    paths
+                        .iter()
+                        .map(|(pattern, targets)| {
+                            let targets = targets
+                                .as_array()
+                                .into_iter()
+                                .flatten()
+                                .filter_map(|t| t.as_str())
+                                .map(|t| normalize(&base.join(t)))
+                                .collect();
+                            (pattern.clone(), targets)
+                        })
+                        .collect(),
+                );
+            }
+        }
+        config.references = value
+            .get("references")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|r| r.get("path").and_then(|p| p.as_str()))
+            .map(|p| {
+                let target = normalize(&dir.join(p));
+                if target.extension().is_some_and(|e| e == "json") {
+                    target
+                } else {
+                    target.join("tsconfig.json")
+                }
+            })
+            .collect();
+        Ok(config)
+    }
+
+    /// Take every setting `base` has, except its references.
+    fn inherit(&mut self, base: TsConfig) {
+        self.files = base.files.or(self.files.take());
+        self.include = base.include.or(self.include.take());
+        self.exclude = base.exclude.or(self.exclude.take());
+        self.allow_js = base.allow_js.or(self.allow_js);
+        self.out_dir = base.out_dir.or(self.out_dir.take());
+        self.base_url = base.base_url.or(self.base_url.take());
+        self.paths = base.paths.or(self.paths.take());
+    }
+
+    /// The files of this project, without its references: `files`, then
+    /// every file matching `include` but not `exclude`. Only TypeScript files
+    /// match `include`, and JavaScript files with `allowJs`.
+    pub fn source_files(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let dir = self.path.parent().unwrap_or(Path::new(""));
+        let include = match (&self.include, &self.files) {
+            (Some(include), _) => include.clone(),
+            (None, Some(_)) => Vec::new(),
+            (None, None) => vec![dir.join("**/*")],
+        };
+        let exclude = match &self.exclude {
+            Some(exclude) => exclude.clone(),
+            None => IMPLICIT_EXCLUDES
+                .iter()
+                .map(|name| dir.join(name))
+                .chain(self.out_dir.clone())
+                .collect(),
+        };
+        let exclude: Vec<Vec<String>> = exclude.iter().map(|p| segments(p)).collect();
+        let allow_js = self.allow_js == Some(true);
+
+        let mut found: Vec<PathBuf> = self.files.clone().unwrap_or_default();
+        for pattern in &include {
+            let mut pattern = segments(pattern);
+            // A last segment without an extension or wildcard names a directory
+            if pattern
+                .last()
+                .is_some_and(|last| !last.contains(['.', '*', '?']))
+            {
+                pattern.extend(["**".to_string(), "*".to_string()]);
+            }
+            let literal = pattern
+                .iter()
+                .take_while(|s| !s.contains(['*', '?']))
+                .count();
+            if literal == pattern.len() {
+                let file: PathBuf = pattern.iter().collect();
+                if file.is_file() {
+                    found.push(file);
+                }
+                continue;
+            }
+            let base: PathBuf = pattern[..literal].iter().collect();
+            let mut candidates = Vec::new();
+            walk(&base, &mut candidates)
+                .with_context(|| format!("failed to search {}", base.display()))?;
+            for file in candidates {
+                let parts = segments(&file);
+                let parts: Vec<&str> = parts.iter().map(String::as_str).collect();
+                let excluded = (1..=parts.len())
+                    .any(|len| exclude.iter().any(|p| match_segments(p, &parts[..len])));
+                if is_project_source(&file, allow_js)
+                    && !excluded
+                    && match_segments(&pattern, &parts)
+                {
+                    found.push(file);
+                }
+            }
+        }
+        let mut seen = BTreeSet::new();
+        found.retain(|f| seen.insert(f.clone()));
+        Ok(found)
+    }
+}
+
+/// The files of the project at `path` (a tsconfig.json or its directory) and
+/// of every project it references, directly or not, sorted.
+pub fn project_files(path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = BTreeSet::new();
+    let mut seen = BTreeSet::new();
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(path) = pending.pop() {
+        let config = TsConfig::load(&path)?;
+        let key = config
+            .path
+            .canonicalize()
+            .unwrap_or_else(|_| config.path.clone());
+        if !seen.insert(key) {
+            continue;
+        }
+        files.extend(config.source_files()?);
+        pending.extend(config.references.iter().rev().cloned());
+    }
+    Ok(files.into_iter().collect())
+}
+
+/// The config an `extends` value names: a path relative to `dir`, with or
+/// without `.json`, or a package in a `node_modules` directory above it.
+fn resolve_extends(dir: &Path, base: &str) -> Option<PathBuf> {
+    let as_file = |path: PathBuf| {
+        if path.is_file() {
+            return Some(path);
+        }
+        let with_json = PathBuf::from(format!("{}.json", path.display()));
+        if with_json.is_file() {
+            return Some(with_json);
+        }
+        let in_dir = path.join("tsconfig.json");
+        in_dir.is_file().then_some(in_dir)
+    };
+    if base.starts_with('.') || Path::new(base).is_absolute() {
+        return as_file(normalize(&dir.join(base)));
+    }
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    let dir = dir.canonicalize().ok()?;
+    dir.ancestors()
+        .find_map(|ancestor| as_file(ancestor.join("node_modules").join(base)))
+}
+
+/// Files below `dir`, skipping hidden directories and those the compiler
+/// never matches with wildcards. Symlinks are not followed.
+fn walk(dir: &Path, out: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    let read = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    let entries = match std::fs::read_dir(read) {
+        Ok(entries) => entries,
+        // An include glob below a missing directory matches nothing
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let path = dir.join(&name);
+        let file_type = entry.file_type()?;
+        if file_type.is_file() {
+            out.push(path);
+        } else if file_type.is_dir()
+            && !name.starts_with('.')
+            && !IMPLICIT_EXCLUDES.contains(&name.as_str())
+        {
+            walk(&path, out)?;
+        }
+    }
+    Ok(())
+}
+
+/// TypeScript sources, and JavaScript ones with `allowJs`.
+fn is_project_source(path: &Path, allow_js: bool) -> bool {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("ts" | "tsx") => true,
+        Some("js" | "jsx") => allow_js,
+        _ => false,
+    }
+}
+
+/// Path segments for glob matching.
+fn segments(path: &Path) -> Vec<String> {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect()
+}
+
+/// Remove `.` segments and resolve `..` against earlier segments, without
+/// touching the filesystem.
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(out.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                out.pop();
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+/// Strip the `//` and `/* */` comments and trailing commas tsconfig.json
+/// allows, so the text parses as JSON.
+fn strip_jsonc(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                out.push(c);
+            }
+            '/' if chars.peek() == Some(&'/') => while chars.next_if(|&n| n != '\n').is_some() {},
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for n in chars.by_ref() {
+                    if prev == '*' && n == '/' {
+                        break;
+                    }
+                    prev = n;
+                }
+                out.push(' ');
+            }
+            '}' | ']' => {
+                let end = out.trim_end().len();
+                if out[..end].ends_with(',') {
+                    out.remove(end - 1);
+                }
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, path: &str, content: &str) {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn relative(root: &Path, files: Vec<PathBuf>) -> Vec<String> {
+        files
+            .iter()
+            .map(|f| {
+                f.strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn strips_comments_and_trailing_commas() {
+        let text = r#"{
+  // line comment
+  "include": ["src/**/*", "a//b",], /* block
+  comment */
+  "exclude": ["x\"/*y"],
+}"#;
+        let value: serde_json::Value = serde_json::from_str(&strip_jsonc(text)).unwrap();
+        assert_eq!(value["include"], serde_json::json!(["src/**/*", "a//b"]));
+        assert_eq!(value["exclude"], serde_json::json!(["x\"/*y"]));
+    }
+
+    #[test]
+    fn resolves_extends_chains() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "node_modules/@tsconfig/strict/tsconfig.json",
+            r#"{"compilerOptions": {"allowJs": true, "outDir": "lib"}}"#,
+        );
+        write(
+            root,
+            "configs/base.json",
+            r#"{
+                "extends": "@tsconfig/strict",
+                "include": ["../src"],
+                "exclude": ["../src/**/*.spec.ts"],
+                "compilerOptions": {"baseUrl": "..", "paths": {"@app/*": ["./src/app/*"]}}
+            }"#,
+        );
+        write(
+            root,
+            "tsconfig.json",
+            "{\n  // app\n  \"extends\": [\"./configs/base\"],\n  \"compilerOptions\": {\"allowJs\": false},\n}\n",
+        );
+        let config = TsConfig::load(&root.join("tsconfig.json")).unwrap();
+        assert_eq!(config.include, Some(vec![root.join("src")]));
+        assert_eq!(config.exclude, Some(vec![root.join("src/**/*.spec.ts")]));
+        assert_eq!(config.allow_js, Some(false));
+        assert_eq!(config.base_url, Some(root.to_path_buf()));
+        assert_eq!(
+            config.paths,
+            Some(vec![("@app/*".to_string(), vec![root.join("src/app/*")])])
+        );
+        assert!(config
+            .out_dir
+            .unwrap()
+            .ends_with("node_modules/@tsconfig/strict/lib"));
+
+        write(root, "loop.json", r#"{"extends": "./loop.json"}"#);
+        let err = TsConfig::load(&root.join("loop.json")).unwrap_err();
+        assert!(format!("{err:#}").contains("extends itself"), "{err:#}");
+        write(root, "missing.json", r#"{"extends": "@tsconfig/none"}"#);
+        let err = TsConfig::load(&root.join("missing.json")).unwrap_err();
+        assert!(
+            err.to_string().contains("cannot find '@tsconfig/none'"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn lists_included_files_of_referenced_projects() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for file in [
+            "src/app.ts",
+            "src/view.tsx",
+            "src/legacy.js",
+            "src/app.spec.ts",
+            "src/.cache/tmp.ts",
+            "fixtures/sample.ts",
+            "scratch.ts",
+            "lib/out.ts",
+            "packages/core/src/core.ts",
+            "packages/core/test/core.test.ts",
+            "tools/gen.ts",
+        ] {
+            write(root, file, "export const x = 1;\n");
+        }
+        write(
+            root,
+            "tsconfig.json",
+            r#"{
+                "files": ["tools/gen.ts"],
+                "include": ["src"],
+                "exclude": ["**/*.spec.ts"],
+                "references": [{"path": "./packages/core"}, {"path": "./tsconfig.json"}]
+            }"#,
+        );
+        write(
+            root,
+            "packages/core/tsconfig.json",
+            r#"{"compilerOptions": {"outDir": "test"}, "references": [{"path": "../.."}]}"#,
+        );
+
+        let files = project_files(&root.join("tsconfig.json")).unwrap();
+        assert_eq!(
+            relative(root, files),
+            [
+                "packages/core/src/core.ts",
+                "src/app.ts",
+                "src/view.tsx",
+                "tools/gen.ts"
+            ]
+        );
+
+        // Without include or files, everything below the config is included
+        write(
+            root,
+            "tsconfig.json",
+            r#"{"compilerOptions": {"allowJs": true, "outDir": "lib"}}"#,
+        );
+        let files = relative(root, project_files(root).unwrap());
+        assert!(files.contains(&"src/legacy.js".to_string()));
+        assert!(files.contains(&"scratch.ts".to_string()));
+        assert!(!files
+            .iter()
+            .any(|f| f.starts_with("lib/") || f.contains(".cache")));
+    }
+}
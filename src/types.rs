@@ -1,4 +1,13 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "discovery")]
+use globset::GlobSet;
+
+/// Identifier of a function within its file's function index.
+///
+/// Assigned in depth-first source order, so it is stable for a given file content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize)]
+pub struct FunctionId(pub u32);
 
 /// Information about a single function extracted from a parsed source file.
 ///
@@ -6,7 +15,12 @@ use std::path::PathBuf;
 /// Line numbers are 1-indexed, columns are 0-indexed.
 #[derive(Debug, Clone)]
 pub struct FunctionInfo {
+    pub id: FunctionId,
+    /// Enclosing function, if nested
+    pub parent: Option<FunctionId>,
     pub name: String,
+    /// Name qualified by owner, call site and enclosing functions
+    pub qualified_name: String,
     pub start_line: usize,
     pub start_column: usize,
     pub end_line: usize,
@@ -20,7 +34,53 @@ pub struct ParseResult {
     pub path: PathBuf,
     pub functions: Vec<FunctionInfo>,
     pub source_len: usize,
-    pub error: bool,
+    pub syntax_errors: Vec<SyntaxError>,
+}
+
+/// Whether a syntax error is unexpected input or a token tree-sitter had to insert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyntaxErrorKind {
+    /// An `ERROR` node: input the grammar could not fit anywhere.
+    Unexpected,
+    /// A `MISSING` node: a token the parser assumed in order to recover.
+    Missing,
+}
+
+/// A single syntax error found in a file that otherwise parsed.
+///
+/// Line is 1-indexed, column is 0-indexed (same as function positions).
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SyntaxError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: SyntaxErrorKind,
+    /// The offending source text, or the expected token for `Missing`.
+    pub snippet: String,
+}
+
+/// What to do with a file that contains syntax errors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SyntaxErrorPolicy {
+    /// Analyze the file and flag functions that contain errors as unreliable.
+    #[default]
+    Analyze,
+    /// Skip the file and report it in the skipped list.
+    Skip,
+    /// Analyze the file but fail the run with the parse error exit code.
+    Fail,
+}
+
+impl SyntaxErrorPolicy {
+    /// Parses a policy name as used in config files and on the command line.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "analyze" => Some(Self::Analyze),
+            "skip" => Some(Self::Skip),
+            "fail" => Some(Self::Fail),
+            _ => None,
+        }
+    }
 }
 
 /// Errors that can occur during file parsing.
@@ -45,7 +105,7 @@ pub enum ParseError {
 // --- Metric types ---
 
 /// Switch/case counting modes for cyclomatic complexity.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum SwitchCaseMode {
     /// Each case increments complexity (+1 per case).
     Classic,
@@ -54,7 +114,7 @@ pub enum SwitchCaseMode {
 }
 
 /// Configuration for cyclomatic complexity calculation.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct CyclomaticConfig {
     pub count_logical_operators: bool,
     pub count_nullish_coalescing: bool,
@@ -82,7 +142,7 @@ impl Default for CyclomaticConfig {
 }
 
 /// Per-function cyclomatic complexity result.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct CyclomaticResult {
     pub name: String,
     pub complexity: u32,
@@ -92,7 +152,7 @@ pub struct CyclomaticResult {
 }
 
 /// Per-function structural metric result.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct StructuralResult {
     pub name: String,
     pub function_length: u32,
@@ -104,14 +164,14 @@ pub struct StructuralResult {
 }
 
 /// Per-file structural metric result.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct FileStructuralResult {
     pub file_length: u32,
     pub export_count: u32,
 }
 
 /// Per-function cognitive complexity result.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct CognitiveResult {
     pub name: String,
     pub complexity: u32,
@@ -121,7 +181,7 @@ pub struct CognitiveResult {
 }
 
 /// Configuration for cognitive complexity calculation.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct CognitiveConfig {
     pub warning_threshold: u32,
     pub error_threshold: u32,
@@ -137,7 +197,7 @@ impl Default for CognitiveConfig {
 }
 
 /// Per-function Halstead metrics result.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct HalsteadResult {
     pub name: String,
     pub volume: f64,
@@ -157,7 +217,7 @@ pub struct HalsteadResult {
 /// Weights are normalized to sum 1.0 before use.
 /// When duplication is disabled, the duplication weight is excluded
 /// and the remaining four weights are re-normalized.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ScoringWeights {
     pub cyclomatic: f64,
     pub cognitive: f64,
@@ -182,7 +242,7 @@ impl Default for ScoringWeights {
 ///
 /// Each metric has a warning and error threshold. The sigmoid
 /// returns 50 at the warning threshold and ~20 at the error threshold.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ScoringThresholds {
     pub cyclomatic_warning: f64,
     pub cyclomatic_error: f64,
@@ -230,7 +290,7 @@ pub struct Token {
 }
 
 /// A single instance of a clone at a specific location.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct CloneInstance {
     pub file_index: usize,
     pub start_token: usize,
@@ -240,14 +300,14 @@ pub struct CloneInstance {
 }
 
 /// A detected clone group: two or more locations with the same normalized token sequence.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct CloneGroup {
     pub instances: Vec<CloneInstance>,
     pub token_count: u32,
 }
 
 /// Result of duplication detection across files.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct DuplicationResult {
     pub clone_groups: Vec<CloneGroup>,
     pub total_tokens: usize,
@@ -256,7 +316,7 @@ pub struct DuplicationResult {
 }
 
 /// Per-file duplication statistics, matching the Zig JSON schema `files` array element.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct DuplicationFileInfo {
     pub path: String,
     pub total_tokens: usize,
@@ -267,7 +327,7 @@ pub struct DuplicationFileInfo {
 }
 
 /// Configuration for duplication detection.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct DuplicationConfig {
     pub min_tokens: u32,
     pub enabled: bool,
@@ -285,9 +345,13 @@ impl Default for DuplicationConfig {
 // --- Analysis result types ---
 
 /// Combined per-function metrics with health score.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct FunctionAnalysisResult {
+    /// Short name, e.g. "Foo.bar" or "map callback"
     pub name: String,
+    /// Name qualified by owner, call site and enclosing functions,
+    /// e.g. `items.map callback` or `describe("cart") > it("adds item")`
+    pub qualified_name: String,
     pub start_line: usize,
     pub end_line: usize,
     pub start_col: usize,
@@ -302,18 +366,131 @@ pub struct FunctionAnalysisResult {
     pub params_count: u32,
     pub nesting_depth: u32,
     pub health_score: f64,
+    /// True when a syntax error lies inside the function, so its metrics may be wrong.
+    pub unreliable: bool,
+    /// SimHash of the normalized body tokens, used to match moved or renamed
+    /// functions between reports; 0 when unknown
+    #[serde(default)]
+    pub fingerprint: u64,
+    /// Functions nested inside this one, at any depth
+    #[serde(default)]
+    pub nested: Vec<NestedFunction>,
+}
+
+/// A function nested inside a reported function.
+///
+/// Nested functions are not units of their own, so they have no metrics; they
+/// are listed so reports can show their qualified names.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct NestedFunction {
+    /// Short name, e.g. "innerHelper" or "map callback"
+    pub name: String,
+    /// Name qualified by the enclosing functions, e.g. `outerFn > innerHelper`
+    pub qualified_name: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// Per-class size, complexity and cohesion metrics.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ClassAnalysisResult {
+    /// Class name, or the variable it is assigned to for class expressions
+    pub name: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub start_col: usize,
+    /// Methods, accessors and function-valued fields, including the constructor
+    pub method_count: u32,
+    /// Weighted methods per class: sum of the methods' cyclomatic complexity
+    pub wmc: u32,
+    /// Distinct instance fields: declared, parameter properties and `this.x` assignments
+    pub field_count: u32,
+    /// LCOM4: groups of methods (constructor excluded) that share no fields or calls.
+    /// 1 is fully cohesive, 0 means the class has no methods.
+    pub lcom: u32,
 }
 
 /// Per-file analysis result containing all metrics.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct FileAnalysisResult {
     pub path: PathBuf,
     pub functions: Vec<FunctionAnalysisResult>,
+    pub classes: Vec<ClassAnalysisResult>,
+    /// Synthetic `<module>` unit for code outside every function; `None` when
+    /// the file has no measurable top-level code
+    pub module: Option<FunctionAnalysisResult>,
+    /// Duplication tokens; not part of the serialized form (the analysis cache
+    /// stores them separately)
+    #[serde(skip)]
     pub tokens: Vec<Token>,
     pub file_score: f64,
     pub file_length: u32,
     pub export_count: u32,
-    pub error: bool,
+    pub syntax_errors: Vec<SyntaxError>,
+    /// Module specifiers the file imports, in source order
+    pub imports: Vec<Import>,
+    /// Fan-in, fan-out and instability in the module graph; `None` unless the
+    /// graph was built
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coupling: Option<FileCoupling>,
+    /// Import cycles through this file, when it has the lowest path in the
+    /// cycle; empty unless the graph was built
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cycles: Vec<ImportCycle>,
+}
+
+/// How a module specifier was imported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportKind {
+    /// `import ... from "x"`, `import "x"` or `import x = require("x")`
+    Import,
+    /// `export ... from "x"`
+    Export,
+    /// `require("x")`
+    Require,
+    /// `import("x")`
+    Dynamic,
+}
+
+/// A string-literal module specifier in an import, re-export, `require()` or
+/// dynamic `import()`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Import {
+    pub specifier: String,
+    pub line: usize,
+    pub kind: ImportKind,
+    /// `import type` and `export type`, which are erased from the compiled output
+    pub type_only: bool,
+}
+
+/// Coupling of a file to the other analyzed files.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FileCoupling {
+    /// Afferent coupling (Ca): analyzed files that import this one
+    pub fan_in: u32,
+    /// Efferent coupling (Ce): analyzed files this one imports
+    pub fan_out: u32,
+    /// Ce / (Ca + Ce), from 0 (only depended on) to 1 (only depends on
+    /// others); 0 for a file with neither
+    pub instability: f64,
+}
+
+/// Files that import each other through static imports, so that one of
+/// them is evaluated before the modules it depends on are.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ImportCycle {
+    /// Every file of the strongly connected component, sorted by path
+    pub files: Vec<String>,
+    /// The shortest import chain from the first file back to it
+    pub chain: Vec<ImportStep>,
+}
+
+/// A file of an import chain and the line where it imports the next one.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ImportStep {
+    pub path: String,
+    pub line: usize,
 }
 
 /// Combined configuration for all metric analyses.
@@ -324,26 +501,93 @@ pub struct AnalysisConfig {
     pub scoring_weights: ScoringWeights,
     pub scoring_thresholds: ScoringThresholds,
     pub duplication: DuplicationConfig,
+    pub size_limits: SizeLimits,
+    /// Per-path size limits from config `overrides`; the last matching entry wins.
+    #[cfg(feature = "discovery")]
+    pub size_limit_overrides: Vec<SizeLimitsOverride>,
+    pub syntax_error_policy: SyntaxErrorPolicy,
+}
+
+impl AnalysisConfig {
+    /// Returns the size limits that apply to `path`, taking overrides into account.
+    pub fn size_limits_for(&self, path: &Path) -> &SizeLimits {
+        #[cfg(feature = "discovery")]
+        if let Some(o) = self
+            .size_limit_overrides
+            .iter()
+            .rev()
+            .find(|o| o.files.is_match(path))
+        {
+            return &o.limits;
+        }
+        #[cfg(not(feature = "discovery"))]
+        let _ = path;
+        &self.size_limits
+    }
 }
 
 // --- Size guard types ---
 
-/// Maximum file line count before the file is skipped entirely.
+/// Default maximum file line count before the file is skipped entirely.
 pub const MAX_FILE_LINES: usize = 10_000;
-/// Maximum function line count before the function is skipped.
+/// Default maximum function line count before the function is skipped.
 pub const MAX_FUNCTION_LINES: u32 = 5_000;
+/// Default average line length above which a file is treated as minified.
+pub const MAX_AVERAGE_LINE_LENGTH: usize = 200;
+
+/// Size guards and generated-file detection applied before and during analysis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeLimits {
+    pub max_file_lines: usize,
+    pub max_function_lines: u32,
+    /// Skip files with an `@generated` header, a source map comment, or minified content.
+    pub skip_generated: bool,
+    pub max_average_line_length: usize,
+}
+
+impl Default for SizeLimits {
+    fn default() -> Self {
+        Self {
+            max_file_lines: MAX_FILE_LINES,
+            max_function_lines: MAX_FUNCTION_LINES,
+            skip_generated: true,
+            max_average_line_length: MAX_AVERAGE_LINE_LENGTH,
+        }
+    }
+}
+
+/// Size limits for files matching a set of override globs.
+///
+/// `limits` is fully resolved (base limits with the override applied on top).
+#[cfg(feature = "discovery")]
+#[derive(Debug, Clone)]
+pub struct SizeLimitsOverride {
+    pub files: GlobSet,
+    pub limits: SizeLimits,
+}
 
 /// Reason a file or function was skipped during analysis.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum SkipReason {
     /// File exceeded the maximum line count threshold.
     FileTooLarge { lines: usize, max_lines: usize },
     /// Function exceeded the maximum line count threshold.
     FunctionTooLarge { lines: u32, max_lines: u32 },
+    /// File carries a generated-code marker (`@generated` or `sourceMappingURL`).
+    GeneratedFile { marker: String },
+    /// File's average line length exceeds the minified-code threshold.
+    MinifiedFile {
+        average_line_length: usize,
+        max_average_line_length: usize,
+    },
+    /// File could not be read or parsed; `error` is the `ParseError` message.
+    Failed { error: String },
+    /// File contains syntax errors and the syntax error policy is `skip`.
+    SyntaxErrors { errors: Vec<SyntaxError> },
 }
 
-/// A file or function that was skipped during analysis.
-#[derive(Debug, Clone, serde::Serialize)]
+/// A file or function that was skipped or could not be analyzed.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SkippedItem {
     /// File path (always present).
     pub path: PathBuf,
@@ -423,5 +667,69 @@ mod tests {
         assert!((c.scoring_weights.cyclomatic - 0.20).abs() < 1e-10);
         assert!((c.scoring_thresholds.cyclomatic_warning - 10.0).abs() < 1e-10);
         assert!(c.duplication.enabled);
+        assert_eq!(c.size_limits, SizeLimits::default());
+        #[cfg(feature = "discovery")]
+        assert!(c.size_limit_overrides.is_empty());
+        assert_eq!(c.syntax_error_policy, SyntaxErrorPolicy::Analyze);
+    }
+
+    #[test]
+    fn syntax_error_policy_from_name() {
+        assert_eq!(
+            SyntaxErrorPolicy::from_name("analyze"),
+            Some(SyntaxErrorPolicy::Analyze)
+        );
+        assert_eq!(
+            SyntaxErrorPolicy::from_name("skip"),
+            Some(SyntaxErrorPolicy::Skip)
+        );
+        assert_eq!(
+            SyntaxErrorPolicy::from_name("fail"),
+            Some(SyntaxErrorPolicy::Fail)
+        );
+        assert_eq!(SyntaxErrorPolicy::from_name("ignore"), None);
+    }
+
+    #[cfg(feature = "discovery")]
+    #[test]
+    fn size_limits_for_last_matching_override_wins() {
+        let glob = |p: &str| {
+            globset::GlobSetBuilder::new()
+                .add(globset::Glob::new(p).unwrap())
+                .build()
+                .unwrap()
+        };
+        let limits = |max_file_lines| SizeLimits {
+            max_file_lines,
+            ..SizeLimits::default()
+        };
+        let config = AnalysisConfig {
+            size_limit_overrides: vec![
+                SizeLimitsOverride {
+                    files: glob("src/**"),
+                    limits: limits(100),
+                },
+                SizeLimitsOverride {
+                    files: glob("src/legacy/**"),
+                    limits: limits(50_000),
+                },
+            ],
+            ..AnalysisConfig::default()
+        };
+
+        assert_eq!(
+            config.size_limits_for(Path::new("src/a.ts")).max_file_lines,
+            100
+        );
+        assert_eq!(
+            config
+                .size_limits_for(Path::new("src/legacy/a.ts"))
+                .max_file_lines,
+            50_000
+        );
+        assert_eq!(
+            config.size_limits_for(Path::new("lib/a.ts")).max_file_lines,
+            MAX_FILE_LINES
+        );
     }
 }
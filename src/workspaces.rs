@@ -0,0 +1,556 @@
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::Context;
+
+use crate::analyzer::ReportViolation;
+use crate::codeowners::match_segments;
+use crate::metrics::scoring::compute_project_score;
+use crate::output::exit_codes::{determine_exit_code, ExitCode};
+use crate::output::violations::Severity;
+use crate::types::FileAnalysisResult;
+
+/// Package name for files outside every workspace package.
+pub const NO_PACKAGE: &str = "(root)";
+
+/// One package of a workspace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Package {
+    /// `name` from its package.json, or its path when it has none
+    pub name: String,
+    /// Directory relative to the workspace root, with `/` separators
+    pub path: String,
+}
+
+/// An npm, yarn or pnpm workspace, with the place of the directory it was
+/// loaded from in it to resolve report paths against.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    /// `npm`, `yarn` or `pnpm`
+    pub manager: String,
+    /// By path
+    pub packages: Vec<Package>,
+    root: PathBuf,
+    /// The directory relative to the workspace root, ending in `/` unless empty
+    prefix: String,
+}
+
+impl Workspace {
+    /// Find the workspace `dir` belongs to: the nearest of `dir` and its
+    /// parents with a `pnpm-workspace.yaml`, or a package.json with a
+    /// `workspaces` field.
+    pub fn load(dir: &Path) -> anyhow::Result<Self> {
+        let dir = dir
+            .canonicalize()
+            .with_context(|| format!("failed to resolve {}", dir.display()))?;
+        for root in dir.ancestors() {
+            let Some((manager, patterns)) = workspace_patterns(root)? else {
+                continue;
+            };
+            let prefix: String = dir
+                .strip_prefix(root)
+                .unwrap_or(Path::new(""))
+                .components()
+                .map(|c| format!("{}/", c.as_os_str().to_string_lossy()))
+                .collect();
+            return Ok(Workspace {
+                manager: manager.to_string(),
+                packages: find_packages(root, &patterns)?,
+                root: root.to_path_buf(),
+                prefix,
+            });
+        }
+        anyhow::bail!(
+            "no npm, yarn or pnpm workspace in {} or its parents",
+            dir.display()
+        )
+    }
+
+    /// The package a file as reported belongs to, relative to the directory
+    /// passed to `load`: the innermost package containing it.
+    fn package_at(&self, path: &Path) -> Option<&Package> {
+        let path = self.workspace_path(path);
+        self.packages
+            .iter()
+            .filter(|p| {
+                path.strip_prefix(p.path.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+            .max_by_key(|p| p.path.len())
+    }
+
+    /// Name of the package a file as reported belongs to; `NO_PACKAGE` when
+    /// it is outside every package.
+    pub fn package_of(&self, path: &Path) -> &str {
+        self.package_at(path)
+            .map_or(NO_PACKAGE, |p| p.name.as_str())
+    }
+
+    /// Name of the package `selector` names, by package name or path;
+    /// `NO_PACKAGE` selects the files outside every package.
+    pub fn resolve(&self, selector: &str) -> anyhow::Result<&str> {
+        if selector == NO_PACKAGE {
+            return Ok(NO_PACKAGE);
+        }
+        let path = selector.trim_start_matches("./").trim_end_matches('/');
+        self.packages
+            .iter()
+            .find(|p| p.name == selector || p.path == path)
+            .map(|p| p.name.as_str())
+            .with_context(|| {
+                let names: Vec<&str> = self.packages.iter().map(|p| p.name.as_str()).collect();
+                format!(
+                    "no package '{selector}' in the {} workspace (packages: {})",
+                    self.manager,
+                    names.join(", ")
+                )
+            })
+    }
+
+    /// `path` relative to the workspace root. Relative paths need not exist,
+    /// so files read from a revision or memory resolve too.
+    fn workspace_path(&self, path: &Path) -> String {
+        let path = match path.strip_prefix(&self.root) {
+            Ok(inside) => inside,
+            Err(_) if path.is_absolute() => return path.to_string_lossy().to_string(),
+            Err(_) => path,
+        };
+        let mut parts: Vec<String> = self
+            .prefix
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+        for component in path.components() {
+            match component {
+                Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+                Component::ParentDir => {
+                    parts.pop();
+                }
+                _ => {}
+            }
+        }
+        parts.join("/")
+    }
+
+    /// Group every file by package and total each package. `fail_on` maps
+    /// package names or paths to the `--fail-on` mode that gates them.
+    pub fn group(
+        &self,
+        files: &[FileAnalysisResult],
+        violations: &[ReportViolation],
+        fail_on: &BTreeMap<String, String>,
+    ) -> PackagesReport {
+        let mut file_packages = BTreeMap::new();
+        let mut by_package: BTreeMap<&str, (Option<&Package>, Vec<&FileAnalysisResult>)> =
+            BTreeMap::new();
+        for file in files {
+            let package = self.package_at(&file.path);
+            let name = package.map_or(NO_PACKAGE, |p| p.name.as_str());
+            let entry = by_package.entry(name).or_insert((package, Vec::new()));
+            entry.1.push(file);
+            file_packages.insert(file.path.to_string_lossy().to_string(), name.to_string());
+        }
+
+        let mut packages: Vec<PackageSummary> = by_package
+            .into_iter()
+            .map(|(name, (package, members))| {
+                let path = package.map_or(".", |p| p.path.as_str());
+                let mut summary = PackageSummary {
+                    name: name.to_string(),
+                    path: path.to_string(),
+                    files: members.len(),
+                    functions: members.iter().map(|f| f.functions.len()).sum(),
+                    errors: 0,
+                    warnings: 0,
+                    health_score: 0.0,
+                    fail_on: fail_on.get(name).or_else(|| fail_on.get(path)).cloned(),
+                };
+                for v in violations {
+                    if members.iter().any(|f| f.path == v.path) {
+                        match v.violation.severity {
+                            Severity::Error => summary.errors += 1,
+                            Severity::Warning => summary.warnings += 1,
+                        }
+                    }
+                }
+                let scores: Vec<f64> = members.iter().map(|f| f.file_score).collect();
+                let counts: Vec<u32> = members.iter().map(|f| f.functions.len() as u32).collect();
+                summary.health_score = compute_project_score(&scores, &counts);
+                summary
+            })
+            .collect();
+        // Files outside every package last
+        packages.sort_by_key(|p| p.name == NO_PACKAGE);
+
+        PackagesReport {
+            manager: self.manager.clone(),
+            files: file_packages,
+            packages,
+        }
+    }
+}
+
+/// The package manager and package globs of a workspace root, `None` when
+/// `dir` is not one.
+fn workspace_patterns(dir: &Path) -> anyhow::Result<Option<(&'static str, Vec<String>)>> {
+    let pnpm = dir.join("pnpm-workspace.yaml");
+    if pnpm.is_file() {
+        let text = std::fs::read_to_string(&pnpm)
+            .with_context(|| format!("failed to read {}", pnpm.display()))?;
+        return Ok(Some(("pnpm", parse_pnpm_workspace(&text))));
+    }
+    let Some(manifest) = read_manifest(dir)? else {
+        return Ok(None);
+    };
+    let list = match manifest.get("workspaces") {
+        // Yarn also accepts { "packages": [...], "nohoist": [...] }
+        Some(serde_json::Value::Object(yarn)) => yarn.get("packages"),
+        list => list,
+    };
+    let Some(list) = list.and_then(|l| l.as_array()) else {
+        return Ok(None);
+    };
+    let patterns = list
+        .iter()
+        .filter_map(|p| p.as_str())
+        .map(str::to_string)
+        .collect();
+    let manager = if dir.join("yarn.lock").is_file() {
+        "yarn"
+    } else {
+        "npm"
+    };
+    Ok(Some((manager, patterns)))
+}
+
+/// The parsed package.json in `dir`, `None` when there is none.
+fn read_manifest(dir: &Path) -> anyhow::Result<Option<serde_json::Value>> {
+    let path = dir.join("package.json");
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let manifest = serde_json::from_str(&text)
+        .with_context(|| format!("failed to parse {}", path.display()))?;
+    Ok(Some(manifest))
+}
+
+/// The `packages` list of a pnpm-workspace.yaml, in block or flow style.
+/// Other keys are ignored.
+fn parse_pnpm_workspace(text: &str) -> Vec<String> {
+    let unquote = |s: &str| s.trim().trim_matches(|c| c == '\'' || c == '"').to_string();
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+    for line in text.lines() {
+        let line = match line.find(" #") {
+            Some(comment) => &line[..comment],
+            None => line,
+        };
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        if !line.starts_with([' ', '\t', '-']) {
+            in_packages = false;
+            if let Some(value) = line.strip_prefix("packages:") {
+                let value = value.trim();
+                match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                    Some(flow) => patterns.extend(
+                        flow.split(',')
+                            .map(unquote)
+                            .filter(|pattern| !pattern.is_empty()),
+                    ),
+                    None => in_packages = value.is_empty(),
+                }
+            }
+            continue;
+        }
+        if let (true, Some(item)) = (in_packages, line.trim_start().strip_prefix('-')) {
+            patterns.push(unquote(item));
+        }
+    }
+    patterns
+}
+
+/// The packages matching the workspace globs below `root`. `!` globs
+/// exclude; hidden directories and node_modules are not searched.
+fn find_packages(root: &Path, patterns: &[String]) -> anyhow::Result<Vec<Package>> {
+    let segments = |pattern: &str| -> Vec<String> {
+        pattern
+            .trim_start_matches("./")
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    let (exclude, include): (Vec<&String>, Vec<&String>) =
+        patterns.iter().partition(|p| p.starts_with('!'));
+    let include: Vec<Vec<String>> = include.iter().map(|p| segments(p)).collect();
+    let exclude: Vec<Vec<String>> = exclude.iter().map(|p| segments(&p[1..])).collect();
+
+    let mut dirs = Vec::new();
+    manifest_dirs(root, &[], &mut dirs)
+        .with_context(|| format!("failed to search {} for packages", root.display()))?;
+    let mut packages = Vec::new();
+    for dir in dirs {
+        let parts: Vec<&str> = dir.iter().map(String::as_str).collect();
+        if !include.iter().any(|p| match_segments(p, &parts))
+            || exclude.iter().any(|p| match_segments(p, &parts))
+        {
+            continue;
+        }
+        let path = dir.join("/");
+        let manifest = read_manifest(&root.join(&path))?;
+        let name = manifest
+            .as_ref()
+            .and_then(|m| m.get("name"))
+            .and_then(|n| n.as_str())
+            .map_or_else(|| path.clone(), str::to_string);
+        packages.push(Package { name, path });
+    }
+    packages.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(packages)
+}
+
+/// Directories below `root`/`rel` that contain a package.json, as segments
+/// relative to `root`. Symlinks are not followed.
+fn manifest_dirs(root: &Path, rel: &[String], out: &mut Vec<Vec<String>>) -> std::io::Result<()> {
+    let dir = rel.iter().fold(root.to_path_buf(), |dir, s| dir.join(s));
+    for entry in std::fs::read_dir(&dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') || name == "node_modules" || !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let mut child = rel.to_vec();
+        child.push(name);
+        if entry.path().join("package.json").is_file() {
+            out.push(child.clone());
+        }
+        manifest_dirs(root, &child, out)?;
+    }
+    Ok(())
+}
+
+/// Totals for the analyzed files of one package.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PackageSummary {
+    /// Package name, or `(root)` for files outside every package
+    pub name: String,
+    /// Directory relative to the workspace root, `.` for `(root)`
+    pub path: String,
+    pub files: usize,
+    pub functions: usize,
+    pub errors: u32,
+    pub warnings: u32,
+    /// File scores weighted by function count, like the project score
+    pub health_score: f64,
+    /// `--fail-on` mode gating this package, when configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fail_on: Option<String>,
+}
+
+/// The package of every analyzed file and the per-package totals.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PackagesReport {
+    /// `npm`, `yarn` or `pnpm`
+    pub manager: String,
+    /// Package of each file, by report path
+    pub files: BTreeMap<String, String>,
+    /// Packages with analyzed files by name, with `(root)` last
+    pub packages: Vec<PackageSummary>,
+}
+
+impl PackagesReport {
+    /// Package of a file by its report path.
+    pub fn package_of(&self, path: &str) -> Option<&str> {
+        self.files.get(path).map(String::as_str)
+    }
+
+    /// The exit code when each package is gated by its own `fail_on`, or by
+    /// `fail_on` when it has none. Parse errors fail the run unless `fail_on`
+    /// is `none`.
+    pub fn exit_code(&self, has_parse_errors: bool, fail_on: Option<&str>) -> ExitCode {
+        if has_parse_errors && fail_on != Some("none") {
+            return ExitCode::ParseError;
+        }
+        let codes: Vec<ExitCode> = self
+            .packages
+            .iter()
+            .map(|p| {
+                let mode = p.fail_on.as_deref().or(fail_on);
+                determine_exit_code(false, p.errors, p.warnings, mode, false)
+            })
+            .collect();
+        if codes.contains(&ExitCode::ErrorsFound) {
+            ExitCode::ErrorsFound
+        } else if codes.contains(&ExitCode::WarningsFound) {
+            ExitCode::WarningsFound
+        } else {
+            ExitCode::Success
+        }
+    }
+}
+
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, path: &str, content: &str) {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn parses_pnpm_workspace_packages() {
+        let yaml = "\
+# workspace
+packages:
+  - 'packages/*'
+  - \"apps/**\"   # all apps
+  - '!**/test/**'
+catalog:
+  react: ^18
+";
+        assert_eq!(
+            parse_pnpm_workspace(yaml),
+            ["packages/*", "apps/**", "!**/test/**"]
+        );
+        assert_eq!(
+            parse_pnpm_workspace("packages: ['libs/*', \"tools\"]\n"),
+            ["libs/*", "tools"]
+        );
+    }
+
+    #[test]
+    fn loads_npm_and_yarn_workspaces() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "package.json",
+            r#"{"workspaces": ["packages/*", "!packages/skip"]}"#,
+        );
+        write(
+            root,
+            "packages/web/package.json",
+            r#"{"name": "@acme/web"}"#,
+        );
+        write(root, "packages/api/package.json", "{}");
+        write(root, "packages/skip/package.json", r#"{"name": "skip"}"#);
+        write(
+            root,
+            "packages/web/node_modules/dep/package.json",
+            r#"{"name": "dep"}"#,
+        );
+        write(root, "docs/package.json", r#"{"name": "docs"}"#);
+
+        let workspace = Workspace::load(&root.join("packages/web")).unwrap();
+        assert_eq!(workspace.manager, "npm");
+        assert_eq!(
+            workspace.packages,
+            [
+                Package {
+                    name: "packages/api".to_string(),
+                    path: "packages/api".to_string(),
+                },
+                Package {
+                    name: "@acme/web".to_string(),
+                    path: "packages/web".to_string(),
+                },
+            ]
+        );
+        // Report paths resolve against the directory it was loaded from
+        assert_eq!(workspace.package_of(Path::new("./src/a.ts")), "@acme/web");
+        assert_eq!(
+            workspace.package_of(Path::new("../api/b.ts")),
+            "packages/api"
+        );
+        assert_eq!(
+            workspace.package_of(Path::new("../../tools/c.ts")),
+            NO_PACKAGE
+        );
+        assert_eq!(workspace.resolve("packages/api/").unwrap(), "packages/api");
+        let err = workspace.resolve("docs").unwrap_err().to_string();
+        assert!(err.contains("packages: packages/api, @acme/web"), "{err}");
+
+        write(
+            root,
+            "package.json",
+            r#"{"workspaces": {"packages": ["packages/web"]}}"#,
+        );
+        write(root, "yarn.lock", "");
+        let workspace = Workspace::load(root).unwrap();
+        assert_eq!(workspace.manager, "yarn");
+        assert_eq!(workspace.packages.len(), 1);
+        assert_eq!(
+            workspace.package_of(Path::new("packages/web/src/a.ts")),
+            "@acme/web"
+        );
+    }
+
+    #[test]
+    fn a_directory_outside_any_workspace_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "package.json", r#"{"name": "single"}"#);
+        let err = Workspace::load(dir.path()).unwrap_err().to_string();
+        assert!(err.contains("no npm, yarn or pnpm workspace"), "{err}");
+    }
+
+    #[test]
+    fn groups_files_by_package_and_gates_each() {
+        let workspace = Workspace {
+            manager: "pnpm".to_string(),
+            packages: vec![
+                Package {
+                    name: "@acme/api".to_string(),
+                    path: "packages/api".to_string(),
+                },
+                Package {
+                    name: "@acme/legacy".to_string(),
+                    path: "packages/legacy".to_string(),
+                },
+            ],
+            root: PathBuf::from("/repo"),
+            prefix: String::new(),
+        };
+        let nested = "export function nested(a: number) {\n  if (a) { if (a) { if (a) { if (a) { if (a) { if (a) { return 1; } } } } } }\n  return 0;\n}\n";
+        let flat =
+            "export function one() {\n  return 1;\n}\nexport function two() {\n  return 2;\n}\n";
+        let analyzed = crate::Analyzer::new()
+            .source("packages/api/src/a.ts", flat)
+            .source("packages/legacy/b.ts", nested)
+            .source("scripts/c.ts", flat)
+            .run()
+            .unwrap();
+        let gates: BTreeMap<String, String> =
+            [("packages/legacy".to_string(), "none".to_string())].into();
+        let report = workspace.group(&analyzed.files, &analyzed.violations, &gates);
+
+        assert_eq!(
+            report.package_of("packages/legacy/b.ts"),
+            Some("@acme/legacy")
+        );
+        assert_eq!(report.package_of("scripts/c.ts"), Some(NO_PACKAGE));
+        let names: Vec<_> = report.packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["@acme/api", "@acme/legacy", NO_PACKAGE]);
+        let legacy = &report.packages[1];
+        assert_eq!((legacy.files, legacy.functions), (1, 1));
+        assert_eq!(legacy.errors, analyzed.error_count());
+        assert_eq!(legacy.fail_on.as_deref(), Some("none"));
+        assert_eq!(report.packages[2].path, ".");
+        assert_eq!(report.packages[0].errors, 0);
+
+        // The legacy package's errors do not fail the run
+        assert_eq!(analyzed.exit_code(None), ExitCode::ErrorsFound);
+        assert_eq!(report.exit_code(false, None), ExitCode::Success);
+        assert_eq!(report.exit_code(true, None), ExitCode::ParseError);
+        let report = workspace.group(&analyzed.files, &analyzed.violations, &BTreeMap::new());
+        assert_eq!(report.exit_code(false, None), ExitCode::ErrorsFound);
+        assert_eq!(report.exit_code(false, Some("none")), ExitCode::Success);
+    }
+}
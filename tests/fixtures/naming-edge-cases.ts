@@ -23,6 +23,18 @@ class Foo {
   }
 }
 
+// Class field arrow — should show "Widget.onClick"
+class Widget {
+  onClick = () => {
+    return 9;
+  };
+}
+
+// Function expression — should show "compute"
+const compute = function () {
+  return 10;
+};
+
 // Object literal methods — should show key name "process" (direct property)
 const obj = {
   handler: () => {
@@ -0,0 +1,24 @@
+// Configuration script with branching outside any function
+import { readFileSync } from "fs";
+
+const env = process.env.NODE_ENV ?? "development";
+let config = {};
+
+if (env === "production") {
+  for (const key of ["API_URL", "API_KEY"]) {
+    if (key && process.env[key]) {
+      config = { ...config, [key]: process.env[key] };
+    }
+  }
+} else {
+  config = JSON.parse(readFileSync("dev.json", "utf8"));
+}
+
+export function load(name: string): string {
+  if (name) {
+    return name;
+  }
+  return "";
+}
+
+export const labels = [1, 2].map((n) => (n > 1 ? "many" : "one"));
@@ -31,6 +31,30 @@ fn fixture_path(relative: &str) -> std::path::PathBuf {
         .join(relative)
 }
 
+/// Run git in `dir` with a fixed identity, panicking on failure.
+fn git(dir: &std::path::Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+/// Run git in `dir` and return its trimmed standard output.
+fn git_output(dir: &std::path::Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {:?} failed", args);
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
 /// Resolve path to a baseline JSON file in tests/fixtures/baselines/.
 fn baseline_path(name: &str) -> std::path::PathBuf {
     std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
@@ -318,6 +342,182 @@ fn test_cognitive_async_patterns_fetchuserdata_is_15() {
     );
 }
 
+#[test]
+fn test_json_reports_short_and_qualified_names() {
+    let actual = run_json("naming-edge-cases.ts");
+    let fns = actual["files"][0]["functions"].as_array().unwrap();
+    let qualified = |name: &str| {
+        fns.iter()
+            .find(|f| f["name"] == name)
+            .unwrap_or_else(|| panic!("should find {}", name))["qualified_name"]
+            .clone()
+    };
+
+    assert_eq!(qualified("Foo.bar"), "Foo.bar");
+    assert_eq!(qualified("process"), "obj.process");
+    assert_eq!(qualified("map callback"), "items.map callback");
+    assert_eq!(
+        qualified("click handler"),
+        "document.addEventListener(\"click\") handler"
+    );
+}
+
+#[test]
+fn test_nested_functions_are_listed_by_qualified_name() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("nested.ts");
+    // Both outer functions nest three levels deep, a nesting-depth warning
+    std::fs::write(
+        &path,
+        "function outerFn(a: number) {
+  if (a) { if (a > 1) { if (a > 2) { return 3; } } }
+  function innerHelper() { return a; }
+  return innerHelper();
+}
+describe('cart', () => {
+  if (x) { if (y) { if (z) { setup(); } } }
+  it('adds item', () => {});
+});
+",
+    )
+    .unwrap();
+
+    let output = cargo_bin()
+        .args(["--format", "json"])
+        .arg(&path)
+        .output()
+        .unwrap();
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let nested: Vec<(&str, &str)> = json["files"][0]["functions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|f| {
+            f["nested"].as_array().unwrap().iter().map(move |n| {
+                (
+                    f["qualified_name"].as_str().unwrap(),
+                    n["qualified_name"].as_str().unwrap(),
+                )
+            })
+        })
+        .collect();
+    assert_eq!(
+        nested,
+        vec![
+            ("outerFn", "outerFn > innerHelper"),
+            (
+                "describe(\"cart\")",
+                "describe(\"cart\") > it(\"adds item\")"
+            ),
+        ]
+    );
+
+    let output = cargo_bin()
+        .args(["--format", "sarif"])
+        .arg(&path)
+        .output()
+        .unwrap();
+    let sarif: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let logical: Vec<Vec<&str>> = sarif["runs"][0]["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|r| r["ruleId"] == "complexity-guard/nesting-depth")
+        .map(|r| {
+            r["locations"][0]["logicalLocations"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|l| l["fullyQualifiedName"].as_str().unwrap())
+                .collect()
+        })
+        .collect();
+    assert_eq!(
+        logical,
+        vec![
+            vec!["outerFn", "outerFn > innerHelper"],
+            vec![
+                "describe(\"cart\")",
+                "describe(\"cart\") > it(\"adds item\")"
+            ],
+        ]
+    );
+}
+
+#[test]
+fn test_json_reports_class_metrics() {
+    let actual = run_json("typescript/class_with_methods.ts");
+    let classes = actual["files"][0]["classes"].as_array().unwrap();
+    assert_eq!(classes.len(), 1);
+
+    let class = &classes[0];
+    assert_eq!(class["name"], "UserService");
+    assert_eq!(class["start_line"], 5);
+    assert_eq!(class["method_count"], 4);
+    assert_eq!(class["wmc"], 10);
+    assert_eq!(class["field_count"], 2);
+    assert_eq!(class["lcom"], 1);
+    assert_eq!(class["status"], "ok");
+}
+
+#[test]
+fn test_json_reports_module_unit() {
+    let actual = run_json("typescript/module_top_level.ts");
+    let file = &actual["files"][0];
+    let module = &file["module"];
+    assert_eq!(module["name"], "<module>");
+    assert_eq!(module["start_line"], 1);
+    assert_eq!(module["cyclomatic"], 6);
+    assert_eq!(module["cognitive"], 9);
+    assert_eq!(module["nesting_depth"], 3);
+    // Cognitive 9 stays under the default warning of 15; nesting depth 3
+    // reaches the default nesting warning of 3
+    assert_eq!(module["status"], "warning");
+    assert_eq!(actual["summary"]["warnings"], 1);
+    assert_eq!(actual["summary"]["total_functions"], 2);
+
+    let output = cargo_bin()
+        .args(["--format", "sarif"])
+        .arg(fixture_path("typescript/module_top_level.ts"))
+        .output()
+        .unwrap();
+    let sarif: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let rules: Vec<(&str, &str, u64)> = sarif["runs"][0]["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| {
+            (
+                r["ruleId"].as_str().unwrap(),
+                r["level"].as_str().unwrap(),
+                r["locations"][0]["physicalLocation"]["region"]["startLine"]
+                    .as_u64()
+                    .unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        rules,
+        vec![("complexity-guard/nesting-depth", "warning", 1)]
+    );
+}
+
+#[test]
+fn test_module_unit_warnings_fail_the_run() {
+    let output = cargo_bin()
+        .args(["--no-color", "--fail-on", "warning"])
+        .arg(fixture_path("typescript/module_top_level.ts"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("Module-level code cyclomatic 6 cognitive 9"),
+        "expected module line in console output: {}",
+        stdout
+    );
+}
+
 // ============================================================
 // Task 3: Exit code parity (OUT-05)
 // ============================================================
@@ -361,20 +561,13 @@ fn test_exit_code_3_bad_config_path() {
         .code(3);
 }
 
-/// Exit code 4 (ParseError) is unreachable by design — documented here via behavioral test.
+/// Syntax errors alone do not produce exit 4 under the default syntax error policy.
 ///
 /// tree-sitter is error-tolerant: it recovers from all syntax errors and returns a partial AST
 /// rather than failing. Even binary content written to a `.ts` file parses successfully,
-/// producing zero functions and exit 0 (not exit 4).
-///
-/// Both Zig v1.0 and Rust v0.8 produce identical behavior for this scenario:
-/// - File discovery filters by extension so unsupported file types never reach analysis.
-/// - For supported extensions (.ts/.tsx/.js/.jsx), tree-sitter never returns a parse failure;
-///   it always produces a (possibly empty) AST.
-/// - `determine_exit_code` checks `has_parse_errors` but this flag is never true in the
-///   normal discovery + analysis pipeline.
-///
-/// This test documents behavioral parity between the two implementations, not a missing feature.
+/// producing zero functions. With the default `analyze` policy the syntax errors are reported
+/// but the run still exits 0; exit 4 is reserved for files that cannot be read or parsed at
+/// all, and for syntax errors under `--on-syntax-error fail`.
 #[test]
 fn test_exit_code_4_unreachable_tree_sitter_error_tolerant() {
     use std::io::Write;
@@ -395,6 +588,90 @@ fn test_exit_code_4_unreachable_tree_sitter_error_tolerant() {
         .success();
 }
 
+#[test]
+fn test_exit_code_4_missing_file_is_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing.ts");
+
+    let output = cargo_bin()
+        .args(["--format", "json", "--no-color"])
+        .arg(&missing)
+        .arg(fixture_path("typescript/simple_function.ts"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(4));
+
+    let parsed: Value = serde_json::from_str(&String::from_utf8(output.stdout).unwrap()).unwrap();
+    assert_eq!(parsed["summary"]["files_analyzed"], 1);
+    let item = &parsed["skipped"][0];
+    assert_eq!(item["reason"], "failed");
+    assert!(item["path"].as_str().unwrap().ends_with("missing.ts"));
+    assert!(item["error"].as_str().unwrap().starts_with("I/O error"));
+}
+
+#[test]
+fn test_syntax_errors_reported_in_json() {
+    let output = cargo_bin()
+        .args(["--format", "json", "--no-color"])
+        .arg(fixture_path("typescript/syntax_error.ts"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let parsed: Value = serde_json::from_str(&String::from_utf8(output.stdout).unwrap()).unwrap();
+    let errors = parsed["files"][0]["syntax_errors"].as_array().unwrap();
+    assert!(!errors.is_empty());
+    assert!(errors[0]["line"].as_u64().unwrap() >= 10);
+    assert!(parsed["summary"]["syntax_error_count"].as_u64().unwrap() > 0);
+}
+
+#[test]
+fn test_exit_code_4_on_syntax_error_fail() {
+    cargo_bin()
+        .args([
+            "--format",
+            "json",
+            "--no-color",
+            "--on-syntax-error",
+            "fail",
+        ])
+        .arg(fixture_path("typescript/syntax_error.ts"))
+        .assert()
+        .code(4);
+}
+
+#[test]
+fn test_on_syntax_error_skip_lists_file_as_skipped() {
+    let output = cargo_bin()
+        .args([
+            "--format",
+            "json",
+            "--no-color",
+            "--on-syntax-error",
+            "skip",
+        ])
+        .arg(fixture_path("typescript/syntax_error.ts"))
+        .arg(fixture_path("typescript/simple_function.ts"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let parsed: Value = serde_json::from_str(&String::from_utf8(output.stdout).unwrap()).unwrap();
+    assert_eq!(parsed["summary"]["files_analyzed"], 1);
+    let item = &parsed["skipped"][0];
+    assert_eq!(item["reason"], "syntax_errors");
+    assert!(!item["syntax_errors"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_invalid_syntax_error_policy_is_config_error() {
+    cargo_bin()
+        .args(["--on-syntax-error", "ignore"])
+        .arg(fixture_path("typescript/simple_function.ts"))
+        .assert()
+        .code(3);
+}
+
 // ============================================================
 // Task 4: CLI flags (CLI-01, CLI-02, CLI-03)
 // ============================================================
@@ -496,6 +773,31 @@ fn test_config_file_loading_lowers_threshold() {
     );
 }
 
+#[test]
+fn test_class_thresholds_from_config_fail_the_run() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join(".complexityguard.json");
+    std::fs::write(
+        &config_path,
+        r#"{"analysis":{"thresholds":{"wmc":{"warning":5,"error":8}}}}"#,
+    )
+    .unwrap();
+
+    let output = cargo_bin()
+        .args(["--no-color", "--config"])
+        .arg(&config_path)
+        .arg(fixture_path("typescript/class_with_methods.ts"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("Class 'UserService' methods 4 wmc 10 fields 2 lcom 1"),
+        "console should list the class: {}",
+        stdout
+    );
+}
+
 #[test]
 fn test_cli_format_overrides_config_format() {
     // Config sets format=json; --format sarif on CLI should take precedence
@@ -776,3 +1078,1379 @@ fn test_no_duplication_flag_default() {
         parsed["duplication"]
     );
 }
+
+// ============================================================
+// Size guards and generated-file detection
+// ============================================================
+
+#[test]
+fn test_max_function_lines_override_applies_per_path() {
+    // The override raises the function limit back up for cyclomatic_cases.ts only
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join(".complexityguard.json");
+    std::fs::write(
+        &config_path,
+        r#"{
+            "analysis": {"limits": {"max_function_lines": 1}},
+            "overrides": [
+                {"files": ["**/cyclomatic_cases.ts"], "analysis": {"limits": {"max_function_lines": 5000}}}
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    let output = cargo_bin()
+        .args(["--format", "json", "--no-color", "--config"])
+        .arg(&config_path)
+        .arg(fixture_path("typescript/cyclomatic_cases.ts"))
+        .arg(fixture_path("typescript/structural_cases.ts"))
+        .output()
+        .unwrap();
+    let parsed: Value = serde_json::from_str(&String::from_utf8(output.stdout).unwrap()).unwrap();
+
+    let skipped = parsed["skipped"].as_array().expect("skipped list expected");
+    assert!(!skipped.is_empty());
+    for item in skipped {
+        assert_eq!(item["reason"], "function_too_large");
+        assert_eq!(item["max_lines"], 1);
+        assert!(
+            item["path"]
+                .as_str()
+                .unwrap()
+                .ends_with("structural_cases.ts"),
+            "override should exempt cyclomatic_cases.ts: {:?}",
+            item
+        );
+    }
+}
+
+#[test]
+fn test_generated_file_skipped_unless_included() {
+    let dir = tempfile::tempdir().unwrap();
+    let generated = dir.path().join("client.ts");
+    std::fs::write(
+        &generated,
+        "// Code generated by openapi. @generated\nexport function get(a: number) { return a; }\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin()
+        .args(["--format", "json", "--no-color"])
+        .arg(&generated)
+        .output()
+        .unwrap();
+    let parsed: Value = serde_json::from_str(&String::from_utf8(output.stdout).unwrap()).unwrap();
+    assert_eq!(parsed["summary"]["files_analyzed"], 0);
+    assert_eq!(parsed["skipped"][0]["reason"], "generated_file");
+    assert_eq!(parsed["skipped"][0]["marker"], "@generated");
+
+    let output = cargo_bin()
+        .args(["--format", "json", "--no-color", "--include-generated"])
+        .arg(&generated)
+        .output()
+        .unwrap();
+    let parsed: Value = serde_json::from_str(&String::from_utf8(output.stdout).unwrap()).unwrap();
+    assert_eq!(parsed["summary"]["files_analyzed"], 1);
+    assert!(parsed["skipped"].is_null());
+}
+
+// ============================================================
+// Analysis cache
+// ============================================================
+
+#[test]
+fn test_cache_reuses_results_across_runs() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache_dir = dir.path().join("cache");
+    let run = || {
+        cargo_bin()
+            .args([
+                "--format",
+                "json",
+                "--no-color",
+                "--verbose",
+                "--duplication",
+            ])
+            .arg("--cache-dir")
+            .arg(&cache_dir)
+            .arg(fixture_path("typescript"))
+            .output()
+            .unwrap()
+    };
+
+    let first = run();
+    let stderr = String::from_utf8(first.stderr).unwrap();
+    assert!(stderr.contains("Cache: 0 hits"), "stderr: {}", stderr);
+
+    let second = run();
+    let stderr = String::from_utf8(second.stderr).unwrap();
+    assert!(stderr.contains(" 0 misses"), "stderr: {}", stderr);
+
+    // Cached results produce the same report, duplication included
+    let mut first: Value = serde_json::from_slice(&first.stdout).unwrap();
+    let mut second: Value = serde_json::from_slice(&second.stdout).unwrap();
+    for report in [&mut first, &mut second] {
+        report["timestamp"] = Value::Null;
+        report["metadata"]["elapsed_ms"] = Value::Null;
+    }
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_clear_cache_removes_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache_dir = dir.path().join("cache");
+    cargo_bin()
+        .args(["--format", "json", "--cache-dir"])
+        .arg(&cache_dir)
+        .arg(fixture_path("typescript/simple_function.ts"))
+        .assert()
+        .success();
+    assert!(cache_dir.exists());
+
+    cargo_bin()
+        .args(["--clear-cache", "--cache-dir"])
+        .arg(&cache_dir)
+        .assert()
+        .success();
+    assert!(!cache_dir.exists());
+}
+
+// ============================================================
+// Merging sharded reports
+// ============================================================
+
+/// Run one shard over `paths` with token export, writing its JSON report to `out`.
+fn run_shard(paths: &[&str], out: &std::path::Path) {
+    let mut cmd = cargo_bin();
+    cmd.args(["--format", "json", "--export-tokens", "--duplication"])
+        .arg("--output")
+        .arg(out);
+    for path in paths {
+        cmd.arg(fixture_path(path));
+    }
+    cmd.output().unwrap();
+}
+
+#[test]
+fn test_merge_combines_shards() {
+    let dir = tempfile::tempdir().unwrap();
+    let a = dir.path().join("a.json");
+    let b = dir.path().join("b.json");
+    run_shard(
+        &[
+            "typescript/simple_function.ts",
+            "typescript/cognitive_cases.ts",
+        ],
+        &a,
+    );
+    run_shard(&["typescript/cyclomatic_cases.ts"], &b);
+
+    let output = cargo_bin()
+        .args(["merge", "--format", "json"])
+        .arg(&a)
+        .arg(&b)
+        .output()
+        .unwrap();
+    let parsed: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["summary"]["files_analyzed"], 3);
+    assert!(parsed["duplication"].is_object());
+    assert!(parsed["files"][0]["tokens"].is_null());
+}
+
+#[test]
+fn test_merge_rejects_unsupported_schema() {
+    let dir = tempfile::tempdir().unwrap();
+    let report = dir.path().join("old.json");
+    std::fs::write(&report, r#"{"version": "0.8.0", "schema_version": 99}"#).unwrap();
+
+    let output = cargo_bin().arg("merge").arg(&report).output().unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("schema"), "stderr: {}", stderr);
+}
+
+// ============================================================
+// Comparing reports
+// ============================================================
+
+/// Analyze `source` as `dir/src/calc.ts` and save the JSON report to `dir/name`.
+fn save_report(dir: &std::path::Path, source: &str, name: &str) -> std::path::PathBuf {
+    let src = dir.join("src");
+    std::fs::create_dir_all(&src).unwrap();
+    std::fs::write(src.join("calc.ts"), source).unwrap();
+    let report = dir.join(name);
+    cargo_bin()
+        .current_dir(dir)
+        .args(["--format", "json", "--output"])
+        .arg(&report)
+        .arg("src")
+        .output()
+        .unwrap();
+    report
+}
+
+#[test]
+fn test_compare_fails_on_new_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let old = save_report(
+        dir.path(),
+        "export function calc(a: number) {\n  return a + 1;\n}\n",
+        "old.json",
+    );
+    let new = save_report(
+        dir.path(),
+        &std::fs::read_to_string(fixture_path("typescript/complex_nested.ts")).unwrap(),
+        "new.json",
+    );
+
+    let output = cargo_bin()
+        .args(["compare", "--no-color"])
+        .arg(&old)
+        .arg(&new)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("New violations:"), "stdout: {}", stdout);
+    assert!(stdout.contains("new errors"), "stdout: {}", stdout);
+
+    // Swapped, the errors are resolved and the run passes
+    let output = cargo_bin()
+        .args(["compare", "--format", "json"])
+        .arg(&new)
+        .arg(&old)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let parsed: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(parsed["summary"]["resolved_errors"].as_u64().unwrap() > 0);
+    assert_eq!(parsed["summary"]["new_errors"], 0);
+}
+
+#[test]
+fn test_compare_fails_on_new_warnings() {
+    let dir = tempfile::tempdir().unwrap();
+    let old = save_report(
+        dir.path(),
+        "export function calc(a: number) {\n  return a + 1;\n}\n",
+        "old.json",
+    );
+    // Three parameters reach the params_count warning, not the error
+    let new = save_report(
+        dir.path(),
+        "export function calc(a: number, b: number, c: number) {\n  return a + b + c;\n}\n",
+        "new.json",
+    );
+
+    let output = cargo_bin()
+        .args(["compare", "--format", "json"])
+        .arg(&old)
+        .arg(&new)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    let parsed: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["summary"]["new_errors"], 0);
+    assert_eq!(parsed["summary"]["new_warnings"], 1);
+
+    let output = cargo_bin()
+        .args(["compare", "--fail-on", "error"])
+        .arg(&old)
+        .arg(&new)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn test_compare_markdown_and_unsupported_format() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = "export function calc(a: number) {\n  return a + 1;\n}\n";
+    let old = save_report(dir.path(), source, "old.json");
+    let new = save_report(dir.path(), source, "new.json");
+
+    let output = cargo_bin()
+        .args(["compare", "--format", "markdown"])
+        .arg(&old)
+        .arg(&new)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("## Complexity comparison"));
+    assert!(stdout.contains("✓ No regressions"));
+
+    let output = cargo_bin()
+        .args(["compare", "--format", "sarif"])
+        .arg(&old)
+        .arg(&new)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+}
+
+// ============================================================
+// Run history
+// ============================================================
+
+#[test]
+fn test_history_records_runs_and_renders_trend() {
+    let dir = tempfile::tempdir().unwrap();
+    let history = dir.path().join("history.jsonl");
+    for _ in 0..2 {
+        let output = cargo_bin()
+            .args(["--format", "json", "--history-file"])
+            .arg(&history)
+            .arg(fixture_path("typescript/simple_function.ts"))
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(0));
+    }
+    let text = std::fs::read_to_string(&history).unwrap();
+    assert_eq!(text.lines().count(), 2);
+
+    let output = cargo_bin()
+        .args(["trend", "--format", "json", "--last", "1", "--history-file"])
+        .arg(&history)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let runs = parsed.as_array().unwrap();
+    assert_eq!(runs.len(), 1);
+    assert!(runs[0]["health_score"].as_f64().unwrap() > 0.0);
+    assert_eq!(runs[0]["files"], 1);
+
+    let output = cargo_bin()
+        .args(["trend", "--no-color", "--history-file"])
+        .arg(&history)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("2 runs from"));
+    assert!(stdout.contains("Health"));
+
+    // The HTML report of a recorded run charts every run so far
+    let output = cargo_bin()
+        .args(["--format", "html", "--history-file"])
+        .arg(&history)
+        .arg(fixture_path("typescript/simple_function.ts"))
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("class=\"trend-chart\""));
+}
+
+#[test]
+fn test_trend_without_history() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = cargo_bin()
+        .args(["trend", "--history-file"])
+        .arg(dir.path().join("missing.jsonl"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("No runs recorded"));
+}
+
+// ============================================================
+// Change hotspots
+// ============================================================
+
+#[test]
+fn test_hotspots_rank_changed_functions() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("src");
+    std::fs::create_dir_all(&src).unwrap();
+    git(dir.path(), &["init", "-q"]);
+    let nested = std::fs::read_to_string(fixture_path("typescript/complex_nested.ts")).unwrap();
+    for edit in ["", "\n// tweak\n"] {
+        std::fs::write(src.join("nested.ts"), format!("{nested}{edit}")).unwrap();
+        git(dir.path(), &["add", "."]);
+        git(dir.path(), &["commit", "-q", "-m", "change"]);
+    }
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--format", "json", "--fail-on", "none", "--hotspots", "src"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let hotspots = &parsed["hotspots"];
+    assert_eq!(hotspots["since"], "1 year ago");
+    assert_eq!(hotspots["commits"], 2);
+    assert_eq!(hotspots["files"][0]["path"], "src/nested.ts");
+    assert_eq!(hotspots["files"][0]["commits"], 2);
+    assert_eq!(hotspots["functions"][0]["name"], "processData");
+    // Only the first commit touched the function's own lines
+    assert_eq!(hotspots["functions"][0]["changes"], 1);
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--no-color", "--fail-on", "none", "--hotspots", "src"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Change hotspots (since 1 year ago, 2 commits):"));
+    assert!(stdout.contains("1. processData (src/nested.ts:5)"));
+}
+
+#[test]
+fn test_hotspots_outside_repository_warns() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("calc.ts"),
+        "export function calc(a: number) {\n  return a + 1;\n}\n",
+    )
+    .unwrap();
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--format", "json", "--hotspots", "."])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Warning: failed to analyze hotspots"));
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(parsed.get("hotspots").is_none());
+}
+
+// ============================================================
+// Blame attribution
+// ============================================================
+
+#[test]
+fn test_blame_attributes_violations_to_authors() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("src");
+    std::fs::create_dir_all(&src).unwrap();
+    git(dir.path(), &["init", "-q"]);
+    std::fs::copy(
+        fixture_path("typescript/complex_nested.ts"),
+        src.join("nested.ts"),
+    )
+    .unwrap();
+    git(dir.path(), &["add", "."]);
+    git(dir.path(), &["commit", "-q", "-m", "add nested"]);
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--format", "json", "--fail-on", "none", "--blame", "src"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let function = &parsed["files"][0]["functions"][0];
+    assert_eq!(function["name"], "processData");
+    assert_eq!(function["blame"]["author"], "Test");
+    assert_eq!(function["blame"]["commit"].as_str().unwrap().len(), 40);
+    let authors = &parsed["blame"]["authors"];
+    assert_eq!(authors[0]["author"], "Test");
+    assert!(authors[0]["units"].as_u64().unwrap() >= 1);
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--no-color", "--fail-on", "none", "--blame", "src"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Violations by author:"));
+    assert!(stdout.contains("1. Test  "));
+
+    // Nothing was committed after the window starts
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args([
+            "--format",
+            "json",
+            "--fail-on",
+            "none",
+            "--blame-since",
+            "2099-01-01",
+            "src",
+        ])
+        .output()
+        .unwrap();
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(parsed["files"][0]["functions"][0].get("blame").is_none());
+    assert_eq!(parsed["blame"]["since"], "2099-01-01");
+    assert_eq!(parsed["blame"]["authors"], serde_json::json!([]));
+}
+
+#[test]
+fn test_blame_outside_repository_warns() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::copy(
+        fixture_path("typescript/complex_nested.ts"),
+        dir.path().join("nested.ts"),
+    )
+    .unwrap();
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--format", "json", "--fail-on", "none", "--blame", "."])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Warning: failed to blame violations"));
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(parsed.get("blame").is_none());
+}
+
+// ============================================================
+// Function history
+// ============================================================
+
+#[test]
+fn test_history_tracks_function_across_commits() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("src");
+    std::fs::create_dir_all(&src).unwrap();
+    git(dir.path(), &["init", "-q"]);
+    let versions = [
+        "export function checkout(a: number) {\n  return a;\n}\n",
+        "export function checkout(a: number) {\n  if (a > 0) {\n    return a;\n  }\n  return 0;\n}\n",
+    ];
+    for (i, source) in versions.iter().enumerate() {
+        std::fs::write(src.join("orders.ts"), source).unwrap();
+        git(dir.path(), &["add", "."]);
+        git(
+            dir.path(),
+            &["commit", "-q", "-m", &format!("Version {}", i + 1)],
+        );
+    }
+    git(dir.path(), &["mv", "src/orders.ts", "src/checkout.ts"]);
+    git(dir.path(), &["commit", "-q", "-m", "Rename"]);
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["history", "src/checkout.ts::checkout", "--format", "json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["path"], "src/checkout.ts");
+    let revisions = parsed["revisions"].as_array().unwrap();
+    assert_eq!(revisions.len(), 3);
+    assert_eq!(revisions[0]["subject"], "Version 1");
+    assert_eq!(revisions[0]["path"], "src/orders.ts");
+    assert_eq!(revisions[0]["metrics"]["cyclomatic"], 1);
+    assert_eq!(revisions[1]["metrics"]["cyclomatic"], 2);
+    assert_eq!(revisions[2]["path"], "src/checkout.ts");
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["history", "src/checkout.ts::checkout", "--no-color"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("checkout in src/checkout.ts (3 revisions)"));
+    assert!(stdout.contains("Version 1 (as src/orders.ts)"));
+}
+
+#[test]
+fn test_history_rejects_bad_targets() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["history", "src/checkout.ts"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("expected <path>::<function>"));
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["history", "src/checkout.ts::checkout"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+}
+
+// ============================================================
+// Git revisions
+// ============================================================
+
+#[test]
+fn test_rev_analyzes_committed_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("src");
+    std::fs::create_dir_all(&src).unwrap();
+    git(dir.path(), &["init", "-q"]);
+    std::fs::copy(
+        fixture_path("typescript/complex_nested.ts"),
+        src.join("nested.ts"),
+    )
+    .unwrap();
+    git(dir.path(), &["add", "."]);
+    git(dir.path(), &["commit", "-q", "-m", "release"]);
+    git(dir.path(), &["tag", "v1.0.0"]);
+    let sha = git_output(dir.path(), &["rev-parse", "HEAD"]);
+
+    // Working-tree changes after the tag are not analyzed
+    std::fs::write(
+        src.join("nested.ts"),
+        "export function simple() {\n  return 1;\n}\n",
+    )
+    .unwrap();
+    std::fs::write(src.join("extra.ts"), "export const extra = 1;\n").unwrap();
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args([
+            "--format",
+            "json",
+            "--fail-on",
+            "none",
+            "--rev",
+            "v1.0.0",
+            "src",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["metadata"]["commit"], sha.as_str());
+    let files = parsed["files"].as_array().unwrap();
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0]["path"], "src/nested.ts");
+    assert_eq!(files[0]["functions"][0]["name"], "processData");
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--no-color", "--fail-on", "none", "--rev", "v1.0.0"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with(&format!("Commit {sha}\n")));
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--format", "sarif", "--rev", "v1.0.0", "src"])
+        .output()
+        .unwrap();
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["runs"][0]["properties"]["commit"], sha.as_str());
+
+    // The working tree is unchanged
+    let current = std::fs::read_to_string(src.join("nested.ts")).unwrap();
+    assert!(current.contains("simple"));
+}
+
+#[test]
+fn test_rev_rejects_unknown_revision() {
+    let dir = tempfile::tempdir().unwrap();
+    git(dir.path(), &["init", "-q"]);
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--rev", "no-such-tag", "."])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("unknown revision 'no-such-tag'"));
+}
+
+// ============================================================
+// Staged changes
+// ============================================================
+
+const NESTED_BODY: &str =
+    "  if (a) { if (a) { if (a) { if (a) { if (a) { if (a) { return 1; } } } } } }\n";
+
+#[test]
+fn test_staged_fails_only_on_worsened_functions() {
+    let dir = tempfile::tempdir().unwrap();
+    git(dir.path(), &["init", "-q"]);
+    let legacy = format!("export function legacy(a: number) {{\n{NESTED_BODY}  return 0;\n}}\n");
+    let simple = "export function simple(a: number) {\n  return a;\n}\n";
+    std::fs::write(dir.path().join("legacy.ts"), &legacy).unwrap();
+    std::fs::write(dir.path().join("simple.ts"), simple).unwrap();
+    git(dir.path(), &["add", "."]);
+    git(dir.path(), &["commit", "-q", "-m", "initial"]);
+
+    // An existing violation in a touched file does not block the commit
+    std::fs::write(dir.path().join("legacy.ts"), format!("// note\n{legacy}")).unwrap();
+    git(dir.path(), &["add", "legacy.ts"]);
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--staged", "--format", "json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["new_violations"].as_array().unwrap().len(), 0);
+
+    // A worsened function does
+    let worse = format!("export function simple(a: number) {{\n{NESTED_BODY}  return a;\n}}\n");
+    std::fs::write(dir.path().join("simple.ts"), worse).unwrap();
+    git(dir.path(), &["add", "simple.ts"]);
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--staged", "--format", "json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let new_violations = parsed["new_violations"].as_array().unwrap();
+    assert!(!new_violations.is_empty());
+    assert!(new_violations
+        .iter()
+        .all(|v| v["path"] == "simple.ts" && v["name"] == "simple"));
+}
+
+#[test]
+fn test_staged_fails_when_an_existing_error_gets_worse() {
+    let dir = tempfile::tempdir().unwrap();
+    git(dir.path(), &["init", "-q"]);
+    let branches = |count: usize| {
+        let body: String = (0..count)
+            .map(|i| format!("  if (a === {i}) return {i};\n"))
+            .collect();
+        format!("export function route(a: number) {{\n{body}  return -1;\n}}\n")
+    };
+    std::fs::write(dir.path().join("route.ts"), branches(25)).unwrap();
+    git(dir.path(), &["add", "."]);
+    git(dir.path(), &["commit", "-q", "-m", "initial"]);
+
+    std::fs::write(dir.path().join("route.ts"), branches(45)).unwrap();
+    git(dir.path(), &["add", "route.ts"]);
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--staged", "--format", "json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let messages: Vec<_> = parsed["new_violations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v["message"].as_str().unwrap())
+        .collect();
+    assert!(
+        messages.contains(&"Cyclomatic complexity 46 exceeds error threshold 20 (was 26)"),
+        "{messages:?}"
+    );
+}
+
+#[test]
+fn test_staged_ignores_unstaged_edits() {
+    let dir = tempfile::tempdir().unwrap();
+    git(dir.path(), &["init", "-q"]);
+    let simple = "export function simple(a: number) {\n  return a;\n}\n";
+    std::fs::write(dir.path().join("simple.ts"), simple).unwrap();
+    git(dir.path(), &["add", "."]);
+    git(dir.path(), &["commit", "-q", "-m", "initial"]);
+
+    // Stage a harmless change, then worsen the file without staging it
+    std::fs::write(dir.path().join("simple.ts"), format!("// note\n{simple}")).unwrap();
+    git(dir.path(), &["add", "simple.ts"]);
+    let worse = format!("export function simple(a: number) {{\n{NESTED_BODY}  return a;\n}}\n");
+    std::fs::write(dir.path().join("simple.ts"), worse).unwrap();
+    // A new file that is not staged is not checked either
+    std::fs::write(dir.path().join("draft.ts"), format!("// note\n{simple}")).unwrap();
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--staged", "--no-color"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("No regressions"));
+}
+
+#[test]
+fn test_staged_outside_repository_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .env("GIT_CEILING_DIRECTORIES", dir.path())
+        .args(["--staged"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Error checking staged changes"));
+}
+
+// ============================================================
+// Code owners
+// ============================================================
+
+fn codeowners_repo() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    git(dir.path(), &["init", "-q"]);
+    let write = |path: &str, source: &str| {
+        let path = dir.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    };
+    write(
+        ".github/CODEOWNERS",
+        "*.ts @org/core\n/src/web/ @org/web\n/src/web/legacy/ @org/web @org/core\n/scripts/\n",
+    );
+    write(
+        "src/core/sum.ts",
+        "export function sum(a: number, b: number) {\n  return a + b;\n}\n",
+    );
+    write(
+        "src/web/page.ts",
+        &format!("export function render(a: number) {{\n{NESTED_BODY}  return 0;\n}}\n"),
+    );
+    write(
+        "src/web/legacy/old.ts",
+        "export function old() {\n  return 1;\n}\n",
+    );
+    write(
+        "scripts/build.ts",
+        "export function build() {\n  return 1;\n}\n",
+    );
+    dir
+}
+
+#[test]
+fn test_owners_aggregates_per_team() {
+    let dir = codeowners_repo();
+    let output = cargo_bin()
+        .current_dir(dir.path().join("src"))
+        .args([
+            "--owners",
+            "--format",
+            "json",
+            "--fail-on",
+            "none",
+            ".",
+            "../scripts",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["owners"]["file"], ".github/CODEOWNERS");
+    let owners = parsed["owners"]["owners"].as_array().unwrap();
+    let names: Vec<_> = owners
+        .iter()
+        .map(|o| o["owner"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["@org/core", "@org/web", "(unowned)"]);
+    // Paths are resolved from the working directory, src/
+    assert_eq!(owners[0]["files"], 2);
+    assert_eq!(owners[1]["files"], 2);
+    assert!(owners[1]["errors"].as_u64().unwrap() > 0);
+    assert_eq!(owners[0]["errors"], 0);
+    assert!(owners[1]["health_score"].as_f64().unwrap() < 100.0);
+    let file = |path: &str| {
+        parsed["files"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|f| f["path"] == path)
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(
+        file("./web/legacy/old.ts")["owners"],
+        serde_json::json!(["@org/web", "@org/core"])
+    );
+    assert_eq!(
+        file("../scripts/build.ts")["owners"],
+        serde_json::json!(["(unowned)"])
+    );
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--owners", "--no-color", "--fail-on", "none"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Owners (.github/CODEOWNERS):"));
+    assert!(stdout.contains("@org/web"));
+}
+
+#[test]
+fn test_owner_filter_limits_run_to_team_files() {
+    let dir = codeowners_repo();
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--owner", "@ORG/core", "--format", "json"])
+        .output()
+        .unwrap();
+    // The failing web file is not analyzed
+    assert_eq!(output.status.code(), Some(0));
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let mut paths: Vec<_> = parsed["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["path"].as_str().unwrap().to_string())
+        .collect();
+    paths.sort();
+    assert_eq!(paths, ["./src/core/sum.ts", "./src/web/legacy/old.ts"]);
+    assert!(parsed["owners"].is_object());
+
+    std::fs::remove_file(dir.path().join(".github/CODEOWNERS")).unwrap();
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--owner", "@org/core"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("no CODEOWNERS file"));
+}
+
+// ============================================================
+// Directory rollups
+// ============================================================
+
+#[test]
+fn test_by_directory_console_and_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let write = |path: &str, source: &str| {
+        let path = dir.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    };
+    let simple = "export function simple(a: number) {\n  return a;\n}\n";
+    write(
+        "src/api/v1/handler.ts",
+        &format!("export function handle(a: number) {{\n{NESTED_BODY}  return 0;\n}}\n"),
+    );
+    write("src/util.ts", simple);
+    write("scripts/build.ts", simple);
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args([
+            "--by-directory",
+            "--no-color",
+            "--fail-on",
+            "none",
+            "src",
+            "scripts",
+        ])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Directories:"));
+    let row = |path: &str| {
+        stdout
+            .lines()
+            .find(|l| l.trim_start().starts_with(&format!("{path} ")))
+            .unwrap_or_else(|| panic!("no row for {path} in\n{stdout}"))
+            .split_whitespace()
+            .skip(1)
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(row("src")[..2], ["2", "2"]);
+    assert_eq!(row("src/api")[..2], ["1", "1"]);
+    assert_eq!(row("scripts")[..4], ["1", "1", "0", "0"]);
+    // Two levels below the root by default
+    assert!(!stdout
+        .lines()
+        .any(|l| l.trim_start().starts_with("src/api/v1 ")));
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args([
+            "--directory-depth",
+            "3",
+            "--no-color",
+            "--fail-on",
+            "none",
+            "src",
+        ])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout
+        .lines()
+        .any(|l| l.trim_start().starts_with("src/api/v1 ")));
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--format", "json", "--fail-on", "none", "src"])
+        .output()
+        .unwrap();
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let root = &parsed["directories"];
+    assert_eq!(root["files"], 2);
+    assert_eq!(root["errors"], parsed["summary"]["errors"]);
+    let v1 = &root["children"][0]["children"][0]["children"][0];
+    assert_eq!(v1["path"], "src/api/v1");
+    assert_eq!(v1["errors"], parsed["summary"]["errors"]);
+}
+
+// ============================================================
+// Workspace packages
+// ============================================================
+
+fn pnpm_workspace() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let write = |path: &str, source: &str| {
+        let path = dir.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    };
+    write("package.json", r#"{"name": "acme", "private": true}"#);
+    write("pnpm-workspace.yaml", "packages:\n  - 'packages/*'\n");
+    write("packages/web/package.json", r#"{"name": "@acme/web"}"#);
+    write(
+        "packages/web/src/page.ts",
+        &format!("export function render(a: number) {{\n{NESTED_BODY}  return 0;\n}}\n"),
+    );
+    write("packages/core/package.json", r#"{"name": "@acme/core"}"#);
+    write(
+        "packages/core/src/sum.ts",
+        "export function sum(a: number, b: number) {\n  return a + b;\n}\n",
+    );
+    write(
+        "scripts/build.ts",
+        "export function build() {\n  return 1;\n}\n",
+    );
+    dir
+}
+
+#[test]
+fn test_packages_totals_and_per_package_gates() {
+    let dir = pnpm_workspace();
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--packages", "--format", "json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["packages"]["manager"], "pnpm");
+    let packages = parsed["packages"]["packages"].as_array().unwrap();
+    let names: Vec<_> = packages
+        .iter()
+        .map(|p| p["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["@acme/core", "@acme/web", "(root)"]);
+    assert_eq!(packages[1]["path"], "packages/web");
+    assert!(packages[1]["errors"].as_u64().unwrap() > 0);
+    assert_eq!(packages[0]["errors"], 0);
+    let page = parsed["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["path"] == "./packages/web/src/page.ts")
+        .unwrap();
+    assert_eq!(page["package"], "@acme/web");
+
+    // A package gated with "none" no longer fails the run
+    std::fs::write(
+        dir.path().join(".complexityguard.json"),
+        r#"{"packages": {"enabled": true, "fail_on": {"packages/web": "none"}}}"#,
+    )
+    .unwrap();
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--no-color"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.starts_with("Packages (pnpm workspace):\n"),
+        "{stdout}"
+    );
+    let web = stdout
+        .lines()
+        .find(|l| l.trim_start().starts_with("@acme/web "))
+        .unwrap();
+    assert!(web.ends_with("  none"), "{web}");
+}
+
+#[test]
+fn test_package_filter_limits_run_to_one_package() {
+    let dir = pnpm_workspace();
+    let output = cargo_bin()
+        .current_dir(dir.path().join("packages"))
+        .args(["--package", "@acme/core", "--format", "json", ".."])
+        .output()
+        .unwrap();
+    // The failing web package is not analyzed
+    assert_eq!(output.status.code(), Some(0));
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let paths: Vec<_> = parsed["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["path"].as_str().unwrap())
+        .collect();
+    assert_eq!(paths, ["../packages/core/src/sum.ts"]);
+    assert_eq!(parsed["packages"]["packages"][0]["name"], "@acme/core");
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--package", "@acme/docs"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("no package '@acme/docs' in the pnpm workspace"));
+    assert!(stderr.contains("packages: @acme/core, @acme/web"));
+}
+
+// ============================================================
+// TypeScript projects
+// ============================================================
+
+#[test]
+fn test_project_analyzes_only_compiled_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let write = |path: &str, source: &str| {
+        let path = dir.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    };
+    let simple = "export function simple(a: number) {\n  return a;\n}\n";
+    let nested = format!("export function nested(a: number) {{\n{NESTED_BODY}  return 0;\n}}\n");
+    write("src/app.ts", simple);
+    write("src/app.spec.ts", simple);
+    write("packages/core/src/core.ts", simple);
+    write("fixtures/broken.ts", &nested);
+    write("scratch.ts", &nested);
+    write(
+        "tsconfig.base.json",
+        "{\n  // shared settings\n  \"exclude\": [\"**/*.spec.ts\"],\n}\n",
+    );
+    write(
+        "tsconfig.json",
+        r#"{"extends": "./tsconfig.base.json", "include": ["src"], "references": [{"path": "packages/core"}]}"#,
+    );
+    write(
+        "packages/core/tsconfig.json",
+        r#"{"include": ["src/**/*.ts"]}"#,
+    );
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--project", "tsconfig.json", "--format", "json"])
+        .output()
+        .unwrap();
+    // The nested functions in fixtures/ and scratch.ts are not analyzed
+    assert_eq!(output.status.code(), Some(0));
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let paths: Vec<_> = parsed["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["path"].as_str().unwrap())
+        .collect();
+    assert_eq!(paths, ["packages/core/src/core.ts", "src/app.ts"]);
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--project", ".", "--format", "json", "packages"])
+        .output()
+        .unwrap();
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["summary"]["files_analyzed"], 1);
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--project", "missing/tsconfig.json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("failed to read missing/tsconfig.json"),
+        "{stderr}"
+    );
+}
+
+// ============================================================
+// Module graph
+// ============================================================
+
+#[test]
+fn test_module_graph_reports_coupling_and_writes_graph() {
+    let dir = tempfile::tempdir().unwrap();
+    let write = |path: &str, source: &str| {
+        let path = dir.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    };
+    write(
+        "src/app.ts",
+        "import { util } from \"./lib/util\";\nimport type { Model } from \"@/model\";\nexport const run = (m: Model) => util(m);\n",
+    );
+    write(
+        "src/cli.ts",
+        "const { util } = require(\"./lib/util.js\");\nutil();\n",
+    );
+    write(
+        "src/lib/util.ts",
+        "import * as path from \"node:path\";\nexport function util(x?: unknown) {\n  return path.sep;\n}\n",
+    );
+    write("src/model.ts", "export interface Model {}\n");
+    write(
+        "tsconfig.json",
+        r#"{"compilerOptions": {"baseUrl": ".", "paths": {"@/*": ["src/*"]}}}"#,
+    );
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--module-graph", "graph.dot", "--format", "json", "src"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let parsed: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let util = parsed["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["path"] == "src/lib/util.ts")
+        .unwrap();
+    assert_eq!(util["coupling"]["fan_in"], 2);
+    assert_eq!(util["coupling"]["fan_out"], 0);
+    let dot = std::fs::read_to_string(dir.path().join("graph.dot")).unwrap();
+    assert!(dot.starts_with("digraph modules {"), "{dot}");
+    assert!(
+        dot.contains("\"src/app.ts\" -> \"src/lib/util.ts\";"),
+        "{dot}"
+    );
+    assert!(
+        dot.contains("\"src/app.ts\" -> \"src/model.ts\" [style=dashed];"),
+        "{dot}"
+    );
+
+    // A fan-in threshold of 1 turns the shared module into an error
+    let config_path = dir.path().join("strict.json");
+    std::fs::write(
+        &config_path,
+        r#"{"analysis": {"thresholds": {"fan_in": {"warning": 1, "error": 2}}}, "modules": {"enabled": true, "graph": "graph.json"}}"#,
+    )
+    .unwrap();
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--no-color", "--config"])
+        .arg(&config_path)
+        .arg("src")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("1:0  ✗  error  Module fan-in 2 fan-out 0 instability 0.00"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("Modules (4 files, 3 imports):"), "{stdout}");
+    let graph: Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.path().join("graph.json")).unwrap())
+            .unwrap();
+    assert_eq!(graph["modules"].as_array().unwrap().len(), 4);
+    assert_eq!(graph["edges"].as_array().unwrap().len(), 3);
+    assert_eq!(graph["edges"][0]["kind"], "import");
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args([
+            "--modules",
+            "--module-graph",
+            "missing/dir/graph.dot",
+            "src",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Error writing module graph"), "{stderr}");
+}
+
+#[test]
+fn test_import_cycles_are_errors_unless_allowed() {
+    let dir = tempfile::tempdir().unwrap();
+    let write = |path: &str, source: &str| {
+        let path = dir.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    };
+    write(
+        "src/orders.ts",
+        "import { Customer } from \"./customers\";\nexport class Order {\n  customer?: Customer;\n}\n",
+    );
+    write(
+        "src/customers.ts",
+        "import type { Audit } from \"./audit\";\nimport { Order } from \"./orders\";\nexport class Customer {\n  orders: Order[] = [];\n}\n",
+    );
+    write(
+        "src/audit.ts",
+        "import { Customer } from \"./customers\";\nexport type Audit = { customer: Customer };\n",
+    );
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--modules", "--no-color", "src"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains(
+            "2:0  ✗  error  Import cycle src/customers.ts:2 -> src/orders.ts:1 -> src/customers.ts"
+        ),
+        "{stdout}"
+    );
+    assert!(stdout.contains("Import cycles: 1 (0 allowed)"), "{stdout}");
+
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--modules", "--format", "sarif", "src"])
+        .output()
+        .unwrap();
+    let parsed: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let results = parsed["runs"][0]["results"].as_array().unwrap();
+    let cycle = results
+        .iter()
+        .find(|r| r["ruleId"] == "complexity-guard/import-cycle")
+        .unwrap();
+    let related: Vec<_> = cycle["relatedLocations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| {
+            (
+                r["physicalLocation"]["artifactLocation"]["uri"]
+                    .as_str()
+                    .unwrap(),
+                r["physicalLocation"]["region"]["startLine"]
+                    .as_u64()
+                    .unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(related, [("src/customers.ts", 2), ("src/orders.ts", 1)]);
+
+    let config_path = dir.path().join("cycles.json");
+    std::fs::write(
+        &config_path,
+        r#"{"modules": {"enabled": true, "allow_cycles": [["src/orders.ts", "src/customers.ts"]]}}"#,
+    )
+    .unwrap();
+    let output = cargo_bin()
+        .current_dir(dir.path())
+        .args(["--format", "json", "--config"])
+        .arg(&config_path)
+        .arg("src")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let parsed: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["summary"]["errors"], 0);
+    // The accepted cycle is still part of the report
+    let customers = &parsed["files"][1];
+    assert_eq!(customers["path"], "src/customers.ts");
+    assert_eq!(customers["cycles"][0]["chain"][0]["line"], 2);
+}
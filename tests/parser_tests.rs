@@ -16,7 +16,10 @@ fn test_parse_typescript_simple_function() {
     let path = fixture_path("typescript/simple_function.ts");
     let result = parse_file(&path).expect("should parse TypeScript file");
 
-    assert!(!result.error, "fixture should parse without errors");
+    assert!(
+        result.syntax_errors.is_empty(),
+        "fixture should parse without errors"
+    );
     assert!(
         !result.functions.is_empty(),
         "should find at least one function"
@@ -41,7 +44,10 @@ fn test_parse_tsx_react_component() {
     let path = fixture_path("typescript/react_component.tsx");
     let result = parse_file(&path).expect("should parse TSX file");
 
-    assert!(!result.error, "fixture should parse without errors");
+    assert!(
+        result.syntax_errors.is_empty(),
+        "fixture should parse without errors"
+    );
 
     // Should find "Greeting" function declaration
     let greeting = result
@@ -65,7 +71,10 @@ fn test_parse_javascript_express_middleware() {
     let path = fixture_path("javascript/express_middleware.js");
     let result = parse_file(&path).expect("should parse JavaScript file");
 
-    assert!(!result.error, "fixture should parse without errors");
+    assert!(
+        result.syntax_errors.is_empty(),
+        "fixture should parse without errors"
+    );
 
     // Should find "errorHandler" function
     let error_handler = result
@@ -89,7 +98,10 @@ fn test_parse_jsx_component() {
     let path = fixture_path("javascript/jsx_component.jsx");
     let result = parse_file(&path).expect("should parse JSX file");
 
-    assert!(!result.error, "fixture should parse without errors");
+    assert!(
+        result.syntax_errors.is_empty(),
+        "fixture should parse without errors"
+    );
 
     // Should find "Card" function
     let card = result
@@ -113,7 +125,10 @@ fn test_parse_typescript_class_with_methods() {
     let path = fixture_path("typescript/class_with_methods.ts");
     let result = parse_file(&path).expect("should parse TypeScript class file");
 
-    assert!(!result.error, "fixture should parse without errors");
+    assert!(
+        result.syntax_errors.is_empty(),
+        "fixture should parse without errors"
+    );
 
     // Should find class methods
     let method_names: Vec<&str> = result.functions.iter().map(|f| f.name.as_str()).collect();
@@ -183,3 +198,54 @@ fn test_function_line_numbers_are_one_indexed() {
         );
     }
 }
+
+#[test]
+fn test_syntax_errors_are_reported_with_locations() {
+    let path = fixture_path("typescript/syntax_error.ts");
+    let result = parse_file(&path).expect("tree-sitter is error tolerant");
+
+    assert!(
+        !result.syntax_errors.is_empty(),
+        "fixture should report syntax errors"
+    );
+    for error in &result.syntax_errors {
+        assert!(
+            error.line >= 10,
+            "error should be after brokenFunction starts"
+        );
+    }
+    assert!(
+        result.functions.iter().any(|f| f.name == "validFunction"),
+        "functions before the error should still be discovered"
+    );
+}
+
+#[test]
+fn test_parse_shares_function_index_with_metrics() {
+    let path = fixture_path("naming-edge-cases.ts");
+    let parsed = parse_file(&path).expect("should parse");
+    let (analyzed, _skipped) = complexity_guard::metrics::analyze_file(
+        &path,
+        &complexity_guard::types::AnalysisConfig::default(),
+    )
+    .expect("should analyze");
+
+    // Every analyzed function is an outermost function of the parser's list, in order
+    let outermost: Vec<(usize, usize)> = parsed
+        .functions
+        .iter()
+        .filter(|f| f.parent.is_none())
+        .map(|f| (f.start_line, f.start_column))
+        .collect();
+    let analyzed_positions: Vec<(usize, usize)> = analyzed
+        .functions
+        .iter()
+        .map(|f| (f.start_line, f.start_col))
+        .collect();
+    assert_eq!(outermost, analyzed_positions);
+
+    // Function expressions and class-field arrows are discovered by the parser too
+    let names: Vec<&str> = parsed.functions.iter().map(|f| f.name.as_str()).collect();
+    assert!(names.contains(&"compute"), "got: {:?}", names);
+    assert!(names.contains(&"onClick"), "got: {:?}", names);
+}